@@ -0,0 +1,282 @@
+//! Decoding of the Wormhole accounts `svm-vaa account` recognizes.
+//!
+//! An account is only decoded when its owner matches the network's programs
+//! and its layout (and address, where it can be derived from the data) is
+//! what they would create.
+
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use wormhole_svm_submit::signatures::signatures_account_len;
+use wormhole_svm_submit::{GuardianSet, SignaturesAccount, WormholeNetwork};
+
+/// Size of the core bridge config: guardian set index, last lamports,
+/// guardian set expiration time and message fee.
+const BRIDGE_CONFIG_LEN: usize = 24;
+
+/// Size of a posted message account before the payload bytes: the `msg` /
+/// `msu` prefix, the message header and the payload length.
+const POSTED_MESSAGE_HEADER_LEN: usize = 3 + 88 + 4;
+
+/// Size of a guardian signatures account before the signatures:
+/// discriminator, refund recipient, guardian set index and vector length.
+const SIGNATURES_HEADER_LEN: usize = 8 + 32 + 4 + 4;
+
+/// A decoded Wormhole account.
+pub enum KnownAccount {
+    BridgeConfig {
+        guardian_set_index: u32,
+        last_lamports: u64,
+        guardian_set_ttl: u32,
+        fee: u64,
+    },
+    GuardianSet(GuardianSet),
+    EmitterSequence {
+        next_sequence: u64,
+        /// Present in accounts created by newer core bridge versions.
+        emitter: Option<Pubkey>,
+    },
+    GuardianSignatures {
+        account: SignaturesAccount,
+        guardian_indices: Vec<u8>,
+    },
+    PostedMessage(PostedMessage),
+}
+
+/// A message posted to the core bridge, through the Post Message Shim or not.
+pub struct PostedMessage {
+    /// Posted with `post_message_unreliable` (`msu` prefix).
+    pub unreliable: bool,
+    pub consistency_level: u8,
+    pub submission_time: u32,
+    pub nonce: u32,
+    pub sequence: u64,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+/// Decode `account` at `pubkey`, if it is one of `network`'s known account
+/// types.
+pub fn decode(
+    network: &WormholeNetwork,
+    pubkey: &Pubkey,
+    account: &Account,
+) -> Option<KnownAccount> {
+    let data = &account.data[..];
+    if account.owner == network.verify_vaa_shim {
+        return decode_signatures(*pubkey, account.lamports, data);
+    }
+    if account.owner != network.core_bridge {
+        return None;
+    }
+
+    if *pubkey == network.core_bridge_config_address() {
+        if data.len() < BRIDGE_CONFIG_LEN {
+            return None;
+        }
+        return Some(KnownAccount::BridgeConfig {
+            guardian_set_index: u32::from_le_bytes(data[0..4].try_into().unwrap()),
+            last_lamports: u64::from_le_bytes(data[4..12].try_into().unwrap()),
+            guardian_set_ttl: u32::from_le_bytes(data[12..16].try_into().unwrap()),
+            fee: u64::from_le_bytes(data[16..24].try_into().unwrap()),
+        });
+    }
+    if data.starts_with(b"msg") || data.starts_with(b"msu") {
+        return decode_message(data).map(KnownAccount::PostedMessage);
+    }
+    if let Ok(set) = GuardianSet::parse(data) {
+        if network.guardian_set_address(set.index) == *pubkey {
+            return Some(KnownAccount::GuardianSet(set));
+        }
+    }
+    decode_sequence(network, pubkey, data)
+}
+
+fn decode_signatures(pubkey: Pubkey, lamports: u64, data: &[u8]) -> Option<KnownAccount> {
+    let account = SignaturesAccount::parse(pubkey, lamports, data)?;
+    if data.len() != signatures_account_len(account.num_signatures) {
+        return None;
+    }
+    let guardian_indices = data[SIGNATURES_HEADER_LEN..]
+        .chunks_exact(66)
+        .map(|signature| signature[0])
+        .collect();
+    Some(KnownAccount::GuardianSignatures {
+        account,
+        guardian_indices,
+    })
+}
+
+fn decode_message(data: &[u8]) -> Option<PostedMessage> {
+    let header = data.get(..POSTED_MESSAGE_HEADER_LEN)?;
+    let u32_at = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+
+    let payload_len = u32_at(POSTED_MESSAGE_HEADER_LEN - 4) as usize;
+    let payload =
+        data.get(POSTED_MESSAGE_HEADER_LEN..POSTED_MESSAGE_HEADER_LEN.checked_add(payload_len)?)?;
+    Some(PostedMessage {
+        unreliable: header.starts_with(b"msu"),
+        consistency_level: header[4],
+        submission_time: u32_at(41),
+        nonce: u32_at(45),
+        sequence: u64::from_le_bytes(header[49..57].try_into().unwrap()),
+        emitter_chain: u16::from_le_bytes(header[57..59].try_into().unwrap()),
+        emitter_address: header[59..91].try_into().unwrap(),
+        payload: payload.to_vec(),
+    })
+}
+
+fn decode_sequence(
+    network: &WormholeNetwork,
+    pubkey: &Pubkey,
+    data: &[u8],
+) -> Option<KnownAccount> {
+    let next_sequence = u64::from_le_bytes(data.get(0..8)?.try_into().unwrap());
+    let emitter = match data.get(8..40) {
+        Some(emitter) => {
+            let emitter = Pubkey::new_from_array(emitter.try_into().unwrap());
+            if network.emitter_sequence_address(&emitter) != *pubkey {
+                return None;
+            }
+            Some(emitter)
+        }
+        None if data.len() == 8 => None,
+        None => return None,
+    };
+    Some(KnownAccount::EmitterSequence {
+        next_sequence,
+        emitter,
+    })
+}
+
+impl KnownAccount {
+    /// Human-readable account type.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            KnownAccount::BridgeConfig { .. } => "core bridge config",
+            KnownAccount::GuardianSet(_) => "guardian set",
+            KnownAccount::EmitterSequence { .. } => "emitter sequence",
+            KnownAccount::GuardianSignatures { .. } => "guardian signatures",
+            KnownAccount::PostedMessage(_) => "posted message",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let fields = match self {
+            KnownAccount::BridgeConfig {
+                guardian_set_index,
+                last_lamports,
+                guardian_set_ttl,
+                fee,
+            } => json!({
+                "guardian_set_index": guardian_set_index,
+                "last_lamports": last_lamports,
+                "guardian_set_ttl": guardian_set_ttl,
+                "fee": fee,
+            }),
+            KnownAccount::GuardianSet(set) => {
+                let keys: Vec<String> = set
+                    .keys
+                    .iter()
+                    .map(|key| format!("0x{}", hex::encode(key)))
+                    .collect();
+                json!({
+                    "index": set.index,
+                    "keys": keys,
+                    "quorum": set.quorum(),
+                    "creation_time": set.creation_time,
+                    "expiration_time": set.expiration_time,
+                })
+            }
+            KnownAccount::EmitterSequence {
+                next_sequence,
+                emitter,
+            } => json!({
+                "next_sequence": next_sequence,
+                "emitter": emitter.map(|emitter| emitter.to_string()),
+            }),
+            KnownAccount::GuardianSignatures {
+                account,
+                guardian_indices,
+            } => json!({
+                "refund_recipient": account.refund_recipient.to_string(),
+                "guardian_set_index": account.guardian_set_index,
+                "guardian_indices": guardian_indices,
+            }),
+            KnownAccount::PostedMessage(message) => json!({
+                "unreliable": message.unreliable,
+                "consistency_level": message.consistency_level,
+                "submission_time": message.submission_time,
+                "nonce": message.nonce,
+                "sequence": message.sequence,
+                "emitter_chain": message.emitter_chain,
+                "emitter_address": hex::encode(message.emitter_address),
+                "payload": hex::encode(&message.payload),
+            }),
+        };
+        json!({ "type": self.kind(), "fields": fields })
+    }
+
+    /// Print the decoded fields, one per line.
+    pub fn print(&self) {
+        println!("type:               {}", self.kind());
+        match self {
+            KnownAccount::BridgeConfig {
+                guardian_set_index,
+                last_lamports,
+                guardian_set_ttl,
+                fee,
+            } => {
+                println!("guardian set index: {}", guardian_set_index);
+                println!("guardian set ttl:   {}s", guardian_set_ttl);
+                println!("message fee:        {} lamports", fee);
+                println!("last lamports:      {}", last_lamports);
+            }
+            KnownAccount::GuardianSet(set) => {
+                println!("index:              {}", set.index);
+                println!("creation time:      {}", set.creation_time);
+                if set.expiration_time == 0 {
+                    println!("expiration time:    never");
+                } else {
+                    println!("expiration time:    {}", set.expiration_time);
+                }
+                println!("quorum:             {}/{}", set.quorum(), set.keys.len());
+                for (i, key) in set.keys.iter().enumerate() {
+                    println!("  {:>2} 0x{}", i, hex::encode(key));
+                }
+            }
+            KnownAccount::EmitterSequence {
+                next_sequence,
+                emitter,
+            } => {
+                println!("next sequence:      {}", next_sequence);
+                if let Some(emitter) = emitter {
+                    println!("emitter:            {}", emitter);
+                }
+            }
+            KnownAccount::GuardianSignatures {
+                account,
+                guardian_indices,
+            } => {
+                println!("guardian set index: {}", account.guardian_set_index);
+                println!("refund recipient:   {}", account.refund_recipient);
+                println!("signatures:         {}", guardian_indices.len());
+                println!("guardian indices:   {:?}", guardian_indices);
+            }
+            KnownAccount::PostedMessage(message) => {
+                println!("unreliable:         {}", message.unreliable);
+                println!("consistency level:  {}", message.consistency_level);
+                println!("submission time:    {}", message.submission_time);
+                println!("nonce:              {}", message.nonce);
+                println!("sequence:           {}", message.sequence);
+                println!("emitter chain:      {}", message.emitter_chain);
+                println!(
+                    "emitter address:    {}",
+                    hex::encode(message.emitter_address)
+                );
+                println!("payload:            {}", hex::encode(&message.payload));
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! `svm-vaa submit --batch`: reading many VAAs and reporting per-VAA results.
+//!
+//! A batch is either a JSON lines file, where each line is a JSON string or
+//! an object with a `vaa` field, or a directory with one VAA per file. VAAs
+//! are hex (optionally `0x`-prefixed) or base64, as returned by Wormholescan.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use solana_sdk::signature::Signature;
+use wormhole_svm_submit::{SignedVaa, VaaBody};
+
+/// One VAA of a batch.
+pub struct BatchVaa {
+    /// Where it came from: `line N` or the file name.
+    pub label: String,
+    /// The signed VAA, or why it couldn't be read.
+    pub vaa: Result<Vec<u8>, String>,
+}
+
+/// Outcome of one VAA of a batch.
+pub struct BatchResult {
+    pub label: String,
+    /// `<chain>/<emitter>/<sequence>`, if the VAA could be parsed.
+    pub id: Option<String>,
+    pub result: Result<Vec<Signature>, String>,
+}
+
+/// Read the VAAs of the JSON lines file or directory at `path`.
+pub fn read_batch(path: &Path) -> Result<Vec<BatchVaa>> {
+    if path.is_dir() {
+        return read_dir(path);
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| BatchVaa {
+            label: format!("line {}", i + 1),
+            vaa: parse_line(line),
+        })
+        .collect())
+}
+
+/// Every non-hidden file of `dir`, in file name order.
+fn read_dir(dir: &Path) -> Result<Vec<BatchVaa>> {
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("reading {}", dir.display()))?;
+    paths.retain(|path| {
+        path.is_file()
+            && !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    });
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| BatchVaa {
+            label: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            vaa: fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| decode_vaa(&contents)),
+        })
+        .collect())
+}
+
+fn parse_line(line: &str) -> Result<Vec<u8>, String> {
+    let value: serde_json::Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    match value.as_str().or_else(|| value.get("vaa")?.as_str()) {
+        Some(vaa) => decode_vaa(vaa),
+        None => Err("expected a JSON string or an object with a \"vaa\" string".to_string()),
+    }
+}
+
+fn decode_vaa(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(text))
+        .map_err(|_| "VAA is neither hex nor base64".to_string())
+}
+
+/// `<chain>/<emitter>/<sequence>` of a signed VAA.
+pub fn vaa_id(vaa: &[u8]) -> Option<String> {
+    let body = VaaBody::parse(&SignedVaa::parse(vaa).ok()?.body).ok()?;
+    Some(format!(
+        "{}/{}/{}",
+        body.emitter_chain,
+        hex::encode(body.emitter_address),
+        body.sequence
+    ))
+}
+
+/// Print one row per VAA: input, VAA id, status and the transaction
+/// signatures or error.
+pub fn print_table(results: &[BatchResult]) {
+    let label_width = results
+        .iter()
+        .map(|result| result.label.len())
+        .chain(["INPUT".len()])
+        .max()
+        .unwrap_or_default();
+    let id_width = results
+        .iter()
+        .map(|result| result.id.as_deref().map_or(1, str::len))
+        .chain(["VAA".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:<label_width$}  {:<id_width$}  {:<6}  DETAIL",
+        "INPUT", "VAA", "STATUS"
+    );
+    for result in results {
+        let (status, detail) = match &result.result {
+            Ok(signatures) => (
+                "ok",
+                signatures
+                    .iter()
+                    .map(Signature::to_string)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            Err(e) => ("failed", e.clone()),
+        };
+        println!(
+            "{:<label_width$}  {:<id_width$}  {:<6}  {}",
+            result.label,
+            result.id.as_deref().unwrap_or("-"),
+            status,
+            detail
+        );
+    }
+}
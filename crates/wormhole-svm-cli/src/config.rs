@@ -0,0 +1,233 @@
+//! `svm-vaa` config file with named profiles.
+//!
+//! ```toml
+//! default_profile = "devnet"
+//!
+//! [profiles.devnet]
+//! rpc_url = "d"
+//! payer = "~/.config/solana/id.json"
+//!
+//! [profiles.mainnet]
+//! rpc_url = "https://my-provider.example/rpc"
+//! payer = "usb://ledger"
+//! compute_unit_price = "auto"
+//! compute_unit_limit = "simulate"
+//! ```
+//!
+//! Command-line flags and environment variables take precedence over the
+//! selected profile.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer};
+
+/// Settings of one profile; every field is optional.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// RPC URL or shorthand, like `--rpc-url`.
+    pub rpc_url: Option<String>,
+    /// Core Bridge program ID, like `--core-bridge`.
+    pub core_bridge: Option<String>,
+    /// Verify VAA Shim program ID, like `--verify-vaa-shim`.
+    pub verify_vaa_shim: Option<String>,
+    /// Post Message Shim program ID, like `--post-message-shim`.
+    pub post_message_shim: Option<String>,
+    /// Payer keypair file (`~/` is expanded) or hardware wallet URI, like
+    /// `--payer`.
+    pub payer: Option<String>,
+    /// Micro-lamports or `"auto"`, like `--compute-unit-price`.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub compute_unit_price: Option<String>,
+    /// Units or `"simulate"`, like `--compute-unit-limit`.
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub compute_unit_limit: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    /// Profile used when `--profile` isn't given.
+    default_profile: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// `$XDG_CONFIG_HOME/svm-vaa/config.toml`, falling back to
+/// `~/.config/svm-vaa/config.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("svm-vaa").join("config.toml"))
+}
+
+/// Load the profile `name` (or the file's default profile) from the config
+/// file at `path` (or [`default_path`]).
+///
+/// Without an explicit path or profile, a missing config file yields an empty
+/// profile.
+pub fn load_profile(path: Option<&Path>, name: Option<&str>) -> Result<Profile> {
+    let (path, explicit) = match path {
+        Some(path) => (path.to_path_buf(), true),
+        None => match default_path() {
+            Some(path) => (path, false),
+            None if name.is_some() => bail!("cannot locate the config file; pass --config"),
+            None => return Ok(Profile::default()),
+        },
+    };
+
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit && name.is_none() => {
+            return Ok(Profile::default());
+        }
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    let mut config: ConfigFile =
+        toml::from_str(&contents).with_context(|| format!("parsing {}", path.display()))?;
+
+    let Some(name) = name.or(config.default_profile.as_deref()) else {
+        return Ok(Profile::default());
+    };
+    let name = name.to_string();
+    let mut profile = config.profiles.remove(&name).with_context(|| {
+        format!(
+            "no profile {:?} in {} (available: {})",
+            name,
+            path.display(),
+            config
+                .profiles
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+    profile.payer = profile.payer.map(|payer| expand_home(&payer));
+    Ok(profile)
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest).to_string_lossy().into_owned(),
+        _ => path.to_string(),
+    }
+}
+
+/// Accept `compute_unit_price = 5000` as well as `compute_unit_price = "auto"`.
+fn string_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        String(String),
+        Number(u64),
+    }
+    Ok(
+        Option::<Value>::deserialize(deserializer)?.map(|value| match value {
+            Value::String(s) => s,
+            Value::Number(n) => n.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+default_profile = "devnet"
+
+[profiles.devnet]
+rpc_url = "d"
+payer = "~/.config/solana/id.json"
+compute_unit_price = 5000
+
+[profiles.mainnet]
+rpc_url = "m"
+payer = "usb://ledger"
+compute_unit_price = "auto"
+compute_unit_limit = "simulate"
+"#;
+
+    /// Write `contents` to a fresh config file.
+    fn config_file(contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("svm-vaa-config-{}.toml", Pubkey::new_unique()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_profile() {
+        let path = config_file(CONFIG);
+
+        let devnet = load_profile(Some(&path), None).unwrap();
+        assert_eq!(devnet.rpc_url.as_deref(), Some("d"));
+        assert_eq!(devnet.payer, Some(expand_home("~/.config/solana/id.json")));
+        assert_eq!(devnet.compute_unit_price.as_deref(), Some("5000"));
+        assert_eq!(devnet.compute_unit_limit, None);
+
+        let mainnet = load_profile(Some(&path), Some("mainnet")).unwrap();
+        assert_eq!(mainnet.payer.as_deref(), Some("usb://ledger"));
+        assert_eq!(mainnet.compute_unit_price.as_deref(), Some("auto"));
+        assert_eq!(mainnet.compute_unit_limit.as_deref(), Some("simulate"));
+
+        let error = load_profile(Some(&path), Some("testnet")).unwrap_err();
+        assert!(format!("{:#}", error).contains("available: devnet, mainnet"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_profile_errors() {
+        let missing =
+            std::env::temp_dir().join(format!("svm-vaa-config-{}.toml", Pubkey::new_unique()));
+        assert!(load_profile(Some(&missing), None).is_err());
+
+        let path = config_file("[profiles.devnet]\nrpc_urls = \"d\"\n");
+        assert!(load_profile(Some(&path), Some("devnet")).is_err());
+        fs::remove_file(&path).unwrap();
+
+        let path = config_file("[profiles.devnet]\nrpc_url = \"d\"\n");
+        let profile = load_profile(Some(&path), None).unwrap();
+        assert_eq!(profile.rpc_url, None);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_expand_home() {
+        match std::env::var_os("HOME") {
+            Some(home) => assert_eq!(
+                expand_home("~/keys/payer.json"),
+                Path::new(&home).join("keys/payer.json").to_string_lossy()
+            ),
+            None => assert_eq!(expand_home("~/keys/payer.json"), "~/keys/payer.json"),
+        }
+        assert_eq!(expand_home("/keys/payer.json"), "/keys/payer.json");
+        assert_eq!(expand_home("~other/payer.json"), "~other/payer.json");
+        assert_eq!(expand_home("usb://ledger"), "usb://ledger");
+    }
+
+    #[test]
+    fn test_string_or_number() {
+        let profile: Profile =
+            toml::from_str("compute_unit_price = 5000\ncompute_unit_limit = \"simulate\"").unwrap();
+        assert_eq!(profile.compute_unit_price.as_deref(), Some("5000"));
+        assert_eq!(profile.compute_unit_limit.as_deref(), Some("simulate"));
+
+        let profile: Profile = toml::from_str("").unwrap();
+        assert_eq!(profile.compute_unit_price, None);
+
+        assert!(toml::from_str::<Profile>("compute_unit_price = -1").is_err());
+        assert!(toml::from_str::<Profile>("compute_unit_price = true").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+//! Confirmation of the resolved plan before `svm-vaa submit` sends anything.
+//!
+//! The VAA itself may come from stdin, so the answer is read from the
+//! controlling terminal.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use wormhole_svm_submit::{
+    placeholder_name, AccountReport, AccountStatus, BroadcastPlan, RESOLVER_PUBKEY_PAYER,
+};
+
+/// Whether there is a terminal to ask on.
+pub fn can_prompt() -> bool {
+    io::stdin().is_terminal() || File::open("/dev/tty").is_ok()
+}
+
+/// Ask `question` on the terminal; anything but `y` or `yes` is a no.
+pub fn prompt(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    if io::stdin().is_terminal() {
+        io::stdin().read_line(&mut answer)?;
+    } else {
+        let tty = File::open("/dev/tty").context("opening the terminal")?;
+        BufReader::new(tty).read_line(&mut answer)?;
+    }
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Print the programs and writable accounts of each instruction group, the
+/// accounts that don't exist yet (from `report`, if it could be fetched) and
+/// the estimated cost.
+pub fn print_plan(plan: &BroadcastPlan, payer: &Pubkey, report: Option<&AccountReport>) {
+    let name = |pubkey: &Pubkey| {
+        if *pubkey == RESOLVER_PUBKEY_PAYER {
+            format!("{} (payer)", payer)
+        } else if pubkey == plan.guardian_set {
+            format!("{} (guardian set)", pubkey)
+        } else {
+            match placeholder_name(pubkey) {
+                Some(placeholder) => placeholder.to_string(),
+                None => pubkey.to_string(),
+            }
+        }
+    };
+
+    eprintln!();
+    eprintln!(
+        "Plan: {} instruction group(s) for {}",
+        plan.instruction_groups.len(),
+        plan.program_id
+    );
+    for (index, group) in plan.instruction_groups.iter().enumerate() {
+        let mut programs: Vec<String> = Vec::new();
+        let mut writable: Vec<String> = Vec::new();
+        for ix in &group.instructions {
+            let program = ix.program_id.to_string();
+            if !programs.contains(&program) {
+                programs.push(program);
+            }
+            for meta in ix.accounts.iter().filter(|meta| meta.is_writable) {
+                let account = name(&meta.pubkey);
+                if !writable.contains(&account) {
+                    writable.push(account);
+                }
+            }
+        }
+        eprintln!(
+            "  Group {}: {} instruction(s)",
+            index,
+            group.instructions.len()
+        );
+        eprintln!("    programs: {}", programs.join(", "));
+        for account in &writable {
+            eprintln!("    writes:   {}", account);
+        }
+    }
+
+    match report {
+        Some(report) => {
+            let new_accounts: Vec<_> = report
+                .accounts
+                .iter()
+                .filter(|account| {
+                    account.writable
+                        && matches!(
+                            account.status,
+                            AccountStatus::Missing | AccountStatus::CreatedByBroadcast
+                        )
+                })
+                .collect();
+            if !new_accounts.is_empty() {
+                eprintln!("  Accounts that don't exist yet:");
+            }
+            for account in new_accounts {
+                eprintln!(
+                    "    {} (groups {:?})",
+                    name(&account.referenced),
+                    account.groups
+                );
+            }
+        }
+        None => eprintln!("  (could not check which accounts exist)"),
+    }
+
+    let estimate = &plan.estimate;
+    eprintln!(
+        "  Estimated cost: {} lamports",
+        estimate.total().saturating_add(plan.bridge_fees)
+    );
+    eprintln!(
+        "    signatures rent:  {} (refunded on close)",
+        estimate.signatures_rent
+    );
+    eprintln!("    transaction fees: {}", estimate.transaction_fees);
+    eprintln!(
+        "    priority fees:    {} (upper bound)",
+        estimate.priority_fees
+    );
+    eprintln!("    bridge fees:      {}", plan.bridge_fees);
+}
@@ -0,0 +1,251 @@
+//! Exit codes per class of failure, and the `--output json` error report.
+//!
+//! | Code | Failure |
+//! |------|---------|
+//! | 1 | anything not listed below |
+//! | 2 | invalid command line |
+//! | 3 | invalid input: VAA, hex, address or other argument |
+//! | 4 | the resolver failed or didn't converge |
+//! | 5 | a simulated submission failed (`simulate`, `submit --dry-run`) |
+//! | 6 | executing the resolved instructions failed |
+//! | 7 | the VAA was already redeemed |
+//!
+//! Code 2 comes from clap. Codes are never reused or renumbered.
+
+use std::fmt;
+
+use serde_json::json;
+use wormhole_svm_submit::SubmitError;
+
+/// Class of a failure, which determines the exit code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    Other,
+    Parse,
+    Resolver,
+    Simulation,
+    Execution,
+    AlreadyRedeemed,
+}
+
+impl ErrorClass {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorClass::Other => 1,
+            ErrorClass::Parse => 3,
+            ErrorClass::Resolver => 4,
+            ErrorClass::Simulation => 5,
+            ErrorClass::Execution => 6,
+            ErrorClass::AlreadyRedeemed => 7,
+        }
+    }
+
+    /// Name in the JSON error report.
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorClass::Other => "other",
+            ErrorClass::Parse => "parse",
+            ErrorClass::Resolver => "resolver",
+            ErrorClass::Simulation => "simulation",
+            ErrorClass::Execution => "execution",
+            ErrorClass::AlreadyRedeemed => "already_redeemed",
+        }
+    }
+
+    /// The class of `error`: that of the first error of its chain with a
+    /// known class.
+    pub fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(failure) = cause.downcast_ref::<Failure>() {
+                return failure.class;
+            }
+            if let Some(e) = cause.downcast_ref::<SubmitError>() {
+                return Self::of_submit_error(e);
+            }
+            if cause.is::<hex::FromHexError>()
+                || cause.is::<solana_sdk::pubkey::ParsePubkeyError>()
+                || cause.is::<std::num::ParseIntError>()
+            {
+                return ErrorClass::Parse;
+            }
+        }
+        ErrorClass::Other
+    }
+
+    /// The class of a library error.
+    pub fn of_submit_error(e: &SubmitError) -> Self {
+        match e.error_code() {
+            300 => ErrorClass::Parse,
+            200..=299 => ErrorClass::Resolver,
+            400..=499 if looks_already_redeemed(&e.to_string()) => ErrorClass::AlreadyRedeemed,
+            400..=499 => ErrorClass::Execution,
+            _ => ErrorClass::Other,
+        }
+    }
+}
+
+/// Whether a failed transaction or simulation looks like a replay: creating
+/// the program's replay protection account failed because it exists.
+pub fn looks_already_redeemed(message: &str) -> bool {
+    message.contains("already in use")
+}
+
+/// The failure of a simulated submission whose group failed with `error`.
+pub fn simulation_failed(error: &str, message: String) -> anyhow::Error {
+    let class = if looks_already_redeemed(error) {
+        ErrorClass::AlreadyRedeemed
+    } else {
+        ErrorClass::Simulation
+    };
+    anyhow::Error::new(Failure { class, message })
+}
+
+/// A failure detected by the CLI itself, tagged with its class.
+#[derive(Debug)]
+pub struct Failure {
+    pub class: ErrorClass,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Print `error` to stderr: as `error: <message>`, or as a JSON object with
+/// the class, exit code, library error code (if any) and message.
+pub fn report(error: &anyhow::Error, json: bool) {
+    if !json {
+        eprintln!("error: {:#}", error);
+        return;
+    }
+    let class = ErrorClass::of(error);
+    let code = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<SubmitError>())
+        .map(SubmitError::error_code);
+    let report = json!({
+        "error": {
+            "class": class.name(),
+            "exit_code": class.exit_code(),
+            "code": code,
+            "message": format!("{:#}", error),
+        }
+    });
+    eprintln!("{}", report);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use anyhow::Context;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signature;
+    use wormhole_svm_submit::TransactionFailure;
+
+    use super::*;
+
+    fn transaction_failed(message: &str) -> SubmitError {
+        SubmitError::TransactionFailed {
+            group_index: 0,
+            signature: Signature::default(),
+            message: message.to_string(),
+            cause: TransactionFailure::from_message(message),
+        }
+    }
+
+    #[test]
+    fn test_submit_error_classes() {
+        let cases = [
+            (
+                SubmitError::Connection("timed out".into()),
+                ErrorClass::Other,
+                1,
+            ),
+            (
+                SubmitError::ResolverSimulation("failed".into()),
+                ErrorClass::Resolver,
+                4,
+            ),
+            (
+                SubmitError::InvalidVaa("truncated".into()),
+                ErrorClass::Parse,
+                3,
+            ),
+            (
+                SubmitError::Execution("failed".into()),
+                ErrorClass::Execution,
+                6,
+            ),
+            (
+                transaction_failed("custom program error: 0x1"),
+                ErrorClass::Execution,
+                6,
+            ),
+            (
+                transaction_failed("Allocate: account Address { .. } already in use"),
+                ErrorClass::AlreadyRedeemed,
+                7,
+            ),
+            (
+                SubmitError::CostCapExceeded {
+                    estimated: 2,
+                    cap: 1,
+                },
+                ErrorClass::Other,
+                1,
+            ),
+            (SubmitError::Declined, ErrorClass::Other, 1),
+        ];
+        for (error, class, exit_code) in cases {
+            let message = error.to_string();
+            assert_eq!(ErrorClass::of_submit_error(&error), class, "{}", message);
+            // The class survives added context.
+            let error = anyhow::Error::new(error).context("submitting");
+            assert_eq!(ErrorClass::of(&error), class, "{}", message);
+            assert_eq!(class.exit_code(), exit_code, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_error_classes() {
+        let cases = [
+            (
+                hex::decode("zz").context("decoding hex").unwrap_err(),
+                ErrorClass::Parse,
+                3,
+            ),
+            (
+                Pubkey::from_str("not-a-key")
+                    .context("invalid program ID")
+                    .unwrap_err(),
+                ErrorClass::Parse,
+                3,
+            ),
+            (
+                "x".parse::<u64>().context("invalid sequence").unwrap_err(),
+                ErrorClass::Parse,
+                3,
+            ),
+            (
+                simulation_failed("custom program error: 0x1", "failed".into()),
+                ErrorClass::Simulation,
+                5,
+            ),
+            (
+                simulation_failed("account already in use", "failed".into()),
+                ErrorClass::AlreadyRedeemed,
+                7,
+            ),
+            (anyhow::anyhow!("no terminal"), ErrorClass::Other, 1),
+        ];
+        for (error, class, exit_code) in cases {
+            assert_eq!(ErrorClass::of(&error), class, "{:#}", error);
+            assert_eq!(class.exit_code(), exit_code, "{:#}", error);
+        }
+    }
+}
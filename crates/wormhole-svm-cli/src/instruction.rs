@@ -0,0 +1,101 @@
+//! Decoding of shim instruction data for `svm-vaa decode-ix`: the Verify VAA
+//! Shim's `post_signatures`, `verify_hash` and `close_signatures`, and the
+//! Post Message Shim's `post_message`.
+//!
+//! The shims are Anchor-compatible: instruction data starts with the 8-byte
+//! selector `sha256("global:<name>")[..8]`, followed by the borsh-encoded
+//! arguments (little-endian).
+
+use solana_sdk::hash::hash;
+use wormhole_svm_submit::PostMessageArgs;
+
+/// Decoded instruction data: the program and instruction, and named fields
+/// in data order.
+pub struct DecodedInstruction {
+    pub program: &'static str,
+    pub instruction: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Size of a guardian signature: guardian index and 65-byte signature.
+const SIGNATURE_LEN: usize = 66;
+
+fn selector(name: &str) -> [u8; 8] {
+    hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]
+        .try_into()
+        .unwrap()
+}
+
+/// Decode `data`, if it is a known shim instruction.
+pub fn decode(data: &[u8]) -> Option<DecodedInstruction> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (tag, args) = data.split_at(8);
+    let verify_vaa = |instruction, fields| DecodedInstruction {
+        program: "Verify VAA Shim",
+        instruction,
+        fields,
+    };
+
+    if tag == selector("post_signatures") {
+        // guardian_set_index: u32, total_signatures: u8, guardian_signatures: Vec<[u8; 66]>
+        if args.len() < 9 {
+            return None;
+        }
+        let guardian_set_index = u32::from_le_bytes(args[..4].try_into().unwrap());
+        let total_signatures = args[4];
+        let count = u32::from_le_bytes(args[5..9].try_into().unwrap()) as usize;
+        let signatures = &args[9..];
+        if signatures.len() != count.checked_mul(SIGNATURE_LEN)? {
+            return None;
+        }
+        let mut fields = vec![
+            ("guardian_set_index", guardian_set_index.to_string()),
+            ("total_signatures", total_signatures.to_string()),
+            ("signatures", count.to_string()),
+        ];
+        fields.extend(signatures.chunks_exact(SIGNATURE_LEN).map(|signature| {
+            (
+                "signature",
+                format!("{}: {}", signature[0], hex::encode(&signature[1..])),
+            )
+        }));
+        return Some(verify_vaa("post_signatures", fields));
+    }
+    if tag == selector("verify_hash") {
+        // guardian_set_bump: u8, digest: [u8; 32]
+        if args.len() != 33 {
+            return None;
+        }
+        return Some(verify_vaa(
+            "verify_hash",
+            vec![
+                ("guardian_set_bump", args[0].to_string()),
+                ("digest", hex::encode(&args[1..])),
+            ],
+        ));
+    }
+    if tag == selector("close_signatures") {
+        return args
+            .is_empty()
+            .then(|| verify_vaa("close_signatures", Vec::new()));
+    }
+
+    let message = PostMessageArgs::parse(data)?;
+    let consistency_level = match message.consistency_level {
+        0 => "0 (confirmed)".to_string(),
+        1 => "1 (finalized)".to_string(),
+        level => level.to_string(),
+    };
+    Some(DecodedInstruction {
+        program: "Post Message Shim",
+        instruction: "post_message",
+        fields: vec![
+            ("nonce", message.nonce.to_string()),
+            ("consistency_level", consistency_level),
+            ("payload_len", message.payload.len().to_string()),
+            ("payload", hex::encode(&message.payload)),
+        ],
+    })
+}
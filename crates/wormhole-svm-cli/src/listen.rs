@@ -0,0 +1,143 @@
+//! `svm-vaa listen`: print the messages posted through the Post Message Shim
+//! as they land.
+//!
+//! The shim's `MessageEvent`s are self-CPIs, which don't show up in program
+//! logs: the log subscription only says which transactions mention the shim,
+//! and each of them is then fetched to read the events from its inner
+//! instructions.
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use solana_client::pubsub_client::PubsubClient;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use wormhole_svm_submit::{MessageEvent, WormholeNetwork};
+
+use crate::tx::executed_instructions;
+
+/// Attempts at fetching a notified transaction, which the RPC node may not
+/// serve yet.
+const FETCH_ATTEMPTS: u32 = 5;
+
+/// The websocket URL of an RPC endpoint: `ws(s)://` on the next port, as
+/// Solana validators serve it.
+pub fn websocket_url(rpc_url: &str) -> Result<String> {
+    let mut url =
+        reqwest::Url::parse(rpc_url).with_context(|| format!("invalid RPC URL: {}", rpc_url))?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme)
+        .map_err(|()| anyhow::anyhow!("cannot derive a websocket URL from {}", rpc_url))?;
+    if let Some(port) = url.port() {
+        url.set_port(Some(port + 1))
+            .map_err(|()| anyhow::anyhow!("cannot derive a websocket URL from {}", rpc_url))?;
+    }
+    Ok(url.to_string())
+}
+
+/// Print every message posted on `network` (by `emitter`, if given) until
+/// the subscription ends.
+pub fn listen(
+    rpc_client: &RpcClient,
+    ws_url: &str,
+    network: &WormholeNetwork,
+    emitter: Option<Pubkey>,
+    json: bool,
+) -> Result<()> {
+    let filter = RpcTransactionLogsFilter::Mentions(vec![network.post_message_shim.to_string()]);
+    let config = RpcTransactionLogsConfig {
+        commitment: Some(CommitmentConfig::confirmed()),
+    };
+    let (_subscription, notifications) = PubsubClient::logs_subscribe(ws_url, filter, config)
+        .with_context(|| format!("subscribing to logs at {}", ws_url))?;
+    eprintln!(
+        "Listening for messages posted through {}...",
+        network.post_message_shim
+    );
+
+    for notification in notifications.iter() {
+        let logs = notification.value;
+        if logs.err.is_some() {
+            continue;
+        }
+        let signature: Signature = match logs.signature.parse() {
+            Ok(signature) => signature,
+            Err(_) => continue,
+        };
+        let events = match fetch_events(rpc_client, network, &signature) {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                continue;
+            }
+        };
+        for event in events {
+            if emitter.is_some_and(|emitter| emitter != event.emitter) {
+                continue;
+            }
+            print_event(network, &event, &signature, json);
+        }
+    }
+    anyhow::bail!("the log subscription ended")
+}
+
+/// The `MessageEvent`s `network`'s shim emitted in the transaction
+/// `signature`, in order.
+fn fetch_events(
+    rpc_client: &RpcClient,
+    network: &WormholeNetwork,
+    signature: &Signature,
+) -> Result<Vec<MessageEvent>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let mut attempt = 1;
+    let tx = loop {
+        match rpc_client.get_transaction_with_config(signature, config) {
+            Ok(tx) => break tx,
+            Err(_) if attempt < FETCH_ATTEMPTS => {
+                attempt += 1;
+                thread::sleep(Duration::from_millis(500));
+            }
+            Err(e) => return Err(e).with_context(|| format!("fetching transaction {}", signature)),
+        }
+    };
+
+    Ok(executed_instructions(tx.transaction)?
+        .iter()
+        .filter_map(|(program_id, data)| MessageEvent::parse(network, program_id, data))
+        .collect())
+}
+
+fn print_event(network: &WormholeNetwork, event: &MessageEvent, signature: &Signature, json: bool) {
+    let vaa_id = format!(
+        "{}/{}/{}",
+        network.chain_id,
+        hex::encode(event.emitter.to_bytes()),
+        event.sequence
+    );
+    if json {
+        let output = serde_json::json!({
+            "id": vaa_id,
+            "emitter": event.emitter.to_string(),
+            "sequence": event.sequence,
+            "timestamp": event.submission_time,
+            "signature": signature.to_string(),
+        });
+        println!("{}", output);
+    } else {
+        println!(
+            "{}\t{}\t{}\t{}",
+            vaa_id, event.emitter, event.submission_time, signature
+        );
+    }
+}
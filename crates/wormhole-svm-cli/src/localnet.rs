@@ -0,0 +1,110 @@
+//! `svm-vaa localnet`: `solana-test-validator` set up like the LiteSVM
+//! environment of `wormhole-svm-test`.
+//!
+//! The bundled Core Bridge, Verify VAA Shim and Post Message Shim are loaded
+//! at their mainnet addresses, next to a guardian set of test guardians, the
+//! bridge config and the fee collector. The program binaries and account
+//! files are written to `<LEDGER>/svm-vaa/` and passed to the validator.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use wormhole_svm_definitions::find_guardian_set_address;
+use wormhole_svm_definitions::solana::mainnet::{
+    CORE_BRIDGE_CONFIG, CORE_BRIDGE_FEE_COLLECTOR, CORE_BRIDGE_PROGRAM_ID,
+    POST_MESSAGE_SHIM_PROGRAM_ID, VERIFY_VAA_SHIM_PROGRAM_ID,
+};
+use wormhole_svm_test::{
+    build_bridge_config_data, build_guardian_set_data, TestGuardianSet, CORE_BRIDGE_BYTES,
+    POST_MESSAGE_SHIM_BYTES, VERIFY_VAA_SHIM_BYTES,
+};
+
+/// Write the programs and accounts to `dir` and return the
+/// `solana-test-validator` arguments loading them.
+pub fn write_fixtures(
+    dir: &Path,
+    guardians: &TestGuardianSet,
+    guardian_set_index: u32,
+) -> Result<Vec<String>> {
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let mut args = Vec::new();
+
+    let programs = [
+        ("core_bridge.so", CORE_BRIDGE_PROGRAM_ID, CORE_BRIDGE_BYTES),
+        (
+            "verify_vaa_shim.so",
+            VERIFY_VAA_SHIM_PROGRAM_ID,
+            VERIFY_VAA_SHIM_BYTES,
+        ),
+        (
+            "post_message_shim.so",
+            POST_MESSAGE_SHIM_PROGRAM_ID,
+            POST_MESSAGE_SHIM_BYTES,
+        ),
+    ];
+    for (name, program_id, bytes) in programs {
+        let path = dir.join(name);
+        fs::write(&path, bytes).with_context(|| format!("writing {}", path.display()))?;
+        args.extend([
+            "--bpf-program".to_string(),
+            program_id.to_string(),
+            path.display().to_string(),
+        ]);
+    }
+
+    let (guardian_set, _) =
+        find_guardian_set_address(guardian_set_index.to_be_bytes(), &CORE_BRIDGE_PROGRAM_ID);
+    let accounts = [
+        (
+            "guardian_set.json",
+            guardian_set,
+            CORE_BRIDGE_PROGRAM_ID,
+            build_guardian_set_data(guardians, guardian_set_index),
+        ),
+        (
+            "bridge_config.json",
+            CORE_BRIDGE_CONFIG,
+            CORE_BRIDGE_PROGRAM_ID,
+            build_bridge_config_data(guardian_set_index),
+        ),
+        (
+            "fee_collector.json",
+            CORE_BRIDGE_FEE_COLLECTOR,
+            solana_sdk::system_program::ID,
+            Vec::new(),
+        ),
+    ];
+    for (name, address, owner, data) in accounts {
+        let path = dir.join(name);
+        let json = account_json(&address, &owner, &data);
+        fs::write(&path, serde_json::to_string_pretty(&json)? + "\n")
+            .with_context(|| format!("writing {}", path.display()))?;
+        args.extend([
+            "--account".to_string(),
+            address.to_string(),
+            path.display().to_string(),
+        ]);
+    }
+    Ok(args)
+}
+
+/// A rent-exempt account in the `solana account --output json` format read
+/// by `solana-test-validator --account`.
+fn account_json(address: &Pubkey, owner: &Pubkey, data: &[u8]) -> serde_json::Value {
+    json!({
+        "pubkey": address.to_string(),
+        "account": {
+            "lamports": Rent::default().minimum_balance(data.len()),
+            "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+            "owner": owner.to_string(),
+            "executable": false,
+            "rentEpoch": 0,
+            "space": data.len(),
+        },
+    })
+}
@@ -0,0 +1,51 @@
+//! Log records (the library's progress, and `serve`/`watch` activity) on
+//! stderr, as plain text or as JSON lines.
+//!
+//! `-q`/`-v`/`-vv` select the level of this crate and the library; other
+//! crates only log warnings, or debug records with `-vv`. `RUST_LOG`, in
+//! `env_logger` syntax, adds to or overrides these filters.
+
+use std::io::Write;
+
+use log::{Level, LevelFilter};
+use serde_json::json;
+
+use crate::OutputFormat;
+
+/// Install the logger. `verbosity` is the number of `-v` flags, or -1 with
+/// `-q`.
+pub fn init(verbosity: i8, format: OutputFormat) {
+    let (level, others) = match verbosity {
+        ..=-1 => (LevelFilter::Warn, LevelFilter::Warn),
+        0 => (LevelFilter::Info, LevelFilter::Warn),
+        1 => (LevelFilter::Debug, LevelFilter::Warn),
+        _ => (LevelFilter::Trace, LevelFilter::Debug),
+    };
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(others)
+        .filter_module("wormhole_svm_submit", level)
+        .filter_module(env!("CARGO_CRATE_NAME"), level);
+    if let Ok(filters) = std::env::var("RUST_LOG") {
+        builder.parse_filters(&filters);
+    }
+    builder.format(move |buf, record| match format {
+        // Info records read like the rest of the CLI's output.
+        OutputFormat::Text => match record.level() {
+            Level::Error => writeln!(buf, "error: {}", record.args()),
+            Level::Warn => writeln!(buf, "Warning: {}", record.args()),
+            Level::Info => writeln!(buf, "{}", record.args()),
+            level => writeln!(buf, "[{} {}] {}", level, record.target(), record.args()),
+        },
+        OutputFormat::Json => {
+            let line = json!({
+                "timestamp": buf.timestamp_millis().to_string(),
+                "level": record.level().as_str().to_lowercase(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{}", line)
+        }
+    });
+    builder.init();
+}
@@ -1,219 +1,3470 @@
+mod account;
+mod batch;
+mod config;
+mod confirm;
+mod exit;
+mod instruction;
+mod listen;
+#[cfg(feature = "bundled-fixtures")]
+mod localnet;
+mod logging;
+mod metrics;
+mod offline;
+mod payload;
+mod plan;
+#[cfg(feature = "bundled-fixtures")]
+mod program_info;
+mod serve;
+mod signer;
+mod tx;
+mod watch;
+mod wormholescan;
+
+use std::collections::{HashMap, VecDeque};
 use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::read_keypair_file;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::null_signer::NullSigner;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use wormhole_svm_definitions::solana::mainnet::{
+    CORE_BRIDGE_PROGRAM_ID, POST_MESSAGE_SHIM_PROGRAM_ID, VERIFY_VAA_SHIM_PROGRAM_ID,
+};
+use wormhole_svm_definitions::{
+    find_emitter_sequence_address, find_event_authority_address, find_fee_collector_address,
+    find_guardian_set_address, find_shim_message_address,
+};
+use wormhole_svm_submit::cost::{
+    bridge_fee, estimate_bridge_fees, estimate_broadcast_cost, estimate_group_cost,
+};
+use wormhole_svm_submit::execute::{ComputeUnitLimit, ExecuteConfig, MAX_COMPUTE_UNIT_LIMIT};
+use wormhole_svm_submit::failover::is_transport_error;
+use wormhole_svm_submit::lookup_table::exceeds_legacy_transaction_size;
+use wormhole_svm_submit::message::{self, Finality, PostedMessage};
+use wormhole_svm_submit::pipeline::{broadcast_stream, PipelineConfig};
+use wormhole_svm_submit::vaa::{vaa_digest, vaa_message_hash};
+use wormhole_svm_submit::{
+    account_report, build_close_signatures_ix, placeholder_name, rpc_client_with_config,
+    BroadcastConfig, BroadcastPlan, FailoverConnection, ForkedConnection, GuardianSet,
+    InstructionGroup, RpcClientWithCommitment, RpcConnectionConfig, SignaturesAccount, SignedVaa,
+    SolanaConnection, SubmitError, VaaBody, WormholeNetwork,
+};
+use wormhole_svm_test::{TestGuardian, TestGuardianSet, TestVaa};
+
+use crate::config::Profile;
+use crate::signer::{keypair_from_path, signer_from_path};
 
 #[derive(Parser)]
 #[command(name = "svm-vaa")]
 #[command(about = "Submit signed VAAs to Solana programs")]
 struct Cli {
-    /// Solana RPC URL (or shorthand: d=devnet, m=mainnet, l=localhost, t=testnet)
-    #[arg(short = 'u', long, env = "SOLANA_RPC_URL")]
+    /// Solana RPC URL (or shorthand: d=devnet, m=mainnet, l=localhost, t=testnet). Repeat or
+    /// comma-separate to fail over between endpoints when submitting
+    #[arg(
+        short = 'u',
+        long = "rpc-url",
+        env = "SOLANA_RPC_URL",
+        value_delimiter = ','
+    )]
+    rpc_url_args: Vec<String>,
+
+    /// Profile of the config file to use (default: the file's default_profile)
+    #[arg(long, env = "SVM_VAA_PROFILE")]
+    profile: Option<String>,
+
+    /// Config file (default: ~/.config/svm-vaa/config.toml)
+    #[arg(long, env = "SVM_VAA_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// The resolved RPC URLs (from --rpc-url or the profile).
+    #[arg(skip)]
+    rpc_urls: Vec<String>,
+
+    /// The first of `rpc_urls`, used for everything but submission.
+    #[arg(skip)]
     rpc_url: String,
 
-    /// Wormhole Core Bridge program ID (auto-detected from --rpc-url if omitted)
-    #[arg(long, env = "CORE_BRIDGE_PROGRAM_ID")]
-    core_bridge: Option<String>,
+    /// Settings of the selected profile, used where flags are omitted.
+    #[arg(skip)]
+    profile_settings: Profile,
+
+    /// Commitment of the submitting connections, from `submit --commitment`.
+    #[arg(skip)]
+    commitment: Option<CommitmentConfig>,
+
+    /// Wormhole Core Bridge program ID (auto-detected from --rpc-url if omitted)
+    #[arg(long, env = "CORE_BRIDGE_PROGRAM_ID")]
+    core_bridge: Option<String>,
+
+    /// Verify VAA Shim program ID, for forks and local deployments (default: the mainnet ID)
+    #[arg(long, env = "VERIFY_VAA_SHIM_PROGRAM_ID")]
+    verify_vaa_shim: Option<String>,
+
+    /// Post Message Shim program ID, for forks and local deployments (default: the mainnet ID)
+    #[arg(long, env = "POST_MESSAGE_SHIM_PROGRAM_ID")]
+    post_message_shim: Option<String>,
+
+    /// Extra HTTP header for RPC requests, e.g. "Authorization: Bearer <token>" (repeatable)
+    #[arg(long = "rpc-header", value_name = "NAME: VALUE")]
+    rpc_headers: Vec<String>,
+
+    /// Format of errors on stderr: text, or a JSON object with the failure class and exit code
+    #[arg(long, value_enum, default_value = "text")]
+    output: OutputFormat,
+
+    /// Show more progress: -v for debug records, -vv for trace records and dependencies' debug
+    /// records (RUST_LOG overrides)
+    #[arg(short = 'v', long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only show warnings and the command's own output
+    #[arg(short = 'q', long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Format of log records on stderr: text, or one JSON object per line
+    #[arg(long, value_enum, default_value = "text")]
+    log_format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Submit a signed VAA to a Solana program
+    Submit {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer keypair (file, env:VAR, stdin or prompt://) or wallet URI (e.g. usb://ledger)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        #[command(flatten)]
+        compute_budget: ComputeBudgetArgs,
+
+        /// Submit every VAA of a JSON lines file (a hex or base64 string, or an object with a
+        /// "vaa" field, per line) or of a directory (one VAA per file), continuing past failures
+        #[arg(long, conflicts_with = "vaa")]
+        batch: Option<PathBuf>,
+
+        /// VAAs of a batch submitted at once (VAAs of the same emitter go one at a time, in order)
+        #[arg(long, default_value_t = 4, requires = "batch")]
+        concurrency: usize,
+
+        /// Resolve and run everything on a local fork, print the plan and expected cost, and exit
+        /// without sending anything
+        #[arg(long, conflicts_with = "batch")]
+        dry_run: bool,
+
+        /// Leave the guardian signatures account open after execution (its rent stays locked
+        /// until it is closed, e.g. with sweep-signatures)
+        #[arg(long)]
+        skip_close: bool,
+
+        /// Commitment of the simulations and of each transaction's confirmation (default:
+        /// confirmed)
+        #[arg(long, value_enum)]
+        commitment: Option<CommitmentArg>,
+
+        /// Send without showing the resolved plan and asking for confirmation (batches never
+        /// ask)
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Run the resolver for a VAA and print the instruction groups as JSON
+    ///
+    /// Nothing is executed. Accounts matching a placeholder (or the payer and
+    /// guardian set it resolved to) are annotated with its name.
+    Resolve {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer for the simulations: pubkey, keypair file or hardware wallet URI (must exist on
+        /// chain)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Execute a plan saved from `resolve`
+    ///
+    /// Runs the plan's instruction groups as they were reviewed, without
+    /// resolving again. The payer must be the one the plan was resolved for.
+    /// Placeholders other than the payer, guardian set and generated keypairs
+    /// are given with --set: the guardian signatures account (see
+    /// `post-signatures`) as RESOLVER_PUBKEY_SHIM_VAA_SIGS, custom
+    /// placeholders by pubkey. Prints each transaction signature.
+    ///
+    /// Example:
+    ///   svm-vaa -u m resolve --program-id <ID> --payer <PUBKEY> @vaa.hex > plan.json
+    ///   svm-vaa -u m post-signatures --payer <KEYPAIR> @vaa.hex
+    ///   svm-vaa -u m execute-plan --payer <KEYPAIR> \
+    ///     --set RESOLVER_PUBKEY_SHIM_VAA_SIGS=<ACCOUNT> plan.json
+    ExecutePlan {
+        /// Payer keypair (file, env:VAR, stdin or prompt://) or wallet URI (e.g. usb://ledger)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Placeholder substitution (repeatable): placeholder name or pubkey, and the account
+        #[arg(long = "set", value_name = "NAME=PUBKEY")]
+        substitutions: Vec<String>,
+
+        #[command(flatten)]
+        compute_budget: ComputeBudgetArgs,
+
+        /// Plan JSON written by `resolve` (file, or stdin if omitted)
+        plan: Option<PathBuf>,
+    },
+
+    /// Estimate what submitting a VAA would cost
+    ///
+    /// Resolves the VAA's instruction groups (nothing is executed) and prints
+    /// the guardian signatures account rent, the fees of each group
+    /// transaction, the core bridge message fees and the total. Priority fees
+    /// use --compute-unit-price, or the recent fees paid for the program's
+    /// accounts if no price is configured.
+    Estimate {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer for the simulations: pubkey, keypair file or hardware wallet URI (must exist on
+        /// chain)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        #[command(flatten)]
+        compute_budget: ComputeBudgetArgs,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Simulate a submission without sending anything
+    ///
+    /// Runs the whole flow (post signatures, resolve, substitute placeholders,
+    /// execute each group) on a local fork of the cluster and prints the logs
+    /// and compute units of every group transaction.
+    Simulate {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer keypair (file, env:VAR, stdin or prompt://) or wallet URI (e.g. usb://ledger)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Report whether a VAA appears to have been redeemed on a program
+    ///
+    /// With --replay-seed, derives the program's replay protection PDA from
+    /// the VAA and checks whether it exists. Otherwise probes by simulating a
+    /// submission on a local fork (see `simulate`): a failing submission
+    /// usually means the VAA was already redeemed. Prints `redeemed`,
+    /// `not-redeemed`, `likely-redeemed` or `unknown` to stdout.
+    ///
+    /// Seeds are strings, hex with a 0x prefix, or VAA fields: {digest},
+    /// {hash} (keccak256 of the body), {emitter_chain} (u16 BE), {emitter},
+    /// {sequence} (u64 BE), {sequence_le} (u64 LE).
+    ///
+    /// Example (token bridge claim account):
+    ///   svm-vaa -u m status --program-id <ID> --replay-seed {emitter} \
+    ///     --replay-seed {emitter_chain} --replay-seed {sequence} @vaa.hex
+    Status {
+        /// Program ID the VAA is redeemed on
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer keypair file or hardware wallet URI, for the simulation probe
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Replay protection PDA seed (repeatable, in order)
+        #[arg(long = "replay-seed", value_name = "SEED")]
+        replay_seeds: Vec<String>,
+
+        /// Program owning the replay PDA (default: --program-id)
+        #[arg(long, requires = "replay_seeds")]
+        replay_program: Option<String>,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Resolve a VAA and write the unsigned transactions for offline signing
+    ///
+    /// Writes one JSON file per transaction (post signatures, each
+    /// instruction group, close signatures) with the serialized message and
+    /// the signers it still needs. Sign them with sign-tx and submit them with
+    /// import-and-send before their blockhash expires (about a minute), or
+    /// give a durable nonce account per transaction to take as long as needed.
+    ///
+    /// Examples:
+    ///   svm-vaa -u m export-tx --program-id <ID> --payer <PUBKEY> --out-dir tx @vaa.hex
+    ///   svm-vaa -u m export-tx --program-id <ID> --payer <PUBKEY> --out-dir tx \
+    ///     --nonce-account <NONCE_1>,<NONCE_2>,<NONCE_3> @vaa.hex
+    ExportTx {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer: pubkey, keypair or hardware wallet URI (must exist on chain)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Directory to write the transaction files to
+        #[arg(long)]
+        out_dir: PathBuf,
+
+        /// Durable nonce accounts to use instead of a recent blockhash, one per transaction
+        /// (post signatures, each instruction group, close signatures), in order; each
+        /// authority must sign with sign-tx
+        #[arg(long, value_delimiter = ',')]
+        nonce_account: Vec<String>,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Sign exported transactions (works offline)
+    ///
+    /// Adds the signature of --keypair to every transaction file of the
+    /// directory it must sign. Needs no RPC URL.
+    SignTx {
+        /// Keypair or hardware wallet URI to sign with
+        #[arg(long)]
+        keypair: String,
+
+        /// Directory written by export-tx
+        dir: PathBuf,
+    },
+
+    /// Submit exported and signed transactions in order
+    ///
+    /// Transactions that already landed are skipped, so an interrupted run
+    /// can be repeated. Prints each transaction signature.
+    ImportAndSend {
+        /// Directory written by export-tx and signed with sign-tx
+        dir: PathBuf,
+    },
+
+    /// Post the guardian signatures of a VAA and print the signatures account
+    ///
+    /// For flows where execution happens separately (e.g. through a multisig).
+    /// The payer can later reclaim the rent with `close-signatures`.
+    PostSignatures {
+        /// Payer keypair file or hardware wallet URI (also the refund recipient of the account)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Close a guardian signatures account and reclaim its rent
+    ///
+    /// The Verify VAA Shim refunds the rent to the refund recipient recorded
+    /// when the signatures were posted, which must sign. With --recipient the
+    /// refund is forwarded in the same transaction.
+    CloseSignatures {
+        /// Guardian signatures account
+        account: String,
+
+        /// Keypair file or hardware wallet URI of the account's refund recipient (the payer that
+        /// posted it)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Forward the reclaimed rent to this address
+        #[arg(long)]
+        recipient: Option<String>,
+    },
+
+    /// Close every guardian signatures account refundable to the payer
+    ///
+    /// Finds the signatures accounts whose refund recipient is the payer
+    /// (left open by --skip-close, post-signatures or interrupted runs) and
+    /// closes them in bulk. Don't run it while broadcasts paid by the same
+    /// payer are in flight: their accounts would be closed too.
+    SweepSignatures {
+        /// Keypair file or hardware wallet URI of the refund recipient
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Only list the accounts
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check a VAA's signatures against the on-chain guardian set
+    ///
+    /// Recovers each signer off-chain and reports which guardians signed and
+    /// whether quorum is met. Nothing is sent.
+    Verify {
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Post a Wormhole message through the Post Message Shim
+    ///
+    /// Pays the core bridge message fee in the same transaction and prints the
+    /// VAA ID (<CHAIN>/<EMITTER>/<SEQUENCE>), ready for `fetch`.
+    Emit {
+        /// Payer keypair (file, env:VAR, stdin or prompt://) or wallet URI (e.g. usb://ledger)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Emitter keypair file or hardware wallet URI (default: the payer)
+        #[arg(long, conflicts_with = "emitter_program")]
+        emitter: Option<String>,
+
+        /// Emit through this program's emitter PDA instead of a keypair. The
+        /// program must implement the message-emitter-example interface.
+        #[arg(long)]
+        emitter_program: Option<String>,
+
+        /// Seed of the emitter PDA of --emitter-program
+        #[arg(long, default_value = "emitter")]
+        emitter_seed: String,
+
+        /// Message nonce
+        #[arg(long, default_value_t = 0)]
+        nonce: u32,
+
+        /// Consistency level of the message
+        #[arg(long, value_enum, default_value = "finalized")]
+        finality: FinalityArg,
+
+        /// Payload (hex string, @file, or stdin)
+        payload: Option<String>,
+    },
+
+    /// Build a VAA and sign it with test guardian keys
+    ///
+    /// For localnets and forks whose guardian set you control: signs with the
+    /// well-known devnet guardian key unless --guardian-key or --guardians is
+    /// given, prints the signed VAA as hex and the guardian addresses to
+    /// stderr. Never use these keys for anything else.
+    ///
+    /// Examples:
+    ///   svm-vaa sign-test-vaa 2/<EMITTER>/1 deadbeef
+    ///   svm-vaa sign-test-vaa --guardians 19 --seed 7 2/<EMITTER>/1 @payload.hex
+    SignTestVaa {
+        /// Guardian secret key as hex; repeat for a set, in guardian index order
+        #[arg(long = "guardian-key", conflicts_with = "guardians")]
+        guardian_keys: Vec<String>,
+
+        /// Generate this many guardian keys deterministically from --seed
+        #[arg(long)]
+        guardians: Option<usize>,
+
+        /// Seed of the generated guardian keys
+        #[arg(long, default_value_t = 0, requires = "guardians")]
+        seed: u64,
+
+        /// Guardian set index of the VAA
+        #[arg(long, default_value_t = 0)]
+        guardian_set_index: u32,
+
+        /// VAA timestamp (default: now)
+        #[arg(long)]
+        timestamp: Option<u32>,
+
+        /// VAA nonce
+        #[arg(long, default_value_t = 0)]
+        nonce: u32,
+
+        /// VAA consistency level
+        #[arg(long, default_value_t = 1)]
+        consistency_level: u8,
+
+        /// Emitter and sequence as <CHAIN>/<EMITTER>/<SEQUENCE> (emitter as hex or
+        /// base58)
+        id: String,
+
+        /// Payload (hex string, @file, or stdin)
+        payload: Option<String>,
+    },
+
+    /// Print the digest of a VAA, as checked by `verify_hash`
+    ///
+    /// The digest is the double keccak256 of the VAA body: the hash the
+    /// guardians sign and a receiving program passes to the Verify VAA Shim.
+    Digest {
+        /// The input is a bare VAA body rather than a signed VAA
+        #[arg(long)]
+        body: bool,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Signed VAA, or body with --body (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Decode a VAA payload of a known format
+    ///
+    /// Recognizes core bridge and token bridge governance, token bridge
+    /// transfers and attestations, and NTT transfers.
+    DecodePayload {
+        /// The input is a bare payload rather than a signed VAA
+        #[arg(long)]
+        payload: bool,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Signed VAA, or payload with --payload (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Decode shim instruction data
+    ///
+    /// Recognizes the Verify VAA Shim's post_signatures, verify_hash and
+    /// close_signatures, and the Post Message Shim's post_message, e.g. from
+    /// the instruction data an explorer shows for a failed transaction.
+    DecodeIx {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Instruction data (hex string, @file, or stdin)
+        data: Option<String>,
+    },
+
+    /// Fetch and decode a guardian set
+    GuardianSet {
+        /// Guardian set index (default: the current set)
+        index: Option<u32>,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the next message sequence of an emitter
+    ///
+    /// Reads the emitter's core bridge sequence account, which holds the
+    /// sequence the emitter's next message will get.
+    ///
+    /// Examples:
+    ///   svm-vaa sequence <EMITTER>
+    ///   svm-vaa sequence <PROGRAM_ID>:emitter
+    Sequence {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Emitter address, or PDA derivation: <PROGRAM_ID>:seed1:seed2:...
+        emitter: String,
+    },
+
+    /// Decode a signed VAA and print its fields
+    Parse {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Signed VAA (hex string, @file, or stdin)
+        vaa: Option<String>,
+    },
+
+    /// Fetch a signed VAA from Wormholescan and print it as hex
+    ///
+    /// Examples:
+    ///   svm-vaa -u m fetch 1/<EMITTER>/42
+    ///   svm-vaa -u m fetch --tx <TX_HASH> | svm-vaa -u m submit --program-id <ID> --payer key.json
+    Fetch {
+        /// VAA ID as <CHAIN>/<EMITTER>/<SEQUENCE> (emitter as hex or base58)
+        #[arg(required_unless_present = "tx", conflicts_with = "tx")]
+        id: Option<String>,
+
+        /// Hash of the transaction that emitted the message, instead of the VAA ID
+        #[arg(long)]
+        tx: Option<String>,
+
+        /// Wormholescan API URL or shorthand: m=mainnet, t=testnet
+        /// (default: testnet for devnet/testnet RPC URLs, mainnet otherwise)
+        #[arg(long, env = "WORMHOLESCAN_API_URL")]
+        api_url: Option<String>,
+    },
+
+    /// Print the Wormhole messages posted by a transaction
+    ///
+    /// Decodes the Post Message Shim instructions and events of a confirmed
+    /// transaction: emitter, sequence, nonce and payload of each message.
+    ///
+    /// Example:
+    ///   svm-vaa -u d tx <SIGNATURE>
+    Tx {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Transaction signature
+        signature: String,
+    },
+
+    /// Print messages posted through the Post Message Shim as they land
+    ///
+    /// Subscribes to the shim's logs over WebSocket and prints the emitter,
+    /// sequence and timestamp of each MessageEvent, as
+    /// <VAA_ID>\t<EMITTER>\t<TIMESTAMP>\t<SIGNATURE>.
+    ///
+    /// Example:
+    ///   svm-vaa -u d listen --emitter <EMITTER>
+    Listen {
+        /// Only print messages of this emitter (address, or PDA derivation:
+        /// <PROGRAM_ID>:seed1:seed2:...)
+        #[arg(long)]
+        emitter: Option<String>,
+
+        /// WebSocket endpoint (default: derived from --rpc-url)
+        #[arg(long)]
+        ws_url: Option<String>,
+
+        /// Print one JSON object per message
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Watch an emitter for new VAAs and submit each to a program
+    ///
+    /// A minimal standalone relayer: polls Wormholescan (or follows a spy with
+    /// --spy) and submits the emitter's VAAs in sequence order. The next
+    /// sequence and interrupted broadcasts are kept in --state-dir, so a
+    /// restart neither resubmits nor skips VAAs. Submitted transaction
+    /// signatures are printed to stdout as <SEQUENCE>\t<SIGNATURE>.
+    ///
+    /// Example:
+    ///   svm-vaa -u d watch --program-id <ID> --payer key.json 10002/<EMITTER>
+    Watch {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer keypair (file, env:VAR, stdin or prompt://) or wallet URI (e.g. usb://ledger)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Emitter as <CHAIN>/<EMITTER> (emitter as hex or base58)
+        emitter: String,
+
+        /// Wormholescan API URL or shorthand: m=mainnet, t=testnet
+        /// (default: testnet for devnet/testnet RPC URLs, mainnet otherwise)
+        #[arg(long, env = "WORMHOLESCAN_API_URL", conflicts_with = "spy")]
+        api_url: Option<String>,
+
+        /// Follow a spy gRPC endpoint (e.g. http://localhost:7073) instead of polling
+        /// Wormholescan
+        #[arg(long)]
+        spy: Option<String>,
+
+        /// Seconds between Wormholescan polls (or spy reconnection attempts)
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+
+        /// Directory holding the watch state
+        #[arg(long, default_value = ".svm-vaa-watch")]
+        state_dir: PathBuf,
+
+        /// Start at this sequence instead of the stored one (default without
+        /// state: only VAAs emitted from now on)
+        #[arg(long)]
+        from_sequence: Option<u64>,
+
+        /// Serve Prometheus metrics on this address (e.g. 127.0.0.1:9090) at /metrics
+        #[arg(long, value_name = "ADDRESS")]
+        metrics_addr: Option<String>,
+    },
+
+    /// Serve an HTTP API that submits the VAAs it receives
+    ///
+    /// POST /submit with a signed VAA (hex) as the body queues it and returns
+    /// its id; GET /status/<CHAIN>/<EMITTER>/<SEQUENCE> returns whether it is
+    /// pending, done (with the transaction signatures) or failed. Statuses are
+    /// kept in memory only.
+    ///
+    /// Example:
+    ///   svm-vaa -u d serve --program-id <ID> --payer key.json --listen 127.0.0.1:8080
+    Serve {
+        /// Program ID implementing resolve_execute_vaa_v1
+        #[arg(long, env = "PROGRAM_ID")]
+        program_id: String,
+
+        /// Payer keypair (file, env:VAR, stdin or prompt://); hardware wallets can't sign
+        /// concurrent submissions
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        #[command(flatten)]
+        compute_budget: ComputeBudgetArgs,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+
+        /// Require `Authorization: Bearer <TOKEN>` on every request
+        #[arg(long, env = "SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// VAAs submitted at once (VAAs of the same emitter go one at a time, in order)
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Leave the guardian signatures accounts open after execution
+        #[arg(long)]
+        skip_close: bool,
+    },
+
+    /// Run solana-test-validator with Wormhole and a test guardian set
+    ///
+    /// Loads the bundled Core Bridge, Verify VAA Shim and Post Message Shim at
+    /// their mainnet addresses, a guardian set of test guardians (the same
+    /// options as sign-test-vaa), the bridge config and the fee collector, and
+    /// funds the payer as the validator's mint. Runs until interrupted; needs
+    /// no RPC URL. Arguments after `--` go to the validator.
+    ///
+    /// Examples:
+    ///   svm-vaa localnet --payer key.json --reset
+    ///   svm-vaa localnet --guardians 19 --seed 7 -- --slots-per-epoch 32
+    #[cfg(feature = "bundled-fixtures")]
+    Localnet {
+        /// Payer to fund: keypair, pubkey or hardware wallet URI (default: a keypair generated
+        /// in --state-dir)
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+
+        /// Guardian secret key as hex; repeat for a set, in guardian index order
+        #[arg(long = "guardian-key", conflicts_with = "guardians")]
+        guardian_keys: Vec<String>,
+
+        /// Generate this many guardian keys deterministically from --seed
+        #[arg(long)]
+        guardians: Option<usize>,
+
+        /// Seed of the generated guardian keys
+        #[arg(long, default_value_t = 0, requires = "guardians")]
+        seed: u64,
+
+        /// Index of the guardian set account
+        #[arg(long, default_value_t = 0)]
+        guardian_set_index: u32,
+
+        /// Validator ledger directory
+        #[arg(long, default_value = "test-ledger")]
+        ledger: PathBuf,
+
+        /// Start from a new ledger (accounts and programs only apply to a new ledger)
+        #[arg(long)]
+        reset: bool,
+
+        /// Directory for the program binaries, account files and generated payer
+        #[arg(long, default_value = ".svm-vaa-localnet")]
+        state_dir: PathBuf,
+
+        /// solana-test-validator binary
+        #[arg(long, default_value = "solana-test-validator")]
+        validator: String,
+
+        /// Extra solana-test-validator arguments
+        #[arg(last = true)]
+        validator_args: Vec<String>,
+    },
+
+    /// Request an airdrop on devnet, testnet or a local validator
+    ///
+    /// The recipient defaults to the payer (--payer, PAYER_KEYPAIR or the
+    /// profile's payer). Prints the airdrop transaction signature once it is
+    /// confirmed.
+    ///
+    /// Examples:
+    ///   svm-vaa -u d airdrop 1 --payer key.json
+    ///   svm-vaa -u l airdrop 10 <ADDRESS>
+    Airdrop {
+        /// Amount in SOL
+        amount: f64,
+
+        /// Recipient address, or PDA derivation: <PROGRAM_ID>:seed1:seed2:... (default: the
+        /// payer)
+        recipient: Option<String>,
+
+        /// Payer keypair, pubkey or hardware wallet URI, used as the default recipient
+        #[arg(long, env = "PAYER_KEYPAIR")]
+        payer: Option<String>,
+    },
+
+    /// Fetch an account and decode it
+    ///
+    /// Core bridge config, guardian set, emitter sequence, posted message and
+    /// guardian signatures accounts are decoded; other accounts are dumped as
+    /// hex.
+    Account {
+        /// Output the decoded account as JSON
+        #[arg(long, conflicts_with = "raw")]
+        json: bool,
+
+        /// Dump the data as hex even for known account types
+        #[arg(long)]
+        raw: bool,
+
+        /// Account address, or PDA derivation: <PROGRAM_ID>:seed1:seed2:...
+        address: String,
+    },
+
+    /// Print the core bridge message fee and guardian set expiration window
+    ///
+    /// Reads the core bridge config of the selected network. Posting a
+    /// message must transfer the fee to the fee collector in the same
+    /// transaction.
+    Fees {
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check which builds of the Wormhole programs the cluster runs
+    ///
+    /// Hashes the deployed Core Bridge, Verify VAA Shim and Post Message Shim
+    /// like `solana-verify get-program-hash` and compares them with the
+    /// bundled mainnet builds, or with --expect. Prints each program's hash,
+    /// upgrade authority and last deployment slot, and fails if any program
+    /// differs or is missing.
+    #[cfg(feature = "bundled-fixtures")]
+    ProgramInfo {
+        /// Expected hash of a program instead of its bundled build (repeatable): core-bridge,
+        /// verify-vaa-shim or post-message-shim
+        #[arg(long = "expect", value_name = "PROGRAM=SHA256")]
+        expectations: Vec<String>,
+
+        /// Print as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Derive a PDA for a program
+    ///
+    /// Seeds can be strings or hex (prefix with 0x). Wormhole accounts have
+    /// named derivations, under the selected network's programs:
+    /// guardian-set <INDEX>, sequence <EMITTER>, shim-message <EMITTER>,
+    /// fee-collector and event-authority [<PROGRAM_ID>] (default: the Post
+    /// Message Shim).
+    ///
+    /// Examples:
+    ///   svm-vaa pda <PROGRAM_ID> foo bar baz
+    ///   svm-vaa pda <PROGRAM_ID> 0xdeadbeef "hello"
+    ///   svm-vaa -u m pda guardian-set 4
+    ///   svm-vaa -u m pda sequence <EMITTER>
+    Pda {
+        /// Program ID to derive PDA for, or the name of a derivation
+        #[arg(value_name = "PROGRAM_ID|NAME")]
+        program_id: String,
+
+        /// Seeds (strings or 0x-prefixed hex), or the arguments of a named derivation
+        seeds: Vec<String>,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// Examples:
+    ///   svm-vaa completions bash > /etc/bash_completion.d/svm-vaa
+    ///   svm-vaa completions zsh > "${fpath[1]}/_svm-vaa"
+    Completions {
+        /// Shell to complete in
+        shell: clap_complete::Shell,
+    },
+
+    /// Print the man page, or write one page per command to a directory
+    ///
+    /// Example:
+    ///   svm-vaa man --out-dir /usr/local/share/man/man1
+    Man {
+        /// Write svm-vaa.1 and a page per command (svm-vaa-submit.1, ...) here instead of
+        /// printing svm-vaa.1
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+}
+
+/// Priority fee and compute limit of the execution transactions.
+#[derive(Args)]
+struct ComputeBudgetArgs {
+    /// Compute unit price in micro-lamports, or "auto" for the 75th percentile of recent fees
+    /// paid for the program's accounts
+    #[arg(
+        long,
+        visible_alias = "priority-fee",
+        value_name = "MICRO_LAMPORTS|auto"
+    )]
+    compute_unit_price: Option<ComputeUnitPriceArg>,
+
+    /// Compute unit limit of each transaction, or "simulate" to use the simulated units plus
+    /// 10% (default: runtime default of 200k per instruction)
+    #[arg(long, value_name = "UNITS|simulate")]
+    compute_unit_limit: Option<ComputeUnitLimitArg>,
+}
+
+#[derive(Clone, Copy)]
+enum ComputeUnitPriceArg {
+    Fixed(u64),
+    Auto,
+}
+
+impl FromStr for ComputeUnitPriceArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            price => price
+                .parse()
+                .map(Self::Fixed)
+                .map_err(|_| format!("expected micro-lamports or \"auto\": {}", price)),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ComputeUnitLimitArg(ComputeUnitLimit);
+
+impl FromStr for ComputeUnitLimitArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simulate" => Ok(Self(ComputeUnitLimit::Simulated { margin_percent: 10 })),
+            units => units
+                .parse()
+                .map(|units| Self(ComputeUnitLimit::Fixed(units)))
+                .map_err(|_| format!("expected compute units or \"simulate\": {}", units)),
+        }
+    }
+}
+
+impl ComputeBudgetArgs {
+    /// Execution config with these settings (falling back to `profile`),
+    /// looking up recent fees for `--compute-unit-price auto`.
+    fn execute_config(
+        &self,
+        profile: &Profile,
+        rpc_client: &RpcClient,
+        program_id: &Pubkey,
+    ) -> Result<ExecuteConfig<'static>> {
+        let price = match self.compute_unit_price {
+            Some(price) => Some(price),
+            None => profile
+                .compute_unit_price
+                .as_deref()
+                .map(ComputeUnitPriceArg::from_str)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("profile compute_unit_price: {}", e))?,
+        };
+        let limit = match self.compute_unit_limit {
+            Some(limit) => Some(limit),
+            None => profile
+                .compute_unit_limit
+                .as_deref()
+                .map(ComputeUnitLimitArg::from_str)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("profile compute_unit_limit: {}", e))?,
+        };
+
+        let compute_unit_price = match price {
+            None => None,
+            Some(ComputeUnitPriceArg::Fixed(price)) => Some(price),
+            Some(ComputeUnitPriceArg::Auto) => {
+                let price = recent_compute_unit_price(rpc_client, program_id)?;
+                eprintln!("  Compute unit price: {} (recent fees)", price);
+                Some(price)
+            }
+        };
+        Ok(ExecuteConfig {
+            compute_unit_price,
+            compute_unit_limit: limit.map(|limit| limit.0).unwrap_or_default(),
+            ..Default::default()
+        })
+    }
+}
+
+/// The 75th percentile of the prioritization fees paid for `program_id`
+/// accounts in recent slots (micro-lamports per compute unit).
+fn recent_compute_unit_price(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<u64> {
+    let mut fees: Vec<u64> = rpc_client
+        .get_recent_prioritization_fees(&[*program_id])
+        .context("fetching recent prioritization fees")?
+        .iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    if fees.is_empty() {
+        return Ok(0);
+    }
+    fees.sort_unstable();
+    Ok(fees[(fees.len() - 1) * 3 / 4])
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FinalityArg {
+    Confirmed,
+    Finalized,
+}
+
+impl From<FinalityArg> for Finality {
+    fn from(finality: FinalityArg) -> Self {
+        match finality {
+            FinalityArg::Confirmed => Finality::Confirmed,
+            FinalityArg::Finalized => Finality::Finalized,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CommitmentArg {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<CommitmentArg> for CommitmentConfig {
+    fn from(commitment: CommitmentArg) -> Self {
+        match commitment {
+            CommitmentArg::Processed => CommitmentConfig::processed(),
+            CommitmentArg::Confirmed => CommitmentConfig::confirmed(),
+            CommitmentArg::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let verbosity = if cli.quiet {
+        -1
+    } else {
+        cli.verbose.min(2) as i8
+    };
+    logging::init(verbosity, cli.log_format);
+    let output = cli.output;
+    if let Err(e) = run(cli) {
+        exit::report(&e, output == OutputFormat::Json);
+        std::process::exit(exit::ErrorClass::of(&e).exit_code());
+    }
+}
+
+fn resolve_rpc_url(url: &str) -> String {
+    match url {
+        "d" | "devnet" => "https://api.devnet.solana.com".to_string(),
+        "m" | "mainnet" | "mainnet-beta" => "https://api.mainnet-beta.solana.com".to_string(),
+        "t" | "testnet" => "https://api.testnet.solana.com".to_string(),
+        "l" | "localhost" => "http://127.0.0.1:8899".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn run(mut cli: Cli) -> Result<()> {
+    // Shell integration, offline signing and instruction decoding need neither a
+    // profile nor an RPC URL.
+    match &cli.command {
+        Command::Completions { shell } => return cmd_completions(*shell),
+        Command::Man { out_dir } => return cmd_man(out_dir.as_deref()),
+        Command::SignTx { keypair, dir } => return cmd_sign_tx(keypair, dir),
+        Command::DecodeIx { json, data } => return cmd_decode_ix(*json, data.clone()),
+        _ => {}
+    }
+
+    cli.profile_settings = config::load_profile(cli.config.as_deref(), cli.profile.as_deref())?;
+    // The local validator is the RPC node, so it needs no RPC URL either.
+    #[cfg(feature = "bundled-fixtures")]
+    if let Command::Localnet {
+        payer,
+        guardian_keys,
+        guardians,
+        seed,
+        guardian_set_index,
+        ledger,
+        reset,
+        state_dir,
+        validator,
+        validator_args,
+    } = &cli.command
+    {
+        let payer = payer.as_deref().or(cli.profile_settings.payer.as_deref());
+        let guardians = test_guardians(guardian_keys, *guardians, *seed)?;
+        return cmd_localnet(
+            payer,
+            &guardians,
+            *guardian_set_index,
+            ledger,
+            *reset,
+            state_dir,
+            validator,
+            validator_args,
+        );
+    }
+    let rpc_urls = match (&cli.rpc_url_args[..], &cli.profile_settings.rpc_url) {
+        ([], Some(urls)) => urls.split(',').map(str::trim).map(String::from).collect(),
+        (urls, _) => urls.to_vec(),
+    };
+    cli.rpc_urls = rpc_urls.iter().map(|url| resolve_rpc_url(url)).collect();
+    cli.rpc_url =
+        cli.rpc_urls.first().cloned().context(
+            "no RPC URL: pass -u/--rpc-url, set SOLANA_RPC_URL or set rpc_url in a profile",
+        )?;
+    if cli.core_bridge.is_none() {
+        cli.core_bridge = cli.profile_settings.core_bridge.clone();
+    }
+    if let Command::Submit {
+        commitment: Some(commitment),
+        ..
+    } = cli.command
+    {
+        cli.commitment = Some(commitment.into());
+    }
+    if cli.verify_vaa_shim.is_none() {
+        cli.verify_vaa_shim = cli.profile_settings.verify_vaa_shim.clone();
+    }
+    if cli.post_message_shim.is_none() {
+        cli.post_message_shim = cli.profile_settings.post_message_shim.clone();
+    }
+
+    match &cli.command {
+        Command::Submit {
+            program_id,
+            payer,
+            compute_budget,
+            batch: Some(batch),
+            concurrency,
+            skip_close,
+            ..
+        } => cmd_submit_batch(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            compute_budget,
+            batch,
+            *concurrency,
+            *skip_close,
+        ),
+        Command::Submit {
+            program_id,
+            payer,
+            compute_budget,
+            dry_run,
+            skip_close,
+            yes,
+            vaa,
+            ..
+        } => cmd_submit(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            compute_budget,
+            *dry_run,
+            *skip_close,
+            *yes,
+            vaa.clone(),
+        ),
+        Command::Fetch { id, tx, api_url } => {
+            cmd_fetch(&cli, id.as_deref(), tx.as_deref(), api_url.as_deref())
+        }
+        Command::Tx { json, signature } => cmd_tx(&cli, signature, *json),
+        Command::Listen {
+            emitter,
+            ws_url,
+            json,
+        } => cmd_listen(&cli, emitter.as_deref(), ws_url.as_deref(), *json),
+        Command::Watch {
+            program_id,
+            payer,
+            emitter,
+            api_url,
+            spy,
+            interval,
+            state_dir,
+            from_sequence,
+            metrics_addr,
+        } => cmd_watch(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            emitter,
+            api_url.as_deref(),
+            spy.as_deref(),
+            Duration::from_secs(*interval),
+            state_dir,
+            *from_sequence,
+            metrics_addr.as_deref(),
+        ),
+        Command::Status {
+            program_id,
+            payer,
+            replay_seeds,
+            replay_program,
+            vaa,
+        } => cmd_status(
+            &cli,
+            program_id,
+            payer.as_deref().or(cli.profile_settings.payer.as_deref()),
+            replay_seeds,
+            replay_program.as_deref(),
+            vaa.clone(),
+        ),
+        Command::Resolve {
+            program_id,
+            payer,
+            vaa,
+        } => cmd_resolve(&cli, program_id, payer_path(&cli, payer)?, vaa.clone()),
+        Command::ExecutePlan {
+            payer,
+            substitutions,
+            compute_budget,
+            plan,
+        } => cmd_execute_plan(
+            &cli,
+            payer_path(&cli, payer)?,
+            substitutions,
+            compute_budget,
+            plan.as_deref(),
+        ),
+        Command::Estimate {
+            program_id,
+            payer,
+            compute_budget,
+            json,
+            vaa,
+        } => cmd_estimate(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            compute_budget,
+            *json,
+            vaa.clone(),
+        ),
+        Command::Simulate {
+            program_id,
+            payer,
+            vaa,
+        } => cmd_simulate(&cli, program_id, payer_path(&cli, payer)?, vaa.clone()),
+        Command::ExportTx {
+            program_id,
+            payer,
+            out_dir,
+            nonce_account,
+            vaa,
+        } => cmd_export_tx(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            out_dir,
+            nonce_account,
+            vaa.clone(),
+        ),
+        Command::ImportAndSend { dir } => cmd_import_and_send(&cli, dir),
+        Command::PostSignatures { payer, vaa } => {
+            cmd_post_signatures(&cli, payer_path(&cli, payer)?, vaa.clone())
+        }
+        Command::CloseSignatures {
+            account,
+            payer,
+            recipient,
+        } => cmd_close_signatures(
+            &cli,
+            account,
+            payer_path(&cli, payer)?,
+            recipient.as_deref(),
+        ),
+        Command::SweepSignatures { payer, dry_run } => {
+            cmd_sweep_signatures(&cli, payer_path(&cli, payer)?, *dry_run)
+        }
+        Command::Verify { vaa } => cmd_verify(&cli, vaa.clone()),
+        Command::Emit {
+            payer,
+            emitter,
+            emitter_program,
+            emitter_seed,
+            nonce,
+            finality,
+            payload,
+        } => cmd_emit(
+            &cli,
+            payer_path(&cli, payer)?,
+            emitter.as_deref(),
+            emitter_program.as_deref(),
+            emitter_seed,
+            *nonce,
+            (*finality).into(),
+            payload.clone(),
+        ),
+        Command::GuardianSet { index, json } => cmd_guardian_set(&cli, *index, *json),
+        Command::Sequence { json, emitter } => cmd_sequence(&cli, emitter, *json),
+        Command::Parse { json, vaa } => cmd_parse(*json, vaa.clone()),
+        Command::SignTestVaa {
+            guardian_keys,
+            guardians,
+            seed,
+            guardian_set_index,
+            timestamp,
+            nonce,
+            consistency_level,
+            id,
+            payload,
+        } => cmd_sign_test_vaa(
+            guardian_keys,
+            *guardians,
+            *seed,
+            *guardian_set_index,
+            *timestamp,
+            *nonce,
+            *consistency_level,
+            id,
+            payload.clone(),
+        ),
+        Command::Digest { body, json, vaa } => cmd_digest(*body, *json, vaa.clone()),
+        Command::DecodePayload { payload, json, vaa } => {
+            cmd_decode_payload(*payload, *json, vaa.clone())
+        }
+        Command::Serve {
+            program_id,
+            payer,
+            compute_budget,
+            listen,
+            token,
+            concurrency,
+            skip_close,
+        } => cmd_serve(
+            &cli,
+            program_id,
+            payer_path(&cli, payer)?,
+            compute_budget,
+            listen,
+            token.clone(),
+            *concurrency,
+            *skip_close,
+        ),
+        Command::Airdrop {
+            amount,
+            recipient,
+            payer,
+        } => cmd_airdrop(&cli, *amount, recipient.as_deref(), payer),
+        Command::Account { json, raw, address } => cmd_account(&cli, address, *json, *raw),
+        Command::Pda { program_id, seeds } => cmd_pda(&cli, program_id, seeds),
+        Command::Fees { json } => cmd_fees(&cli, *json),
+        #[cfg(feature = "bundled-fixtures")]
+        Command::ProgramInfo { expectations, json } => cmd_program_info(&cli, expectations, *json),
+        Command::Completions { .. }
+        | Command::Man { .. }
+        | Command::SignTx { .. }
+        | Command::DecodeIx { .. } => unreachable!("handled above"),
+        #[cfg(feature = "bundled-fixtures")]
+        Command::Localnet { .. } => unreachable!("handled above"),
+    }
+}
+
+fn cmd_submit(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: &str,
+    compute_budget: &ComputeBudgetArgs,
+    dry_run: bool,
+    skip_close: bool,
+    yes: bool,
+    vaa_arg: Option<String>,
+) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer_path])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    if !yes && !dry_run && !confirm::can_prompt() {
+        bail!(
+            "no terminal to confirm the submission on; pass --yes to submit without confirmation"
+        );
+    }
+
+    let payer = signer_from_path(payer_path, "payer")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let network = network_for_cli(cli)?;
+
+    let mut conn = failover_connection(cli)?;
+
+    eprintln!("Submitting VAA to {}...", program_id);
+    eprintln!("  Payer: {}", payer.pubkey());
+    eprintln!("  Core Bridge: {}", network.core_bridge);
+    eprintln!("  Guardian set index: {}", vaa.guardian_set_index);
+    eprintln!("  Signatures: {}", vaa.signatures.len());
+    eprintln!("  RPC: {}", cli.rpc_urls.join(", "));
+
+    let report_client = rpc_client(cli)?;
+    let payer_pubkey = payer.pubkey();
+    let confirm = |plan: &BroadcastPlan| {
+        let report = account_report(
+            &report_client,
+            plan.instruction_groups,
+            &payer_pubkey,
+            plan.guardian_set,
+            &[],
+        );
+        if let Err(e) = &report {
+            eprintln!("Warning: fetching the referenced accounts failed: {}", e);
+        }
+        confirm::print_plan(plan, &payer_pubkey, report.as_ref().ok());
+        confirm::prompt("Submit?").unwrap_or_else(|e| {
+            eprintln!("Warning: {:#}", e);
+            false
+        })
+    };
+    let config = BroadcastConfig {
+        network,
+        execute: compute_budget.execute_config(
+            &cli.profile_settings,
+            conn.current(),
+            &program_id,
+        )?,
+        skip_close,
+        confirm: if yes {
+            None
+        } else {
+            Some(&confirm as &dyn Fn(&BroadcastPlan) -> bool)
+        },
+        ..Default::default()
+    };
+    if dry_run {
+        return dry_run_submit(cli, &program_id, &*payer, &vaa, &config.execute);
+    }
+    let tx_sigs = wormhole_svm_submit::broadcast_vaa_with_config(
+        &mut conn,
+        &payer,
+        &program_id,
+        vaa.guardian_set_index,
+        &vaa.body,
+        &vaa.signatures,
+        &config,
+    )?;
+
+    for sig in &tx_sigs {
+        println!("{}", sig);
+    }
+
+    Ok(())
+}
+
+/// `submit --dry-run`: run the submission on a local fork and print the
+/// instruction groups it would execute and what it would cost.
+fn dry_run_submit(
+    cli: &Cli,
+    program_id: &Pubkey,
+    payer: &dyn Signer,
+    vaa: &SignedVaa,
+    execute: &ExecuteConfig,
+) -> Result<()> {
+    eprintln!(
+        "Dry run on a local fork of {}; nothing will be sent",
+        cli.rpc_url
+    );
+    let (resolved, units) = match run_on_fork(cli, program_id, payer, vaa, false)? {
+        ForkRun::Succeeded { resolved, units } => (resolved, units),
+        ForkRun::Failed { group, error } => {
+            return Err(exit::simulation_failed(
+                &error,
+                format!(
+                    "group {} would fail:\n  {}",
+                    group,
+                    error.replace('\n', "\n  ")
+                ),
+            ))
+        }
+    };
+
+    println!("Post {} guardian signatures", vaa.signatures.len());
+    for (index, (group, units)) in resolved.iter().zip(&units).enumerate() {
+        let mut programs: Vec<String> = Vec::new();
+        for ix in &group.instructions {
+            let program = ix.program_id.to_string();
+            if !programs.contains(&program) {
+                programs.push(program);
+            }
+        }
+        println!(
+            "Group {}: {} instruction(s), {} compute units, programs: {}",
+            index,
+            group.instructions.len(),
+            units.map_or("unknown".to_string(), |units| units.to_string()),
+            programs.join(", ")
+        );
+    }
+    println!("Close the guardian signatures account");
+
+    let cost = estimate_broadcast_cost(&resolved, &payer.pubkey(), vaa.signatures.len(), execute);
+    println!("Estimated cost: {} lamports", cost.total());
+    println!(
+        "  signatures rent:  {} (refunded on close)",
+        cost.signatures_rent
+    );
+    println!("  transaction fees: {}", cost.transaction_fees);
+    println!("  priority fees:    {} (upper bound)", cost.priority_fees);
+    Ok(())
+}
+
+fn cmd_submit_batch(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: &str,
+    compute_budget: &ComputeBudgetArgs,
+    batch_path: &Path,
+    concurrency: usize,
+    skip_close: bool,
+) -> Result<()> {
+    let items = batch::read_batch(batch_path)?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let network = network_for_cli(cli)?;
+    let mut conn = failover_connection(cli)?;
+
+    eprintln!(
+        "Submitting {} VAAs from {} to {}...",
+        items.len(),
+        batch_path.display(),
+        program_id
+    );
+    eprintln!("  Core Bridge: {}", network.core_bridge);
+    eprintln!("  RPC: {}", cli.rpc_urls.join(", "));
+    let execute =
+        compute_budget.execute_config(&cli.profile_settings, conn.current(), &program_id)?;
+
+    // Results in input order; unreadable VAAs fail without a broadcast.
+    let mut results: Vec<Option<batch::BatchResult>> = Vec::with_capacity(items.len());
+    let mut vaas = Vec::new();
+    for (index, item) in items.into_iter().enumerate() {
+        match item.vaa {
+            Ok(vaa) => {
+                results.push(None);
+                vaas.push((index, item.label, vaa));
+            }
+            Err(e) => results.push(Some(batch::BatchResult {
+                label: item.label,
+                id: None,
+                result: Err(e),
+            })),
+        }
+    }
+
+    let hardware_wallet = payer_path.starts_with("usb://");
+    if hardware_wallet && concurrency > 1 {
+        eprintln!("Hardware wallets sign one transaction at a time; submitting sequentially");
+    }
+    if concurrency <= 1 || hardware_wallet {
+        let payer = signer_from_path(payer_path, "payer")?;
+        eprintln!("  Payer: {}", payer.pubkey());
+        let config = BroadcastConfig {
+            network,
+            execute,
+            skip_close,
+            ..Default::default()
+        };
+        for (index, label, vaa) in vaas {
+            eprintln!("Submitting {}...", label);
+            let result = broadcast_signed(&mut conn, &*payer, &program_id, &vaa, &config);
+            results[index] = Some(batch::BatchResult {
+                label,
+                id: batch::vaa_id(&vaa),
+                result: result.map_err(|e| e.to_string()),
+            });
+        }
+    } else {
+        let payer = Arc::new(keypair_from_path(payer_path, "payer")?);
+        eprintln!("  Payer: {}", payer.pubkey());
+        let submit = pipeline_submitter(cli, payer, program_id, network, &execute, skip_close)?;
+
+        // The pipeline reports VAAs by content; map them back to their inputs.
+        let mut pending: HashMap<Vec<u8>, VecDeque<(usize, String)>> = HashMap::new();
+        let mut stream_vaas = Vec::with_capacity(vaas.len());
+        for (index, label, vaa) in vaas {
+            pending
+                .entry(vaa.clone())
+                .or_default()
+                .push_back((index, label));
+            stream_vaas.push(vaa);
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("starting async runtime")?;
+        let config = PipelineConfig {
+            concurrency,
+            ..Default::default()
+        };
+        let mut stream = Box::pin(broadcast_stream(
+            futures::stream::iter(stream_vaas),
+            config,
+            submit,
+        ));
+        while let Some(done) = runtime.block_on(stream.next()) {
+            let Some((index, label)) = pending.get_mut(&done.vaa).and_then(VecDeque::pop_front)
+            else {
+                continue;
+            };
+            match &done.result {
+                Ok(_) => eprintln!("{}: done", label),
+                Err(e) => eprintln!("{}: failed: {}", label, e),
+            }
+            results[index] = Some(batch::BatchResult {
+                label,
+                id: batch::vaa_id(&done.vaa),
+                result: done.result.map_err(|e| e.to_string()),
+            });
+        }
+    }
+
+    let results: Vec<_> = results.into_iter().flatten().collect();
+    batch::print_table(&results);
+    let failed = results
+        .iter()
+        .filter(|result| result.result.is_err())
+        .count();
+    if failed > 0 {
+        bail!("{} of {} VAAs failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// A submission function for the pipeline: each broadcast runs on its own
+/// thread with its own connections to the CLI's RPC URLs.
+fn pipeline_submitter(
+    cli: &Cli,
+    payer: Arc<Keypair>,
+    program_id: Pubkey,
+    network: WormholeNetwork,
+    execute: &ExecuteConfig,
+    skip_close: bool,
+) -> Result<impl Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static> {
+    let urls = cli.rpc_urls.clone();
+    let rpc_config = rpc_connection_config(cli)?;
+    let commitment = cli.commitment;
+    let (compute_unit_price, compute_unit_limit) =
+        (execute.compute_unit_price, execute.compute_unit_limit);
+    Ok(move |vaa: &[u8]| -> Result<Vec<Signature>, SubmitError> {
+        let mut conn = failover_clients(&urls, &rpc_config, commitment)?;
+        let config = BroadcastConfig {
+            network,
+            execute: ExecuteConfig {
+                compute_unit_price,
+                compute_unit_limit,
+                ..Default::default()
+            },
+            skip_close,
+            ..Default::default()
+        };
+        broadcast_signed(&mut conn, &*payer, &program_id, vaa, &config)
+    })
+}
+
+/// Broadcast the signed VAA `vaa` over `conn`.
+fn broadcast_signed<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn Signer,
+    program_id: &Pubkey,
+    vaa: &[u8],
+    config: &BroadcastConfig,
+) -> Result<Vec<Signature>, SubmitError> {
+    let vaa = SignedVaa::parse(vaa)?;
+    wormhole_svm_submit::broadcast_vaa_with_config(
+        conn,
+        payer,
+        program_id,
+        vaa.guardian_set_index,
+        &vaa.body,
+        &vaa.signatures,
+        config,
+    )
+}
+
+fn cmd_resolve(cli: &Cli, program_id: &str, payer: &str, vaa_arg: Option<String>) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let payer = parse_payer_pubkey(payer)?;
+    let network = network_for_cli(cli)?;
+    let guardian_set = network.guardian_set_address(vaa.guardian_set_index);
+
+    let rpc_client = rpc_client(cli)?;
+    let resolved = wormhole_svm_submit::resolve::resolve_execute_vaa_v1(
+        &rpc_client,
+        &program_id,
+        &NullSigner::new(&payer),
+        &vaa.body,
+        &guardian_set,
+        wormhole_svm_submit::MAX_RESOLVER_ITERATIONS,
+    )?;
+
+    let annotate = |pubkey: &Pubkey| {
+        if *pubkey == payer {
+            Some("RESOLVER_PUBKEY_PAYER")
+        } else if *pubkey == guardian_set {
+            Some("RESOLVER_PUBKEY_GUARDIAN_SET")
+        } else {
+            placeholder_name(pubkey)
+        }
+    };
+    let groups: Vec<serde_json::Value> = resolved
+        .instruction_groups
+        .iter()
+        .map(|group| {
+            let instructions: Vec<serde_json::Value> = group
+                .instructions
+                .iter()
+                .map(|ix| {
+                    let accounts: Vec<serde_json::Value> = ix
+                        .accounts
+                        .iter()
+                        .map(|meta| {
+                            serde_json::json!({
+                                "pubkey": meta.pubkey.to_string(),
+                                "is_signer": meta.is_signer,
+                                "is_writable": meta.is_writable,
+                                "placeholder": annotate(&meta.pubkey),
+                            })
+                        })
+                        .collect();
+                    serde_json::json!({
+                        "program_id": ix.program_id.to_string(),
+                        "accounts": accounts,
+                        "data": hex::encode(&ix.data),
+                    })
+                })
+                .collect();
+            serde_json::json!({ "instructions": instructions })
+        })
+        .collect();
+
+    eprintln!(
+        "Resolved {} instruction group(s) in {} iteration(s)",
+        groups.len(),
+        resolved.iterations
+    );
+    let output = serde_json::json!({
+        "program_id": program_id.to_string(),
+        "payer": payer.to_string(),
+        "guardian_set": guardian_set.to_string(),
+        "iterations": resolved.iterations,
+        "instruction_groups": groups,
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+fn cmd_execute_plan(
+    cli: &Cli,
+    payer_path: &str,
+    substitutions: &[String],
+    compute_budget: &ComputeBudgetArgs,
+    plan_path: Option<&Path>,
+) -> Result<()> {
+    let json = match plan_path {
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+        }
+        None => {
+            if io::stdin().is_terminal() {
+                bail!("no plan given; pass a file or pipe one on stdin");
+            }
+            let mut json = String::new();
+            io::stdin().read_to_string(&mut json)?;
+            json
+        }
+    };
+    let plan = plan::Plan::parse(&json)?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    if payer.pubkey() != plan.payer {
+        bail!(
+            "the plan was resolved for payer {}; resolve it again with --payer {}",
+            plan.payer,
+            payer.pubkey()
+        );
+    }
+
+    let mut signatures_account = None;
+    let mut placeholders = Vec::new();
+    for substitution in substitutions {
+        let (name, value) = substitution
+            .split_once('=')
+            .with_context(|| format!("expected NAME=PUBKEY: {}", substitution))?;
+        let value = Pubkey::from_str(value).with_context(|| format!("invalid {}", name))?;
+        match name {
+            "RESOLVER_PUBKEY_SHIM_VAA_SIGS" => signatures_account = Some(value),
+            "RESOLVER_PUBKEY_PAYER" | "RESOLVER_PUBKEY_GUARDIAN_SET" => {
+                bail!("{} comes from the plan and can't be set", name)
+            }
+            name if name.starts_with("RESOLVER_PUBKEY_KEYPAIR_") => {
+                bail!("{} is generated when executing and can't be set", name)
+            }
+            name => {
+                let placeholder = match plan.placeholder(name) {
+                    Some(placeholder) => placeholder,
+                    None => Pubkey::from_str(name)
+                        .with_context(|| format!("unknown placeholder {}", name))?,
+                };
+                if !plan.references(&placeholder) {
+                    bail!("the plan doesn't reference {}", name);
+                }
+                placeholders.push((placeholder, value));
+            }
+        }
+    }
+    let signatures_account = match signatures_account {
+        Some(account) => account,
+        None if plan.placeholder("RESOLVER_PUBKEY_SHIM_VAA_SIGS").is_some() => bail!(
+            "the plan needs the guardian signatures account; post it with post-signatures and \
+             pass --set RESOLVER_PUBKEY_SHIM_VAA_SIGS=<ACCOUNT>"
+        ),
+        None => Pubkey::default(),
+    };
+
+    let mut rpc_client = rpc_client(cli)?;
+    eprintln!(
+        "Executing {} instruction group(s) for {}...",
+        plan.instruction_groups.len(),
+        plan.program_id
+    );
+    eprintln!("  Payer: {}", payer.pubkey());
+    let config = ExecuteConfig {
+        placeholders: &placeholders,
+        ..compute_budget.execute_config(&cli.profile_settings, &rpc_client, &plan.program_id)?
+    };
+    let signatures = wormhole_svm_submit::execute::execute_instruction_groups_with_config(
+        &mut rpc_client,
+        &payer,
+        &plan.instruction_groups,
+        &signatures_account,
+        &plan.guardian_set,
+        &config,
+    )?;
+    for signature in signatures {
+        println!("{}", signature);
+    }
+    Ok(())
+}
+
+fn cmd_estimate(
+    cli: &Cli,
+    program_id: &str,
+    payer: &str,
+    compute_budget: &ComputeBudgetArgs,
+    json: bool,
+    vaa_arg: Option<String>,
+) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let payer = parse_payer_pubkey(payer)?;
+    let network = network_for_cli(cli)?;
+    let rpc_client = rpc_client(cli)?;
+
+    let mut execute =
+        compute_budget.execute_config(&cli.profile_settings, &rpc_client, &program_id)?;
+    if execute.compute_unit_price.is_none() {
+        let price = recent_compute_unit_price(&rpc_client, &program_id)?;
+        eprintln!("Compute unit price: {} (recent fees)", price);
+        execute.compute_unit_price = Some(price);
+    }
+
+    let resolved = wormhole_svm_submit::resolve::resolve_execute_vaa_v1(
+        &rpc_client,
+        &program_id,
+        &NullSigner::new(&payer),
+        &vaa.body,
+        &network.guardian_set_address(vaa.guardian_set_index),
+        wormhole_svm_submit::MAX_RESOLVER_ITERATIONS,
+    )?;
+    let groups = &resolved.instruction_groups;
+
+    let cost = estimate_broadcast_cost(groups, &payer, vaa.signatures.len(), &execute);
+    let group_costs: Vec<_> = groups
+        .iter()
+        .map(|group| estimate_group_cost(group, &payer, &execute))
+        .collect();
+    let bridge_fees = estimate_bridge_fees(&rpc_client, &network, groups)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let total = cost.total().saturating_add(bridge_fees);
+
+    if json {
+        let group_costs: Vec<_> = group_costs
+            .iter()
+            .map(|cost| {
+                serde_json::json!({
+                    "transaction_fee": cost.transaction_fee,
+                    "priority_fee": cost.priority_fee,
+                    "compute_unit_limit": cost.compute_unit_limit,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "signatures_rent": cost.signatures_rent,
+            "transaction_fees": cost.transaction_fees,
+            "priority_fees": cost.priority_fees,
+            "compute_unit_price": execute.compute_unit_price,
+            "bridge_fees": bridge_fees,
+            "groups": group_costs,
+            "total": total,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Estimated cost: {} lamports", total);
+    println!(
+        "  signatures rent:  {} (refunded on close)",
+        cost.signatures_rent
+    );
+    println!(
+        "  transaction fees: {} (post, close and {} group(s))",
+        cost.transaction_fees,
+        groups.len()
+    );
+    println!("  priority fees:    {} (upper bound)", cost.priority_fees);
+    println!("  bridge fees:      {}", bridge_fees);
+    for (index, group) in group_costs.iter().enumerate() {
+        println!(
+            "  group {}: {} fee + {} priority ({} compute units)",
+            index, group.transaction_fee, group.priority_fee, group.compute_unit_limit
+        );
+    }
+    Ok(())
+}
+
+fn cmd_simulate(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: &str,
+    vaa_arg: Option<String>,
+) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer_path])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+
+    eprintln!("Simulating on a local fork of {}...", cli.rpc_url);
+    match run_on_fork(cli, &program_id, &payer, &vaa, true)? {
+        ForkRun::Succeeded { resolved, .. } => {
+            eprintln!("All {} group(s) would succeed", resolved.len());
+            Ok(())
+        }
+        ForkRun::Failed { group, error } => Err(exit::simulation_failed(
+            &error,
+            format!("group {} would fail", group),
+        )),
+    }
+}
+
+/// Outcome of [`run_on_fork`].
+enum ForkRun {
+    /// The resolved instruction groups and the compute units each consumed.
+    Succeeded {
+        resolved: Vec<InstructionGroup>,
+        units: Vec<Option<u64>>,
+    },
+    Failed {
+        group: usize,
+        error: String,
+    },
+}
+
+/// Run the whole submission flow (post signatures, resolve, substitute
+/// placeholders, execute each group) on a local fork of the cluster, stopping
+/// at the first failing group. With `print_groups`, prints the logs and
+/// compute units of every group transaction.
+fn run_on_fork(
+    cli: &Cli,
+    program_id: &Pubkey,
+    payer: &dyn Signer,
+    vaa: &SignedVaa,
+    print_groups: bool,
+) -> Result<ForkRun> {
+    let payer_pubkey = payer.pubkey();
+    let network = network_for_cli(cli)?;
+    let guardian_set = network.guardian_set_address(vaa.guardian_set_index);
+
+    let rpc_client = rpc_client(cli)?;
+    let mut fork = ForkedConnection::new(&rpc_client).map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let posted = wormhole_svm_submit::signatures::post_signatures(
+        &mut fork,
+        payer,
+        &network.verify_vaa_shim,
+        vaa.guardian_set_index,
+        &vaa.signatures,
+    )
+    .map_err(|e| anyhow::anyhow!("posting signatures: {}", e))?;
+    let resolved = wormhole_svm_submit::resolve::resolve_execute_vaa_v1(
+        &fork,
+        program_id,
+        payer,
+        &vaa.body,
+        &guardian_set,
+        wormhole_svm_submit::MAX_RESOLVER_ITERATIONS,
+    )?;
+    let substituted = wormhole_svm_submit::execute::substitute_instruction_groups(
+        &resolved.instruction_groups,
+        &payer_pubkey,
+        &posted.pubkey,
+        &guardian_set,
+        &[],
+    );
+
+    let mut units = Vec::with_capacity(substituted.groups.len());
+    for (group_index, instructions) in substituted.groups.iter().enumerate() {
+        if exceeds_legacy_transaction_size(instructions, &payer_pubkey) {
+            eprintln!(
+                "Warning: group {} is too large for a legacy transaction (needs a lookup table)",
+                group_index
+            );
+        }
+        let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            MAX_COMPUTE_UNIT_LIMIT,
+        )];
+        ixs.extend_from_slice(instructions);
+        let mut signers: Vec<&dyn Signer> = vec![payer];
+        for keypair in &substituted.keypairs {
+            let signs = ixs
+                .iter()
+                .flat_map(|ix| &ix.accounts)
+                .any(|meta| meta.is_signer && meta.pubkey == keypair.pubkey());
+            if signs {
+                signers.push(keypair);
+            }
+        }
+        let blockhash = fork
+            .get_latest_blockhash()
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer_pubkey), &signers, blockhash);
+
+        match fork.simulate_with_post_accounts(&tx, &[]) {
+            Ok(result) => {
+                units.push(result.units_consumed);
+                if print_groups {
+                    println!(
+                        "Group {}: {} compute units",
+                        group_index,
+                        result
+                            .units_consumed
+                            .map_or("unknown".to_string(), |units| units.to_string())
+                    );
+                    for log in &result.logs {
+                        println!("  {}", log);
+                    }
+                }
+            }
+            Err(e) => {
+                if print_groups {
+                    println!("Group {}: failed", group_index);
+                    println!("  {}", e.to_string().replace('\n', "\n  "));
+                }
+                return Ok(ForkRun::Failed {
+                    group: group_index,
+                    error: e.to_string(),
+                });
+            }
+        }
+        // Apply the group so later groups see its effects.
+        fork.send_and_confirm(&tx)
+            .map_err(|e| anyhow::anyhow!("applying group {} on the fork: {}", group_index, e))?;
+    }
+
+    Ok(ForkRun::Succeeded {
+        resolved: resolved.instruction_groups,
+        units,
+    })
+}
+
+fn cmd_tx(cli: &Cli, signature: &str, json: bool) -> Result<()> {
+    let signature = Signature::from_str(signature).context("invalid transaction signature")?;
+    let network = network_for_cli(cli)?;
+    let messages = tx::fetch_posted_messages(&rpc_client(cli)?, &network, &signature)?;
+
+    if json {
+        let messages: Vec<_> = messages
+            .iter()
+            .map(|message| message.to_json(network.chain_id))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&messages)?);
+        return Ok(());
+    }
+    if messages.is_empty() {
+        eprintln!("No Wormhole messages posted by {}", signature);
+    }
+    for (i, message) in messages.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        message.print(network.chain_id);
+    }
+    Ok(())
+}
+
+fn cmd_listen(cli: &Cli, emitter: Option<&str>, ws_url: Option<&str>, json: bool) -> Result<()> {
+    let emitter = emitter.map(parse_address).transpose()?;
+    let network = network_for_cli(cli)?;
+    let ws_url = match ws_url {
+        Some(url) => url.to_string(),
+        None => listen::websocket_url(&cli.rpc_url)?,
+    };
+    listen::listen(&rpc_client(cli)?, &ws_url, &network, emitter, json)
+}
+
+fn cmd_status(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: Option<&str>,
+    replay_seeds: &[String],
+    replay_program: Option<&str>,
+    vaa_arg: Option<String>,
+) -> Result<()> {
+    check_stdin(&vaa_arg, payer_path.as_slice())?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let body = VaaBody::parse(&vaa.body).context("parsing VAA body")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    eprintln!(
+        "VAA {}/{}/{}",
+        body.emitter_chain,
+        hex::encode(body.emitter_address),
+        body.sequence
+    );
+
+    if !replay_seeds.is_empty() {
+        let replay_program = match replay_program {
+            Some(id) => Pubkey::from_str(id).context("invalid replay program ID")?,
+            None => program_id,
+        };
+        let seeds = replay_seeds
+            .iter()
+            .map(|seed| replay_seed(seed, &vaa.body, &body))
+            .collect::<Result<Vec<_>>>()?;
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(|s| s.as_slice()).collect();
+        let (pda, _) = Pubkey::find_program_address(&seed_slices, &replay_program);
+
+        let rpc_client = rpc_client(cli)?;
+        let account = rpc_client
+            .get_account_with_commitment(&pda, rpc_client.commitment())
+            .context("fetching replay account")?
+            .value;
+        match account {
+            Some(account) => {
+                eprintln!(
+                    "Replay account {} exists (owner {}, {} bytes)",
+                    pda,
+                    account.owner,
+                    account.data.len()
+                );
+                println!("redeemed");
+            }
+            None => {
+                eprintln!("Replay account {} does not exist", pda);
+                println!("not-redeemed");
+            }
+        }
+        return Ok(());
+    }
+
+    let payer_path = payer_path.context("pass --replay-seed or --payer for a simulation probe")?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    eprintln!("Probing with a simulated submission on a local fork...");
+    match run_on_fork(cli, &program_id, &payer, &vaa, false) {
+        Ok(ForkRun::Succeeded { .. }) => {
+            eprintln!("A submission would succeed now");
+            println!("not-redeemed");
+        }
+        Ok(ForkRun::Failed { group, error }) => {
+            eprintln!("A submission would fail at group {}:", group);
+            eprintln!("  {}", error.replace('\n', "\n  "));
+            eprintln!("This usually means the VAA was already redeemed; check the error above");
+            println!("likely-redeemed");
+        }
+        Err(e) => {
+            eprintln!("The probe failed before executing: {:#}", e);
+            println!("unknown");
+        }
+    }
+    Ok(())
+}
+
+/// A replay PDA seed: a string, `0x`-prefixed hex, or a placeholder for a
+/// field of the VAA.
+fn replay_seed(seed: &str, raw_body: &[u8], body: &VaaBody) -> Result<Vec<u8>> {
+    Ok(match seed {
+        "{digest}" => vaa_digest(raw_body).to_vec(),
+        "{hash}" => vaa_message_hash(raw_body).to_vec(),
+        "{emitter_chain}" => body.emitter_chain.to_be_bytes().to_vec(),
+        "{emitter}" => body.emitter_address.to_vec(),
+        "{sequence}" => body.sequence.to_be_bytes().to_vec(),
+        "{sequence_le}" => body.sequence.to_le_bytes().to_vec(),
+        other => parse_seed(other)?,
+    })
+}
+
+/// The `--payer` argument, or the profile's payer.
+fn payer_path<'a>(cli: &'a Cli, payer: &'a Option<String>) -> Result<&'a str> {
+    payer
+        .as_deref()
+        .or(cli.profile_settings.payer.as_deref())
+        .context("no payer: pass --payer, set PAYER_KEYPAIR or set payer in a profile")
+}
+
+/// A payer given as a pubkey, a keypair file or a hardware wallet URI.
+fn parse_payer_pubkey(payer: &str) -> Result<Pubkey> {
+    if let Ok(pubkey) = Pubkey::from_str(payer) {
+        return Ok(pubkey);
+    }
+    Ok(signer_from_path(payer, "payer")?.pubkey())
+}
+
+/// Read input from hex string argument, @file reference, or stdin.
+/// Refuse to read a keypair from stdin when the input is read from it too:
+/// stdin can only carry one of them.
+fn check_stdin(input: &Option<String>, keypair_paths: &[&str]) -> Result<()> {
+    if input.is_none() && keypair_paths.contains(&"stdin") {
+        bail!(
+            "the input and a keypair can't both be read from stdin; \
+             pass the input as an argument or @file"
+        );
+    }
+    Ok(())
+}
+
+fn read_input(arg: Option<String>) -> Result<Vec<u8>> {
+    match arg {
+        Some(s) if s.starts_with('@') => {
+            let path = &s[1..];
+            let contents =
+                std::fs::read_to_string(path).with_context(|| format!("reading file: {}", path))?;
+            hex::decode(contents.trim()).context("decoding hex from file")
+        }
+        Some(s) => hex::decode(s.trim()).context("decoding hex payload"),
+        None => {
+            if io::stdin().is_terminal() {
+                bail!("no VAA provided; pass as argument, @file, or pipe to stdin");
+            }
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            hex::decode(buf.trim()).context("decoding hex from stdin")
+        }
+    }
+}
+
+fn cmd_export_tx(
+    cli: &Cli,
+    program_id: &str,
+    payer: &str,
+    out_dir: &Path,
+    nonce_accounts: &[String],
+    vaa_arg: Option<String>,
+) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let payer = parse_payer_pubkey(payer)?;
+    let network = network_for_cli(cli)?;
+
+    eprintln!("Resolving {} for offline signing...", program_id);
+    eprintln!("  Payer: {}", payer);
+    let rpc_client = rpc_client(cli)?;
+    let nonces = nonce_accounts
+        .iter()
+        .map(|account| {
+            let account = Pubkey::from_str(account)
+                .with_context(|| format!("invalid nonce account: {}", account))?;
+            offline::durable_nonce(&rpc_client, &account)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let export = wormhole_svm_submit::export_broadcast_with_nonces(
+        &rpc_client,
+        &payer,
+        &program_id,
+        vaa.guardian_set_index,
+        &vaa.body,
+        &vaa.signatures,
+        &network,
+        &[],
+        &nonces,
+    )?;
+    eprintln!(
+        "Resolved in {} iterations; signatures account: {}",
+        export.resolver_iterations, export.signatures_pubkey
+    );
+
+    for (path, file) in offline::write_export(out_dir, &export)? {
+        eprintln!(
+            "  {}: needs {}",
+            path.display(),
+            file.required_signers.join(", ")
+        );
+    }
+    let deadline = if nonces.is_empty() {
+        " before the blockhash expires (about a minute)"
+    } else {
+        ""
+    };
+    eprintln!(
+        "Sign with `svm-vaa sign-tx --keypair <KEYPAIR> {}` and submit with \
+         `svm-vaa import-and-send {}`{}",
+        out_dir.display(),
+        out_dir.display(),
+        deadline
+    );
+    Ok(())
+}
+
+fn cmd_sign_tx(keypair: &str, dir: &Path) -> Result<()> {
+    let signer = signer_from_path(keypair, "signer")?;
+    let signed = offline::sign_export(dir, &*signer)?;
+    if signed == 0 {
+        bail!(
+            "{} doesn't sign any transaction in {}",
+            signer.pubkey(),
+            dir.display()
+        );
+    }
+    eprintln!("Signed {} transaction(s) as {}", signed, signer.pubkey());
+    Ok(())
+}
+
+fn cmd_import_and_send(cli: &Cli, dir: &Path) -> Result<()> {
+    let files = offline::read_export(dir)?;
+    let mut transactions = Vec::with_capacity(files.len());
+    for (path, file) in &files {
+        let missing = file.missing_signers()?;
+        if !missing.is_empty() {
+            let missing: Vec<String> = missing.iter().map(ToString::to_string).collect();
+            bail!(
+                "{} is missing signatures from {}",
+                path.display(),
+                missing.join(", ")
+            );
+        }
+        let transaction = file
+            .transaction()
+            .with_context(|| format!("loading {}", path.display()))?;
+        let nonce_account = file
+            .nonce_account
+            .as_deref()
+            .map(Pubkey::from_str)
+            .transpose()
+            .with_context(|| format!("invalid nonce account in {}", path.display()))?;
+        transactions.push((path, transaction, nonce_account));
+    }
+
+    let rpc_client = rpc_client(cli)?;
+    for (path, transaction, nonce_account) in transactions {
+        let signature = transaction.signatures[0];
+        let status = rpc_client
+            .get_signature_status(&signature)
+            .with_context(|| format!("checking {}", signature))?;
+        if let Some(Ok(())) = status {
+            eprintln!("{}: already landed", path.display());
+            println!("{}", signature);
+            continue;
+        }
+        let blockhash = transaction.message.recent_blockhash;
+        match nonce_account {
+            Some(account) => {
+                if offline::durable_nonce(&rpc_client, &account)?.nonce != blockhash {
+                    bail!(
+                        "nonce account {} of {} was advanced; export the transactions again",
+                        account,
+                        path.display()
+                    );
+                }
+            }
+            None => {
+                if !rpc_client.is_blockhash_valid(&blockhash, CommitmentConfig::processed())? {
+                    bail!(
+                        "the blockhash of {} expired; export the transactions again",
+                        path.display()
+                    );
+                }
+            }
+        }
+        eprintln!("{}: sending...", path.display());
+        rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .with_context(|| format!("sending {}", path.display()))?;
+        println!("{}", signature);
+    }
+    Ok(())
+}
+
+fn cmd_post_signatures(cli: &Cli, payer_path: &str, vaa_arg: Option<String>) -> Result<()> {
+    check_stdin(&vaa_arg, &[payer_path])?;
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    let network = network_for_cli(cli)?;
+
+    let mut rpc_client = rpc_client(cli)?;
+
+    eprintln!(
+        "Posting {} signatures for guardian set {}...",
+        vaa.signatures.len(),
+        vaa.guardian_set_index
+    );
+    eprintln!("  Payer: {}", payer.pubkey());
+    eprintln!("  Verify VAA Shim: {}", network.verify_vaa_shim);
+    let posted = wormhole_svm_submit::signatures::post_signatures(
+        &mut rpc_client,
+        &payer,
+        &network.verify_vaa_shim,
+        vaa.guardian_set_index,
+        &vaa.signatures,
+    )
+    .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    println!("{}", posted.pubkey);
+    Ok(())
+}
+
+fn cmd_close_signatures(
+    cli: &Cli,
+    account: &str,
+    payer_path: &str,
+    recipient: Option<&str>,
+) -> Result<()> {
+    let signatures_pubkey = Pubkey::from_str(account).context("invalid signatures account")?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    let recipient = recipient
+        .map(|r| Pubkey::from_str(r).context("invalid recipient"))
+        .transpose()?;
+    let network = network_for_cli(cli)?;
+
+    let mut rpc_client = rpc_client(cli)?;
+    let Some(account) = SolanaConnection::get_account(&rpc_client, &signatures_pubkey)
+        .with_context(|| format!("fetching account {}", signatures_pubkey))?
+    else {
+        eprintln!("{} is already closed", signatures_pubkey);
+        return Ok(());
+    };
+    if account.owner != network.verify_vaa_shim {
+        bail!(
+            "{} is owned by {}, not the Verify VAA Shim {}",
+            signatures_pubkey,
+            account.owner,
+            network.verify_vaa_shim
+        );
+    }
+    let signatures_account =
+        SignaturesAccount::parse(signatures_pubkey, account.lamports, &account.data)
+            .context("not a guardian signatures account")?;
+    if signatures_account.refund_recipient != payer.pubkey() {
+        bail!(
+            "the refund recipient of {} is {}; pass its keypair as --payer",
+            signatures_pubkey,
+            signatures_account.refund_recipient
+        );
+    }
+
+    let mut ixs = vec![build_close_signatures_ix(
+        &network.verify_vaa_shim,
+        &signatures_pubkey,
+        &payer.pubkey(),
+    )];
+    if let Some(recipient) = recipient {
+        ixs.push(solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &recipient,
+            account.lamports,
+        ));
+    }
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[&payer], blockhash);
+    let signature = SolanaConnection::send_and_confirm(&mut rpc_client, &tx)
+        .context("closing signatures account")?;
+
+    eprintln!(
+        "Closed {}, reclaimed {} lamports to {}",
+        signatures_pubkey,
+        account.lamports,
+        recipient.unwrap_or(payer.pubkey())
+    );
+    println!("{}", signature);
+    Ok(())
+}
+
+/// Close instructions per `sweep-signatures` transaction.
+const SWEEP_CLOSES_PER_TRANSACTION: usize = 10;
+
+fn cmd_sweep_signatures(cli: &Cli, payer_path: &str, dry_run: bool) -> Result<()> {
+    let payer = signer_from_path(payer_path, "payer")?;
+    let network = network_for_cli(cli)?;
+    let mut rpc_client = rpc_client(cli)?;
+
+    let accounts = wormhole_svm_submit::signatures::find_signatures_accounts(
+        &rpc_client,
+        &network.verify_vaa_shim,
+        &payer.pubkey(),
+    )
+    .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let total: u64 = accounts.iter().map(|account| account.lamports).sum();
+    eprintln!(
+        "Found {} signatures account(s) refundable to {} ({} lamports)",
+        accounts.len(),
+        payer.pubkey(),
+        total
+    );
+    for account in &accounts {
+        eprintln!(
+            "  {} guardian set {}, {} signatures, {} lamports",
+            account.pubkey, account.guardian_set_index, account.num_signatures, account.lamports
+        );
+    }
+    if dry_run || accounts.is_empty() {
+        return Ok(());
+    }
+
+    let mut reclaimed = 0u64;
+    let mut failed = 0;
+    for chunk in accounts.chunks(SWEEP_CLOSES_PER_TRANSACTION) {
+        let ixs: Vec<_> = chunk
+            .iter()
+            .map(|account| {
+                build_close_signatures_ix(
+                    &network.verify_vaa_shim,
+                    &account.pubkey,
+                    &payer.pubkey(),
+                )
+            })
+            .collect();
+        let blockhash = rpc_client.get_latest_blockhash()?;
+        let tx =
+            Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[&payer], blockhash);
+        match SolanaConnection::send_and_confirm(&mut rpc_client, &tx) {
+            Ok(signature) => {
+                reclaimed += chunk.iter().map(|account| account.lamports).sum::<u64>();
+                println!("{}", signature);
+            }
+            Err(e) => {
+                failed += chunk.len();
+                eprintln!("Warning: closing {} account(s) failed: {}", chunk.len(), e);
+            }
+        }
+    }
+
+    eprintln!("Reclaimed {} lamports", reclaimed);
+    if failed > 0 {
+        bail!(
+            "{} of {} accounts could not be closed",
+            failed,
+            accounts.len()
+        );
+    }
+    Ok(())
+}
+
+fn cmd_verify(cli: &Cli, vaa_arg: Option<String>) -> Result<()> {
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let network = network_for_cli(cli)?;
+
+    let rpc_client = rpc_client(cli)?;
+    let guardian_set = GuardianSet::fetch(&rpc_client, &network, vaa.guardian_set_index)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let check = guardian_set.check_signatures(&vaa.body, &vaa.signatures);
+
+    println!(
+        "guardian set {} ({} guardians)",
+        guardian_set.index,
+        guardian_set.keys.len()
+    );
+    for (index, key) in guardian_set.keys.iter().enumerate() {
+        let index = index as u8;
+        let status = if check.valid.contains(&index) {
+            "signed"
+        } else if check.invalid.contains(&index) {
+            "INVALID SIGNATURE"
+        } else {
+            "-"
+        };
+        println!("  {:>2} 0x{} {}", index, hex::encode(key), status);
+    }
+    for index in &check.invalid {
+        if *index as usize >= guardian_set.keys.len() {
+            println!("  signature for unknown guardian index {}", index);
+        }
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs() as i64;
+    if guardian_set.is_expired(now) {
+        eprintln!(
+            "Warning: guardian set {} expired at {}",
+            guardian_set.index, guardian_set.expiration_time
+        );
+    }
+
+    println!(
+        "{}/{} valid signatures, quorum {}: {}",
+        check.valid.len(),
+        guardian_set.keys.len(),
+        check.quorum,
+        if check.has_quorum() { "met" } else { "NOT met" }
+    );
+    if !check.has_quorum() {
+        bail!("VAA does not reach quorum");
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_emit(
+    cli: &Cli,
+    payer_path: &str,
+    emitter_path: Option<&str>,
+    emitter_program: Option<&str>,
+    emitter_seed: &str,
+    nonce: u32,
+    finality: Finality,
+    payload_arg: Option<String>,
+) -> Result<()> {
+    let keypairs: Vec<&str> = std::iter::once(payer_path).chain(emitter_path).collect();
+    check_stdin(&payload_arg, &keypairs)?;
+    let payload = read_input(payload_arg)?;
+    let payer = signer_from_path(payer_path, "payer")?;
+    let network = network_for_cli(cli)?;
+    let mut rpc_client = rpc_client(cli)?;
+
+    let posted = match emitter_program {
+        None => {
+            let emitter = emitter_path
+                .map(|path| signer_from_path(path, "emitter"))
+                .transpose()?;
+            wormhole_svm_submit::post_message(
+                &mut rpc_client,
+                &network,
+                &payer,
+                emitter.as_deref().unwrap_or(&*payer),
+                nonce,
+                finality,
+                &payload,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?
+        }
+        Some(program) => {
+            let program = Pubkey::from_str(program).context("invalid emitter program")?;
+            let (emitter, _) = Pubkey::find_program_address(&[emitter_seed.as_bytes()], &program);
+            let sequence = message::next_sequence(&rpc_client, &network, &emitter)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            // Same accounts as the shim instruction, with the PDA signing via CPI.
+            let mut ix = message::build_post_message_ix(
+                &network,
+                &payer.pubkey(),
+                &emitter,
+                nonce,
+                finality,
+                &payload,
+            );
+            ix.program_id = program;
+            ix.accounts[2].is_signer = false;
+            ix.data = ix.data[8..].to_vec();
+
+            let fee = bridge_fee(&rpc_client, &network).map_err(|e| anyhow::anyhow!("{}", e))?;
+            let ixs = [
+                solana_sdk::system_instruction::transfer(
+                    &payer.pubkey(),
+                    &network.fee_collector_address(),
+                    fee,
+                ),
+                ix,
+            ];
+            let blockhash = rpc_client.get_latest_blockhash()?;
+            let tx = Transaction::new_signed_with_payer(
+                &ixs,
+                Some(&payer.pubkey()),
+                &[&payer],
+                blockhash,
+            );
+            let signature = SolanaConnection::send_and_confirm(&mut rpc_client, &tx)
+                .context("posting message")?;
+            PostedMessage {
+                signature,
+                emitter,
+                sequence,
+            }
+        }
+    };
+
+    eprintln!("Posted message ({})", posted.signature);
+    println!(
+        "{}/{}/{}",
+        network.chain_id,
+        hex::encode(posted.emitter.to_bytes()),
+        posted.sequence
+    );
+    Ok(())
+}
+
+fn cmd_guardian_set(cli: &Cli, index: Option<u32>, json: bool) -> Result<()> {
+    let network = network_for_cli(cli)?;
+    let rpc_client = rpc_client(cli)?;
+    let index = match index {
+        Some(index) => index,
+        None => GuardianSet::current_index(&rpc_client, &network)
+            .map_err(|e| anyhow::anyhow!("{}", e))?,
+    };
+    let address = network.guardian_set_address(index);
+    let set =
+        GuardianSet::fetch_address(&rpc_client, &address).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let keys: Vec<String> = set
+        .keys
+        .iter()
+        .map(|key| format!("0x{}", hex::encode(key)))
+        .collect();
 
-    #[command(subcommand)]
-    command: Command,
+    if json {
+        let output = serde_json::json!({
+            "index": set.index,
+            "address": address.to_string(),
+            "keys": keys,
+            "quorum": set.quorum(),
+            "creation_time": set.creation_time,
+            "expiration_time": set.expiration_time,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("index:           {}", set.index);
+    println!("address:         {}", address);
+    println!("creation time:   {}", set.creation_time);
+    if set.expiration_time == 0 {
+        println!("expiration time: never");
+    } else {
+        println!("expiration time: {}", set.expiration_time);
+    }
+    println!("quorum:          {}/{}", set.quorum(), keys.len());
+    for (i, key) in keys.iter().enumerate() {
+        println!("  {:>2} {}", i, key);
+    }
+    Ok(())
 }
 
-#[derive(Subcommand)]
-enum Command {
-    /// Submit a signed VAA to a Solana program
-    Submit {
-        /// Program ID implementing resolve_execute_vaa_v1
-        #[arg(long, env = "PROGRAM_ID")]
-        program_id: String,
+fn cmd_sequence(cli: &Cli, emitter: &str, json: bool) -> Result<()> {
+    let emitter = parse_address(emitter)?;
+    let network = network_for_cli(cli)?;
+    let address = network.emitter_sequence_address(&emitter);
+    let rpc_client = rpc_client(cli)?;
+    let account = rpc_client
+        .get_account_with_commitment(&address, rpc_client.commitment())
+        .with_context(|| format!("fetching sequence account {}", address))?
+        .value;
+    let next_sequence = match &account {
+        Some(account) => {
+            let bytes = account
+                .data
+                .get(0..8)
+                .with_context(|| format!("sequence account {} too short", address))?;
+            u64::from_le_bytes(bytes.try_into().unwrap())
+        }
+        None => 0,
+    };
+    let last_sequence = next_sequence.checked_sub(1);
 
-        /// Payer keypair file
-        #[arg(long, env = "PAYER_KEYPAIR")]
-        payer: String,
+    if json {
+        let output = serde_json::json!({
+            "emitter": emitter.to_string(),
+            "emitter_address": hex::encode(emitter.to_bytes()),
+            "sequence_account": address.to_string(),
+            "next_sequence": next_sequence,
+            "last_sequence": last_sequence,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-        /// Signed VAA (hex string, @file, or stdin)
-        vaa: Option<String>,
-    },
+    eprintln!("emitter:          {}", emitter);
+    eprintln!("emitter address:  {}", hex::encode(emitter.to_bytes()));
+    eprintln!("sequence account: {}", address);
+    match last_sequence {
+        Some(last) => eprintln!("last sequence:    {}", last),
+        None if account.is_none() => eprintln!("no messages posted yet (sequence account missing)"),
+        None => eprintln!("no messages posted yet"),
+    }
+    println!("{}", next_sequence);
+    Ok(())
+}
 
-    /// Fetch and dump an account's data as hex
-    Account {
-        /// Account address, or PDA derivation: <PROGRAM_ID>:seed1:seed2:...
-        address: String,
-    },
+fn cmd_parse(json: bool, vaa_arg: Option<String>) -> Result<()> {
+    let raw = read_input(vaa_arg)?;
+    let vaa = SignedVaa::parse(&raw).context("parsing signed VAA")?;
+    let body = VaaBody::parse(&vaa.body).context("parsing VAA body")?;
+    let signature_indices: Vec<u8> = vaa.signatures.iter().map(|sig| sig[0]).collect();
+    let emitter_address = hex::encode(body.emitter_address);
+    let digest = hex::encode(vaa_digest(&vaa.body));
 
-    /// Derive a PDA for a program
-    ///
-    /// Seeds can be strings or hex (prefix with 0x).
-    ///
-    /// Examples:
-    ///   svm-vaa pda <PROGRAM_ID> foo bar baz
-    ///   svm-vaa pda <PROGRAM_ID> 0xdeadbeef "hello"
-    Pda {
-        /// Program ID to derive PDA for
-        program_id: String,
+    if json {
+        let output = serde_json::json!({
+            "version": raw[0],
+            "guardian_set_index": vaa.guardian_set_index,
+            "signature_indices": signature_indices,
+            "timestamp": body.timestamp,
+            "nonce": body.nonce,
+            "emitter_chain": body.emitter_chain,
+            "emitter_address": emitter_address,
+            "sequence": body.sequence,
+            "consistency_level": body.consistency_level,
+            "digest": digest,
+            "payload": hex::encode(&body.payload),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-        /// Seeds (strings or 0x-prefixed hex)
-        #[arg(required = true, num_args = 1..)]
-        seeds: Vec<String>,
-    },
+    println!("version:            {}", raw[0]);
+    println!("guardian set index: {}", vaa.guardian_set_index);
+    println!("signature indices:  {:?}", signature_indices);
+    println!("timestamp:          {}", body.timestamp);
+    println!("nonce:              {}", body.nonce);
+    println!("emitter chain:      {}", body.emitter_chain);
+    println!("emitter address:    {}", emitter_address);
+    println!("sequence:           {}", body.sequence);
+    println!("consistency level:  {}", body.consistency_level);
+    println!("digest:             {}", digest);
+    println!("payload:            {}", hex::encode(&body.payload));
+    Ok(())
 }
 
-fn main() {
-    if let Err(e) = run() {
-        eprintln!("error: {:#}", e);
-        std::process::exit(1);
+/// Test guardians with the given secret keys, or the devnet guardian alone.
+fn test_guardians_from_keys(keys: &[String]) -> Result<TestGuardianSet> {
+    if keys.is_empty() {
+        return Ok(TestGuardianSet::default());
+    }
+    if keys.len() > u8::MAX as usize {
+        bail!("at most {} guardian keys", u8::MAX);
     }
+    let guardians = keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let key = key.trim_start_matches("0x");
+            let key: [u8; 32] = hex::decode(key)
+                .ok()
+                .and_then(|key| key.try_into().ok())
+                .with_context(|| format!("guardian key {} is not 32 bytes of hex", index))?;
+            // TestGuardian::new panics on keys outside the curve order.
+            libsecp256k1::SecretKey::parse(&key)
+                .map_err(|e| anyhow::anyhow!("invalid guardian key {}: {:?}", index, e))?;
+            Ok(TestGuardian::new(key, index as u8))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(TestGuardianSet::new(guardians))
 }
 
-fn resolve_rpc_url(url: &str) -> String {
-    match url {
-        "d" | "devnet" => "https://api.devnet.solana.com".to_string(),
-        "m" | "mainnet" | "mainnet-beta" => "https://api.mainnet-beta.solana.com".to_string(),
-        "t" | "testnet" => "https://api.testnet.solana.com".to_string(),
-        "l" | "localhost" => "http://127.0.0.1:8899".to_string(),
-        other => other.to_string(),
+/// Test guardians from `--guardian-key`s, or `--guardians` generated from
+/// `--seed`.
+fn test_guardians(keys: &[String], generate: Option<usize>, seed: u64) -> Result<TestGuardianSet> {
+    match generate {
+        Some(count) if count == 0 || count > u8::MAX as usize => {
+            bail!("--guardians must be between 1 and {}", u8::MAX)
+        }
+        Some(count) => Ok(TestGuardianSet::generate(count, seed)),
+        None => test_guardians_from_keys(keys),
     }
 }
 
-fn run() -> Result<()> {
-    let mut cli = Cli::parse();
-    cli.rpc_url = resolve_rpc_url(&cli.rpc_url);
+#[allow(clippy::too_many_arguments)]
+fn cmd_sign_test_vaa(
+    guardian_keys: &[String],
+    generate: Option<usize>,
+    seed: u64,
+    guardian_set_index: u32,
+    timestamp: Option<u32>,
+    nonce: u32,
+    consistency_level: u8,
+    id: &str,
+    payload_arg: Option<String>,
+) -> Result<()> {
+    let guardians = test_guardians(guardian_keys, generate, seed)?;
+    let id: wormholescan::VaaId = id.parse()?;
+    let emitter_address: [u8; 32] = hex::decode(&id.emitter)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("invalid emitter: {}", id.emitter))?;
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as u32,
+    };
+    let vaa = TestVaa::new(
+        id.chain,
+        emitter_address,
+        id.sequence,
+        read_input(payload_arg)?,
+    )
+    .with_timestamp(timestamp)
+    .with_nonce(nonce)
+    .with_consistency_level(consistency_level)
+    .with_guardian_set_index(guardian_set_index);
 
-    match &cli.command {
-        Command::Submit {
-            program_id,
-            payer,
-            vaa,
-        } => cmd_submit(&cli, program_id, payer, vaa.clone()),
-        Command::Account { address } => cmd_account(&cli, address),
-        Command::Pda { program_id, seeds } => cmd_pda(program_id, seeds),
+    eprintln!(
+        "Signed by {} test guardian(s) of guardian set {}:",
+        guardians.len(),
+        guardian_set_index
+    );
+    for guardian in guardians.iter() {
+        eprintln!(
+            "  {:>2} 0x{}",
+            guardian.index,
+            hex::encode(guardian.eth_address)
+        );
     }
+    println!("{}", hex::encode(vaa.sign(&guardians)));
+    Ok(())
 }
 
-fn cmd_submit(
-    cli: &Cli,
-    program_id: &str,
-    payer_path: &str,
-    vaa_arg: Option<String>,
-) -> Result<()> {
+fn cmd_digest(is_body: bool, json: bool, vaa_arg: Option<String>) -> Result<()> {
     let raw = read_input(vaa_arg)?;
+    let body = if is_body {
+        raw
+    } else {
+        SignedVaa::parse(&raw)
+            .context("parsing signed VAA (pass --body for a bare body)")?
+            .body
+    };
+    let digest = hex::encode(vaa_digest(&body));
+    let message_hash = hex::encode(vaa_message_hash(&body));
 
-    let (guardian_set_index, signatures, body) =
-        parse_signed_vaa(&raw).context("parsing signed VAA")?;
+    if json {
+        let output = serde_json::json!({
+            "digest": digest,
+            "message_hash": message_hash,
+            "body_len": body.len(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-    let payer = read_keypair_file(payer_path)
-        .map_err(|e| anyhow::anyhow!("failed to read payer keypair: {}", e))?;
-    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
-    let core_bridge = match &cli.core_bridge {
-        Some(addr) => Pubkey::from_str(addr).context("invalid core bridge ID")?,
-        None => core_bridge_from_rpc_url(&cli.rpc_url)
-            .context("cannot auto-detect core bridge for this RPC URL; use --core-bridge")?,
+    eprintln!("body len:     {}", body.len());
+    eprintln!("message hash: {} (keccak256 of the body)", message_hash);
+    println!("{}", digest);
+    Ok(())
+}
+
+fn cmd_decode_payload(is_payload: bool, json: bool, vaa_arg: Option<String>) -> Result<()> {
+    let raw = read_input(vaa_arg)?;
+    let payload = if is_payload {
+        raw
+    } else {
+        let vaa = SignedVaa::parse(&raw)
+            .context("parsing signed VAA (pass --payload for a bare payload)")?;
+        VaaBody::parse(&vaa.body)
+            .context("parsing VAA body")?
+            .payload
     };
+    let decoded = payload::decode(&payload).with_context(|| {
+        format!(
+            "unrecognized payload format ({} bytes): {}",
+            payload.len(),
+            hex::encode(&payload)
+        )
+    })?;
 
-    let mut rpc_client = solana_client::rpc_client::RpcClient::new(&cli.rpc_url);
+    if json {
+        let fields: serde_json::Map<String, serde_json::Value> = decoded
+            .fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone().into()))
+            .collect();
+        let output = serde_json::json!({
+            "format": decoded.format,
+            "fields": fields,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-    eprintln!("Submitting VAA to {}...", program_id);
-    eprintln!("  Payer: {}", solana_sdk::signer::Signer::pubkey(&payer));
-    eprintln!("  Core Bridge: {}", core_bridge);
-    eprintln!("  Guardian set index: {}", guardian_set_index);
-    eprintln!("  Signatures: {}", signatures.len());
-    eprintln!("  RPC: {}", cli.rpc_url);
+    let width = decoded
+        .fields
+        .iter()
+        .map(|(name, _)| name.len() + 1)
+        .max()
+        .unwrap_or_default()
+        .max("format:".len());
+    println!("{:<width$} {}", "format:", decoded.format);
+    for (name, value) in &decoded.fields {
+        println!("{:<width$} {}", format!("{}:", name), value);
+    }
+    Ok(())
+}
 
-    let tx_sigs = wormhole_svm_submit::broadcast_vaa(
-        &mut rpc_client,
-        &payer,
-        &program_id,
-        guardian_set_index,
-        &body,
-        &signatures,
-        &core_bridge,
-    )
-    .map_err(|e| anyhow::anyhow!("{}", e))?;
+fn cmd_decode_ix(json: bool, data_arg: Option<String>) -> Result<()> {
+    let data = read_input(data_arg)?;
+    let decoded = instruction::decode(&data).with_context(|| {
+        format!(
+            "unrecognized instruction data ({} bytes): {}",
+            data.len(),
+            hex::encode(&data)
+        )
+    })?;
 
-    for sig in &tx_sigs {
-        println!("{}", sig);
+    if json {
+        // Repeated fields (the signatures of post_signatures) become arrays.
+        let mut fields = serde_json::Map::new();
+        for (name, value) in &decoded.fields {
+            match fields.get_mut(*name) {
+                Some(serde_json::Value::Array(values)) => values.push(value.clone().into()),
+                Some(first) => *first = serde_json::json!([first.clone(), value]),
+                None => {
+                    fields.insert(name.to_string(), value.clone().into());
+                }
+            }
+        }
+        let output = serde_json::json!({
+            "program": decoded.program,
+            "instruction": decoded.instruction,
+            "fields": fields,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
     }
 
+    let width = decoded
+        .fields
+        .iter()
+        .map(|(name, _)| name.len() + 1)
+        .max()
+        .unwrap_or_default()
+        .max("instruction:".len());
+    println!("{:<width$} {}", "program:", decoded.program);
+    println!("{:<width$} {}", "instruction:", decoded.instruction);
+    for (name, value) in &decoded.fields {
+        println!("{:<width$} {}", format!("{}:", name), value);
+    }
     Ok(())
 }
 
-/// Parse a signed VAA into (guardian_set_index, signatures, body).
-fn parse_signed_vaa(raw: &[u8]) -> Result<(u32, Vec<[u8; 66]>, Vec<u8>)> {
-    if raw.is_empty() {
-        bail!("empty VAA");
+/// The Wormholescan API matching the RPC URL's cluster, unless overridden.
+fn api_url_for_cli(cli: &Cli, api_url: Option<&str>) -> String {
+    match api_url {
+        Some(url) => wormholescan::resolve_api_url(url),
+        None => {
+            let rpc_url = cli.rpc_url.to_lowercase();
+            if rpc_url.contains("devnet") || rpc_url.contains("testnet") {
+                wormholescan::TESTNET_API_URL.to_string()
+            } else {
+                wormholescan::MAINNET_API_URL.to_string()
+            }
+        }
     }
-    if raw[0] != 1 {
-        bail!("unsupported VAA version: {}", raw[0]);
+}
+
+fn cmd_fetch(cli: &Cli, id: Option<&str>, tx: Option<&str>, api_url: Option<&str>) -> Result<()> {
+    let api_url = api_url_for_cli(cli, api_url);
+
+    let vaas = match (id, tx) {
+        (Some(id), _) => vec![wormholescan::fetch_vaa(&api_url, &id.parse()?)?],
+        (None, Some(tx)) => wormholescan::fetch_vaas_by_tx(&api_url, tx)?,
+        (None, None) => bail!("pass a VAA ID or --tx"),
+    };
+    if vaas.len() > 1 {
+        eprintln!(
+            "Warning: transaction emitted {} VAAs, printing one per line",
+            vaas.len()
+        );
     }
-    if raw.len() < 6 {
-        bail!("VAA too short to contain header");
+    for vaa in &vaas {
+        let parsed = SignedVaa::parse(vaa).context("parsing fetched VAA")?;
+        eprintln!(
+            "guardian set {}, {} signatures, {} byte body",
+            parsed.guardian_set_index,
+            parsed.signatures.len(),
+            parsed.body.len()
+        );
+        println!("{}", hex::encode(vaa));
     }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_watch(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: &str,
+    emitter: &str,
+    api_url: Option<&str>,
+    spy: Option<&str>,
+    interval: Duration,
+    state_dir: &Path,
+    from_sequence: Option<u64>,
+    metrics_addr: Option<&str>,
+) -> Result<()> {
+    let (chain, address) = emitter
+        .split_once('/')
+        .with_context(|| format!("emitter must be <CHAIN>/<EMITTER>: {}", emitter))?;
+    let chain: u16 = chain
+        .parse()
+        .with_context(|| format!("invalid chain ID: {}", chain))?;
+    let address: [u8; 32] = hex::decode(wormholescan::parse_emitter(address)?)?
+        .try_into()
+        .expect("parse_emitter returns 32 bytes");
 
-    let guardian_set_index = u32::from_be_bytes(raw[1..5].try_into().unwrap());
-    let sig_count = raw[5] as usize;
-    let body_offset = 6 + sig_count * 66;
+    let payer = signer_from_path(payer_path, "payer")?;
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let network = network_for_cli(cli)?;
+    let source = match spy {
+        Some(endpoint) => watch::Source::Spy(endpoint.to_string()),
+        None => watch::Source::Wormholescan(api_url_for_cli(cli, api_url)),
+    };
 
-    if raw.len() < body_offset {
-        bail!(
-            "VAA truncated: expected at least {} bytes for {} signatures, got {}",
-            body_offset,
-            sig_count,
-            raw.len()
-        );
+    eprintln!(
+        "Watching {}/{} for {}...",
+        chain,
+        hex::encode(address),
+        program_id
+    );
+    eprintln!("  Payer: {}", payer.pubkey());
+    match &source {
+        watch::Source::Wormholescan(url) => eprintln!("  Wormholescan: {}", url),
+        watch::Source::Spy(endpoint) => eprintln!("  Spy: {}", endpoint),
     }
+    eprintln!("  State: {}", state_dir.display());
+    eprintln!("  RPC: {}", cli.rpc_urls.join(", "));
 
-    let mut signatures = Vec::with_capacity(sig_count);
-    for i in 0..sig_count {
-        let start = 6 + i * 66;
-        let mut sig = [0u8; 66];
-        sig.copy_from_slice(&raw[start..start + 66]);
-        signatures.push(sig);
+    let mut watcher = watch::Watcher::open(
+        failover_connection(cli)?,
+        payer,
+        program_id,
+        network,
+        chain,
+        address,
+        interval,
+        state_dir,
+        from_sequence,
+    )?;
+    if let Some(address) = metrics_addr {
+        metrics::serve(address, watcher.metrics())?;
     }
+    watcher.run(&source)
+}
 
-    let body = raw[body_offset..].to_vec();
-    Ok((guardian_set_index, signatures, body))
+#[allow(clippy::too_many_arguments)]
+fn cmd_serve(
+    cli: &Cli,
+    program_id: &str,
+    payer_path: &str,
+    compute_budget: &ComputeBudgetArgs,
+    listen: &str,
+    token: Option<String>,
+    concurrency: usize,
+    skip_close: bool,
+) -> Result<()> {
+    let payer = Arc::new(keypair_from_path(payer_path, "payer")?);
+    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+    let network = network_for_cli(cli)?;
+    let conn = failover_connection(cli)?;
+    let execute =
+        compute_budget.execute_config(&cli.profile_settings, conn.current(), &program_id)?;
+
+    eprintln!("Serving submissions to {}...", program_id);
+    eprintln!("  Payer: {}", payer.pubkey());
+    eprintln!("  Core Bridge: {}", network.core_bridge);
+    eprintln!("  RPC: {}", cli.rpc_urls.join(", "));
+
+    let submit = pipeline_submitter(cli, payer, program_id, network, &execute, skip_close)?;
+    let config = PipelineConfig {
+        concurrency,
+        ..Default::default()
+    };
+    serve::serve(listen, token, config, submit)
 }
 
-/// Read input from hex string argument, @file reference, or stdin.
-fn read_input(arg: Option<String>) -> Result<Vec<u8>> {
-    match arg {
-        Some(s) if s.starts_with('@') => {
-            let path = &s[1..];
-            let contents =
-                std::fs::read_to_string(path).with_context(|| format!("reading file: {}", path))?;
-            hex::decode(contents.trim()).context("decoding hex from file")
-        }
-        Some(s) => hex::decode(s.trim()).context("decoding hex payload"),
+#[cfg(feature = "bundled-fixtures")]
+#[allow(clippy::too_many_arguments)]
+fn cmd_localnet(
+    payer: Option<&str>,
+    guardians: &TestGuardianSet,
+    guardian_set_index: u32,
+    ledger: &Path,
+    reset: bool,
+    state_dir: &Path,
+    validator: &str,
+    validator_args: &[String],
+) -> Result<()> {
+    let mut args = localnet::write_fixtures(state_dir, guardians, guardian_set_index)?;
+    let payer = match payer {
+        Some(payer) => parse_payer_pubkey(payer)?,
         None => {
-            if io::stdin().is_terminal() {
-                bail!("no VAA provided; pass as argument, @file, or pipe to stdin");
+            let path = state_dir.join("payer.json");
+            if !path.exists() {
+                solana_sdk::signature::write_keypair_file(&Keypair::new(), &path)
+                    .map_err(|e| anyhow::anyhow!("writing {}: {}", path.display(), e))?;
             }
-            let mut buf = String::new();
-            io::stdin().read_to_string(&mut buf)?;
-            hex::decode(buf.trim()).context("decoding hex from stdin")
+            keypair_from_path(&path.display().to_string(), "payer")?.pubkey()
         }
+    };
+    args.extend([
+        "--mint".to_string(),
+        payer.to_string(),
+        "--ledger".to_string(),
+        ledger.display().to_string(),
+    ]);
+    if reset {
+        args.push("--reset".to_string());
+    } else if ledger.exists() {
+        log::warn!(
+            "resuming the ledger in {}; programs, accounts and the payer only apply with --reset",
+            ledger.display()
+        );
+    }
+    args.extend_from_slice(validator_args);
+
+    let (guardian_set, _) =
+        find_guardian_set_address(guardian_set_index.to_be_bytes(), &CORE_BRIDGE_PROGRAM_ID);
+    eprintln!("Starting {}...", validator);
+    eprintln!("  Payer: {}", payer);
+    eprintln!("  Core Bridge: {}", CORE_BRIDGE_PROGRAM_ID);
+    eprintln!("  Verify VAA Shim: {}", VERIFY_VAA_SHIM_PROGRAM_ID);
+    eprintln!("  Post Message Shim: {}", POST_MESSAGE_SHIM_PROGRAM_ID);
+    eprintln!(
+        "  Guardian set {}: {} ({} guardian(s))",
+        guardian_set_index,
+        guardian_set,
+        guardians.len()
+    );
+    eprintln!(
+        "Use it with: svm-vaa -u l --core-bridge {} ...",
+        CORE_BRIDGE_PROGRAM_ID
+    );
+
+    let status = std::process::Command::new(validator)
+        .args(&args)
+        .status()
+        .with_context(|| format!("running {} (is the Solana CLI installed?)", validator))?;
+    if !status.success() {
+        bail!("{} exited with {}", validator, status);
     }
+    Ok(())
 }
 
-const CORE_BRIDGE_MAINNET: Pubkey =
-    wormhole_svm_definitions::solana::mainnet::CORE_BRIDGE_PROGRAM_ID;
-const CORE_BRIDGE_DEVNET: Pubkey = wormhole_svm_definitions::solana::devnet::CORE_BRIDGE_PROGRAM_ID;
+fn cmd_airdrop(
+    cli: &Cli,
+    amount: f64,
+    recipient: Option<&str>,
+    payer: &Option<String>,
+) -> Result<()> {
+    if network_from_rpc_url(&cli.rpc_url) == Some(WormholeNetwork::SOLANA_MAINNET) {
+        bail!("airdrops are only available on devnet, testnet and local validators");
+    }
+    if amount.is_nan() || amount <= 0.0 {
+        bail!("invalid amount: {}", amount);
+    }
+    let lamports = (amount * LAMPORTS_PER_SOL as f64).round() as u64;
+    let recipient = match recipient {
+        Some(recipient) => parse_address(recipient)?,
+        None => parse_payer_pubkey(payer_path(cli, payer)?)?,
+    };
+
+    let rpc_client = rpc_client(cli)?;
+    eprintln!("Requesting {} SOL for {}...", amount, recipient);
+    let signature = rpc_client
+        .request_airdrop(&recipient, lamports)
+        .context("requesting airdrop (public faucets are rate limited; try a smaller amount)")?;
+    rpc_client
+        .poll_for_signature_with_commitment(&signature, CommitmentConfig::confirmed())
+        .with_context(|| format!("confirming airdrop {}", signature))?;
+    let balance = rpc_client
+        .get_balance(&recipient)
+        .with_context(|| format!("fetching the balance of {}", recipient))?;
+    eprintln!("Balance: {} SOL", balance as f64 / LAMPORTS_PER_SOL as f64);
+    println!("{}", signature);
+    Ok(())
+}
 
-fn cmd_account(cli: &Cli, address: &str) -> Result<()> {
+fn cmd_account(cli: &Cli, address: &str, json: bool, raw: bool) -> Result<()> {
     let pubkey = parse_address(address)?;
-    let rpc = solana_client::rpc_client::RpcClient::new(&cli.rpc_url);
+    let rpc = rpc_client(cli)?;
     let account = rpc
         .get_account(&pubkey)
         .with_context(|| format!("fetching account {}", pubkey))?;
+    let decoded = if raw {
+        None
+    } else {
+        account::decode(&network_for_cli(cli)?, &pubkey, &account)
+    };
+
+    if json {
+        let output = serde_json::json!({
+            "address": pubkey.to_string(),
+            "owner": account.owner.to_string(),
+            "lamports": account.lamports,
+            "data_len": account.data.len(),
+            "decoded": decoded.as_ref().map(|decoded| decoded.to_json()),
+            "data": hex::encode(&account.data),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
     eprintln!("address: {}", pubkey);
     eprintln!("owner:   {}", account.owner);
     eprintln!("lamports: {}", account.lamports);
     eprintln!("data len: {}", account.data.len());
-    println!("{}", hex::encode(&account.data));
+    match decoded {
+        Some(decoded) => decoded.print(),
+        None => println!("{}", hex::encode(&account.data)),
+    }
+    Ok(())
+}
+
+fn cmd_fees(cli: &Cli, json: bool) -> Result<()> {
+    let network = network_for_cli(cli)?;
+    let config_address = network.core_bridge_config_address();
+    let rpc = rpc_client(cli)?;
+    let account = rpc
+        .get_account(&config_address)
+        .with_context(|| format!("fetching core bridge config {}", config_address))?;
+    let Some(account::KnownAccount::BridgeConfig {
+        guardian_set_index,
+        guardian_set_ttl,
+        fee,
+        ..
+    }) = account::decode(&network, &config_address, &account)
+    else {
+        bail!(
+            "{} is not a config of core bridge {}",
+            config_address,
+            network.core_bridge
+        );
+    };
+    let fee_collector = network.fee_collector_address();
+
+    if json {
+        let output = serde_json::json!({
+            "core_bridge": network.core_bridge.to_string(),
+            "config": config_address.to_string(),
+            "message_fee": fee,
+            "fee_collector": fee_collector.to_string(),
+            "guardian_set_index": guardian_set_index,
+            "guardian_set_ttl": guardian_set_ttl,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+    println!(
+        "message fee:        {} lamports ({} SOL)",
+        fee,
+        fee as f64 / LAMPORTS_PER_SOL as f64
+    );
+    println!("fee collector:      {}", fee_collector);
+    println!("guardian set index: {}", guardian_set_index);
+    println!(
+        "guardian set ttl:   {}s ({:.1}h)",
+        guardian_set_ttl,
+        guardian_set_ttl as f64 / 3600.0
+    );
+    Ok(())
+}
+
+#[cfg(feature = "bundled-fixtures")]
+fn cmd_program_info(cli: &Cli, expectations: &[String], json: bool) -> Result<()> {
+    let network = network_for_cli(cli)?;
+    let addresses = [
+        network.core_bridge,
+        network.verify_vaa_shim,
+        network.post_message_shim,
+    ];
+    let mut expected: Vec<(&str, String, &str)> = program_info::PROGRAMS
+        .iter()
+        .map(|(name, bytes)| (*name, program_info::code_hash(bytes), "bundled"))
+        .collect();
+    for expectation in expectations {
+        let (name, hash) = expectation
+            .split_once('=')
+            .with_context(|| format!("expected PROGRAM=SHA256: {}", expectation))?;
+        let Some(entry) = expected.iter_mut().find(|(known, _, _)| *known == name) else {
+            bail!(
+                "unknown program {}; expected core-bridge, verify-vaa-shim or post-message-shim",
+                name
+            );
+        };
+        if hash.len() != 64 || hex::decode(hash).is_err() {
+            bail!("the hash of {} must be 32 bytes of hex", name);
+        }
+        *entry = (entry.0, hash.to_lowercase(), "--expect");
+    }
+
+    let rpc_client = rpc_client(cli)?;
+    let mut reports = Vec::new();
+    let mut failing = 0;
+    for ((name, expected, source), address) in expected.iter().zip(addresses) {
+        let deployed = program_info::fetch(&rpc_client, &address)?;
+        let status = program_info::Status::of(deployed.as_ref(), expected);
+        if !matches!(status, program_info::Status::Match) {
+            failing += 1;
+        }
+        if json {
+            reports.push(serde_json::json!({
+                "program": name,
+                "address": address.to_string(),
+                "status": status.name(),
+                "expected_sha256": expected,
+                "expected_source": source,
+                "deployed": deployed.as_ref().map(|deployed| deployed.to_json()),
+            }));
+            continue;
+        }
+        println!("{:<18} {}  {}", name, address, status.name());
+        if let Some(deployed) = &deployed {
+            println!("  sha256:            {}", deployed.sha256);
+        }
+        println!("  expected:          {} ({})", expected, source);
+        if let Some(deployed) = &deployed {
+            match deployed.upgrade_authority {
+                Some(authority) => println!("  upgrade authority: {}", authority),
+                None => println!("  upgrade authority: none (immutable)"),
+            }
+            if let Some(slot) = deployed.deployed_slot {
+                println!("  deployed at slot:  {}", slot);
+            }
+        }
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    }
+    if failing > 0 {
+        bail!("{} program(s) don't match the expected build", failing);
+    }
     Ok(())
 }
 
@@ -227,13 +3478,7 @@ fn parse_address(address: &str) -> Result<Pubkey> {
         let program_id = Pubkey::from_str(parts[0]).context("invalid program ID in PDA address")?;
         let seed_bytes: Vec<Vec<u8>> = parts[1..]
             .iter()
-            .map(|s| {
-                if let Some(hex_str) = s.strip_prefix("0x") {
-                    hex::decode(hex_str).with_context(|| format!("invalid hex seed: {}", s))
-                } else {
-                    Ok(s.as_bytes().to_vec())
-                }
-            })
+            .map(|s| parse_seed(s))
             .collect::<Result<_>>()?;
         let seed_slices: Vec<&[u8]> = seed_bytes.iter().map(|s| s.as_slice()).collect();
         let (pda, bump) = Pubkey::find_program_address(&seed_slices, &program_id);
@@ -244,35 +3489,198 @@ fn parse_address(address: &str) -> Result<Pubkey> {
     }
 }
 
-fn cmd_pda(program_id: &str, seeds: &[String]) -> Result<()> {
-    let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+/// A PDA seed: a string, or hex with a `0x` prefix.
+fn parse_seed(seed: &str) -> Result<Vec<u8>> {
+    match seed.strip_prefix("0x") {
+        Some(hex_str) => {
+            hex::decode(hex_str).with_context(|| format!("invalid hex seed: {}", seed))
+        }
+        None => Ok(seed.as_bytes().to_vec()),
+    }
+}
 
-    let seed_bytes: Vec<Vec<u8>> = seeds
-        .iter()
-        .map(|s| {
-            if let Some(hex_str) = s.strip_prefix("0x") {
-                hex::decode(hex_str).with_context(|| format!("invalid hex seed: {}", s))
-            } else {
-                Ok(s.as_bytes().to_vec())
+fn cmd_pda(cli: &Cli, program_id: &str, seeds: &[String]) -> Result<()> {
+    let (pda, bump) = match named_pda(cli, program_id, seeds)? {
+        Some(derived) => derived,
+        None => {
+            let program_id = Pubkey::from_str(program_id).context("invalid program ID")?;
+            if seeds.is_empty() {
+                bail!("no seeds given");
             }
-        })
-        .collect::<Result<_>>()?;
 
-    let seed_slices: Vec<&[u8]> = seed_bytes.iter().map(|s| s.as_slice()).collect();
-    let (pda, bump) = Pubkey::find_program_address(&seed_slices, &program_id);
+            let seed_bytes: Vec<Vec<u8>> =
+                seeds.iter().map(|s| parse_seed(s)).collect::<Result<_>>()?;
+
+            let seed_slices: Vec<&[u8]> = seed_bytes.iter().map(|s| s.as_slice()).collect();
+            Pubkey::find_program_address(&seed_slices, &program_id)
+        }
+    };
 
     println!("{}", pda);
     eprintln!("bump: {}", bump);
     Ok(())
 }
 
-fn core_bridge_from_rpc_url(rpc_url: &str) -> Option<Pubkey> {
+/// The named derivation `name` of `svm-vaa pda` with its arguments, or
+/// `None` if `name` isn't one.
+fn named_pda(cli: &Cli, name: &str, args: &[String]) -> Result<Option<(Pubkey, u8)>> {
+    let one_arg = |what: &str| match args {
+        [arg] => Ok(arg.as_str()),
+        _ => Err(anyhow::anyhow!("{} takes {}", name, what)),
+    };
+    let derived = match name {
+        "guardian-set" => {
+            let index: u32 = one_arg("a guardian set index")?
+                .parse()
+                .context("invalid guardian set index")?;
+            find_guardian_set_address(index.to_be_bytes(), &network_for_cli(cli)?.core_bridge)
+        }
+        "sequence" => {
+            let emitter = parse_address(one_arg("an emitter")?)?;
+            find_emitter_sequence_address(&emitter, &network_for_cli(cli)?.core_bridge)
+        }
+        "shim-message" => {
+            let emitter = parse_address(one_arg("an emitter")?)?;
+            find_shim_message_address(&emitter, &network_for_cli(cli)?.post_message_shim)
+        }
+        "fee-collector" => {
+            if !args.is_empty() {
+                bail!("fee-collector takes no arguments");
+            }
+            find_fee_collector_address(&network_for_cli(cli)?.core_bridge)
+        }
+        "event-authority" => {
+            let program_id = match args {
+                [] => network_for_cli(cli)?.post_message_shim,
+                [program_id] => Pubkey::from_str(program_id).context("invalid program ID")?,
+                _ => bail!("event-authority takes at most a program ID"),
+            };
+            find_event_authority_address(&program_id)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(derived))
+}
+
+fn cmd_completions(shell: clap_complete::Shell) -> Result<()> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut io::stdout());
+    Ok(())
+}
+
+fn cmd_man(out_dir: Option<&Path>) -> Result<()> {
+    let command = Cli::command();
+    match out_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+            clap_mangen::generate_to(command, dir)
+                .with_context(|| format!("writing man pages to {}", dir.display()))?;
+            eprintln!("Wrote man pages to {}", dir.display());
+        }
+        None => clap_mangen::Man::new(command).render(&mut io::stdout())?,
+    }
+    Ok(())
+}
+
+/// RPC client for the first `--rpc-url`, sending any `--rpc-header`s.
+fn rpc_client(cli: &Cli) -> Result<RpcClient> {
+    rpc_client_for(cli, &cli.rpc_url)
+}
+
+/// A connection submitting through every `--rpc-url` in turn, moving to the
+/// next one on transport errors.
+fn failover_connection(cli: &Cli) -> Result<FailoverConnection<RpcClientWithCommitment>> {
+    failover_clients(&cli.rpc_urls, &rpc_connection_config(cli)?, cli.commitment)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// Clients for `urls`, failing over on transport errors and simulating and
+/// confirming at `commitment` (confirmed by default).
+fn failover_clients(
+    urls: &[String],
+    config: &RpcConnectionConfig,
+    commitment: Option<CommitmentConfig>,
+) -> Result<FailoverConnection<RpcClientWithCommitment>, SubmitError> {
+    let commitment = commitment.unwrap_or_else(CommitmentConfig::confirmed);
+    let clients = urls
+        .iter()
+        .map(|url| {
+            let client = rpc_client_with_config(url, config)?;
+            Ok(RpcClientWithCommitment::new(client, commitment))
+        })
+        .collect::<Result<_, SubmitError>>()?;
+    Ok(FailoverConnection::new(clients, is_transport_error))
+}
+
+/// RPC client for `url`, sending any `--rpc-header`s.
+fn rpc_client_for(cli: &Cli, url: &str) -> Result<RpcClient> {
+    rpc_client_with_config(url, &rpc_connection_config(cli)?).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// HTTP options from `--rpc-header`.
+fn rpc_connection_config(cli: &Cli) -> Result<RpcConnectionConfig> {
+    let headers = cli
+        .rpc_headers
+        .iter()
+        .map(|header| {
+            let (name, value) = header.split_once(':').with_context(|| {
+                format!("invalid RPC header (expected NAME: VALUE): {}", header)
+            })?;
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect::<Result<_>>()?;
+    Ok(RpcConnectionConfig {
+        headers,
+        ..Default::default()
+    })
+}
+
+fn network_from_rpc_url(rpc_url: &str) -> Option<WormholeNetwork> {
     let url = rpc_url.to_lowercase();
     if url.contains("mainnet") {
-        Some(CORE_BRIDGE_MAINNET)
+        Some(WormholeNetwork::SOLANA_MAINNET)
     } else if url.contains("devnet") {
-        Some(CORE_BRIDGE_DEVNET)
+        Some(WormholeNetwork::SOLANA_DEVNET)
     } else {
         None
     }
 }
+
+/// Wormhole network from `--rpc-url`, with `--core-bridge`, `--verify-vaa-shim` and
+/// `--post-message-shim` taking precedence.
+///
+/// An explicit core bridge on an unrecognized URL keeps the mainnet shim addresses unless
+/// they are overridden as well.
+fn network_for_cli(cli: &Cli) -> Result<WormholeNetwork> {
+    let detected = network_from_rpc_url(&cli.rpc_url);
+    let mut network = match &cli.core_bridge {
+        Some(addr) => WormholeNetwork {
+            core_bridge: Pubkey::from_str(addr).context("invalid core bridge ID")?,
+            ..detected.unwrap_or_default()
+        },
+        None => detected
+            .context("cannot auto-detect core bridge for this RPC URL; use --core-bridge")?,
+    };
+    if let Some(addr) = &cli.verify_vaa_shim {
+        network.verify_vaa_shim = Pubkey::from_str(addr).context("invalid Verify VAA Shim ID")?;
+    }
+    if let Some(addr) = &cli.post_message_shim {
+        network.post_message_shim =
+            Pubkey::from_str(addr).context("invalid Post Message Shim ID")?;
+    }
+    Ok(network)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stdin_is_read_once() {
+        assert!(check_stdin(&None, &["stdin"]).is_err());
+        assert!(check_stdin(&None, &["payer.json", "stdin"]).is_err());
+        assert!(check_stdin(&Some("@vaa.hex".to_string()), &["stdin"]).is_ok());
+        assert!(check_stdin(&None, &["payer.json", "prompt://"]).is_ok());
+    }
+}
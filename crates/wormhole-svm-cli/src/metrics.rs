@@ -0,0 +1,169 @@
+//! Prometheus metrics of `svm-vaa watch`, served on `--metrics-addr`.
+//!
+//! `GET /metrics` answers in the Prometheus text format:
+//!
+//! | Metric | Type | |
+//! |--------|------|-|
+//! | `svm_vaa_watch_vaas_seen_total` | counter | VAAs picked up for submission |
+//! | `svm_vaa_watch_vaas_submitted_total` | counter | VAAs fully broadcast |
+//! | `svm_vaa_watch_vaas_failed_total` | counter | failed submission attempts |
+//! | `svm_vaa_watch_vaas_dead_lettered_total` | counter | VAAs set aside as impossible to submit |
+//! | `svm_vaa_watch_lamports_spent_total` | counter | payer balance spent on submissions |
+//! | `svm_vaa_watch_latest_sequence` | gauge | highest sequence received |
+//! | `svm_vaa_watch_next_sequence` | gauge | next sequence to submit |
+//! | `svm_vaa_watch_lag_seconds` | gauge | age of the last submitted VAA when it landed |
+//!
+//! The sequence gauges are only reported once known. Metrics start from zero
+//! whenever the watcher starts.
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use anyhow::Result;
+use tiny_http::{Header, Method, Response, Server};
+
+#[derive(Default)]
+struct Values {
+    seen: u64,
+    submitted: u64,
+    failed: u64,
+    dead_lettered: u64,
+    lamports_spent: u64,
+    latest_sequence: Option<u64>,
+    next_sequence: Option<u64>,
+    lag_seconds: Option<u64>,
+    /// Last sequence picked up, so that retries aren't counted as new VAAs.
+    last_attempted: Option<u64>,
+}
+
+/// Metrics shared between the watcher and the HTTP server.
+#[derive(Default)]
+pub struct Metrics {
+    values: Mutex<Values>,
+}
+
+impl Metrics {
+    /// A VAA with `sequence` was received from the source.
+    pub fn received(&self, sequence: u64) {
+        let mut values = self.values.lock().unwrap();
+        values.latest_sequence = values.latest_sequence.max(Some(sequence));
+    }
+
+    /// The VAA with `sequence` is about to be submitted.
+    pub fn attempted(&self, sequence: u64) {
+        let mut values = self.values.lock().unwrap();
+        if values.last_attempted != Some(sequence) {
+            values.seen += 1;
+            values.last_attempted = Some(sequence);
+        }
+        values.latest_sequence = values.latest_sequence.max(Some(sequence));
+    }
+
+    /// A VAA was broadcast, `lag_seconds` after it was emitted, spending
+    /// `lamports` (if the balances could be fetched).
+    pub fn submitted(&self, lag_seconds: u64, lamports: Option<u64>) {
+        let mut values = self.values.lock().unwrap();
+        values.submitted += 1;
+        values.lamports_spent += lamports.unwrap_or(0);
+        values.lag_seconds = Some(lag_seconds);
+    }
+
+    pub fn failed(&self) {
+        self.values.lock().unwrap().failed += 1;
+    }
+
+    /// A VAA was set aside because it can never be submitted.
+    pub fn dead_lettered(&self) {
+        self.values.lock().unwrap().dead_lettered += 1;
+    }
+
+    pub fn set_next_sequence(&self, next: u64) {
+        self.values.lock().unwrap().next_sequence = Some(next);
+    }
+
+    /// The metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let values = self.values.lock().unwrap();
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: Option<u64>| {
+            if let Some(value) = value {
+                let _ = writeln!(out, "# HELP {} {}", name, help);
+                let _ = writeln!(out, "# TYPE {} {}", name, kind);
+                let _ = writeln!(out, "{} {}", name, value);
+            }
+        };
+        metric(
+            "svm_vaa_watch_vaas_seen_total",
+            "counter",
+            "VAAs picked up for submission.",
+            Some(values.seen),
+        );
+        metric(
+            "svm_vaa_watch_vaas_submitted_total",
+            "counter",
+            "VAAs fully broadcast.",
+            Some(values.submitted),
+        );
+        metric(
+            "svm_vaa_watch_vaas_failed_total",
+            "counter",
+            "Failed submission attempts.",
+            Some(values.failed),
+        );
+        metric(
+            "svm_vaa_watch_vaas_dead_lettered_total",
+            "counter",
+            "VAAs set aside as impossible to submit.",
+            Some(values.dead_lettered),
+        );
+        metric(
+            "svm_vaa_watch_lamports_spent_total",
+            "counter",
+            "Payer balance spent on submissions, in lamports.",
+            Some(values.lamports_spent),
+        );
+        metric(
+            "svm_vaa_watch_latest_sequence",
+            "gauge",
+            "Highest sequence received.",
+            values.latest_sequence,
+        );
+        metric(
+            "svm_vaa_watch_next_sequence",
+            "gauge",
+            "Next sequence to submit.",
+            values.next_sequence,
+        );
+        metric(
+            "svm_vaa_watch_lag_seconds",
+            "gauge",
+            "Age of the last submitted VAA when it landed.",
+            values.lag_seconds,
+        );
+        out
+    }
+}
+
+/// Serve `GET /metrics` on `address` from its own thread.
+pub fn serve(address: &str, metrics: Arc<Metrics>) -> Result<()> {
+    let server =
+        Server::http(address).map_err(|e| anyhow::anyhow!("listening on {}: {}", address, e))?;
+    log::info!("Metrics on http://{}/metrics", address);
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if *request.method() == Method::Get && request.url() == "/metrics" {
+                let content_type =
+                    Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..])
+                        .expect("valid header");
+                Response::from_string(metrics.render()).with_header(content_type)
+            } else {
+                Response::from_string("not found\n").with_status_code(404)
+            };
+            if let Err(e) = request.respond(response) {
+                log::warn!("responding to a metrics request: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! Transaction files for offline signing: `svm-vaa export-tx` writes them,
+//! `svm-vaa sign-tx` adds signatures (on the air-gapped machine) and
+//! `svm-vaa import-and-send` submits them.
+//!
+//! Each transaction of the flow is a JSON file in the export directory,
+//! named so that sorting by name gives the submission order:
+//!
+//! ```json
+//! {
+//!   "index": 1,
+//!   "kind": "group-0",
+//!   "message": "<base64 serialized message>",
+//!   "required_signers": ["<PUBKEY>"],
+//!   "signatures": { "<PUBKEY>": "<SIGNATURE>" },
+//!   "nonce_account": "<PUBKEY>"
+//! }
+//! ```
+//!
+//! `signatures` holds every signature made so far, starting with those of
+//! the keypairs generated during export. `nonce_account` is only present
+//! when the transaction uses a durable nonce instead of a recent blockhash.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_client::nonce_utils;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use wormhole_svm_submit::{DurableNonce, OfflineBroadcast};
+
+/// One exported transaction.
+#[derive(Serialize, Deserialize)]
+pub struct TxFile {
+    pub index: usize,
+    /// `post-signatures`, `group-<N>` or `close-signatures`.
+    pub kind: String,
+    /// The serialized message, base64.
+    pub message: String,
+    /// Signers that were missing at export time, in message order.
+    pub required_signers: Vec<String>,
+    /// Signatures by signer pubkey.
+    pub signatures: BTreeMap<String, String>,
+    /// The durable nonce account the transaction advances, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_account: Option<String>,
+}
+
+impl TxFile {
+    pub fn message(&self) -> Result<Message> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.message)
+            .context("invalid base64 message")?;
+        bincode::deserialize(&bytes).context("invalid message")
+    }
+
+    /// Signers whose signature is still missing.
+    pub fn missing_signers(&self) -> Result<Vec<Pubkey>> {
+        let message = self.message()?;
+        Ok(signer_keys(&message)
+            .iter()
+            .filter(|pubkey| !self.signatures.contains_key(&pubkey.to_string()))
+            .copied()
+            .collect())
+    }
+
+    /// The transaction with the signatures collected so far (missing ones
+    /// left as default), after checking each against the message.
+    pub fn transaction(&self) -> Result<Transaction> {
+        let message = self.message()?;
+        let message_data = message.serialize();
+        let mut signatures = Vec::new();
+        for pubkey in signer_keys(&message) {
+            let signature = match self.signatures.get(&pubkey.to_string()) {
+                Some(signature) => {
+                    let signature = Signature::from_str(signature)
+                        .with_context(|| format!("invalid signature of {}", pubkey))?;
+                    if !signature.verify(pubkey.as_ref(), &message_data) {
+                        bail!("the signature of {} doesn't match the message", pubkey);
+                    }
+                    signature
+                }
+                None => Signature::default(),
+            };
+            signatures.push(signature);
+        }
+        Ok(Transaction {
+            signatures,
+            message,
+        })
+    }
+}
+
+fn signer_keys(message: &Message) -> &[Pubkey] {
+    &message.account_keys[..message.header.num_required_signatures as usize]
+}
+
+/// Write the transactions of `export` to `dir`, returning the files written.
+pub fn write_export(dir: &Path, export: &OfflineBroadcast) -> Result<Vec<(PathBuf, TxFile)>> {
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let count = export.transactions.len();
+    let mut written = Vec::with_capacity(count);
+    for (index, unsigned) in export.transactions.iter().enumerate() {
+        let kind = match index {
+            0 => "post-signatures".to_string(),
+            index if index == count - 1 => "close-signatures".to_string(),
+            index => format!("group-{}", index - 1),
+        };
+        let transaction = &unsigned.transaction;
+        let signatures = signer_keys(&transaction.message)
+            .iter()
+            .zip(&transaction.signatures)
+            .filter(|(_, signature)| **signature != Signature::default())
+            .map(|(pubkey, signature)| (pubkey.to_string(), signature.to_string()))
+            .collect();
+        let file = TxFile {
+            index,
+            message: base64::engine::general_purpose::STANDARD
+                .encode(transaction.message.serialize()),
+            required_signers: unsigned
+                .required_signers
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            signatures,
+            kind,
+            nonce_account: unsigned.nonce_account.map(|account| account.to_string()),
+        };
+        let path = dir.join(format!("{:02}-{}.json", index, file.kind));
+        write_file(&path, &file)?;
+        written.push((path, file));
+    }
+    Ok(written)
+}
+
+/// The current nonce of the durable nonce `account`.
+pub fn durable_nonce(rpc_client: &RpcClient, account: &Pubkey) -> Result<DurableNonce> {
+    let data = nonce_utils::get_account(rpc_client, account)
+        .and_then(|account| nonce_utils::data_from_account(&account))
+        .with_context(|| format!("reading nonce account {}", account))?;
+    Ok(DurableNonce {
+        account: *account,
+        authority: data.authority,
+        nonce: data.blockhash(),
+    })
+}
+
+/// The transaction files of `dir`, in submission order.
+pub fn read_export(dir: &Path) -> Result<Vec<(PathBuf, TxFile)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+    paths.sort();
+    if paths.is_empty() {
+        bail!("no transaction files in {}", dir.display());
+    }
+    paths
+        .into_iter()
+        .map(|path| {
+            let contents =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let file = serde_json::from_str(&contents)
+                .with_context(|| format!("parsing {}", path.display()))?;
+            Ok((path, file))
+        })
+        .collect()
+}
+
+pub fn write_file(path: &Path, file: &TxFile) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(file)? + "\n")
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Sign every transaction of `dir` that `signer` must sign. Returns how many
+/// were signed.
+pub fn sign_export(dir: &Path, signer: &dyn Signer) -> Result<usize> {
+    let pubkey = signer.pubkey();
+    let mut signed = 0;
+    for (path, mut file) in read_export(dir)? {
+        let message = file.message()?;
+        if !signer_keys(&message).contains(&pubkey) {
+            continue;
+        }
+        let signature = signer
+            .try_sign_message(&message.serialize())
+            .with_context(|| format!("signing {}", path.display()))?;
+        file.signatures
+            .insert(pubkey.to_string(), signature.to_string());
+        write_file(&path, &file)?;
+        signed += 1;
+    }
+    Ok(signed)
+}
@@ -0,0 +1,439 @@
+//! Decoding of common VAA payload formats for `svm-vaa decode-payload`:
+//! core bridge and token bridge governance, token bridge transfers and
+//! attestations, and NTT transfers sent through the Wormhole transceiver.
+//!
+//! Payloads are big-endian. Detection goes from the most to the least
+//! specific format: governance payloads start with a 32-byte module name and
+//! NTT messages with a 4-byte prefix, while token bridge payloads are only
+//! recognized by their payload ID and exact length.
+
+/// A decoded payload: its format and named fields, in payload order.
+pub struct DecodedPayload {
+    pub format: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Decode `payload`, if it has one of the known formats.
+pub fn decode(payload: &[u8]) -> Option<DecodedPayload> {
+    decode_governance(payload)
+        .or_else(|| decode_ntt(payload))
+        .or_else(|| decode_token_bridge(payload))
+}
+
+/// Token bridge transfers and attestations have a fixed size (a transfer
+/// with payload has at least this size).
+const TRANSFER_LEN: usize = 133;
+const ATTEST_META_LEN: usize = 100;
+
+/// Prefix of a Wormhole transceiver message.
+const WH_TRANSCEIVER_PREFIX: [u8; 4] = [0x99, 0x45, 0xFF, 0x10];
+/// Prefix of an NTT native token transfer.
+const NTT_TRANSFER_PREFIX: [u8; 4] = [0x99, 0x4E, 0x54, 0x54];
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// A 32-byte field as hex.
+    fn address(&mut self) -> Option<String> {
+        Some(hex::encode(self.bytes(32)?))
+    }
+
+    /// A uint256 as decimal.
+    fn u256(&mut self) -> Option<String> {
+        Some(u256_to_string(self.bytes(32)?))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+/// Decimal if the value fits in a u128, hex otherwise.
+fn u256_to_string(bytes: &[u8]) -> String {
+    let (high, low) = bytes.split_at(16);
+    if high.iter().all(|&b| b == 0) {
+        u128::from_be_bytes(low.try_into().unwrap()).to_string()
+    } else {
+        format!("0x{}", hex::encode(bytes))
+    }
+}
+
+/// A fixed-size string field (token symbol or name), with the zero padding
+/// removed.
+fn padded_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// A governance module name, right-aligned in 32 bytes.
+fn module(name: &str) -> [u8; 32] {
+    let mut module = [0u8; 32];
+    module[32 - name.len()..].copy_from_slice(name.as_bytes());
+    module
+}
+
+fn decode_governance(payload: &[u8]) -> Option<DecodedPayload> {
+    let mut r = Reader { data: payload };
+    let name = r.bytes(32)?;
+    let action = r.u8()?;
+    // Recovering the chain ID only applies to chains whose EVM chain ID
+    // changed, so the action has no target chain.
+    let recover_chain_id =
+        (name == module("Core") && action == 5) || (name == module("TokenBridge") && action == 3);
+    let chain = if recover_chain_id {
+        None
+    } else {
+        Some(r.u16()?)
+    };
+
+    let (format, mut fields) = if name == module("Core") {
+        match action {
+            1 => (
+                "core bridge governance: contract upgrade",
+                vec![("new contract", r.address()?)],
+            ),
+            2 => {
+                let new_index = r.u32()?;
+                let num_keys = r.u8()?;
+                let mut fields = vec![
+                    ("new guardian set index", new_index.to_string()),
+                    ("guardians", num_keys.to_string()),
+                ];
+                for _ in 0..num_keys {
+                    fields.push(("guardian", format!("0x{}", hex::encode(r.bytes(20)?))));
+                }
+                ("core bridge governance: guardian set upgrade", fields)
+            }
+            3 => (
+                "core bridge governance: set message fee",
+                vec![("fee", r.u256()?)],
+            ),
+            4 => (
+                "core bridge governance: transfer fees",
+                vec![("amount", r.u256()?), ("recipient", r.address()?)],
+            ),
+            5 => (
+                "core bridge governance: recover chain ID",
+                vec![
+                    ("EVM chain ID", r.u256()?),
+                    ("new chain ID", r.u16()?.to_string()),
+                ],
+            ),
+            _ => return None,
+        }
+    } else if name == module("TokenBridge") {
+        match action {
+            1 => (
+                "token bridge governance: register chain",
+                vec![
+                    ("emitter chain", r.u16()?.to_string()),
+                    ("emitter address", r.address()?),
+                ],
+            ),
+            2 => (
+                "token bridge governance: contract upgrade",
+                vec![("new contract", r.address()?)],
+            ),
+            3 => (
+                "token bridge governance: recover chain ID",
+                vec![
+                    ("EVM chain ID", r.u256()?),
+                    ("new chain ID", r.u16()?.to_string()),
+                ],
+            ),
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    if !r.is_empty() {
+        return None;
+    }
+    match chain {
+        Some(0) => fields.insert(0, ("target chain", "0 (all chains)".to_string())),
+        Some(chain) => fields.insert(0, ("target chain", chain.to_string())),
+        None => {}
+    }
+    Some(DecodedPayload { format, fields })
+}
+
+fn decode_ntt(payload: &[u8]) -> Option<DecodedPayload> {
+    let mut r = Reader { data: payload };
+    if r.bytes(4)? != WH_TRANSCEIVER_PREFIX {
+        return None;
+    }
+    let source_manager = r.address()?;
+    let recipient_manager = r.address()?;
+    let manager_len = r.u16()? as usize;
+    let mut manager = Reader {
+        data: r.bytes(manager_len)?,
+    };
+    let transceiver_len = r.u16()? as usize;
+    let transceiver_payload = r.bytes(transceiver_len)?;
+    if !r.is_empty() {
+        return None;
+    }
+
+    let id = manager.address()?;
+    let sender = manager.address()?;
+    let transfer_len = manager.u16()? as usize;
+    let mut transfer = Reader {
+        data: manager.bytes(transfer_len)?,
+    };
+    if transfer.bytes(4)? != NTT_TRANSFER_PREFIX {
+        return None;
+    }
+    let decimals = transfer.u8()?;
+    let amount = transfer.u64()?;
+    let source_token = transfer.address()?;
+    let to = transfer.address()?;
+    let to_chain = transfer.u16()?;
+    let mut fields = vec![
+        ("source NTT manager", source_manager),
+        ("recipient NTT manager", recipient_manager),
+        ("message ID", id),
+        ("sender", sender),
+        ("amount", amount.to_string()),
+        ("decimals", decimals.to_string()),
+        ("source token", source_token),
+        ("recipient", to),
+        ("recipient chain", to_chain.to_string()),
+    ];
+    if !transfer.is_empty() {
+        let additional_len = transfer.u16()? as usize;
+        fields.push((
+            "additional payload",
+            hex::encode(transfer.bytes(additional_len)?),
+        ));
+    }
+    if !transceiver_payload.is_empty() {
+        fields.push(("transceiver payload", hex::encode(transceiver_payload)));
+    }
+    Some(DecodedPayload {
+        format: "NTT transfer",
+        fields,
+    })
+}
+
+fn decode_token_bridge(payload: &[u8]) -> Option<DecodedPayload> {
+    let mut r = Reader { data: payload };
+    match (r.u8()?, payload.len()) {
+        (1, TRANSFER_LEN) => Some(DecodedPayload {
+            format: "token bridge transfer",
+            fields: vec![
+                ("amount (8 decimals)", r.u256()?),
+                ("token address", r.address()?),
+                ("token chain", r.u16()?.to_string()),
+                ("recipient", r.address()?),
+                ("recipient chain", r.u16()?.to_string()),
+                ("fee", r.u256()?),
+            ],
+        }),
+        (2, ATTEST_META_LEN) => Some(DecodedPayload {
+            format: "token bridge attestation",
+            fields: vec![
+                ("token address", r.address()?),
+                ("token chain", r.u16()?.to_string()),
+                ("decimals", r.u8()?.to_string()),
+                ("symbol", padded_string(r.bytes(32)?)),
+                ("name", padded_string(r.bytes(32)?)),
+            ],
+        }),
+        (3, len) if len >= TRANSFER_LEN => Some(DecodedPayload {
+            format: "token bridge transfer with payload",
+            fields: vec![
+                ("amount (8 decimals)", r.u256()?),
+                ("token address", r.address()?),
+                ("token chain", r.u16()?.to_string()),
+                ("recipient", r.address()?),
+                ("recipient chain", r.u16()?.to_string()),
+                ("sender", r.address()?),
+                ("payload", hex::encode(r.data)),
+            ],
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a hex payload and return its format and fields.
+    fn decode_hex(payload: &str) -> (&'static str, Vec<(&'static str, String)>) {
+        let decoded = decode(&hex::decode(payload).unwrap()).expect("decodes");
+        (decoded.format, decoded.fields)
+    }
+
+    fn fields(fields: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+        fields
+            .iter()
+            .map(|&(name, value)| (name, value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_core_contract_upgrade() {
+        let (format, decoded) = decode_hex(
+            "00000000000000000000000000000000000000000000000000000000436f7265010001\
+             aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        );
+        assert_eq!(format, "core bridge governance: contract upgrade");
+        assert_eq!(
+            decoded,
+            fields(&[("target chain", "1"), ("new contract", &"aa".repeat(32)),])
+        );
+    }
+
+    #[test]
+    fn test_core_guardian_set_upgrade() {
+        let (format, decoded) = decode_hex(
+            "00000000000000000000000000000000000000000000000000000000436f7265020000\
+             0000000402\
+             58cc3ae5c097b213ce3c81979e1b9f9570746aa5\
+             ff6cb952589bde862c25ef4392132fb9d4a42157",
+        );
+        assert_eq!(format, "core bridge governance: guardian set upgrade");
+        assert_eq!(
+            decoded,
+            fields(&[
+                ("target chain", "0 (all chains)"),
+                ("new guardian set index", "4"),
+                ("guardians", "2"),
+                ("guardian", "0x58cc3ae5c097b213ce3c81979e1b9f9570746aa5"),
+                ("guardian", "0xff6cb952589bde862c25ef4392132fb9d4a42157"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_core_set_message_fee() {
+        let (format, decoded) = decode_hex(
+            "00000000000000000000000000000000000000000000000000000000436f7265030001\
+             00000000000000000000000000000000000000000000000000000000000003e8",
+        );
+        assert_eq!(format, "core bridge governance: set message fee");
+        assert_eq!(decoded, fields(&[("target chain", "1"), ("fee", "1000")]));
+    }
+
+    #[test]
+    fn test_core_transfer_fees() {
+        let (format, decoded) = decode_hex(
+            "00000000000000000000000000000000000000000000000000000000436f7265040001\
+             0000000000000000000000000000000000000000000000000000000000001388\
+             bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+        );
+        assert_eq!(format, "core bridge governance: transfer fees");
+        assert_eq!(
+            decoded,
+            fields(&[
+                ("target chain", "1"),
+                ("amount", "5000"),
+                ("recipient", &"bb".repeat(32)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_core_recover_chain_id() {
+        // No target chain: the EVM chain ID follows the action.
+        let (format, decoded) = decode_hex(
+            "00000000000000000000000000000000000000000000000000000000436f726505\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0002",
+        );
+        assert_eq!(format, "core bridge governance: recover chain ID");
+        assert_eq!(
+            decoded,
+            fields(&[("EVM chain ID", "1"), ("new chain ID", "2")])
+        );
+    }
+
+    #[test]
+    fn test_token_bridge_register_chain() {
+        // Registers Ethereum's token bridge on every chain.
+        let (format, decoded) = decode_hex(
+            "000000000000000000000000000000000000000000546f6b656e427269646765010000\
+             0002\
+             0000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585",
+        );
+        assert_eq!(format, "token bridge governance: register chain");
+        assert_eq!(
+            decoded,
+            fields(&[
+                ("target chain", "0 (all chains)"),
+                ("emitter chain", "2"),
+                (
+                    "emitter address",
+                    "0000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585"
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_token_bridge_contract_upgrade() {
+        let (format, decoded) = decode_hex(
+            "000000000000000000000000000000000000000000546f6b656e427269646765020001\
+             cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc",
+        );
+        assert_eq!(format, "token bridge governance: contract upgrade");
+        assert_eq!(
+            decoded,
+            fields(&[("target chain", "1"), ("new contract", &"cc".repeat(32)),])
+        );
+    }
+
+    #[test]
+    fn test_token_bridge_recover_chain_id() {
+        let (format, decoded) = decode_hex(
+            "000000000000000000000000000000000000000000546f6b656e42726964676503\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0002",
+        );
+        assert_eq!(format, "token bridge governance: recover chain ID");
+        assert_eq!(
+            decoded,
+            fields(&[("EVM chain ID", "1"), ("new chain ID", "2")])
+        );
+    }
+
+    #[test]
+    fn test_governance_with_trailing_bytes_is_not_decoded() {
+        let payload = hex::decode(
+            "00000000000000000000000000000000000000000000000000000000436f726505\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             000200",
+        )
+        .unwrap();
+        assert!(decode_governance(&payload).is_none());
+    }
+}
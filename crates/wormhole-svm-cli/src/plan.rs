@@ -0,0 +1,124 @@
+//! Plans saved from `svm-vaa resolve`, loaded back by `svm-vaa execute-plan`.
+//!
+//! The plan holds the resolved accounts, with the payer and guardian set
+//! already filled in and every placeholder annotated with its name. Loading
+//! turns the payer and guardian set back into their placeholders, so the
+//! groups go through the usual substitution when executed.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use wormhole_svm_submit::{
+    InstructionGroup, SerializableAccountMeta, SerializableInstruction,
+    RESOLVER_PUBKEY_GUARDIAN_SET, RESOLVER_PUBKEY_PAYER,
+};
+
+#[derive(Deserialize)]
+struct PlanFile {
+    program_id: String,
+    payer: String,
+    guardian_set: String,
+    instruction_groups: Vec<GroupFile>,
+}
+
+#[derive(Deserialize)]
+struct GroupFile {
+    instructions: Vec<InstructionFile>,
+}
+
+#[derive(Deserialize)]
+struct InstructionFile {
+    program_id: String,
+    accounts: Vec<AccountFile>,
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct AccountFile {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+    placeholder: Option<String>,
+}
+
+/// A loaded plan.
+pub struct Plan {
+    pub program_id: Pubkey,
+    /// The payer the plan was resolved for.
+    pub payer: Pubkey,
+    pub guardian_set: Pubkey,
+    pub instruction_groups: Vec<InstructionGroup>,
+    /// Placeholders referenced by the groups, by name.
+    pub placeholders: Vec<(String, Pubkey)>,
+}
+
+impl Plan {
+    pub fn parse(json: &str) -> Result<Self> {
+        let file: PlanFile = serde_json::from_str(json).context("not a plan from `resolve`")?;
+        let pubkey = |s: &str, what: &str| {
+            Pubkey::from_str(s).with_context(|| format!("invalid {}: {}", what, s))
+        };
+
+        let mut placeholders: Vec<(String, Pubkey)> = Vec::new();
+        let mut instruction_groups = Vec::with_capacity(file.instruction_groups.len());
+        for group in &file.instruction_groups {
+            let mut instructions = Vec::with_capacity(group.instructions.len());
+            for ix in &group.instructions {
+                let mut accounts = Vec::with_capacity(ix.accounts.len());
+                for meta in &ix.accounts {
+                    let account = match meta.placeholder.as_deref() {
+                        Some("RESOLVER_PUBKEY_PAYER") => RESOLVER_PUBKEY_PAYER,
+                        Some("RESOLVER_PUBKEY_GUARDIAN_SET") => RESOLVER_PUBKEY_GUARDIAN_SET,
+                        _ => pubkey(&meta.pubkey, "account")?,
+                    };
+                    if let Some(name) = &meta.placeholder {
+                        if !placeholders.iter().any(|(known, _)| known == name) {
+                            placeholders.push((name.clone(), account));
+                        }
+                    }
+                    accounts.push(SerializableAccountMeta {
+                        pubkey: account,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    });
+                }
+                instructions.push(SerializableInstruction {
+                    program_id: pubkey(&ix.program_id, "program ID")?,
+                    accounts,
+                    data: hex::decode(&ix.data).context("invalid instruction data")?,
+                });
+            }
+            instruction_groups.push(InstructionGroup { instructions });
+        }
+        if instruction_groups.is_empty() {
+            bail!("the plan has no instruction groups");
+        }
+
+        Ok(Plan {
+            program_id: pubkey(&file.program_id, "program ID")?,
+            payer: pubkey(&file.payer, "payer")?,
+            guardian_set: pubkey(&file.guardian_set, "guardian set")?,
+            instruction_groups,
+            placeholders,
+        })
+    }
+
+    /// The account of the placeholder named `name`, if the plan references it.
+    pub fn placeholder(&self, name: &str) -> Option<Pubkey> {
+        self.placeholders
+            .iter()
+            .find(|(known, _)| known == name)
+            .map(|(_, pubkey)| *pubkey)
+    }
+
+    /// Whether any instruction references `pubkey`.
+    pub fn references(&self, pubkey: &Pubkey) -> bool {
+        self.instruction_groups
+            .iter()
+            .flat_map(|group| &group.instructions)
+            .flat_map(|ix| &ix.accounts)
+            .any(|meta| meta.pubkey == *pubkey)
+    }
+}
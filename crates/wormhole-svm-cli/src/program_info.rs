@@ -0,0 +1,142 @@
+//! `svm-vaa program-info`: which builds of the Wormhole programs a cluster
+//! runs.
+//!
+//! Code hashes follow `solana-verify get-program-hash`: the sha256 of the
+//! program's executable data without the trailing zero padding of its
+//! program data account. They are compared against the bundled mainnet
+//! binaries (the ones `localnet` and the LiteSVM helpers load), or against
+//! hashes given on the command line.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable};
+use wormhole_svm_test::{CORE_BRIDGE_BYTES, POST_MESSAGE_SHIM_BYTES, VERIFY_VAA_SHIM_BYTES};
+
+/// Size of the `UpgradeableLoaderState::ProgramData` header: tag (u32),
+/// slot (u64) and optional upgrade authority (1 + 32 bytes).
+const PROGRAMDATA_METADATA_LEN: usize = 45;
+
+/// The Wormhole programs checked, by name, with their bundled binaries.
+pub const PROGRAMS: [(&str, &[u8]); 3] = [
+    ("core-bridge", CORE_BRIDGE_BYTES),
+    ("verify-vaa-shim", VERIFY_VAA_SHIM_BYTES),
+    ("post-message-shim", POST_MESSAGE_SHIM_BYTES),
+];
+
+/// A deployed program.
+pub struct Deployed {
+    pub loader: Pubkey,
+    /// `None` for immutable programs.
+    pub upgrade_authority: Option<Pubkey>,
+    /// Slot of the last deployment (upgradeable programs only).
+    pub deployed_slot: Option<u64>,
+    pub size: usize,
+    pub sha256: String,
+}
+
+/// Fetch the program at `address`; `None` if there is no executable account.
+pub fn fetch(rpc_client: &RpcClient, address: &Pubkey) -> Result<Option<Deployed>> {
+    let Some(account) = rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())
+        .with_context(|| format!("fetching {}", address))?
+        .value
+    else {
+        return Ok(None);
+    };
+    if !account.executable {
+        return Ok(None);
+    }
+
+    if account.owner == bpf_loader::ID || account.owner == bpf_loader_deprecated::ID {
+        let code = trim_padding(&account.data);
+        return Ok(Some(Deployed {
+            loader: account.owner,
+            upgrade_authority: None,
+            deployed_slot: None,
+            size: code.len(),
+            sha256: code_hash(code),
+        }));
+    }
+    if account.owner != bpf_loader_upgradeable::ID {
+        anyhow::bail!("{} is owned by unknown loader {}", address, account.owner);
+    }
+
+    // UpgradeableLoaderState::Program is tag 2 (u32 LE) followed by the
+    // program data address.
+    let data = &account.data;
+    if data.len() < 36 || data[..4] != [2, 0, 0, 0] {
+        anyhow::bail!("{} is not an upgradeable program account", address);
+    }
+    let programdata_address = Pubkey::new_from_array(data[4..36].try_into().unwrap());
+    let programdata = rpc_client
+        .get_account(&programdata_address)
+        .with_context(|| format!("fetching program data {}", programdata_address))?;
+    let data = &programdata.data;
+    if data.len() < PROGRAMDATA_METADATA_LEN || data[..4] != [3, 0, 0, 0] {
+        anyhow::bail!("{} is not a program data account", programdata_address);
+    }
+    let deployed_slot = u64::from_le_bytes(data[4..12].try_into().unwrap());
+    let upgrade_authority =
+        (data[12] == 1).then(|| Pubkey::new_from_array(data[13..45].try_into().unwrap()));
+    let code = trim_padding(&data[PROGRAMDATA_METADATA_LEN..]);
+    Ok(Some(Deployed {
+        loader: account.owner,
+        upgrade_authority,
+        deployed_slot: Some(deployed_slot),
+        size: code.len(),
+        sha256: code_hash(code),
+    }))
+}
+
+/// The sha256 of `code` (without trailing zeros), as hex.
+pub fn code_hash(code: &[u8]) -> String {
+    hex::encode(hash(trim_padding(code)).as_ref())
+}
+
+fn trim_padding(code: &[u8]) -> &[u8] {
+    let len = code
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    &code[..len]
+}
+
+/// How a deployed program compares with the expected build.
+pub enum Status {
+    Match,
+    Differs,
+    NotDeployed,
+}
+
+impl Status {
+    pub fn of(deployed: Option<&Deployed>, expected: &str) -> Self {
+        match deployed {
+            None => Status::NotDeployed,
+            Some(deployed) if deployed.sha256.eq_ignore_ascii_case(expected) => Status::Match,
+            Some(_) => Status::Differs,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Match => "match",
+            Status::Differs => "differs",
+            Status::NotDeployed => "not-deployed",
+        }
+    }
+}
+
+impl Deployed {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "loader": self.loader.to_string(),
+            "upgrade_authority": self.upgrade_authority.map(|a| a.to_string()),
+            "deployed_slot": self.deployed_slot,
+            "size": self.size,
+            "sha256": self.sha256,
+        })
+    }
+}
@@ -0,0 +1,299 @@
+//! `svm-vaa serve`: a small HTTP API for handing VAAs off for submission.
+//!
+//! - `POST /submit` with a signed VAA (hex) as the body queues it and
+//!   answers with its id, `<CHAIN>/<EMITTER>/<SEQUENCE>`.
+//! - `GET /status/<CHAIN>/<EMITTER>/<SEQUENCE>` answers with where the
+//!   submission stands: `pending`, `done` (with the transaction signatures)
+//!   or `failed` (with the failure class and error).
+//!
+//! With a token, every request must carry `Authorization: Bearer <TOKEN>`.
+//! Bodies over [`MAX_BODY_LEN`] are refused with 413, and submissions with
+//! 503 while [`MAX_QUEUED`] VAAs are waiting.
+//!
+//! Requests are served on their own thread; VAAs go through the same
+//! pipeline as `submit --batch`. Statuses are kept in memory, so they are
+//! lost when the server stops, and finished ones are dropped after
+//! [`STATUS_TTL`].
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use futures::channel::mpsc::{self, Sender};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+use tiny_http::{Header, Method, Request, Response, Server};
+use wormhole_svm_submit::pipeline::{broadcast_stream, PipelineConfig};
+use wormhole_svm_submit::{SignedVaa, SubmitError};
+
+use crate::batch;
+use crate::exit::ErrorClass;
+
+/// Largest request body accepted: a VAA with 19 signatures and a payload of
+/// a few kilobytes, hex encoded, fits comfortably.
+const MAX_BODY_LEN: u64 = 64 * 1024;
+
+/// VAAs waiting for the pipeline before submissions are refused.
+const MAX_QUEUED: usize = 1024;
+
+/// How long the status of a finished submission is kept.
+const STATUS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where the submission of a VAA stands.
+enum Status {
+    Pending,
+    Done(Vec<Signature>),
+    Failed { class: ErrorClass, error: String },
+}
+
+impl Status {
+    fn to_json(&self, id: &str) -> Value {
+        match self {
+            Status::Pending => json!({ "id": id, "status": "pending" }),
+            Status::Done(signatures) => json!({
+                "id": id,
+                "status": "done",
+                "signatures": signatures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            }),
+            Status::Failed { class, error } => json!({
+                "id": id,
+                "status": "failed",
+                "class": class.name(),
+                "error": error,
+            }),
+        }
+    }
+}
+
+/// Statuses by VAA id, with when they last changed.
+type Statuses = Arc<Mutex<HashMap<String, (Status, Instant)>>>;
+
+/// Serve the API on `address` and submit the VAAs it receives with `submit`
+/// until the server stops. With `token`, requests without it are refused.
+pub fn serve<F>(
+    address: &str,
+    token: Option<String>,
+    config: PipelineConfig,
+    submit: F,
+) -> Result<()>
+where
+    F: Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static,
+{
+    let server =
+        Server::http(address).map_err(|e| anyhow::anyhow!("listening on {}: {}", address, e))?;
+    log::info!("Listening on http://{}", address);
+    if token.is_none() {
+        log::warn!(
+            "No token set: anyone who can reach {} can submit VAAs",
+            address
+        );
+    }
+
+    let statuses = Statuses::default();
+    let (mut sender, receiver) = mpsc::channel(MAX_QUEUED);
+    let server_statuses = statuses.clone();
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            handle(request, token.as_deref(), &server_statuses, &mut sender);
+        }
+    });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("starting async runtime")?;
+    let mut results = Box::pin(broadcast_stream(receiver, config, submit));
+    while let Some(done) = runtime.block_on(results.next()) {
+        // Unparseable VAAs are rejected before they are queued.
+        let Some(id) = batch::vaa_id(&done.vaa) else {
+            continue;
+        };
+        let status = match done.result {
+            Ok(signatures) => {
+                log::info!("{}: done", id);
+                Status::Done(signatures)
+            }
+            Err(e) => {
+                log::warn!("{}: failed: {}", id, e);
+                Status::Failed {
+                    class: ErrorClass::of_submit_error(&e),
+                    error: e.to_string(),
+                }
+            }
+        };
+        let now = Instant::now();
+        let mut statuses = statuses.lock().unwrap();
+        prune(&mut statuses, now);
+        statuses.insert(id, (status, now));
+    }
+    anyhow::bail!("the HTTP server stopped")
+}
+
+fn handle(
+    mut request: Request,
+    token: Option<&str>,
+    statuses: &Statuses,
+    sender: &mut Sender<Vec<u8>>,
+) {
+    let url = request.url().to_string();
+    let method = request.method().clone();
+    let (code, body) = match (method, url.strip_prefix("/status/")) {
+        _ if !authorized(&request, token) => {
+            (401, json!({ "error": "missing or invalid bearer token" }))
+        }
+        (Method::Post, _) if url == "/submit" => post_submit(&mut request, statuses, sender),
+        (Method::Get, Some(id)) => match statuses.lock().unwrap().get(id) {
+            Some((status, _)) => (200, status.to_json(id)),
+            None => (404, json!({ "error": format!("unknown VAA {}", id) })),
+        },
+        _ => (404, json!({ "error": "not found" })),
+    };
+    let content_type =
+        Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(code)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        log::warn!("responding to {}: {}", url, e);
+    }
+}
+
+/// Whether `request` carries `token`, if one is required.
+fn authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let expected = format!("Bearer {}", token);
+    request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && constant_time_eq(header.value.as_str().as_bytes(), expected.as_bytes())
+    })
+}
+
+/// Compare without returning at the first difference, so that response times
+/// don't reveal how much of the token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Drop the statuses of submissions that finished more than [`STATUS_TTL`]
+/// before `now`.
+fn prune(statuses: &mut HashMap<String, (Status, Instant)>, now: Instant) {
+    statuses.retain(|_, (status, updated)| {
+        matches!(status, Status::Pending) || now.duration_since(*updated) < STATUS_TTL
+    });
+}
+
+/// Read a body of at most [`MAX_BODY_LEN`] bytes, or the error response.
+fn read_body(reader: impl Read) -> Result<String, (u16, Value)> {
+    let mut body = String::new();
+    reader
+        .take(MAX_BODY_LEN + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| {
+            (
+                400,
+                json!({ "error": format!("reading the request: {}", e) }),
+            )
+        })?;
+    if body.len() as u64 > MAX_BODY_LEN {
+        return Err((
+            413,
+            json!({ "error": format!("the body exceeds {} bytes", MAX_BODY_LEN) }),
+        ));
+    }
+    Ok(body)
+}
+
+/// Queue the VAA in the request body, unless it is already pending or done.
+fn post_submit(
+    request: &mut Request,
+    statuses: &Statuses,
+    sender: &mut Sender<Vec<u8>>,
+) -> (u16, Value) {
+    if request
+        .body_length()
+        .is_some_and(|len| len as u64 > MAX_BODY_LEN)
+    {
+        return (
+            413,
+            json!({ "error": format!("the body exceeds {} bytes", MAX_BODY_LEN) }),
+        );
+    }
+    let body = match read_body(request.as_reader()) {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let body = body.trim();
+    let vaa = match hex::decode(body.strip_prefix("0x").unwrap_or(body)) {
+        Ok(vaa) => vaa,
+        Err(e) => return (400, json!({ "error": format!("invalid hex: {}", e) })),
+    };
+    if let Err(e) = SignedVaa::parse(&vaa) {
+        return (400, json!({ "error": format!("invalid VAA: {}", e) }));
+    }
+    let Some(id) = batch::vaa_id(&vaa) else {
+        return (400, json!({ "error": "invalid VAA body" }));
+    };
+
+    let mut statuses = statuses.lock().unwrap();
+    if let Some((status @ (Status::Pending | Status::Done(_)), _)) = statuses.get(&id) {
+        return (200, status.to_json(&id));
+    }
+    if let Err(e) = sender.try_send(vaa) {
+        if e.is_full() {
+            return (503, json!({ "error": "too many VAAs queued; retry later" }));
+        }
+        return (503, json!({ "error": "the submission pipeline stopped" }));
+    }
+    log::info!("{}: queued", id);
+    statuses.insert(id.clone(), (Status::Pending, Instant::now()));
+    (202, Status::Pending.to_json(&id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oversized_body_is_refused() {
+        let limit = MAX_BODY_LEN as usize;
+        assert_eq!(read_body(&b"ab"[..]).unwrap(), "ab");
+        assert_eq!(
+            read_body("a".repeat(limit).as_bytes()).unwrap().len(),
+            limit
+        );
+        let (code, _) = read_body("a".repeat(limit + 1).as_bytes()).unwrap_err();
+        assert_eq!(code, 413);
+    }
+
+    #[test]
+    fn test_finished_statuses_expire() {
+        let start = Instant::now();
+        let mut statuses = HashMap::new();
+        statuses.insert("pending".to_string(), (Status::Pending, start));
+        statuses.insert("done".to_string(), (Status::Done(vec![]), start));
+        let failed = Status::Failed {
+            class: ErrorClass::Execution,
+            error: String::new(),
+        };
+        statuses.insert("failed".to_string(), (failed, start + STATUS_TTL));
+
+        prune(&mut statuses, start + STATUS_TTL);
+
+        let mut kept: Vec<_> = statuses.keys().map(String::as_str).collect();
+        kept.sort();
+        assert_eq!(kept, ["failed", "pending"]);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"Bearer secret", b"Bearer secret"));
+        assert!(!constant_time_eq(b"Bearer secreT", b"Bearer secret"));
+        assert!(!constant_time_eq(b"Bearer secret2", b"Bearer secret"));
+    }
+}
@@ -0,0 +1,168 @@
+//! Signers for `--payer` and friends: keypairs (from a file, an environment
+//! variable, stdin or a seed phrase) or hardware wallets.
+
+use std::io::{self, IsTerminal, Read};
+
+use anyhow::{Context, Result};
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signer::keypair::keypair_from_seed_phrase_and_passphrase;
+use solana_sdk::signer::Signer;
+
+/// Load the signer at `path`: a keypair (see [`keypair_from_path`]), or a
+/// hardware wallet URI such as `usb://ledger` or
+/// `usb://ledger/<WALLET_PUBKEY>?key=0/1`. The optional `key` query selects
+/// the derivation path `44'/501'/<ACCOUNT>'/<CHANGE>'`.
+///
+/// `name` ("payer", "emitter", ...) is used in messages.
+pub fn signer_from_path(path: &str, name: &str) -> Result<Box<dyn Signer>> {
+    if path.starts_with("usb://") {
+        return remote_signer(path, name);
+    }
+    Ok(Box::new(keypair_from_path(path, name)?))
+}
+
+/// Load the keypair at `path`, for signing from several threads at once
+/// (which hardware wallets can't do):
+///
+/// - `env:<VAR>`: the environment variable holds the keypair, as base58 or
+///   as the JSON byte array of a keypair file
+/// - `stdin`: the keypair is piped to stdin, in either format
+/// - `prompt://`: ask for a seed phrase (and optional passphrase) on the
+///   terminal
+/// - anything else: a keypair file
+pub fn keypair_from_path(path: &str, name: &str) -> Result<Keypair> {
+    if path.starts_with("usb://") {
+        anyhow::bail!(
+            "{} must be a keypair, not a hardware wallet: {}",
+            name,
+            path
+        );
+    }
+    if let Some(var) = path.strip_prefix("env:") {
+        let value =
+            std::env::var(var).with_context(|| format!("{} keypair: {} is not set", name, var))?;
+        return parse_keypair(&value)
+            .with_context(|| format!("invalid {} keypair in {}", name, var));
+    }
+    if path == "stdin" {
+        if io::stdin().is_terminal() {
+            anyhow::bail!("{} keypair: nothing piped to stdin", name);
+        }
+        let mut value = String::new();
+        io::stdin().read_to_string(&mut value)?;
+        return parse_keypair(&value).with_context(|| format!("invalid {} keypair on stdin", name));
+    }
+    if path == "prompt://" {
+        return keypair_from_seed_phrase(name);
+    }
+    read_keypair_file(path).map_err(|e| anyhow::anyhow!("failed to read {} keypair: {}", name, e))
+}
+
+/// A keypair as the JSON byte array of a keypair file, or as base58.
+fn parse_keypair(value: &str) -> Result<Keypair> {
+    let value = value.trim();
+    let bytes = if value.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(value).context("expected a JSON byte array")?
+    } else {
+        bs58::decode(value)
+            .into_vec()
+            .context("expected base58 or a JSON byte array")?
+    };
+    Keypair::try_from(bytes.as_slice()).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// Derive a keypair from a seed phrase and passphrase typed on the terminal,
+/// like `solana-keygen recover prompt://` does.
+fn keypair_from_seed_phrase(name: &str) -> Result<Keypair> {
+    let phrase = rpassword::prompt_password(format!("Seed phrase of the {}: ", name))
+        .context("reading the seed phrase")?;
+    let phrase =
+        normalize_seed_phrase(&phrase).with_context(|| format!("invalid {} seed phrase", name))?;
+    let passphrase = rpassword::prompt_password("Passphrase (empty for none): ")
+        .context("reading the passphrase")?;
+    let keypair = keypair_from_seed_phrase_and_passphrase(&phrase, &passphrase)
+        .map_err(|e| anyhow::anyhow!("invalid {} seed phrase: {}", name, e))?;
+    eprintln!("Using {} as {}", keypair.pubkey(), name);
+    Ok(keypair)
+}
+
+/// `phrase` with single spaces between its words, once checked to be an
+/// English BIP39 mnemonic: any phrase would derive some keypair, so a typo
+/// would otherwise go unnoticed until the wrong account is used.
+fn normalize_seed_phrase(phrase: &str) -> Result<String> {
+    let phrase = phrase
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    bip39::Mnemonic::parse_in_normalized(bip39::Language::English, &phrase)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(phrase)
+}
+
+#[cfg(feature = "ledger")]
+fn remote_signer(uri: &str, name: &str) -> Result<Box<dyn Signer>> {
+    use anyhow::Context;
+    use solana_derivation_path::DerivationPath;
+    use solana_remote_wallet::locator::Locator;
+    use solana_remote_wallet::remote_keypair::generate_remote_keypair;
+    use solana_remote_wallet::remote_wallet::maybe_wallet_manager;
+
+    let (locator, query) = match uri.split_once('?') {
+        Some((locator, query)) => (locator, Some(query)),
+        None => (uri, None),
+    };
+    let derivation_path = match query {
+        Some(query) => {
+            let key = query.strip_prefix("key=").with_context(|| {
+                format!(
+                    "unsupported wallet URI query (expected key=<ACCOUNT>[/<CHANGE>]): {}",
+                    uri
+                )
+            })?;
+            DerivationPath::from_key_str(key)
+                .with_context(|| format!("invalid derivation path: {}", key))?
+        }
+        None => DerivationPath::default(),
+    };
+    let locator =
+        Locator::new_from_path(locator).with_context(|| format!("invalid wallet URI: {}", uri))?;
+
+    let wallet_manager = maybe_wallet_manager()
+        .map_err(|e| anyhow::anyhow!("{}", e))?
+        .context("no hardware wallet found (is it connected, unlocked and in the Solana app?)")?;
+    let keypair = generate_remote_keypair(locator, derivation_path, &wallet_manager, false, name)
+        .map_err(|e| anyhow::anyhow!("{} wallet {}: {}", name, uri, e))?;
+    eprintln!(
+        "Using {} as {} {}; approve each transaction on the device",
+        keypair.path, name, keypair.pubkey
+    );
+    Ok(Box::new(keypair))
+}
+
+#[cfg(not(feature = "ledger"))]
+fn remote_signer(uri: &str, _name: &str) -> Result<Box<dyn Signer>> {
+    anyhow::bail!(
+        "hardware wallets are not supported in this build (enable the `ledger` feature): {}",
+        uri
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_phrase_checksum() {
+        let valid = format!("{} about", ["abandon"; 11].join(" "));
+        assert_eq!(
+            normalize_seed_phrase(&format!("  {}\n", valid.to_uppercase().replace(' ', "  ")))
+                .unwrap(),
+            valid
+        );
+        // Right words, wrong checksum.
+        assert!(normalize_seed_phrase(&["abandon"; 12].join(" ")).is_err());
+        // Not a BIP39 word.
+        assert!(normalize_seed_phrase(&format!("{} abouts", ["abandon"; 11].join(" "))).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+//! `svm-vaa tx`: the Wormhole messages posted by a transaction.
+//!
+//! Each message is made of a Post Message Shim `post_message` instruction
+//! (top-level or CPI), which carries the nonce, consistency level and
+//! payload, and the `MessageEvent` self-CPI the shim emits for it, which
+//! carries the emitter, sequence and timestamp. They are paired in execution
+//! order, like the test crate's `extract_posted_message_info_from_tx` does.
+//! Only instructions to the network's shim count.
+//!
+//! Messages posted by calling the core bridge's `post_message` directly are
+//! not reported: no event is emitted for them, and their emitter and
+//! sequence are only in the message account, which may be reused since.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, UiCompiledInstruction, UiInstruction,
+    UiLoadedAddresses, UiMessage, UiTransactionEncoding,
+};
+use wormhole_svm_submit::{MessageEvent, PostMessageArgs, WormholeNetwork};
+
+/// A message posted by a transaction.
+pub struct TxMessage {
+    pub emitter: Pubkey,
+    pub sequence: u64,
+    pub submission_time: u32,
+    pub nonce: u32,
+    pub consistency_level: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Fetch the confirmed transaction `signature` and decode the messages it
+/// posted through `network`'s shim, in order.
+pub fn fetch_posted_messages(
+    rpc_client: &RpcClient,
+    network: &WormholeNetwork,
+    signature: &Signature,
+) -> Result<Vec<TxMessage>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let tx = rpc_client
+        .get_transaction_with_config(signature, config)
+        .with_context(|| format!("fetching transaction {}", signature))?
+        .transaction;
+    if let Some(err) = tx.meta.as_ref().and_then(|meta| meta.err.as_ref()) {
+        anyhow::bail!("transaction {} failed: {:?}", signature, err);
+    }
+    Ok(decode_messages(network, &executed_instructions(tx)?))
+}
+
+/// The instructions of a fetched transaction in execution order, as
+/// `(program id, data)`: each top-level instruction, then the instructions
+/// it invoked.
+pub fn executed_instructions(
+    tx: EncodedTransactionWithStatusMeta,
+) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let meta = tx
+        .meta
+        .context("the RPC node returned no transaction status")?;
+    let message = match tx.transaction {
+        EncodedTransaction::Json(tx) => match tx.message {
+            UiMessage::Raw(message) => message,
+            UiMessage::Parsed(_) => anyhow::bail!("unexpected parsed transaction message"),
+        },
+        _ => anyhow::bail!("unexpected transaction encoding"),
+    };
+
+    // Program ids index the static keys, then the keys loaded from lookup
+    // tables: writable first, then read-only.
+    let loaded: Option<UiLoadedAddresses> = meta.loaded_addresses.into();
+    let loaded = loaded.unwrap_or_default();
+    let keys = message
+        .account_keys
+        .iter()
+        .chain(&loaded.writable)
+        .chain(&loaded.readonly)
+        .map(|key| Pubkey::from_str(key).with_context(|| format!("invalid account key {}", key)))
+        .collect::<Result<Vec<_>>>()?;
+    let decode = |ix: &UiCompiledInstruction| -> Option<(Pubkey, Vec<u8>)> {
+        let program_id = *keys.get(usize::from(ix.program_id_index))?;
+        Some((program_id, bs58::decode(&ix.data).into_vec().ok()?))
+    };
+
+    let inner_instructions: Option<Vec<_>> = meta.inner_instructions.into();
+    let inner_instructions = inner_instructions.unwrap_or_default();
+    let mut instructions = Vec::new();
+    for (index, ix) in message.instructions.iter().enumerate() {
+        instructions.extend(decode(ix));
+        for inner in inner_instructions
+            .iter()
+            .filter(|inner| usize::from(inner.index) == index)
+        {
+            instructions.extend(inner.instructions.iter().filter_map(|ix| match ix {
+                UiInstruction::Compiled(ix) => decode(ix),
+                UiInstruction::Parsed(_) => None,
+            }));
+        }
+    }
+    Ok(instructions)
+}
+
+/// Pair the `post_message` instructions and the shim's events among
+/// `instructions`, in order.
+fn decode_messages(
+    network: &WormholeNetwork,
+    instructions: &[(Pubkey, Vec<u8>)],
+) -> Vec<TxMessage> {
+    let mut posts = Vec::new();
+    let mut events = Vec::new();
+    for (program_id, data) in instructions {
+        if *program_id != network.post_message_shim {
+            continue;
+        }
+        if let Some(args) = PostMessageArgs::parse(data) {
+            posts.push(args);
+        } else if let Some(event) = MessageEvent::parse(network, program_id, data) {
+            events.push(event);
+        }
+    }
+    posts
+        .into_iter()
+        .zip(events)
+        .map(|(args, event)| TxMessage {
+            emitter: event.emitter,
+            sequence: event.sequence,
+            submission_time: event.submission_time,
+            nonce: args.nonce,
+            consistency_level: args.consistency_level,
+            payload: args.payload,
+        })
+        .collect()
+}
+
+impl TxMessage {
+    /// `<chain>/<emitter>/<sequence>`, ready for `svm-vaa fetch`.
+    pub fn vaa_id(&self, chain: u16) -> String {
+        format!(
+            "{}/{}/{}",
+            chain,
+            hex::encode(self.emitter.to_bytes()),
+            self.sequence
+        )
+    }
+
+    pub fn to_json(&self, chain: u16) -> Value {
+        json!({
+            "id": self.vaa_id(chain),
+            "emitter": self.emitter.to_string(),
+            "sequence": self.sequence,
+            "nonce": self.nonce,
+            "consistency_level": self.consistency_level,
+            "timestamp": self.submission_time,
+            "payload": hex::encode(&self.payload),
+        })
+    }
+
+    /// Print the message fields, one per line.
+    pub fn print(&self, chain: u16) {
+        println!("id:                {}", self.vaa_id(chain));
+        println!("emitter:           {}", self.emitter);
+        println!("sequence:          {}", self.sequence);
+        println!("nonce:             {}", self.nonce);
+        println!("consistency level: {}", self.consistency_level);
+        println!("timestamp:         {}", self.submission_time);
+        println!("payload:           {}", hex::encode(&self.payload));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wormhole_svm_definitions::MESSAGE_EVENT_DISCRIMINATOR;
+    use wormhole_svm_submit::message::{build_post_message_ix, EVENT_CPI_TAG};
+    use wormhole_svm_submit::Finality;
+
+    use super::*;
+
+    fn event_data(emitter: &Pubkey, sequence: u64) -> Vec<u8> {
+        let mut data = EVENT_CPI_TAG.to_vec();
+        data.extend_from_slice(&MESSAGE_EVENT_DISCRIMINATOR);
+        data.extend_from_slice(emitter.as_ref());
+        data.extend_from_slice(&sequence.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_events_of_other_programs_are_ignored() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        let emitter = Pubkey::new_unique();
+        let post = build_post_message_ix(
+            &network,
+            &Pubkey::new_unique(),
+            &emitter,
+            7,
+            Finality::Confirmed,
+            &[1, 2, 3],
+        );
+        let instructions = vec![
+            (post.program_id, post.data),
+            // An unrelated program logging bytes that look like an event.
+            (Pubkey::new_unique(), event_data(&Pubkey::new_unique(), 99)),
+            (network.post_message_shim, event_data(&emitter, 5)),
+        ];
+
+        let messages = decode_messages(&network, &instructions);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].emitter, emitter);
+        assert_eq!(messages[0].sequence, 5);
+        assert_eq!(messages[0].nonce, 7);
+        assert_eq!(messages[0].payload, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_direct_core_bridge_messages_are_not_reported() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        // The core bridge's own `post_message`: the instruction index, then
+        // the nonce, the payload and the consistency level (borsh).
+        let mut data = vec![1];
+        data.extend_from_slice(&7u32.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data.push(1);
+        let emitter = Pubkey::new_unique();
+        let post = build_post_message_ix(
+            &network,
+            &Pubkey::new_unique(),
+            &emitter,
+            8,
+            Finality::Finalized,
+            &[4],
+        );
+        let instructions = vec![
+            (network.core_bridge, data),
+            (post.program_id, post.data),
+            (network.post_message_shim, event_data(&emitter, 6)),
+        ];
+
+        let messages = decode_messages(&network, &instructions);
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!((messages[0].emitter, messages[0].sequence), (emitter, 6));
+        assert_eq!(messages[0].nonce, 8);
+    }
+}
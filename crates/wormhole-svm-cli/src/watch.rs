@@ -0,0 +1,462 @@
+//! `svm-vaa watch`: a minimal standalone relayer for a single emitter.
+//!
+//! New VAAs come either from polling Wormholescan or from a spy
+//! subscription. Progress lives in a state directory:
+//!
+//! - `cursor`: the next sequence to submit. It only moves forward once a
+//!   VAA has been fully broadcast, so a restart never resubmits it.
+//! - `in-flight/`: a [`FileStore`] with the broadcasts that were interrupted
+//!   midway; they resume (reusing their signatures account) when the VAA is
+//!   submitted again.
+//! - `dead-letter/`: VAAs that can never go through (e.g. malformed ones),
+//!   one `<SEQUENCE>.json` file each with the VAA and the error. The cursor
+//!   moves past them.
+//!
+//! Failures are classified like the CLI's exit codes: a VAA that was already
+//! redeemed is skipped, an invalid one is dead-lettered, and anything else is
+//! retried on the next poll.
+//! With `--metrics-addr`, the watcher's [`Metrics`] are served for
+//! Prometheus.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use wormhole_svm_submit::spy::{subscribe_signed_vaas, EmitterFilter};
+use wormhole_svm_submit::{
+    BroadcastConfig, FailoverConnection, FileStore, RpcClientWithCommitment, SignedVaa,
+    SubmitError, VaaBody, WormholeNetwork,
+};
+
+use crate::exit::ErrorClass;
+use crate::metrics::Metrics;
+use crate::wormholescan;
+
+/// VAAs requested per Wormholescan poll.
+const PAGE_SIZE: usize = 50;
+
+/// Where new VAAs come from.
+pub enum Source {
+    /// Wormholescan API base URL.
+    Wormholescan(String),
+    /// Spy gRPC endpoint.
+    Spy(String),
+}
+
+/// What to do with a VAA whose broadcast failed.
+#[derive(Debug, PartialEq, Eq)]
+enum Disposition {
+    /// Retry on the next poll; the broadcast resumes from the store.
+    Retry,
+    /// Someone else redeemed it: move past it.
+    Skip,
+    /// It can never go through: set it aside and move past it.
+    DeadLetter,
+}
+
+impl Disposition {
+    fn of(error: &SubmitError) -> Self {
+        match ErrorClass::of_submit_error(error) {
+            ErrorClass::AlreadyRedeemed => Disposition::Skip,
+            ErrorClass::Parse => Disposition::DeadLetter,
+            _ => Disposition::Retry,
+        }
+    }
+}
+
+pub struct Watcher {
+    rpc_client: FailoverConnection<RpcClientWithCommitment>,
+    payer: Box<dyn Signer>,
+    program_id: Pubkey,
+    network: WormholeNetwork,
+    chain: u16,
+    emitter: [u8; 32],
+    interval: Duration,
+    store: FileStore,
+    cursor_path: PathBuf,
+    dead_letter_dir: PathBuf,
+    /// Next sequence to submit; `None` until the first VAA is seen.
+    next_sequence: Option<u64>,
+    metrics: Arc<Metrics>,
+}
+
+impl Watcher {
+    /// Open (or create) the state in `state_dir`. `from_sequence` overrides
+    /// the stored cursor.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(
+        rpc_client: FailoverConnection<RpcClientWithCommitment>,
+        payer: Box<dyn Signer>,
+        program_id: Pubkey,
+        network: WormholeNetwork,
+        chain: u16,
+        emitter: [u8; 32],
+        interval: Duration,
+        state_dir: &Path,
+        from_sequence: Option<u64>,
+    ) -> Result<Self> {
+        let store =
+            FileStore::open(state_dir.join("in-flight")).map_err(|e| anyhow::anyhow!("{}", e))?;
+        let cursor_path = state_dir.join("cursor");
+        let next_sequence = match from_sequence {
+            Some(sequence) => Some(sequence),
+            None => read_cursor(&cursor_path)?,
+        };
+        let metrics = Arc::new(Metrics::default());
+        if let Some(next) = next_sequence {
+            metrics.set_next_sequence(next);
+        }
+        Ok(Self {
+            rpc_client,
+            payer,
+            program_id,
+            network,
+            chain,
+            emitter,
+            interval,
+            store,
+            cursor_path,
+            dead_letter_dir: state_dir.join("dead-letter"),
+            next_sequence,
+            metrics,
+        })
+    }
+
+    /// The watcher's metrics, updated as it goes.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    pub fn run(&mut self, source: &Source) -> Result<()> {
+        match self.next_sequence {
+            Some(sequence) => log::info!("Watching from sequence {}", sequence),
+            None => log::info!("No cursor yet; only VAAs emitted from now on will be submitted"),
+        }
+        match source {
+            Source::Wormholescan(api_url) => self.poll_wormholescan(api_url),
+            Source::Spy(endpoint) => self.follow_spy(endpoint),
+        }
+    }
+
+    fn poll_wormholescan(&mut self, api_url: &str) -> Result<()> {
+        let emitter = hex::encode(self.emitter);
+        loop {
+            match wormholescan::fetch_latest_vaas(api_url, self.chain, &emitter, PAGE_SIZE) {
+                Ok(vaas) => self.process_page(api_url, &emitter, vaas)?,
+                Err(e) => log::warn!("polling Wormholescan failed: {:#}", e),
+            }
+            thread::sleep(self.interval);
+        }
+    }
+
+    fn process_page(&mut self, api_url: &str, emitter: &str, vaas: Vec<Vec<u8>>) -> Result<()> {
+        let mut vaas = vaas
+            .into_iter()
+            .filter_map(|raw| match sequence_of(&raw) {
+                Ok(sequence) => Some((sequence, raw)),
+                Err(e) => {
+                    log::warn!("skipping malformed VAA from Wormholescan: {:#}", e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        vaas.sort_by_key(|(sequence, _)| *sequence);
+        for (sequence, _) in &vaas {
+            self.metrics.received(*sequence);
+        }
+
+        let Some(next) = self.next_sequence else {
+            let next = vaas.last().map_or(0, |(sequence, _)| sequence + 1);
+            log::info!("Starting at sequence {}", next);
+            return self.advance(next);
+        };
+
+        // Fill the gap between the cursor and the oldest VAA of the page one
+        // sequence at a time (after a long downtime or with --from-sequence).
+        let oldest = vaas.first().map_or(next, |(sequence, _)| *sequence);
+        for sequence in next..oldest {
+            let id = wormholescan::VaaId {
+                chain: self.chain,
+                emitter: emitter.to_string(),
+                sequence,
+            };
+            let raw = match wormholescan::fetch_vaa(api_url, &id) {
+                Ok(raw) => raw,
+                Err(e) => {
+                    log::warn!("{:#}", e);
+                    return Ok(());
+                }
+            };
+            if !self.submit(sequence, &raw)? {
+                return Ok(());
+            }
+        }
+
+        for (sequence, raw) in vaas {
+            if sequence < self.next_sequence.unwrap_or(0) {
+                continue;
+            }
+            if !self.submit(sequence, &raw)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn follow_spy(&mut self, endpoint: &str) -> Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("starting async runtime")?;
+        let filters = [EmitterFilter {
+            chain: self.chain,
+            address: self.emitter,
+        }];
+        loop {
+            match runtime.block_on(subscribe_signed_vaas(endpoint, &filters)) {
+                Ok(vaas) => {
+                    log::info!("Subscribed to {}", endpoint);
+                    let mut vaas = Box::pin(vaas);
+                    while let Some(raw) = runtime.block_on(vaas.next()) {
+                        self.process_spy_vaa(&raw)?;
+                    }
+                }
+                Err(e) => log::warn!("{}", e),
+            }
+            log::info!("Reconnecting to the spy in {}s...", self.interval.as_secs());
+            thread::sleep(self.interval);
+        }
+    }
+
+    fn process_spy_vaa(&mut self, raw: &[u8]) -> Result<()> {
+        let sequence = match sequence_of(raw) {
+            Ok(sequence) => sequence,
+            Err(e) => {
+                log::warn!("skipping malformed VAA from the spy: {:#}", e);
+                return Ok(());
+            }
+        };
+        self.metrics.received(sequence);
+        // The spy may deliver a VAA more than once; anything behind the
+        // cursor has already been submitted.
+        if self.next_sequence.is_some_and(|next| sequence < next) {
+            return Ok(());
+        }
+        if let Some(next) = self.next_sequence.filter(|next| sequence > *next) {
+            log::warn!(
+                "sequences {}..{} were not received from the spy; submit them with \
+                 `svm-vaa fetch | svm-vaa submit`",
+                next,
+                sequence
+            );
+        }
+        self.submit(sequence, raw)?;
+        Ok(())
+    }
+
+    /// Broadcast one VAA. Returns whether the cursor moved past it; see
+    /// [`Self::failed`] for failures.
+    fn submit(&mut self, sequence: u64, raw: &[u8]) -> Result<bool> {
+        let parsed =
+            SignedVaa::parse(raw).and_then(|vaa| Ok((VaaBody::parse(&vaa.body)?.timestamp, vaa)));
+        let (emitted_at, vaa) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return self.failed(sequence, raw, &e),
+        };
+        log::info!("Submitting sequence {}...", sequence);
+        self.metrics.attempted(sequence);
+        let balance_before = self.payer_balance();
+
+        let config = BroadcastConfig {
+            network: self.network,
+            store: Some(&self.store),
+            ..Default::default()
+        };
+        match wormhole_svm_submit::broadcast_vaa_with_config(
+            &mut self.rpc_client,
+            &*self.payer,
+            &self.program_id,
+            vaa.guardian_set_index,
+            &vaa.body,
+            &vaa.signatures,
+            &config,
+        ) {
+            Ok(signatures) => {
+                for signature in &signatures {
+                    println!("{}\t{}", sequence, signature);
+                }
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |now| now.as_secs());
+                let spent = balance_before
+                    .zip(self.payer_balance())
+                    .map(|(before, after)| before.saturating_sub(after));
+                self.metrics
+                    .submitted(now.saturating_sub(emitted_at as u64), spent);
+                self.advance(sequence + 1)?;
+                Ok(true)
+            }
+            Err(e) => self.failed(sequence, raw, &e),
+        }
+    }
+
+    /// Handle the failed broadcast of `raw` according to its [`Disposition`].
+    /// Returns whether the cursor moved past it; if not, the broadcast stays
+    /// in the store to resume on a retry.
+    fn failed(&mut self, sequence: u64, raw: &[u8], error: &SubmitError) -> Result<bool> {
+        log::error!("sequence {} failed: {}", sequence, error);
+        self.metrics.failed();
+        match Disposition::of(error) {
+            Disposition::Retry => return Ok(false),
+            Disposition::Skip => log::info!("sequence {} was already redeemed", sequence),
+            Disposition::DeadLetter => {
+                let path = write_dead_letter(&self.dead_letter_dir, sequence, raw, error)?;
+                log::warn!(
+                    "sequence {} can never be submitted; set aside in {}",
+                    sequence,
+                    path.display()
+                );
+                self.metrics.dead_lettered();
+            }
+        }
+        self.advance(sequence + 1)?;
+        Ok(true)
+    }
+
+    /// The payer's balance, for the spending metric; `None` if it can't be
+    /// fetched.
+    fn payer_balance(&self) -> Option<u64> {
+        self.rpc_client
+            .current()
+            .get_balance(&self.payer.pubkey())
+            .ok()
+    }
+
+    fn advance(&mut self, next: u64) -> Result<()> {
+        self.next_sequence = Some(next);
+        self.metrics.set_next_sequence(next);
+        write_cursor(&self.cursor_path, next)
+    }
+}
+
+fn sequence_of(raw: &[u8]) -> Result<u64> {
+    let vaa = SignedVaa::parse(raw).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let body = VaaBody::parse(&vaa.body).map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(body.sequence)
+}
+
+fn read_cursor(path: &Path) -> Result<Option<u64>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => contents
+            .trim()
+            .parse()
+            .map(Some)
+            .with_context(|| format!("invalid cursor in {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// Record `raw` and why it failed in `dir`, returning the file written.
+fn write_dead_letter(
+    dir: &Path,
+    sequence: u64,
+    raw: &[u8],
+    error: &SubmitError,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let path = dir.join(format!("{}.json", sequence));
+    let letter = json!({
+        "sequence": sequence,
+        "vaa": hex::encode(raw),
+        "code": error.error_code(),
+        "error": error.to_string(),
+    });
+    fs::write(&path, format!("{:#}\n", letter))
+        .with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
+/// Replace the cursor atomically, like [`FileStore`] does with its records.
+fn write_cursor(path: &Path, next: u64) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, format!("{}\n", next))
+        .and_then(|()| fs::rename(&tmp, path))
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_client::RpcClient;
+    use solana_sdk::commitment_config::CommitmentConfig;
+    use solana_sdk::signature::{Keypair, Signature};
+    use wormhole_svm_submit::TransactionFailure;
+
+    #[test]
+    fn test_disposition() {
+        let failed = |message: &str| SubmitError::TransactionFailed {
+            group_index: 0,
+            signature: Signature::default(),
+            message: message.to_string(),
+            cause: TransactionFailure::from_message(message),
+        };
+        let cases = [
+            (
+                SubmitError::Connection("timed out".into()),
+                Disposition::Retry,
+            ),
+            (
+                SubmitError::InvalidVaa("too short".into()),
+                Disposition::DeadLetter,
+            ),
+            (failed("custom program error: 0x0"), Disposition::Retry),
+            (
+                failed("Allocate: account Address { .. } already in use"),
+                Disposition::Skip,
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(Disposition::of(&error), expected, "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_malformed_vaa_is_dead_lettered() {
+        let state_dir =
+            std::env::temp_dir().join(format!("svm-vaa-watch-{}", Pubkey::new_unique()));
+        // Nothing is sent to the cluster for a VAA that doesn't parse.
+        let rpc_client = RpcClient::new("http://127.0.0.1:1".to_string());
+        let rpc_client = RpcClientWithCommitment::new(rpc_client, CommitmentConfig::confirmed());
+        let mut watcher = Watcher::open(
+            FailoverConnection::new(vec![rpc_client], |_| false),
+            Box::new(Keypair::new()),
+            Pubkey::new_unique(),
+            WormholeNetwork::SOLANA_DEVNET,
+            2,
+            [1; 32],
+            Duration::from_secs(1),
+            &state_dir,
+            Some(7),
+        )
+        .unwrap();
+
+        assert!(watcher.submit(7, b"not a VAA").unwrap());
+
+        assert_eq!(read_cursor(&state_dir.join("cursor")).unwrap(), Some(8));
+        let letter: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(state_dir.join("dead-letter/7.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(letter["vaa"], hex::encode(b"not a VAA"));
+        assert_eq!(letter["code"], 300);
+        fs::remove_dir_all(&state_dir).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+//! Minimal Wormholescan API client for fetching signed VAAs.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use solana_sdk::pubkey::Pubkey;
+
+pub const MAINNET_API_URL: &str = "https://api.wormholescan.io";
+pub const TESTNET_API_URL: &str = "https://api.testnet.wormholescan.io";
+
+/// Resolve an API URL shorthand (`m`/`mainnet`, `t`/`testnet`).
+pub fn resolve_api_url(url: &str) -> String {
+    match url {
+        "m" | "mainnet" => MAINNET_API_URL.to_string(),
+        "t" | "testnet" => TESTNET_API_URL.to_string(),
+        other => other.trim_end_matches('/').to_string(),
+    }
+}
+
+/// A VAA identified by emitter chain, emitter address and sequence.
+pub struct VaaId {
+    pub chain: u16,
+    /// Hex, 32 bytes, without `0x`.
+    pub emitter: String,
+    pub sequence: u64,
+}
+
+impl FromStr for VaaId {
+    type Err = anyhow::Error;
+
+    /// Parse `<CHAIN>/<EMITTER>/<SEQUENCE>`. The emitter is 64 hex characters,
+    /// `0x`-prefixed hex (left-padded to 32 bytes) or a base58 Solana address.
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split('/').collect();
+        let [chain, emitter, sequence] = parts.as_slice() else {
+            bail!("VAA ID must be <CHAIN>/<EMITTER>/<SEQUENCE>: {}", s);
+        };
+        let chain = chain
+            .parse()
+            .with_context(|| format!("invalid chain ID: {}", chain))?;
+        let sequence = sequence
+            .parse()
+            .with_context(|| format!("invalid sequence: {}", sequence))?;
+        Ok(Self {
+            chain,
+            emitter: parse_emitter(emitter)?,
+            sequence,
+        })
+    }
+}
+
+/// Normalize an emitter address to 64 hex characters (see [`VaaId`]).
+pub fn parse_emitter(emitter: &str) -> Result<String> {
+    if emitter.starts_with("0x") || emitter.len() == 64 {
+        let bytes = hex::decode(emitter.trim_start_matches("0x"))
+            .with_context(|| format!("invalid hex emitter: {}", emitter))?;
+        if bytes.len() > 32 {
+            bail!("emitter longer than 32 bytes: {}", emitter);
+        }
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+        return Ok(hex::encode(padded));
+    }
+    let pubkey = Pubkey::from_str(emitter)
+        .with_context(|| format!("emitter must be hex or a base58 address: {}", emitter))?;
+    Ok(hex::encode(pubkey.to_bytes()))
+}
+
+/// Fetch the signed VAA with `id`.
+pub fn fetch_vaa(api_url: &str, id: &VaaId) -> Result<Vec<u8>> {
+    let url = format!(
+        "{}/api/v1/vaas/{}/{}/{}",
+        api_url, id.chain, id.emitter, id.sequence
+    );
+    let response = get_json(&url)?;
+    decode_vaa(&response["data"])
+}
+
+/// Fetch the latest (at most `page_size`) signed VAAs of an emitter, newest
+/// first.
+pub fn fetch_latest_vaas(
+    api_url: &str,
+    chain: u16,
+    emitter: &str,
+    page_size: usize,
+) -> Result<Vec<Vec<u8>>> {
+    let url = format!(
+        "{}/api/v1/vaas/{}/{}?page=0&pageSize={}&sortOrder=DESC",
+        api_url, chain, emitter, page_size
+    );
+    let response = get_json(&url)?;
+    let vaas = response["data"]
+        .as_array()
+        .context("unexpected Wormholescan response: missing data")?;
+    vaas.iter().map(decode_vaa).collect()
+}
+
+/// Fetch the signed VAAs emitted by the transaction `tx_hash`.
+pub fn fetch_vaas_by_tx(api_url: &str, tx_hash: &str) -> Result<Vec<Vec<u8>>> {
+    let url = format!("{}/api/v1/vaas/?txHash={}", api_url, tx_hash);
+    let response = get_json(&url)?;
+    let vaas = response["data"]
+        .as_array()
+        .context("unexpected Wormholescan response: missing data")?;
+    if vaas.is_empty() {
+        bail!(
+            "no VAA found for transaction {} (it may not have reached quorum yet)",
+            tx_hash
+        );
+    }
+    vaas.iter().map(decode_vaa).collect()
+}
+
+fn get_json(url: &str) -> Result<serde_json::Value> {
+    let response = reqwest::blocking::get(url).with_context(|| format!("requesting {}", url))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        bail!(
+            "VAA not found (it may not have reached quorum yet): {}",
+            url
+        );
+    }
+    if !response.status().is_success() {
+        bail!("Wormholescan returned {} for {}", response.status(), url);
+    }
+    response
+        .json()
+        .with_context(|| format!("decoding response from {}", url))
+}
+
+fn decode_vaa(entry: &serde_json::Value) -> Result<Vec<u8>> {
+    let vaa = entry["vaa"]
+        .as_str()
+        .context("unexpected Wormholescan response: missing vaa")?;
+    base64::engine::general_purpose::STANDARD
+        .decode(vaa)
+        .context("decoding base64 VAA from Wormholescan")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMITTER: &str = "ec7372995d5cc8732397fb0ad35c0121e0eaa90d26f828a534cab54391b3a4f5";
+
+    #[test]
+    fn test_parse_emitter() {
+        assert_eq!(parse_emitter(EMITTER).unwrap(), EMITTER);
+        assert_eq!(parse_emitter(&format!("0x{}", EMITTER)).unwrap(), EMITTER);
+        // Short hex is left-padded, like an EVM address.
+        assert_eq!(
+            parse_emitter("0x3ee18b2214aff97000d974cf647e7c347e8fa585").unwrap(),
+            "0000000000000000000000003ee18b2214aff97000d974cf647e7c347e8fa585"
+        );
+        let base58 = Pubkey::new_from_array(hex::decode(EMITTER).unwrap().try_into().unwrap());
+        assert_eq!(parse_emitter(&base58.to_string()).unwrap(), EMITTER);
+
+        assert!(parse_emitter(&format!("0x00{}", EMITTER)).is_err());
+        assert!(parse_emitter(&format!("{}zz", &EMITTER[2..])).is_err());
+        assert!(parse_emitter("not-an-address").is_err());
+    }
+
+    #[test]
+    fn test_parse_vaa_id() {
+        let id = VaaId::from_str(&format!("1/{}/42", EMITTER)).unwrap();
+        assert_eq!(
+            (id.chain, id.emitter.as_str(), id.sequence),
+            (1, EMITTER, 42)
+        );
+
+        for malformed in [
+            format!("1/{}", EMITTER),
+            format!("1/{}/42/0", EMITTER),
+            format!("solana/{}/42", EMITTER),
+            format!("65536/{}/42", EMITTER),
+            "1/0xzz/42".to_string(),
+            format!("1/{}/-1", EMITTER),
+            format!("1/{}/", EMITTER),
+        ] {
+            assert!(VaaId::from_str(&malformed).is_err(), "{}", malformed);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! Existence report of the accounts referenced by resolved instruction groups.
+//!
+//! A program that expects an initialized account fails only once its
+//! transaction executes, usually with an opaque "account not initialized"
+//! error. [`account_report`] fetches every referenced account up front and
+//! flags writable accounts that don't exist and won't be created by the
+//! broadcast itself, so those failures can be diagnosed before anything is
+//! sent.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::connection::SolanaConnection;
+use crate::execute::KEYPAIR_PLACEHOLDERS;
+use crate::resolve::InstructionGroup;
+use crate::{
+    SubmitError, RESOLVER_PUBKEY_GUARDIAN_SET, RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+};
+
+/// On-chain state of a referenced account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountStatus {
+    /// The account exists.
+    Exists {
+        owner: Pubkey,
+        lamports: u64,
+        data_len: usize,
+    },
+    /// The account doesn't exist.
+    Missing,
+    /// The account is created during the broadcast (the guardian signatures
+    /// account or a `RESOLVER_PUBKEY_KEYPAIR_*` placeholder), so it wasn't
+    /// fetched.
+    CreatedByBroadcast,
+}
+
+/// One account referenced by the instruction groups.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferencedAccount {
+    /// The account as referenced by the groups (possibly a placeholder).
+    pub referenced: Pubkey,
+    /// The address after placeholder substitution.
+    pub address: Pubkey,
+    /// Whether any instruction writes the account.
+    pub writable: bool,
+    /// Indices of the groups referencing the account.
+    pub groups: Vec<usize>,
+    pub status: AccountStatus,
+}
+
+impl ReferencedAccount {
+    /// Whether the account is written but doesn't exist, which usually means
+    /// the program expects it to be initialized already.
+    pub fn is_flagged(&self) -> bool {
+        self.writable && self.status == AccountStatus::Missing
+    }
+}
+
+/// Result of [`account_report`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountReport {
+    /// Referenced accounts, in order of first reference.
+    pub accounts: Vec<ReferencedAccount>,
+}
+
+impl AccountReport {
+    /// Writable accounts that don't exist (see [`ReferencedAccount::is_flagged`]).
+    pub fn flagged(&self) -> impl Iterator<Item = &ReferencedAccount> {
+        self.accounts.iter().filter(|account| account.is_flagged())
+    }
+}
+
+/// Fetch every account referenced by `groups` in one batch and report
+/// whether it exists.
+///
+/// Placeholders are substituted like during execution: `payer` for
+/// `RESOLVER_PUBKEY_PAYER`, `guardian_set` for `RESOLVER_PUBKEY_GUARDIAN_SET`
+/// and custom `placeholders`. The signatures account and keypair placeholders
+/// are reported as [`AccountStatus::CreatedByBroadcast`].
+pub fn account_report<C: SolanaConnection>(
+    conn: &C,
+    groups: &[InstructionGroup],
+    payer: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+) -> Result<AccountReport, SubmitError> {
+    let mut accounts: Vec<ReferencedAccount> = Vec::new();
+    for (group_index, referenced, writable) in references(groups) {
+        if let Some(account) = accounts.iter_mut().find(|a| a.referenced == referenced) {
+            account.writable |= writable;
+            if !account.groups.contains(&group_index) {
+                account.groups.push(group_index);
+            }
+            continue;
+        }
+
+        let created_by_broadcast = referenced == RESOLVER_PUBKEY_SHIM_VAA_SIGS
+            || KEYPAIR_PLACEHOLDERS.contains(&referenced);
+        let address = if referenced == RESOLVER_PUBKEY_PAYER {
+            *payer
+        } else if referenced == RESOLVER_PUBKEY_GUARDIAN_SET {
+            *guardian_set
+        } else {
+            placeholders
+                .iter()
+                .find(|(placeholder, _)| *placeholder == referenced)
+                .map_or(referenced, |(_, actual)| *actual)
+        };
+        accounts.push(ReferencedAccount {
+            referenced,
+            address,
+            writable,
+            groups: vec![group_index],
+            status: if created_by_broadcast {
+                AccountStatus::CreatedByBroadcast
+            } else {
+                AccountStatus::Missing
+            },
+        });
+    }
+
+    let to_fetch: Vec<usize> = (0..accounts.len())
+        .filter(|&i| accounts[i].status == AccountStatus::Missing)
+        .collect();
+    let addresses: Vec<Pubkey> = to_fetch.iter().map(|&i| accounts[i].address).collect();
+    let fetched = conn
+        .get_multiple_accounts(&addresses)
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    for (i, account) in to_fetch.into_iter().zip(fetched) {
+        if let Some(account) = account {
+            accounts[i].status = AccountStatus::Exists {
+                owner: account.owner,
+                lamports: account.lamports,
+                data_len: account.data.len(),
+            };
+        }
+    }
+
+    Ok(AccountReport { accounts })
+}
+
+/// `(group index, account, writable)` for every account meta of `groups`.
+fn references(groups: &[InstructionGroup]) -> impl Iterator<Item = (usize, Pubkey, bool)> + '_ {
+    groups.iter().enumerate().flat_map(|(group_index, group)| {
+        group
+            .instructions
+            .iter()
+            .flat_map(|ix| &ix.accounts)
+            .map(move |meta| (group_index, meta.pubkey, meta.is_writable))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{group, MockConnection};
+    use executor_account_resolver_svm::RESOLVER_PUBKEY_KEYPAIR_00;
+    use solana_sdk::account::Account;
+
+    fn account(writable: bool, status: AccountStatus) -> ReferencedAccount {
+        ReferencedAccount {
+            referenced: Pubkey::new_unique(),
+            address: Pubkey::new_unique(),
+            writable,
+            groups: vec![0],
+            status,
+        }
+    }
+
+    #[test]
+    fn test_flagged_accounts() {
+        let report = AccountReport {
+            accounts: vec![
+                account(true, AccountStatus::Missing),
+                account(false, AccountStatus::Missing),
+                account(true, AccountStatus::CreatedByBroadcast),
+                account(
+                    true,
+                    AccountStatus::Exists {
+                        owner: Pubkey::new_unique(),
+                        lamports: 1,
+                        data_len: 0,
+                    },
+                ),
+            ],
+        };
+
+        let flagged: Vec<&ReferencedAccount> = report.flagged().collect();
+
+        assert_eq!(flagged, vec![&report.accounts[0]]);
+    }
+
+    #[test]
+    fn test_account_report() {
+        let (payer, guardian_set) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (existing, missing) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (placeholder, substituted) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let owner = Pubkey::new_unique();
+        let mut conn = MockConnection::new();
+        for pubkey in [payer, guardian_set] {
+            conn.accounts.insert(pubkey, Account::default());
+        }
+        conn.accounts.insert(
+            existing,
+            Account {
+                lamports: 5,
+                data: vec![0; 8],
+                owner,
+                ..Account::default()
+            },
+        );
+        let program_id = Pubkey::new_unique();
+        let groups = [
+            group(
+                program_id,
+                &[
+                    (RESOLVER_PUBKEY_PAYER, true, true),
+                    (existing, false, false),
+                    (missing, true, false),
+                    (RESOLVER_PUBKEY_SHIM_VAA_SIGS, true, false),
+                    (RESOLVER_PUBKEY_KEYPAIR_00, true, true),
+                    (placeholder, false, false),
+                ],
+            ),
+            group(
+                program_id,
+                &[
+                    (existing, true, false),
+                    (RESOLVER_PUBKEY_GUARDIAN_SET, false, false),
+                ],
+            ),
+        ];
+
+        let report = account_report(
+            &conn,
+            &groups,
+            &payer,
+            &guardian_set,
+            &[(placeholder, substituted)],
+        )
+        .unwrap();
+
+        let summary: Vec<_> = report
+            .accounts
+            .iter()
+            .map(|a| (a.referenced, a.address, a.writable, a.groups.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (RESOLVER_PUBKEY_PAYER, payer, true, vec![0]),
+                (existing, existing, true, vec![0, 1]),
+                (missing, missing, true, vec![0]),
+                (
+                    RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+                    RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+                    true,
+                    vec![0]
+                ),
+                (
+                    RESOLVER_PUBKEY_KEYPAIR_00,
+                    RESOLVER_PUBKEY_KEYPAIR_00,
+                    true,
+                    vec![0]
+                ),
+                (placeholder, substituted, false, vec![0]),
+                (RESOLVER_PUBKEY_GUARDIAN_SET, guardian_set, false, vec![1]),
+            ]
+        );
+        let statuses: Vec<_> = report.accounts.iter().map(|a| a.status.clone()).collect();
+        let exists = |lamports, data_len, owner| AccountStatus::Exists {
+            owner,
+            lamports,
+            data_len,
+        };
+        assert_eq!(
+            statuses,
+            vec![
+                exists(0, 0, Pubkey::default()),
+                exists(5, 8, owner),
+                AccountStatus::Missing,
+                AccountStatus::CreatedByBroadcast,
+                AccountStatus::CreatedByBroadcast,
+                AccountStatus::Missing,
+                exists(0, 0, Pubkey::default()),
+            ]
+        );
+        let flagged: Vec<Pubkey> = report.flagged().map(|a| a.address).collect();
+        assert_eq!(flagged, vec![missing]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Conversion helpers for exchanging instructions with code on the solana 1.x
+//! line.
+//!
+//! The crate is built against the 2.x `solana-sdk` line, and the 1.x crates
+//! can't join that dependency graph: 1.x `solana-program` pins `zeroize` below
+//! 1.4 through `curve25519-dalek` 3, while the 2.x line needs 1.7 or later. A
+//! 1.x program or service therefore talks to this crate across a serialization
+//! boundary (a separate process or a file) instead of through Rust types.
+//!
+//! The bincode encodings of instructions, addresses and hashes are the same on
+//! both lines, so the bytes produced here deserialize directly into the 1.x
+//! `solana_program::instruction::Instruction`, and 1.x bytes into the 2.x one.
+//! Signed transactions need no helper: their wire format is shared too, and
+//! [`VersionedTransaction`](solana_sdk::transaction::VersionedTransaction)
+//! already serializes to it.
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+
+use crate::resolve::InstructionGroup;
+
+/// The bincode encoding of an instruction, readable by 1.x code.
+pub fn instruction_to_bytes(instruction: &Instruction) -> Vec<u8> {
+    bincode::serialize(instruction).expect("instructions always serialize")
+}
+
+/// An instruction from its bincode encoding, e.g. written by a 1.x program
+/// crate.
+pub fn instruction_from_bytes(bytes: &[u8]) -> bincode::Result<Instruction> {
+    bincode::deserialize(bytes)
+}
+
+/// The instructions of resolved groups in their bincode encoding, one `Vec`
+/// per group. Placeholders are left as they are.
+pub fn instruction_groups_to_bytes(groups: &[InstructionGroup]) -> Vec<Vec<Vec<u8>>> {
+    groups
+        .iter()
+        .map(|group| {
+            group
+                .instructions
+                .iter()
+                .map(|ix| {
+                    instruction_to_bytes(&Instruction {
+                        program_id: ix.program_id,
+                        accounts: ix
+                            .accounts
+                            .iter()
+                            .map(|meta| AccountMeta {
+                                pubkey: meta.pubkey,
+                                is_signer: meta.is_signer,
+                                is_writable: meta.is_writable,
+                            })
+                            .collect(),
+                        data: ix.data.clone(),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_instruction_roundtrip() {
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(Pubkey::new_unique(), true),
+                AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            ],
+            data: vec![1, 2, 3],
+        };
+
+        let bytes = instruction_to_bytes(&instruction);
+
+        assert_eq!(instruction_from_bytes(&bytes).unwrap(), instruction);
+    }
+
+    #[test]
+    fn test_instruction_encoding_matches_v1_layout() {
+        // The layout of the 1.x `Instruction`: the program id, then the
+        // accounts and the data as u64-length-prefixed sequences.
+        let program_id = Pubkey::new_from_array([1; 32]);
+        let account = Pubkey::new_from_array([2; 32]);
+        let instruction = Instruction {
+            program_id,
+            accounts: vec![AccountMeta::new_readonly(account, true)],
+            data: vec![9, 8],
+        };
+
+        let mut expected = program_id.to_bytes().to_vec();
+        expected.extend_from_slice(&1u64.to_le_bytes());
+        expected.extend_from_slice(&account.to_bytes());
+        expected.extend_from_slice(&[1, 0]);
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        expected.extend_from_slice(&[9, 8]);
+
+        assert_eq!(instruction_to_bytes(&instruction), expected);
+    }
+
+    #[test]
+    fn test_instruction_groups_keep_placeholders() {
+        let groups = [crate::test_support::group(
+            Pubkey::new_unique(),
+            &[(crate::RESOLVER_PUBKEY_PAYER, true, true)],
+        )];
+
+        let encoded = instruction_groups_to_bytes(&groups);
+
+        assert_eq!(encoded.len(), 1);
+        let instruction = instruction_from_bytes(&encoded[0][0]).unwrap();
+        assert_eq!(instruction.accounts[0].pubkey, crate::RESOLVER_PUBKEY_PAYER);
+        assert!(instruction.accounts[0].is_signer && instruction.accounts[0].is_writable);
+    }
+}
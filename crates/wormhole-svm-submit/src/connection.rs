@@ -1,15 +1,81 @@
 //! The [`SolanaConnection`] trait and its implementation for [`RpcClient`].
+//!
+//! With the `rpc` feature, [`rpc_client_with_config`] builds an [`RpcClient`]
+//! that sends custom HTTP headers, for authenticated RPC providers, and
+//! [`RpcClientWithCommitment`] uses another commitment than confirmed.
 
 use solana_sdk::{
-    account::Account, hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction,
+    account::Account,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, TransactionError, VersionedTransaction},
 };
 
+/// Why a sent transaction failed; see
+/// [`SolanaConnection::transaction_failure`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionFailure {
+    /// The transaction expired or was dropped before landing, so re-signing
+    /// it with a fresh blockhash may succeed.
+    Expired,
+    /// An instruction returned an error.
+    ProgramError,
+    /// An account can't pay the fee or its rent.
+    InsufficientFunds,
+    /// Anything else.
+    Other,
+}
+
+impl TransactionFailure {
+    /// Classify a failure by its message, for errors that carry nothing
+    /// more structured.
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("blockhash not found")
+            || message.contains("blockhashnotfound")
+            || message.contains("expired")
+            || message.contains("unable to confirm transaction")
+            || message.contains("timed out")
+        {
+            TransactionFailure::Expired
+        } else if message.contains("insufficient funds") || message.contains("insufficientfunds") {
+            TransactionFailure::InsufficientFunds
+        } else if message.contains("custom program error")
+            || message.contains("instructionerror")
+            || message.contains("error processing instruction")
+        {
+            TransactionFailure::ProgramError
+        } else {
+            TransactionFailure::Other
+        }
+    }
+}
+
+impl From<&TransactionError> for TransactionFailure {
+    fn from(error: &TransactionError) -> Self {
+        match error {
+            TransactionError::BlockhashNotFound => TransactionFailure::Expired,
+            TransactionError::InstructionError(..) => TransactionFailure::ProgramError,
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. } => {
+                TransactionFailure::InsufficientFunds
+            }
+            _ => TransactionFailure::Other,
+        }
+    }
+}
+
 /// Result of simulating a transaction, including post-simulation account data.
 pub struct SimulationResult {
     /// The program return data bytes, if any.
     pub return_data: Option<Vec<u8>>,
     /// Post-simulation account data for requested accounts (pubkey -> data bytes).
     pub post_accounts: Vec<(Pubkey, Vec<u8>)>,
+    /// Compute units consumed by the simulated transaction, if reported.
+    pub units_consumed: Option<u64>,
+    /// Program log messages.
+    pub logs: Vec<String>,
 }
 
 /// Abstraction over Solana connectivity for resolver and executor logic.
@@ -31,23 +97,191 @@ pub trait SolanaConnection {
     /// Send a transaction and wait for confirmation.
     fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error>;
 
+    /// Send a versioned (e.g. v0, using address lookup tables) transaction and
+    /// wait for confirmation.
+    ///
+    /// Connections that can't send v0 transactions return an error for them
+    /// (transactions with a legacy message can still go through
+    /// [`send_and_confirm`](Self::send_and_confirm) after
+    /// [`VersionedTransaction::into_legacy_transaction`]) and keep
+    /// [`supports_versioned_transactions`](Self::supports_versioned_transactions)
+    /// at `false`.
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error>;
+
+    /// Whether [`send_and_confirm_versioned`](Self::send_and_confirm_versioned)
+    /// accepts v0 transactions. Groups that need an address lookup table fail
+    /// before anything is sent when it doesn't.
+    fn supports_versioned_transactions(&self) -> bool {
+        false
+    }
+
+    /// Why a send failed with `error`.
+    ///
+    /// Classified by the error message by default; connections whose errors
+    /// carry the [`TransactionError`] classify that instead.
+    fn transaction_failure(&self, error: &Self::Error) -> TransactionFailure {
+        TransactionFailure::from_message(&error.to_string())
+    }
+
+    /// Outcome of a sent transaction: `Some(Ok(()))` if it landed,
+    /// `Some(Err(message))` if it landed and failed, `None` if the cluster
+    /// hasn't seen it.
+    ///
+    /// Used to check whether a send that reported an error landed anyway.
+    /// Returns `None` by default, for connections whose sends never land
+    /// after failing.
+    fn get_signature_status(
+        &self,
+        _signature: &Signature,
+    ) -> Result<Option<Result<(), String>>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Whether a transaction with `blockhash` can still land.
+    ///
+    /// `false` by default, like [`get_signature_status`](Self::get_signature_status).
+    fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
     /// Fetch an account, returning `None` if it doesn't exist.
     fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error>;
+
+    /// Fetch several accounts, returning `None` for those that don't exist.
+    ///
+    /// Fetches them one by one by default; RPC batches the requests.
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, Self::Error> {
+        pubkeys
+            .iter()
+            .map(|pubkey| self.get_account(pubkey))
+            .collect()
+    }
+
+    /// Called while waiting for the slot to advance, e.g. for a new address
+    /// lookup table to become usable.
+    ///
+    /// Clusters advance on their own, so this does nothing by default; local
+    /// SVMs move their clock forward instead.
+    fn advance_slot(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// HTTP options for [`rpc_client_with_config`].
+#[cfg(feature = "rpc")]
+#[derive(Clone, Debug, Default)]
+pub struct RpcConnectionConfig {
+    /// Extra headers sent with every request, as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+    /// Sent as `Authorization: Bearer <token>`.
+    pub bearer_token: Option<String>,
+    /// Request timeout (defaults to 30 seconds, like [`RpcClient::new`]).
+    pub timeout: Option<std::time::Duration>,
+}
+
+/// Build an [`RpcClient`] that sends the headers in `config` with every request.
+#[cfg(feature = "rpc")]
+pub fn rpc_client_with_config(
+    url: &str,
+    config: &RpcConnectionConfig,
+) -> Result<solana_client::rpc_client::RpcClient, crate::SubmitError> {
+    use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+    use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+    use solana_rpc_client::http_sender::HttpSender;
+
+    let invalid_header = |e: &dyn std::fmt::Display| {
+        crate::SubmitError::Connection(format!("invalid header: {}", e))
+    };
+
+    let mut headers = HttpSender::default_headers();
+    for (name, value) in &config.headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid_header(&e))?;
+        let value = HeaderValue::from_str(value).map_err(|e| invalid_header(&e))?;
+        headers.insert(name, value);
+    }
+    if let Some(token) = &config.bearer_token {
+        let mut value =
+            HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|e| invalid_header(&e))?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    let timeout = config.timeout.unwrap_or(std::time::Duration::from_secs(30));
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(timeout)
+        .pool_idle_timeout(timeout)
+        .build()
+        .map_err(|e| {
+            crate::SubmitError::Connection(format!("failed to build HTTP client: {}", e))
+        })?;
+
+    Ok(RpcClient::new_sender(
+        HttpSender::new_with_client(url, client),
+        RpcClientConfig::default(),
+    ))
+}
+
+/// An [`RpcClient`] connection whose reads, simulations and transaction
+/// confirmations all use `commitment`.
+///
+/// A plain [`RpcClient`] simulates and confirms at confirmed, whatever its
+/// own commitment. Use this to wait for finalized before moving to the next
+/// transaction, or to use processed on a local validator.
+#[cfg(feature = "rpc")]
+pub struct RpcClientWithCommitment {
+    pub client: solana_client::rpc_client::RpcClient,
+    pub commitment: solana_sdk::commitment_config::CommitmentConfig,
+}
+
+#[cfg(feature = "rpc")]
+impl RpcClientWithCommitment {
+    pub fn new(
+        client: solana_client::rpc_client::RpcClient,
+        commitment: solana_sdk::commitment_config::CommitmentConfig,
+    ) -> Self {
+        Self { client, commitment }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl std::ops::Deref for RpcClientWithCommitment {
+    type Target = solana_client::rpc_client::RpcClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
 }
 
 #[cfg(feature = "rpc")]
 mod rpc_impl {
+    use solana_client::client_error::ClientError;
     use solana_client::rpc_client::RpcClient;
     use solana_client::rpc_config::RpcSimulateTransactionConfig;
     use solana_sdk::{
-        account::Account, commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey,
-        signature::Signature, transaction::Transaction,
+        account::Account,
+        commitment_config::CommitmentConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{Transaction, VersionedTransaction},
     };
 
-    use super::{SimulationResult, SolanaConnection};
+    use super::{RpcClientWithCommitment, SimulationResult, SolanaConnection, TransactionFailure};
 
+    /// Maximum number of accounts per `getMultipleAccounts` request.
+    const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+    /// Simulations and confirmations use confirmed; reads use the client's
+    /// commitment. See [`RpcClientWithCommitment`] for another commitment.
     impl SolanaConnection for RpcClient {
-        type Error = solana_client::client_error::ClientError;
+        type Error = ClientError;
 
         fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
             RpcClient::get_latest_blockhash(self)
@@ -58,107 +292,55 @@ mod rpc_impl {
             tx: &Transaction,
             accounts: &[Pubkey],
         ) -> Result<SimulationResult, Self::Error> {
-            use solana_account_decoder_client_types::UiAccountEncoding;
-            use solana_client::rpc_config::RpcSimulateTransactionAccountsConfig;
+            simulate(self, tx, accounts, CommitmentConfig::confirmed())
+        }
 
-            let sim_result = self.simulate_transaction_with_config(
+        fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+            self.send_and_confirm_transaction_with_spinner_and_commitment(
                 tx,
-                RpcSimulateTransactionConfig {
-                    sig_verify: false,
-                    replace_recent_blockhash: true,
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    accounts: Some(RpcSimulateTransactionAccountsConfig {
-                        encoding: Some(UiAccountEncoding::Base64),
-                        addresses: accounts.iter().map(|p| p.to_string()).collect(),
-                    }),
-                    ..Default::default()
-                },
-            )?;
-
-            let sim_value = sim_result.value;
-
-            if let Some(err) = &sim_value.err {
-                if let Some(logs) = &sim_value.logs {
-                    for log in logs {
-                        if log.contains("Error") || log.contains("error") || log.contains("failed")
-                        {
-                            eprintln!("  SIM LOG: {}", log);
-                        }
-                    }
-                }
-                return Err(solana_client::client_error::ClientError::from(
-                    solana_client::rpc_request::RpcError::ForUser(format!(
-                        "Simulation error: {:?}",
-                        err
-                    )),
-                ));
-            }
-
-            let return_data = match sim_value.return_data {
-                Some(rd) => {
-                    let data_bytes = base64::Engine::decode(
-                        &base64::engine::general_purpose::STANDARD,
-                        &rd.data.0,
-                    )
-                    .map_err(|e| {
-                        solana_client::client_error::ClientError::from(
-                            solana_client::rpc_request::RpcError::ForUser(format!(
-                                "Failed to decode base64 return data: {}",
-                                e
-                            )),
-                        )
-                    })?;
-                    if data_bytes.is_empty() {
-                        None
-                    } else {
-                        Some(data_bytes)
-                    }
-                }
-                None => None,
-            };
-
-            let mut post_accounts = Vec::new();
-            if let Some(sim_accounts) = sim_value.accounts {
-                for (i, maybe_account) in sim_accounts.iter().enumerate() {
-                    if i < accounts.len() {
-                        if let Some(ui_account) = maybe_account {
-                            use solana_account_decoder_client_types::UiAccountData;
-                            let b64_str = match &ui_account.data {
-                                UiAccountData::Binary(s, _) => s.as_str(),
-                                UiAccountData::LegacyBinary(s) => s.as_str(),
-                                _ => continue,
-                            };
-                            let data_bytes = base64::Engine::decode(
-                                &base64::engine::general_purpose::STANDARD,
-                                b64_str,
-                            )
-                            .map_err(|e| {
-                                solana_client::client_error::ClientError::from(
-                                    solana_client::rpc_request::RpcError::ForUser(format!(
-                                        "Failed to decode account data: {}",
-                                        e
-                                    )),
-                                )
-                            })?;
-                            post_accounts.push((accounts[i], data_bytes));
-                        }
-                    }
-                }
-            }
-
-            Ok(SimulationResult {
-                return_data,
-                post_accounts,
-            })
+                CommitmentConfig::confirmed(),
+            )
         }
 
-        fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+        fn send_and_confirm_versioned(
+            &mut self,
+            tx: &VersionedTransaction,
+        ) -> Result<Signature, Self::Error> {
             self.send_and_confirm_transaction_with_spinner_and_commitment(
                 tx,
                 CommitmentConfig::confirmed(),
             )
         }
 
+        fn supports_versioned_transactions(&self) -> bool {
+            true
+        }
+
+        /// Confirmation timeouts carry no transaction error, so those are
+        /// classified by their message.
+        fn transaction_failure(&self, error: &ClientError) -> TransactionFailure {
+            match error.get_transaction_error() {
+                Some(e) => TransactionFailure::from(&e),
+                None => TransactionFailure::from_message(&error.to_string()),
+            }
+        }
+
+        fn get_signature_status(
+            &self,
+            signature: &Signature,
+        ) -> Result<Option<Result<(), String>>, Self::Error> {
+            let statuses = self.get_signature_statuses(&[*signature])?.value;
+            Ok(statuses
+                .into_iter()
+                .flatten()
+                .next()
+                .map(|status| status.status.map_err(|e| e.to_string())))
+        }
+
+        fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, Self::Error> {
+            RpcClient::is_blockhash_valid(self, blockhash, CommitmentConfig::processed())
+        }
+
         fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
             match RpcClient::get_account(self, pubkey) {
                 Ok(account) => Ok(Some(account)),
@@ -175,5 +357,241 @@ mod rpc_impl {
                 }
             }
         }
+
+        fn get_multiple_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<Account>>, Self::Error> {
+            let mut accounts = Vec::with_capacity(pubkeys.len());
+            for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+                accounts.extend(RpcClient::get_multiple_accounts(self, chunk)?);
+            }
+            Ok(accounts)
+        }
+    }
+
+    impl SolanaConnection for RpcClientWithCommitment {
+        type Error = ClientError;
+
+        fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
+            self.client
+                .get_latest_blockhash_with_commitment(self.commitment)
+                .map(|(blockhash, _)| blockhash)
+        }
+
+        fn simulate_with_post_accounts(
+            &self,
+            tx: &Transaction,
+            accounts: &[Pubkey],
+        ) -> Result<SimulationResult, Self::Error> {
+            simulate(&self.client, tx, accounts, self.commitment)
+        }
+
+        fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+            self.client
+                .send_and_confirm_transaction_with_spinner_and_commitment(tx, self.commitment)
+        }
+
+        fn send_and_confirm_versioned(
+            &mut self,
+            tx: &VersionedTransaction,
+        ) -> Result<Signature, Self::Error> {
+            self.client
+                .send_and_confirm_transaction_with_spinner_and_commitment(tx, self.commitment)
+        }
+
+        fn supports_versioned_transactions(&self) -> bool {
+            true
+        }
+
+        fn transaction_failure(&self, error: &ClientError) -> TransactionFailure {
+            SolanaConnection::transaction_failure(&self.client, error)
+        }
+
+        fn get_signature_status(
+            &self,
+            signature: &Signature,
+        ) -> Result<Option<Result<(), String>>, Self::Error> {
+            SolanaConnection::get_signature_status(&self.client, signature)
+        }
+
+        fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, Self::Error> {
+            SolanaConnection::is_blockhash_valid(&self.client, blockhash)
+        }
+
+        fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
+            self.client
+                .get_account_with_commitment(pubkey, self.commitment)
+                .map(|response| response.value)
+        }
+
+        fn get_multiple_accounts(
+            &self,
+            pubkeys: &[Pubkey],
+        ) -> Result<Vec<Option<Account>>, Self::Error> {
+            let mut accounts = Vec::with_capacity(pubkeys.len());
+            for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+                accounts.extend(
+                    self.client
+                        .get_multiple_accounts_with_commitment(chunk, self.commitment)?
+                        .value,
+                );
+            }
+            Ok(accounts)
+        }
+    }
+
+    fn simulate(
+        client: &RpcClient,
+        tx: &Transaction,
+        accounts: &[Pubkey],
+        commitment: CommitmentConfig,
+    ) -> Result<SimulationResult, ClientError> {
+        use solana_account_decoder_client_types::UiAccountEncoding;
+        use solana_client::rpc_config::RpcSimulateTransactionAccountsConfig;
+
+        let sim_result = client.simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(commitment),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: accounts.iter().map(|p| p.to_string()).collect(),
+                }),
+                ..Default::default()
+            },
+        )?;
+
+        let sim_value = sim_result.value;
+
+        if let Some(err) = &sim_value.err {
+            if let Some(logs) = &sim_value.logs {
+                for log in logs {
+                    if log.contains("Error") || log.contains("error") || log.contains("failed") {
+                        log::debug!("simulation log: {}", log);
+                    }
+                }
+            }
+            return Err(ClientError::from(
+                solana_client::rpc_request::RpcError::ForUser(format!(
+                    "Simulation error: {:?}",
+                    err
+                )),
+            ));
+        }
+
+        let return_data = match sim_value.return_data {
+            Some(rd) => {
+                let data_bytes =
+                    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &rd.data.0)
+                        .map_err(|e| {
+                            ClientError::from(solana_client::rpc_request::RpcError::ForUser(
+                                format!("Failed to decode base64 return data: {}", e),
+                            ))
+                        })?;
+                if data_bytes.is_empty() {
+                    None
+                } else {
+                    Some(data_bytes)
+                }
+            }
+            None => None,
+        };
+
+        let mut post_accounts = Vec::new();
+        if let Some(sim_accounts) = sim_value.accounts {
+            for (i, maybe_account) in sim_accounts.iter().enumerate() {
+                if i < accounts.len() {
+                    if let Some(ui_account) = maybe_account {
+                        use solana_account_decoder_client_types::UiAccountData;
+                        let b64_str = match &ui_account.data {
+                            UiAccountData::Binary(s, _) => s.as_str(),
+                            UiAccountData::LegacyBinary(s) => s.as_str(),
+                            _ => continue,
+                        };
+                        let data_bytes = base64::Engine::decode(
+                            &base64::engine::general_purpose::STANDARD,
+                            b64_str,
+                        )
+                        .map_err(|e| {
+                            ClientError::from(solana_client::rpc_request::RpcError::ForUser(
+                                format!("Failed to decode account data: {}", e),
+                            ))
+                        })?;
+                        post_accounts.push((accounts[i], data_bytes));
+                    }
+                }
+            }
+        }
+
+        Ok(SimulationResult {
+            return_data,
+            post_accounts,
+            units_consumed: sim_value.units_consumed,
+            logs: sim_value.logs.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::instruction::InstructionError;
+
+    use super::*;
+
+    #[test]
+    fn test_failure_from_message() {
+        let cases = [
+            (
+                "Transaction simulation failed: Blockhash not found",
+                TransactionFailure::Expired,
+            ),
+            ("BlockhashNotFound", TransactionFailure::Expired),
+            (
+                "unable to confirm transaction. This can happen in situations such as transaction expiration",
+                TransactionFailure::Expired,
+            ),
+            (
+                "Error processing Instruction 0: custom program error: 0x1",
+                TransactionFailure::ProgramError,
+            ),
+            (
+                "Transaction results in an account (1) with insufficient funds for rent",
+                TransactionFailure::InsufficientFunds,
+            ),
+            ("unknown", TransactionFailure::Other),
+        ];
+        for (message, failure) in cases {
+            assert_eq!(
+                TransactionFailure::from_message(message),
+                failure,
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_failure_from_transaction_error() {
+        let cases = [
+            (
+                TransactionError::BlockhashNotFound,
+                TransactionFailure::Expired,
+            ),
+            (
+                TransactionError::InstructionError(0, InstructionError::Custom(1)),
+                TransactionFailure::ProgramError,
+            ),
+            (
+                TransactionError::InsufficientFundsForFee,
+                TransactionFailure::InsufficientFunds,
+            ),
+            (TransactionError::AccountInUse, TransactionFailure::Other),
+        ];
+        for (error, failure) in cases {
+            assert_eq!(TransactionFailure::from(&error), failure, "{}", error);
+        }
     }
 }
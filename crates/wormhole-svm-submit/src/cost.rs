@@ -0,0 +1,385 @@
+//! Up-front estimate of what a broadcast costs the payer.
+//!
+//! Set [`BroadcastConfig::max_cost_lamports`](crate::BroadcastConfig::max_cost_lamports)
+//! to abort a broadcast whose estimate exceeds a cap before anything is sent.
+//! Broadcasts also check that the payer can afford the estimate up front, so a
+//! broke payer fails before any rent is spent.
+
+use solana_sdk::{pubkey::Pubkey, rent::Rent, signature::Signer};
+
+use crate::connection::SolanaConnection;
+
+use crate::execute::{
+    convert_instruction, ComputeUnitLimit, ExecuteConfig, MAX_COMPUTE_UNIT_LIMIT,
+};
+use crate::network::WormholeNetwork;
+use crate::resolve::InstructionGroup;
+use crate::signatures::signatures_account_rent;
+use crate::SubmitError;
+
+/// Base fee charged per transaction signature.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Compute units the runtime allots per instruction when no limit is set.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// Base fees of posting (payer and signatures account) and closing (payer)
+/// the guardian signatures, which the payer pays even with a separate fee
+/// payer.
+const POST_AND_CLOSE_FEES: u64 = 3 * LAMPORTS_PER_SIGNATURE;
+
+/// Offset of the message fee in the core bridge config account (after the
+/// guardian set index, last lamports and guardian set expiration time).
+const BRIDGE_FEE_OFFSET: usize = 16;
+
+/// Estimated lamports spent by a broadcast.
+///
+/// Covers the guardian signatures account rent (reclaimed when the account is
+/// closed, but needed up front), base fees for every transaction of the flow
+/// and priority fees of the execution transactions. Rent for accounts created
+/// by the resolved instructions themselves isn't included.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Rent-exempt balance of the guardian signatures account.
+    pub signatures_rent: u64,
+    /// Base fees of the post, execution and close transactions.
+    pub transaction_fees: u64,
+    /// Upper bound of the priority fees of the execution transactions.
+    pub priority_fees: u64,
+}
+
+impl CostEstimate {
+    /// Total estimated lamports.
+    pub fn total(&self) -> u64 {
+        self.signatures_rent
+            .saturating_add(self.transaction_fees)
+            .saturating_add(self.priority_fees)
+    }
+}
+
+/// Estimate the cost of posting `num_signatures` guardian signatures,
+/// executing `groups` with `config` and closing the signatures account.
+///
+/// Priority fees assume the worst case: the highest compute unit price
+/// retries may escalate to, and the full compute unit limit of each
+/// transaction (the runtime default, or [`MAX_COMPUTE_UNIT_LIMIT`] for
+/// [`ComputeUnitLimit::Simulated`]).
+pub fn estimate_broadcast_cost(
+    groups: &[InstructionGroup],
+    payer: &Pubkey,
+    num_signatures: usize,
+    config: &ExecuteConfig,
+) -> CostEstimate {
+    // Posting is signed by the payer and the new signatures account, closing
+    // by the payer alone.
+    let mut transaction_fees = POST_AND_CLOSE_FEES;
+    let mut priority_fees = 0u64;
+
+    for group in groups {
+        let cost = estimate_group_cost(group, payer, config);
+        transaction_fees = transaction_fees.saturating_add(cost.transaction_fee);
+        priority_fees = priority_fees.saturating_add(cost.priority_fee);
+    }
+
+    CostEstimate {
+        signatures_rent: signatures_account_rent(&Rent::default(), num_signatures),
+        transaction_fees,
+        priority_fees,
+    }
+}
+
+/// Estimated lamports spent by the execution transaction of one instruction
+/// group (see [`estimate_group_cost`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GroupCost {
+    /// Base fee: one per signer of the transaction.
+    pub transaction_fee: u64,
+    /// Upper bound of the priority fee.
+    pub priority_fee: u64,
+    /// Compute unit limit the priority fee is charged for.
+    pub compute_unit_limit: u64,
+}
+
+/// Estimate the cost of executing `group` with `config`, with the same worst
+/// case assumptions as [`estimate_broadcast_cost`].
+pub fn estimate_group_cost(
+    group: &InstructionGroup,
+    payer: &Pubkey,
+    config: &ExecuteConfig,
+) -> GroupCost {
+    let fee_payer = config.fee_payer.map_or(*payer, |signer| signer.pubkey());
+    let instructions: Vec<_> = group
+        .instructions
+        .iter()
+        .map(|si| {
+            convert_instruction(
+                si,
+                payer,
+                &Pubkey::default(),
+                &Pubkey::default(),
+                config.placeholders,
+            )
+        })
+        .collect();
+
+    let mut signers = vec![fee_payer];
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_signer && !signers.contains(&meta.pubkey) {
+            signers.push(meta.pubkey);
+        }
+    }
+
+    let instruction_count = instructions.len() + usize::from(config.memo.is_some());
+    let compute_unit_limit = match config.compute_unit_limit {
+        ComputeUnitLimit::Default => (instruction_count as u64
+            * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT)
+            .min(MAX_COMPUTE_UNIT_LIMIT as u64),
+        ComputeUnitLimit::Fixed(units) => units.min(MAX_COMPUTE_UNIT_LIMIT) as u64,
+        ComputeUnitLimit::Simulated { .. } => MAX_COMPUTE_UNIT_LIMIT as u64,
+    };
+
+    GroupCost {
+        transaction_fee: signers.len() as u64 * LAMPORTS_PER_SIGNATURE,
+        priority_fee: priority_fee(worst_case_compute_unit_price(config), compute_unit_limit),
+        compute_unit_limit,
+    }
+}
+
+/// Core bridge message fees paid by `groups`: the current fee of `network`
+/// for each group that references its fee collector.
+pub fn estimate_bridge_fees<C: SolanaConnection>(
+    conn: &C,
+    network: &WormholeNetwork,
+    groups: &[InstructionGroup],
+) -> Result<u64, SubmitError> {
+    let fee_collector = network.fee_collector_address();
+    let paying_groups = groups
+        .iter()
+        .filter(|group| {
+            group
+                .instructions
+                .iter()
+                .any(|ix| ix.accounts.iter().any(|meta| meta.pubkey == fee_collector))
+        })
+        .count() as u64;
+    if paying_groups == 0 {
+        return Ok(0);
+    }
+
+    Ok(bridge_fee(conn, network)?.saturating_mul(paying_groups))
+}
+
+/// The current core bridge message fee of `network` (0 if the config account
+/// doesn't exist).
+pub fn bridge_fee<C: SolanaConnection>(
+    conn: &C,
+    network: &WormholeNetwork,
+) -> Result<u64, SubmitError> {
+    let config = conn
+        .get_account(&network.core_bridge_config_address())
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    Ok(config
+        .and_then(|account| {
+            let bytes = account.data.get(BRIDGE_FEE_OFFSET..BRIDGE_FEE_OFFSET + 8)?;
+            Some(u64::from_le_bytes(bytes.try_into().unwrap()))
+        })
+        .unwrap_or(0))
+}
+
+/// Lamports each wallet needs for a broadcast costing `estimate` plus
+/// `bridge_fees`.
+///
+/// With a separate `fee_payer`, the payer only needs the signatures rent,
+/// bridge fees and the fees of posting and closing the signatures; the fee
+/// payer covers the execution transactions.
+pub fn required_balances(
+    estimate: &CostEstimate,
+    bridge_fees: u64,
+    payer: &Pubkey,
+    fee_payer: Option<&Pubkey>,
+) -> Vec<(Pubkey, u64)> {
+    match fee_payer.filter(|fee_payer| *fee_payer != payer) {
+        None => vec![(*payer, estimate.total().saturating_add(bridge_fees))],
+        Some(fee_payer) => {
+            let payer_share = estimate
+                .signatures_rent
+                .saturating_add(bridge_fees)
+                .saturating_add(POST_AND_CLOSE_FEES);
+            let fee_payer_share = estimate
+                .transaction_fees
+                .saturating_sub(POST_AND_CLOSE_FEES)
+                .saturating_add(estimate.priority_fees);
+            vec![(*payer, payer_share), (*fee_payer, fee_payer_share)]
+        }
+    }
+}
+
+/// Fail with [`SubmitError::InsufficientFunds`] unless every wallet holds
+/// its share of the broadcast cost (see [`required_balances`]).
+pub fn ensure_sufficient_balances<C: SolanaConnection>(
+    conn: &C,
+    estimate: &CostEstimate,
+    bridge_fees: u64,
+    payer: &Pubkey,
+    fee_payer: Option<&Pubkey>,
+) -> Result<(), SubmitError> {
+    for (account, required) in required_balances(estimate, bridge_fees, payer, fee_payer) {
+        let available = conn
+            .get_account(&account)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?
+            .map_or(0, |account| account.lamports);
+        if available < required {
+            return Err(SubmitError::InsufficientFunds {
+                account,
+                required,
+                available,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The highest compute unit price an execution transaction may land with.
+fn worst_case_compute_unit_price(config: &ExecuteConfig) -> u64 {
+    let initial = config.compute_unit_price.unwrap_or(0);
+    match &config.fee_escalation {
+        Some(escalation) if config.max_retries > 0 => initial.max(escalation.cap),
+        _ => initial,
+    }
+}
+
+/// Priority fee in lamports for `compute_unit_limit` units at `price`
+/// micro-lamports per unit, rounded up.
+fn priority_fee(price: u64, compute_unit_limit: u64) -> u64 {
+    let micro_lamports = price as u128 * compute_unit_limit as u128;
+    micro_lamports.div_ceil(1_000_000).min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execute::FeeEscalation;
+    use crate::test_support::signer_group;
+    use crate::RESOLVER_PUBKEY_PAYER;
+    use executor_account_resolver_svm::RESOLVER_PUBKEY_KEYPAIR_00;
+
+    #[test]
+    fn test_estimate_without_priority_fees() {
+        let payer = Pubkey::new_unique();
+        let groups = [
+            signer_group(&[RESOLVER_PUBKEY_PAYER]),
+            signer_group(&[RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_KEYPAIR_00]),
+        ];
+
+        let estimate = estimate_broadcast_cost(&groups, &payer, 13, &ExecuteConfig::default());
+
+        assert_eq!(
+            estimate.signatures_rent,
+            Rent::default().minimum_balance(8 + 32 + 4 + 4 + 13 * 66)
+        );
+        // post (2) + group 0 (1) + group 1 (2) + close (1)
+        assert_eq!(estimate.transaction_fees, 6 * LAMPORTS_PER_SIGNATURE);
+        assert_eq!(estimate.priority_fees, 0);
+        assert_eq!(
+            estimate.total(),
+            estimate.signatures_rent + estimate.transaction_fees
+        );
+    }
+
+    #[test]
+    fn test_estimate_priority_fees() {
+        let payer = Pubkey::new_unique();
+        let groups = [signer_group(&[RESOLVER_PUBKEY_PAYER])];
+
+        let config = ExecuteConfig {
+            compute_unit_price: Some(1_000),
+            ..Default::default()
+        };
+        let estimate = estimate_broadcast_cost(&groups, &payer, 1, &config);
+        assert_eq!(estimate.priority_fees, 200);
+
+        let config = ExecuteConfig {
+            compute_unit_limit: ComputeUnitLimit::Fixed(50_000),
+            compute_unit_price: Some(1_000),
+            ..Default::default()
+        };
+        let estimate = estimate_broadcast_cost(&groups, &payer, 1, &config);
+        assert_eq!(estimate.priority_fees, 50);
+
+        let config = ExecuteConfig {
+            compute_unit_limit: ComputeUnitLimit::Simulated { margin_percent: 10 },
+            compute_unit_price: Some(1_000),
+            max_retries: 2,
+            fee_escalation: Some(FeeEscalation {
+                multiplier_percent: 200,
+                floor: 1,
+                cap: 5_000,
+            }),
+            ..Default::default()
+        };
+        let estimate = estimate_broadcast_cost(&groups, &payer, 1, &config);
+        assert_eq!(estimate.priority_fees, 7_000);
+    }
+
+    #[test]
+    fn test_estimate_group_cost() {
+        let payer = Pubkey::new_unique();
+        let groups = [
+            signer_group(&[RESOLVER_PUBKEY_PAYER]),
+            signer_group(&[RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_KEYPAIR_00]),
+        ];
+        let config = ExecuteConfig {
+            compute_unit_price: Some(1_000),
+            ..Default::default()
+        };
+
+        let costs: Vec<_> = groups
+            .iter()
+            .map(|group| estimate_group_cost(group, &payer, &config))
+            .collect();
+        assert_eq!(
+            costs[1],
+            GroupCost {
+                transaction_fee: 2 * LAMPORTS_PER_SIGNATURE,
+                priority_fee: 200,
+                compute_unit_limit: 200_000,
+            }
+        );
+
+        let estimate = estimate_broadcast_cost(&groups, &payer, 1, &config);
+        assert_eq!(
+            estimate.transaction_fees,
+            POST_AND_CLOSE_FEES + costs.iter().map(|c| c.transaction_fee).sum::<u64>()
+        );
+        assert_eq!(
+            estimate.priority_fees,
+            costs.iter().map(|c| c.priority_fee).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_required_balances() {
+        let (payer, fee_payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let estimate = CostEstimate {
+            signatures_rent: 1_000_000,
+            transaction_fees: 5 * LAMPORTS_PER_SIGNATURE,
+            priority_fees: 700,
+        };
+
+        assert_eq!(
+            required_balances(&estimate, 10, &payer, None),
+            vec![(payer, estimate.total() + 10)]
+        );
+        assert_eq!(
+            required_balances(&estimate, 10, &payer, Some(&payer)),
+            vec![(payer, estimate.total() + 10)]
+        );
+        assert_eq!(
+            required_balances(&estimate, 10, &payer, Some(&fee_payer)),
+            vec![
+                (payer, 1_000_010 + POST_AND_CLOSE_FEES),
+                (fee_payer, 2 * LAMPORTS_PER_SIGNATURE + 700)
+            ]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Dependency analysis between resolved instruction groups.
+//!
+//! Resolvers emit instruction groups in an order that usually, but not
+//! necessarily, works: a group that uses an account created by a later group
+//! fails. [`order_instruction_groups`] derives the order from the accounts
+//! instead:
+//! - a group that creates a missing account runs before every other group
+//!   that references it
+//! - groups that touch the same account, at least one of them writing it,
+//!   keep their resolver order. The payer, guardian set and signatures
+//!   account placeholders don't count: nearly every group references them,
+//!   and sharing them doesn't make one group depend on another.
+//!
+//! The result is an order only: groups are still sent one at a time.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use solana_sdk::{pubkey::Pubkey, system_program};
+
+use crate::connection::SolanaConnection;
+use crate::execute::KEYPAIR_PLACEHOLDERS;
+use crate::resolve::InstructionGroup;
+use crate::{
+    SubmitError, RESOLVER_PUBKEY_GUARDIAN_SET, RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+};
+
+/// Execution order of instruction groups derived from their dependencies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupOrder {
+    /// Group indices in execution order. Among groups that could run in either
+    /// order, the resolver order is kept.
+    pub order: Vec<usize>,
+}
+
+impl GroupOrder {
+    /// Whether the derived order differs from the resolver order.
+    pub fn is_reordered(&self) -> bool {
+        self.order.iter().enumerate().any(|(i, group)| i != *group)
+    }
+
+    /// Rearrange `groups` into execution order.
+    pub fn apply<T>(&self, groups: Vec<T>) -> Vec<T> {
+        let mut slots: Vec<Option<T>> = groups.into_iter().map(Some).collect();
+        self.order
+            .iter()
+            .map(|&index| slots[index].take().expect("order is a permutation"))
+            .collect()
+    }
+}
+
+/// How a group uses one account.
+#[derive(Clone, Copy, Default)]
+struct AccountUse {
+    writable: bool,
+    signer: bool,
+}
+
+/// Accounts referenced by each group, and whether the group can create
+/// accounts (invokes or references the System Program).
+fn account_uses(groups: &[InstructionGroup]) -> Vec<(HashMap<Pubkey, AccountUse>, bool)> {
+    groups
+        .iter()
+        .map(|group| {
+            let mut uses: HashMap<Pubkey, AccountUse> = HashMap::new();
+            let mut creates_accounts = false;
+            for ix in &group.instructions {
+                creates_accounts |= ix.program_id == system_program::ID;
+                for meta in &ix.accounts {
+                    creates_accounts |= meta.pubkey == system_program::ID;
+                    let entry = uses.entry(meta.pubkey).or_default();
+                    entry.writable |= meta.is_writable;
+                    entry.signer |= meta.is_signer;
+                }
+            }
+            (uses, creates_accounts)
+        })
+        .collect()
+}
+
+/// Derive the execution order of `groups`.
+///
+/// `missing` are the accounts (as referenced by the groups, i.e. before
+/// placeholder substitution) that don't exist yet, e.g. from
+/// [`missing_accounts`]. The creator of a missing account is the first group
+/// that writes it and either signs for it or can create accounts; every other
+/// group referencing it depends on the creator.
+///
+/// Fails with [`SubmitError::CyclicGroupDependencies`] if the dependencies
+/// can't be satisfied by any order.
+pub fn order_instruction_groups(
+    groups: &[InstructionGroup],
+    missing: &[Pubkey],
+) -> Result<GroupOrder, SubmitError> {
+    let uses = account_uses(groups);
+    let count = groups.len();
+    // edges[i][j]: group j must run after group i
+    let mut edges = vec![vec![false; count]; count];
+
+    for account in missing {
+        let writers: Vec<usize> = (0..count)
+            .filter(|&i| uses[i].0.get(account).is_some_and(|u| u.writable))
+            .collect();
+        let creator = writers
+            .iter()
+            .copied()
+            .find(|&i| uses[i].1 || uses[i].0[account].signer)
+            .or_else(|| writers.first().copied());
+        let Some(creator) = creator else {
+            continue;
+        };
+        for (user, (accounts, _)) in uses.iter().enumerate() {
+            if user != creator && accounts.contains_key(account) {
+                edges[creator][user] = true;
+            }
+        }
+    }
+
+    for i in 0..count {
+        for j in i + 1..count {
+            if edges[i][j] || edges[j][i] {
+                continue;
+            }
+            let conflict = uses[i].0.iter().any(|(account, a)| {
+                !is_shared_placeholder(account)
+                    && uses[j]
+                        .0
+                        .get(account)
+                        .is_some_and(|b| a.writable || b.writable)
+            });
+            if conflict {
+                edges[i][j] = true;
+            }
+        }
+    }
+
+    // Kahn's algorithm, preferring the lowest resolver index among ready groups.
+    let mut in_degree: Vec<usize> = (0..count)
+        .map(|j| (0..count).filter(|&i| edges[i][j]).count())
+        .collect();
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..count)
+        .filter(|&j| in_degree[j] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(count);
+
+    while let Some(Reverse(i)) = ready.pop() {
+        order.push(i);
+        for j in 0..count {
+            if edges[i][j] {
+                in_degree[j] -= 1;
+                if in_degree[j] == 0 {
+                    ready.push(Reverse(j));
+                }
+            }
+        }
+    }
+
+    if order.len() < count {
+        return Err(SubmitError::CyclicGroupDependencies {
+            groups: (0..count).filter(|&j| in_degree[j] > 0).collect(),
+        });
+    }
+
+    Ok(GroupOrder { order })
+}
+
+/// Whether `account` is a placeholder every group may reference without
+/// depending on the others.
+fn is_shared_placeholder(account: &Pubkey) -> bool {
+    *account == RESOLVER_PUBKEY_PAYER
+        || *account == RESOLVER_PUBKEY_GUARDIAN_SET
+        || *account == RESOLVER_PUBKEY_SHIM_VAA_SIGS
+}
+
+/// The accounts referenced by `groups` that don't exist yet.
+///
+/// `RESOLVER_PUBKEY_KEYPAIR_*` placeholders are always missing (they become
+/// fresh keypairs); the payer, guardian set and signatures account
+/// placeholders never are. Other accounts, including custom `placeholders`
+/// (checked under their substituted address), are looked up via `conn`.
+pub fn missing_accounts<C: SolanaConnection>(
+    conn: &C,
+    groups: &[InstructionGroup],
+    placeholders: &[(Pubkey, Pubkey)],
+) -> Result<Vec<Pubkey>, SubmitError> {
+    let mut checked: Vec<Pubkey> = Vec::new();
+    let mut missing = Vec::new();
+
+    let accounts = groups
+        .iter()
+        .flat_map(|group| &group.instructions)
+        .flat_map(|ix| &ix.accounts)
+        .map(|meta| meta.pubkey);
+    for account in accounts {
+        if checked.contains(&account) {
+            continue;
+        }
+        checked.push(account);
+
+        if is_shared_placeholder(&account) {
+            continue;
+        }
+        if KEYPAIR_PLACEHOLDERS.contains(&account) {
+            missing.push(account);
+            continue;
+        }
+
+        let address = placeholders
+            .iter()
+            .find(|(placeholder, _)| *placeholder == account)
+            .map_or(account, |(_, actual)| *actual);
+        let exists = conn
+            .get_account(&address)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?
+            .is_some();
+        if !exists {
+            missing.push(account);
+        }
+    }
+
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::group;
+
+    #[test]
+    fn test_creator_runs_first() {
+        let program = Pubkey::new_unique();
+        let new_account = Pubkey::new_unique();
+        let groups = [
+            group(program, &[(new_account, true, false)]),
+            group(
+                program,
+                &[
+                    (RESOLVER_PUBKEY_PAYER, true, true),
+                    (new_account, true, false),
+                    (system_program::ID, false, false),
+                ],
+            ),
+        ];
+
+        let order = order_instruction_groups(&groups, &[new_account]).unwrap();
+
+        assert_eq!(order.order, vec![1, 0]);
+        assert!(order.is_reordered());
+        assert_eq!(order.apply(vec!['a', 'b']), vec!['b', 'a']);
+    }
+
+    #[test]
+    fn test_independent_groups_keep_resolver_order() {
+        let program = Pubkey::new_unique();
+        let shared = Pubkey::new_unique();
+        let groups = [
+            group(program, &[(Pubkey::new_unique(), true, false)]),
+            group(program, &[(shared, false, false)]),
+            group(program, &[(shared, true, false)]),
+        ];
+
+        let order = order_instruction_groups(&groups, &[]).unwrap();
+
+        assert_eq!(order.order, vec![0, 1, 2]);
+        assert!(!order.is_reordered());
+    }
+
+    #[test]
+    fn test_backward_creator_with_shared_payer() {
+        let program = Pubkey::new_unique();
+        let new_account = Pubkey::new_unique();
+        let payer = (RESOLVER_PUBKEY_PAYER, true, true);
+        let groups = [
+            group(program, &[payer, (new_account, false, false)]),
+            group(program, &[payer, (Pubkey::new_unique(), true, false)]),
+            group(
+                program,
+                &[
+                    payer,
+                    (new_account, true, false),
+                    (system_program::ID, false, false),
+                ],
+            ),
+        ];
+
+        let order = order_instruction_groups(&groups, &[new_account]).unwrap();
+
+        assert_eq!(order.order, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_cyclic_dependencies() {
+        let program = Pubkey::new_unique();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let groups = [
+            group(program, &[(a, true, true), (b, false, false)]),
+            group(program, &[(b, true, true), (a, false, false)]),
+        ];
+
+        let result = order_instruction_groups(&groups, &[a, b]);
+
+        assert!(matches!(
+            result,
+            Err(SubmitError::CyclicGroupDependencies { groups }) if groups == vec![0, 1]
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Structured events emitted during a broadcast.
+//!
+//! Set [`BroadcastConfig::event_sink`](crate::BroadcastConfig::event_sink) to
+//! record each step, e.g. with [`JsonLinesSink`] for an audit trail of a
+//! relayer deployment.
+
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// A step of the broadcast flow.
+///
+/// Addresses and signatures are base58 strings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BroadcastEvent {
+    /// The resolver returned the instruction groups to execute.
+    Resolved {
+        program_id: String,
+        iterations: usize,
+        instruction_groups: usize,
+    },
+    /// Writable accounts referenced by the instruction groups don't exist
+    /// (see [`BroadcastConfig::report_accounts`](crate::BroadcastConfig::report_accounts)).
+    AccountsMissing { accounts: Vec<String> },
+    /// Guardian signatures were posted to the Verify VAA Shim.
+    SignaturesPosted { signatures_account: String },
+    /// An instruction group's transaction was confirmed.
+    GroupExecuted {
+        group_index: usize,
+        signature: String,
+        attempts: u32,
+    },
+    /// The guardian signatures account was closed.
+    Closed {
+        signatures_account: String,
+        /// `None` if the account was already closed.
+        signature: Option<String>,
+        /// Rent returned to the payer (0 if the account was already closed).
+        reclaimed_lamports: u64,
+    },
+    /// A step failed. `code` is [`SubmitError::error_code`](crate::SubmitError::error_code).
+    Error { code: u32, message: String },
+}
+
+/// Receives broadcast events as they happen.
+pub trait EventSink {
+    fn emit(&self, event: &BroadcastEvent);
+}
+
+/// Writes each event as one JSON object per line, with a `timestamp_ms` field.
+///
+/// Write errors are logged as warnings and otherwise ignored, so a full disk
+/// doesn't abort a broadcast in progress.
+pub struct JsonLinesSink<W: Write> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Consume the sink and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Serialize)]
+struct TimestampedEvent<'a> {
+    timestamp_ms: u64,
+    #[serde(flatten)]
+    event: &'a BroadcastEvent,
+}
+
+impl<W: Write> EventSink for JsonLinesSink<W> {
+    fn emit(&self, event: &BroadcastEvent) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let line = serde_json::to_string(&TimestampedEvent {
+            timestamp_ms,
+            event,
+        })
+        .expect("broadcast events are always serializable");
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            log::warn!("failed to write broadcast event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_lines_sink() {
+        let sink = JsonLinesSink::new(Vec::new());
+        sink.emit(&BroadcastEvent::SignaturesPosted {
+            signatures_account: "sigs".to_string(),
+        });
+        sink.emit(&BroadcastEvent::Error {
+            code: 100,
+            message: "boom".to_string(),
+        });
+
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["event"], "signatures_posted");
+        assert_eq!(lines[0]["signatures_account"], "sigs");
+        assert!(lines[0]["timestamp_ms"].is_u64());
+        assert_eq!(lines[1]["event"], "error");
+        assert_eq!(lines[1]["code"], 100);
+        assert_eq!(lines[1]["message"], "boom");
+    }
+}
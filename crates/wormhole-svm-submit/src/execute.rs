@@ -8,17 +8,22 @@ use executor_account_resolver_svm::{
     RESOLVER_PUBKEY_SHIM_VAA_SIGS,
 };
 use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 
-use crate::connection::SolanaConnection;
+use crate::connection::{SolanaConnection, TransactionFailure};
+use crate::events::{BroadcastEvent, EventSink};
+use crate::lookup_table;
 use crate::resolve::{InstructionGroup, SerializableInstruction};
 use crate::SubmitError;
 
-const KEYPAIR_PLACEHOLDERS: [Pubkey; 10] = [
+pub(crate) const KEYPAIR_PLACEHOLDERS: [Pubkey; 10] = [
     RESOLVER_PUBKEY_KEYPAIR_00,
     RESOLVER_PUBKEY_KEYPAIR_01,
     RESOLVER_PUBKEY_KEYPAIR_02,
@@ -31,6 +36,108 @@ const KEYPAIR_PLACEHOLDERS: [Pubkey; 10] = [
     RESOLVER_PUBKEY_KEYPAIR_09,
 ];
 
+/// SPL Memo program ID.
+pub const MEMO_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Maximum compute units a single transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// How often to check on a transaction whose confirmation failed.
+const SIGNATURE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How the compute unit limit of execution transactions is chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComputeUnitLimit {
+    /// Don't add a `set_compute_unit_limit` instruction (runtime default).
+    #[default]
+    Default,
+    /// Request this many compute units (capped at [`MAX_COMPUTE_UNIT_LIMIT`]).
+    Fixed(u32),
+    /// Simulate each transaction first and set the limit to the consumed
+    /// units plus `margin_percent` percent (capped at [`MAX_COMPUTE_UNIT_LIMIT`]).
+    Simulated { margin_percent: u32 },
+}
+
+/// Compute unit price escalation for retried transactions.
+///
+/// Each retry after an expired or dropped transaction (see
+/// [`ExecuteConfig::max_retries`]) raises the compute unit price, so the
+/// transaction competes harder for inclusion during congestion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeEscalation {
+    /// Percentage applied to the price on each retry (e.g. 200 doubles it).
+    pub multiplier_percent: u32,
+    /// Price for the first retry when no initial price is set
+    /// (micro-lamports per compute unit).
+    pub floor: u64,
+    /// Maximum escalated price (micro-lamports per compute unit).
+    pub cap: u64,
+}
+
+/// Options for [`execute_instruction_groups_with_config`].
+#[derive(Clone, Copy, Default)]
+pub struct ExecuteConfig<'a> {
+    /// Additional signers controlled by the caller (e.g. a config authority).
+    ///
+    /// Each signer is matched by pubkey and only attached to the groups whose
+    /// instructions require its signature.
+    pub extra_signers: &'a [&'a dyn Signer],
+    /// Compute unit limit strategy for each transaction.
+    pub compute_unit_limit: ComputeUnitLimit,
+    /// How many times to retry a group whose transaction expired or was
+    /// dropped, re-signing it with a fresh blockhash (0 disables retries).
+    /// A transaction that may still land is only re-signed once its
+    /// blockhash has expired.
+    ///
+    /// Only the failed group is retried; its generated keypairs are reused.
+    pub max_retries: u32,
+    /// Receives a [`BroadcastEvent::GroupExecuted`] for each confirmed group.
+    pub event_sink: Option<&'a dyn EventSink>,
+    /// Called with the group index, signature and blockhash of each
+    /// transaction right before it is sent; an error aborts execution without
+    /// sending it. Broadcasts use it to record transactions that may land
+    /// without being confirmed (see [`store`](crate::store)).
+    pub before_send: Option<&'a dyn Fn(usize, &Signature, &Hash) -> Result<(), SubmitError>>,
+    /// Text of an SPL Memo instruction appended to each transaction (e.g.
+    /// [`vaa_memo`](crate::vaa::vaa_memo), to make redemptions searchable).
+    pub memo: Option<&'a str>,
+    /// Initial compute unit price (micro-lamports per compute unit).
+    pub compute_unit_price: Option<u64>,
+    /// How the compute unit price is raised on retries.
+    pub fee_escalation: Option<FeeEscalation>,
+    /// Pays transaction fees instead of `payer`, which then only signs as
+    /// the wallet substituted for `RESOLVER_PUBKEY_PAYER`.
+    pub fee_payer: Option<&'a dyn Signer>,
+    /// Additional `(placeholder, pubkey)` substitutions, applied after the
+    /// built-in placeholders. Broadcasts also use them during resolution.
+    pub placeholders: &'a [(Pubkey, Pubkey)],
+    /// Send groups that don't fit in a legacy transaction as v0 transactions
+    /// through a temporary address lookup table, created and paid for by the
+    /// fee payer and deactivated afterwards.
+    ///
+    /// The deactivated tables still hold rent until the caller closes them
+    /// with [`close_lookup_tables`](crate::lookup_table::close_lookup_tables).
+    pub lookup_tables: bool,
+}
+
+/// Outcome of executing one instruction group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupExecution {
+    /// Signature of the confirmed transaction.
+    pub signature: Signature,
+    /// Number of send attempts, including the successful one.
+    pub attempts: u32,
+    /// Compute unit price of the confirmed transaction, if any.
+    pub compute_unit_price: Option<u64>,
+    /// Address lookup table created for the group (see
+    /// [`ExecuteConfig::lookup_tables`]), deactivated after execution. It is
+    /// not closed: pass it to
+    /// [`close_lookup_tables`](crate::lookup_table::close_lookup_tables) once
+    /// it has cooled down to reclaim its rent.
+    pub lookup_table: Option<Pubkey>,
+}
+
 /// Execute resolved instruction groups, substituting placeholder pubkeys.
 ///
 /// Each `InstructionGroup` becomes one transaction. Placeholders are replaced:
@@ -40,23 +147,68 @@ const KEYPAIR_PLACEHOLDERS: [Pubkey; 10] = [
 /// - `RESOLVER_PUBKEY_KEYPAIR_00..09` -> freshly generated keypairs (consistent across groups)
 pub fn execute_instruction_groups<C: SolanaConnection>(
     conn: &mut C,
-    payer: &Keypair,
+    payer: &dyn Signer,
+    groups: &[InstructionGroup],
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+) -> Result<Vec<Signature>, SubmitError> {
+    execute_instruction_groups_with_config(
+        conn,
+        payer,
+        groups,
+        signatures_pubkey,
+        guardian_set,
+        &ExecuteConfig::default(),
+    )
+}
+
+/// Execute resolved instruction groups with additional options.
+///
+/// See [`execute_instruction_groups`] for placeholder substitution rules.
+pub fn execute_instruction_groups_with_config<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn Signer,
     groups: &[InstructionGroup],
     signatures_pubkey: &Pubkey,
     guardian_set: &Pubkey,
+    config: &ExecuteConfig,
 ) -> Result<Vec<Signature>, SubmitError> {
+    let executions = execute_instruction_groups_with_report(
+        conn,
+        payer,
+        groups,
+        signatures_pubkey,
+        guardian_set,
+        config,
+    )?;
+    Ok(executions.iter().map(|e| e.signature).collect())
+}
+
+/// Execute resolved instruction groups and report the outcome of each group.
+///
+/// Like [`execute_instruction_groups_with_config`], but also reports how many
+/// attempts each group needed (see [`ExecuteConfig::max_retries`]).
+pub fn execute_instruction_groups_with_report<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn Signer,
+    groups: &[InstructionGroup],
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+    config: &ExecuteConfig,
+) -> Result<Vec<GroupExecution>, SubmitError> {
     // Generate keypairs up front so they're consistent across instruction groups.
     let generated_keypairs = discover_keypairs(groups);
 
-    let keypair_map: Vec<(Pubkey, Pubkey)> = generated_keypairs
+    let mut keypair_map: Vec<(Pubkey, Pubkey)> = generated_keypairs
         .iter()
         .map(|(placeholder, kp)| (*placeholder, kp.pubkey()))
         .collect();
+    keypair_map.extend_from_slice(config.placeholders);
 
-    let mut tx_sigs = Vec::new();
+    let mut executions = Vec::new();
 
-    for group in groups {
-        let instructions: Vec<Instruction> = group
+    for (group_index, group) in groups.iter().enumerate() {
+        let mut instructions: Vec<Instruction> = group
             .instructions
             .iter()
             .map(|si| {
@@ -69,9 +221,12 @@ pub fn execute_instruction_groups<C: SolanaConnection>(
                 )
             })
             .collect();
+        if let Some(memo) = config.memo {
+            instructions.push(memo_instruction(memo));
+        }
 
         // Collect signers: payer + any generated keypairs used in this group
-        let used_keypairs: Vec<&Keypair> = generated_keypairs
+        let used_keypairs = generated_keypairs
             .iter()
             .filter(|(placeholder, _)| {
                 group
@@ -79,33 +234,544 @@ pub fn execute_instruction_groups<C: SolanaConnection>(
                     .iter()
                     .any(|ix| ix.accounts.iter().any(|a| a.pubkey == *placeholder))
             })
-            .map(|(_, kp)| kp)
-            .collect();
+            .map(|(_, kp)| kp as &dyn Signer);
+
+        let fee_payer: &dyn Signer = config.fee_payer.unwrap_or(payer);
 
-        let mut signers: Vec<&Keypair> = vec![payer];
+        // Caller-supplied signers are attached only where the group requires them.
+        let used_extra_signers = config.extra_signers.iter().copied().filter(|signer| {
+            let pubkey = signer.pubkey();
+            pubkey != payer.pubkey()
+                && pubkey != fee_payer.pubkey()
+                && requires_signature(&instructions, &pubkey)
+        });
+
+        let mut signers: Vec<&dyn Signer> = vec![fee_payer];
+        if fee_payer.pubkey() != payer.pubkey()
+            && requires_signature(&instructions, &payer.pubkey())
+        {
+            signers.push(payer);
+        }
         signers.extend(used_keypairs);
+        signers.extend(used_extra_signers);
+
+        if let Some((instruction_index, account_index, placeholder)) =
+            find_unresolved_placeholder(&instructions, config.placeholders)
+        {
+            return Err(SubmitError::UnresolvedPlaceholder {
+                group_index,
+                instruction_index,
+                account_index,
+                placeholder,
+            });
+        }
+
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|s| s.pubkey()).collect();
+        if let Some(pubkey) = missing_signer(&instructions, &signer_pubkeys) {
+            return Err(SubmitError::MissingSigner {
+                group_index,
+                pubkey,
+            });
+        }
+
+        let alt = if config.lookup_tables
+            && lookup_table::exceeds_legacy_transaction_size(&instructions, &fee_payer.pubkey())
+        {
+            if !conn.supports_versioned_transactions() {
+                return Err(SubmitError::Execution(format!(
+                    "Group {} needs an address lookup table, but the connection can't send \
+                     versioned transactions",
+                    group_index
+                )));
+            }
+            log::info!(
+                "Group {} is too large for a legacy transaction; creating an address lookup table",
+                group_index
+            );
+            let addresses = lookup_table::lookup_table_addresses(&instructions);
+            Some(lookup_table::create_lookup_table(
+                conn, fee_payer, &addresses,
+            )?)
+        } else {
+            None
+        };
 
         let blockhash = conn
             .get_latest_blockhash()
             .map_err(|e| SubmitError::Connection(e.to_string()))?;
-        let tx = Transaction::new_signed_with_payer(
-            &instructions,
-            Some(&payer.pubkey()),
-            &signers,
+        let build_tx = |instructions: &[Instruction],
+                        blockhash: Hash,
+                        price: Option<u64>|
+         -> Result<GroupTransaction, SubmitError> {
+            let mut tx_instructions = Vec::with_capacity(instructions.len() + 1);
+            if let Some(price) = price {
+                tx_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+            }
+            tx_instructions.extend_from_slice(instructions);
+            match &alt {
+                Some(table) => {
+                    let message = v0::Message::try_compile(
+                        &fee_payer.pubkey(),
+                        &tx_instructions,
+                        std::slice::from_ref(table),
+                        blockhash,
+                    )
+                    .map_err(|e| SubmitError::Execution(e.to_string()))?;
+                    VersionedTransaction::try_new(VersionedMessage::V0(message), &signers)
+                        .map(GroupTransaction::V0)
+                        .map_err(|e| SubmitError::Execution(e.to_string()))
+                }
+                None => Ok(GroupTransaction::Legacy(
+                    Transaction::new_signed_with_payer(
+                        &tx_instructions,
+                        Some(&fee_payer.pubkey()),
+                        &signers,
+                        blockhash,
+                    ),
+                )),
+            }
+        };
+        let mut price = config.compute_unit_price;
+
+        let limit = match config.compute_unit_limit {
+            ComputeUnitLimit::Default => None,
+            ComputeUnitLimit::Fixed(units) => Some(units.min(MAX_COMPUTE_UNIT_LIMIT)),
+            ComputeUnitLimit::Simulated { margin_percent } => {
+                // Simulation takes legacy transactions only, so groups that need a
+                // lookup table get the maximum limit instead.
+                let sim_instructions = with_max_compute_unit_limit(&instructions);
+                Some(match build_tx(&sim_instructions, blockhash, price)? {
+                    GroupTransaction::Legacy(sim_tx) => compute_unit_limit_with_margin(
+                        simulate_units_consumed(conn, &sim_tx, group_index)?,
+                        margin_percent,
+                    ),
+                    GroupTransaction::V0(_) => MAX_COMPUTE_UNIT_LIMIT,
+                })
+            }
+        };
+        if let Some(limit) = limit {
+            instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(limit));
+        }
+
+        let sent = send_with_retries(
+            conn,
+            config,
+            group_index,
             blockhash,
+            &mut price,
+            |blockhash, price| build_tx(&instructions, blockhash, price),
         );
+        // Deactivate the table even if the group failed; closing it has to
+        // wait for the deactivation cool-down.
+        if let Some(table) = &alt {
+            match lookup_table::deactivate_lookup_table(conn, fee_payer, &table.key) {
+                Ok(_) => log::info!("Deactivated address lookup table {}", table.key),
+                Err(e) => log::warn!(
+                    "failed to deactivate address lookup table {}: {}",
+                    table.key,
+                    e
+                ),
+            }
+        }
+        let (signature, attempts) = sent?;
+
+        if let Some(sink) = config.event_sink {
+            sink.emit(&BroadcastEvent::GroupExecuted {
+                group_index,
+                signature: signature.to_string(),
+                attempts,
+            });
+        }
+        executions.push(GroupExecution {
+            signature,
+            attempts,
+            compute_unit_price: price,
+            lookup_table: alt.map(|table| table.key),
+        });
+    }
+
+    Ok(executions)
+}
+
+/// An execution transaction: legacy, or v0 when the group uses an address
+/// lookup table.
+enum GroupTransaction {
+    Legacy(Transaction),
+    V0(VersionedTransaction),
+}
+
+impl GroupTransaction {
+    fn signature(&self) -> Signature {
+        match self {
+            Self::Legacy(tx) => tx.signatures[0],
+            Self::V0(tx) => tx.signatures[0],
+        }
+    }
+
+    fn send_and_confirm<C: SolanaConnection>(&self, conn: &mut C) -> Result<Signature, C::Error> {
+        match self {
+            Self::Legacy(tx) => conn.send_and_confirm(tx),
+            Self::V0(tx) => conn.send_and_confirm_versioned(tx),
+        }
+    }
+}
+
+/// Send the transaction built by `build`, re-signing it with a fresh
+/// blockhash (and escalated `price`) when it expires or is dropped.
+///
+/// A transaction whose confirmation failed may still land, so it is only
+/// re-signed once its blockhash has expired without it landing; otherwise
+/// the group could execute twice.
+///
+/// Returns the signature and the number of attempts.
+fn send_with_retries<C: SolanaConnection>(
+    conn: &mut C,
+    config: &ExecuteConfig,
+    group_index: usize,
+    mut blockhash: Hash,
+    price: &mut Option<u64>,
+    build: impl Fn(Hash, Option<u64>) -> Result<GroupTransaction, SubmitError>,
+) -> Result<(Signature, u32), SubmitError> {
+    let mut tx = build(blockhash, *price)?;
+    let mut attempts = 1;
 
-        let sig = conn
-            .send_and_confirm(&tx)
-            .map_err(|e| SubmitError::Execution(e.to_string()))?;
-        tx_sigs.push(sig);
+    loop {
+        if let Some(before_send) = config.before_send {
+            before_send(group_index, &tx.signature(), &blockhash)?;
+        }
+        match tx.send_and_confirm(conn) {
+            Ok(sig) => return Ok((sig, attempts)),
+            Err(e) => {
+                let message = e.to_string();
+                let cause = conn.transaction_failure(&e);
+                if attempts > config.max_retries || cause != TransactionFailure::Expired {
+                    return Err(SubmitError::TransactionFailed {
+                        group_index,
+                        signature: tx.signature(),
+                        message,
+                        cause,
+                    });
+                }
+                match await_landed_or_expired(conn, &tx.signature(), &blockhash)? {
+                    Some(Ok(())) => {
+                        log::info!(
+                            "Transaction {} for group {} landed despite: {}",
+                            tx.signature(),
+                            group_index,
+                            message
+                        );
+                        return Ok((tx.signature(), attempts));
+                    }
+                    Some(Err(message)) => {
+                        return Err(SubmitError::TransactionFailed {
+                            group_index,
+                            signature: tx.signature(),
+                            cause: TransactionFailure::from_message(&message),
+                            message,
+                        })
+                    }
+                    None => {}
+                }
+                log::warn!(
+                    "Transaction {} for group {} not confirmed ({}); retrying with a fresh blockhash",
+                    tx.signature(), group_index, message
+                );
+                if let Some(escalation) = &config.fee_escalation {
+                    let escalated = escalate_compute_unit_price(*price, escalation);
+                    log::info!("Raising compute unit price to {} micro-lamports", escalated);
+                    *price = Some(escalated);
+                }
+                blockhash = conn
+                    .get_latest_blockhash()
+                    .map_err(|e| SubmitError::Connection(e.to_string()))?;
+                tx = build(blockhash, *price)?;
+                attempts += 1;
+            }
+        }
     }
+}
+
+/// Wait until the transaction with `signature` lands or `blockhash` expires,
+/// returning its outcome if it landed.
+pub(crate) fn await_landed_or_expired<C: SolanaConnection>(
+    conn: &C,
+    signature: &Signature,
+    blockhash: &Hash,
+) -> Result<Option<Result<(), String>>, SubmitError> {
+    loop {
+        // Checked before the status: once the blockhash has expired, a status
+        // that is still missing can't appear anymore.
+        let expired = !conn
+            .is_blockhash_valid(blockhash)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?;
+        let status = conn
+            .get_signature_status(signature)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?;
+        if status.is_some() || expired {
+            return Ok(status);
+        }
+        std::thread::sleep(SIGNATURE_POLL_INTERVAL);
+    }
+}
+
+/// Values substituted for placeholders by [`estimate_group_compute`].
+pub struct Substitutions<'a> {
+    /// Substituted for `RESOLVER_PUBKEY_PAYER`; pays for and signs the simulations.
+    pub payer: &'a dyn Signer,
+    /// Substituted for `RESOLVER_PUBKEY_SHIM_VAA_SIGS`.
+    pub signatures_pubkey: Pubkey,
+    /// Substituted for `RESOLVER_PUBKEY_GUARDIAN_SET`.
+    pub guardian_set: Pubkey,
+    /// Additional `(placeholder, pubkey)` substitutions.
+    pub placeholders: &'a [(Pubkey, Pubkey)],
+}
+
+/// Simulate each resolved instruction group and return the compute units it consumes.
+///
+/// Each group is simulated on its own against current state, with the limit
+/// raised to [`MAX_COMPUTE_UNIT_LIMIT`]; a group that doesn't fit in a single
+/// transaction fails with a simulation error. Groups that depend on state
+/// created by earlier groups (or by posting guardian signatures) only simulate
+/// successfully once that state exists.
+pub fn estimate_group_compute<C: SolanaConnection>(
+    conn: &C,
+    groups: &[InstructionGroup],
+    substitutions: &Substitutions,
+) -> Result<Vec<u64>, SubmitError> {
+    let payer = substitutions.payer;
+    let generated_keypairs = discover_keypairs(groups);
+    let mut substitution_map: Vec<(Pubkey, Pubkey)> = generated_keypairs
+        .iter()
+        .map(|(placeholder, kp)| (*placeholder, kp.pubkey()))
+        .collect();
+    substitution_map.extend_from_slice(substitutions.placeholders);
+
+    let blockhash = conn
+        .get_latest_blockhash()
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+
+    groups
+        .iter()
+        .enumerate()
+        .map(|(group_index, group)| {
+            let instructions: Vec<Instruction> = group
+                .instructions
+                .iter()
+                .map(|si| {
+                    convert_instruction(
+                        si,
+                        &payer.pubkey(),
+                        &substitutions.signatures_pubkey,
+                        &substitutions.guardian_set,
+                        &substitution_map,
+                    )
+                })
+                .collect();
+
+            let mut signers: Vec<&dyn Signer> = vec![payer];
+            signers.extend(
+                generated_keypairs
+                    .iter()
+                    .filter(|(_, kp)| requires_signature(&instructions, &kp.pubkey()))
+                    .map(|(_, kp)| kp as &dyn Signer),
+            );
+
+            let tx = Transaction::new_signed_with_payer(
+                &with_max_compute_unit_limit(&instructions),
+                Some(&payer.pubkey()),
+                &signers,
+                blockhash,
+            );
+            simulate_units_consumed(conn, &tx, group_index)
+        })
+        .collect()
+}
+
+/// Prepend a compute unit limit of [`MAX_COMPUTE_UNIT_LIMIT`], so heavy CPIs
+/// don't fail a measurement.
+fn with_max_compute_unit_limit(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut sim_instructions = Vec::with_capacity(instructions.len() + 1);
+    sim_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        MAX_COMPUTE_UNIT_LIMIT,
+    ));
+    sim_instructions.extend_from_slice(instructions);
+    sim_instructions
+}
+
+/// Simulate `tx` and return the compute units it consumed.
+fn simulate_units_consumed<C: SolanaConnection>(
+    conn: &C,
+    tx: &Transaction,
+    group_index: usize,
+) -> Result<u64, SubmitError> {
+    let sim_result = conn.simulate_with_post_accounts(tx, &[]).map_err(|e| {
+        SubmitError::Execution(format!(
+            "Compute unit simulation failed for group {}: {}",
+            group_index, e
+        ))
+    })?;
+    sim_result.units_consumed.ok_or_else(|| {
+        SubmitError::Execution(format!(
+            "Simulation of group {} did not report units consumed",
+            group_index
+        ))
+    })
+}
 
-    Ok(tx_sigs)
+/// Resolved instruction groups with every placeholder substituted.
+pub struct SubstitutedGroups {
+    /// Concrete instructions, one entry per instruction group in execution order.
+    pub groups: Vec<Vec<Instruction>>,
+    /// Keypairs generated for `RESOLVER_PUBKEY_KEYPAIR_*` placeholders. Each must
+    /// sign the transactions whose instructions require its signature.
+    pub keypairs: Vec<Keypair>,
+}
+
+/// Substitute placeholders in resolved instruction groups without executing them.
+///
+/// Uses the same substitution rules as [`execute_instruction_groups`], so the
+/// result can be composed into custom transactions alongside other instructions.
+/// `placeholders` are the additional substitutions, as in
+/// [`ExecuteConfig::placeholders`].
+pub fn substitute_instruction_groups(
+    groups: &[InstructionGroup],
+    payer: &Pubkey,
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+) -> SubstitutedGroups {
+    let generated_keypairs = discover_keypairs(groups);
+    let mut keypair_map: Vec<(Pubkey, Pubkey)> = generated_keypairs
+        .iter()
+        .map(|(placeholder, kp)| (*placeholder, kp.pubkey()))
+        .collect();
+    keypair_map.extend_from_slice(placeholders);
+
+    let substituted = groups
+        .iter()
+        .map(|group| {
+            group
+                .instructions
+                .iter()
+                .map(|si| {
+                    convert_instruction(si, payer, signatures_pubkey, guardian_set, &keypair_map)
+                })
+                .collect()
+        })
+        .collect();
+
+    SubstitutedGroups {
+        groups: substituted,
+        keypairs: generated_keypairs.into_iter().map(|(_, kp)| kp).collect(),
+    }
+}
+
+/// Add `margin_percent` percent to `consumed`, capped at [`MAX_COMPUTE_UNIT_LIMIT`].
+fn compute_unit_limit_with_margin(consumed: u64, margin_percent: u32) -> u32 {
+    let limit = consumed.saturating_mul(100 + margin_percent as u64) / 100;
+    limit.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// The next compute unit price after a failed attempt at `current`.
+fn escalate_compute_unit_price(current: Option<u64>, escalation: &FeeEscalation) -> u64 {
+    let escalated = match current {
+        Some(price) if price > 0 => {
+            price.saturating_mul(escalation.multiplier_percent as u64) / 100
+        }
+        _ => escalation.floor,
+    };
+    escalated.min(escalation.cap)
+}
+
+/// Build an SPL Memo instruction without signer accounts.
+fn memo_instruction(memo: &str) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
+/// Name of the built-in placeholder `pubkey`, e.g. `RESOLVER_PUBKEY_PAYER`.
+pub fn placeholder_name(pubkey: &Pubkey) -> Option<&'static str> {
+    const KEYPAIR_NAMES: [&str; 10] = [
+        "RESOLVER_PUBKEY_KEYPAIR_00",
+        "RESOLVER_PUBKEY_KEYPAIR_01",
+        "RESOLVER_PUBKEY_KEYPAIR_02",
+        "RESOLVER_PUBKEY_KEYPAIR_03",
+        "RESOLVER_PUBKEY_KEYPAIR_04",
+        "RESOLVER_PUBKEY_KEYPAIR_05",
+        "RESOLVER_PUBKEY_KEYPAIR_06",
+        "RESOLVER_PUBKEY_KEYPAIR_07",
+        "RESOLVER_PUBKEY_KEYPAIR_08",
+        "RESOLVER_PUBKEY_KEYPAIR_09",
+    ];
+    if *pubkey == RESOLVER_PUBKEY_PAYER {
+        Some("RESOLVER_PUBKEY_PAYER")
+    } else if *pubkey == RESOLVER_PUBKEY_GUARDIAN_SET {
+        Some("RESOLVER_PUBKEY_GUARDIAN_SET")
+    } else if *pubkey == RESOLVER_PUBKEY_SHIM_VAA_SIGS {
+        Some("RESOLVER_PUBKEY_SHIM_VAA_SIGS")
+    } else {
+        KEYPAIR_PLACEHOLDERS
+            .iter()
+            .position(|placeholder| placeholder == pubkey)
+            .map(|i| KEYPAIR_NAMES[i])
+    }
+}
+
+/// Whether `pubkey` is a built-in placeholder or one of the `custom` ones.
+fn is_placeholder(pubkey: &Pubkey, custom: &[(Pubkey, Pubkey)]) -> bool {
+    *pubkey == RESOLVER_PUBKEY_PAYER
+        || *pubkey == RESOLVER_PUBKEY_GUARDIAN_SET
+        || *pubkey == RESOLVER_PUBKEY_SHIM_VAA_SIGS
+        || KEYPAIR_PLACEHOLDERS.contains(pubkey)
+        || custom.iter().any(|(placeholder, _)| placeholder == pubkey)
+}
+
+/// The first placeholder that survived substitution, as
+/// `(instruction index, account index, placeholder)`.
+fn find_unresolved_placeholder(
+    instructions: &[Instruction],
+    custom: &[(Pubkey, Pubkey)],
+) -> Option<(usize, usize, Pubkey)> {
+    instructions
+        .iter()
+        .enumerate()
+        .find_map(|(instruction_index, ix)| {
+            ix.accounts
+                .iter()
+                .position(|a| is_placeholder(&a.pubkey, custom))
+                .map(|account_index| {
+                    (
+                        instruction_index,
+                        account_index,
+                        ix.accounts[account_index].pubkey,
+                    )
+                })
+        })
+}
+
+/// The first account that must sign but has no signer available.
+fn missing_signer(instructions: &[Instruction], signers: &[Pubkey]) -> Option<Pubkey> {
+    instructions
+        .iter()
+        .flat_map(|ix| &ix.accounts)
+        .find(|a| a.is_signer && !signers.contains(&a.pubkey))
+        .map(|a| a.pubkey)
+}
+
+/// Whether any of the instructions require a signature from `pubkey`.
+fn requires_signature(instructions: &[Instruction], pubkey: &Pubkey) -> bool {
+    instructions.iter().any(|ix| {
+        ix.accounts
+            .iter()
+            .any(|a| a.is_signer && a.pubkey == *pubkey)
+    })
 }
 
 /// Scan all instruction groups for keypair placeholders and generate a keypair for each.
-fn discover_keypairs(groups: &[InstructionGroup]) -> Vec<(Pubkey, Keypair)> {
+pub(crate) fn discover_keypairs(groups: &[InstructionGroup]) -> Vec<(Pubkey, Keypair)> {
     let mut result = Vec::new();
     for placeholder in &KEYPAIR_PLACEHOLDERS {
         let used = groups.iter().any(|group| {
@@ -123,7 +789,7 @@ fn discover_keypairs(groups: &[InstructionGroup]) -> Vec<(Pubkey, Keypair)> {
 
 /// Convert a `SerializableInstruction` to a `solana_sdk::instruction::Instruction`,
 /// substituting placeholder pubkeys.
-fn convert_instruction(
+pub(crate) fn convert_instruction(
     si: &SerializableInstruction,
     payer: &Pubkey,
     signatures_pubkey: &Pubkey,
@@ -175,3 +841,271 @@ fn substitute(
         pubkey
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::connection::SimulationResult;
+    use crate::test_support::{group, signer_group, writable_group, MockConnection, SendOutcome};
+
+    #[test]
+    fn test_placeholder_name() {
+        assert_eq!(
+            placeholder_name(&RESOLVER_PUBKEY_SHIM_VAA_SIGS),
+            Some("RESOLVER_PUBKEY_SHIM_VAA_SIGS")
+        );
+        assert_eq!(
+            placeholder_name(&RESOLVER_PUBKEY_KEYPAIR_03),
+            Some("RESOLVER_PUBKEY_KEYPAIR_03")
+        );
+        assert_eq!(placeholder_name(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn test_compute_unit_limit_with_margin() {
+        assert_eq!(compute_unit_limit_with_margin(100_000, 10), 110_000);
+        assert_eq!(compute_unit_limit_with_margin(100_000, 0), 100_000);
+
+        // Never exceeds the per-transaction maximum
+        assert_eq!(
+            compute_unit_limit_with_margin(1_300_000, 20),
+            MAX_COMPUTE_UNIT_LIMIT
+        );
+    }
+
+    #[test]
+    fn test_substitute_keypairs_consistent_across_groups() {
+        let group = || signer_group(&[RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_KEYPAIR_00]);
+        let payer = Pubkey::new_unique();
+
+        let substituted = substitute_instruction_groups(
+            &[group(), group()],
+            &payer,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &[],
+        );
+
+        assert_eq!(substituted.keypairs.len(), 1);
+        let generated = substituted.keypairs[0].pubkey();
+        for instructions in &substituted.groups {
+            assert_eq!(instructions[0].accounts[0].pubkey, payer);
+            assert_eq!(instructions[0].accounts[1].pubkey, generated);
+        }
+    }
+
+    fn send_one_group(conn: &mut MockConnection) -> Result<Vec<GroupExecution>, SubmitError> {
+        execute_instruction_groups_with_report(
+            conn,
+            &Keypair::new(),
+            &[signer_group(&[RESOLVER_PUBKEY_PAYER])],
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &ExecuteConfig {
+                max_retries: 3,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_landed_transaction_is_not_resent() {
+        let mut conn = MockConnection::new();
+        conn.on_send = Box::new(|_| SendOutcome::LandUnconfirmed("request timed out".into()));
+
+        let executions = send_one_group(&mut conn).unwrap();
+
+        assert_eq!(conn.sent.len(), 1);
+        assert_eq!(executions[0].signature, conn.sent[0].signatures[0]);
+        assert_eq!(executions[0].attempts, 1);
+    }
+
+    #[test]
+    fn test_expired_transaction_is_resent() {
+        let mut conn = MockConnection::new();
+        conn.blockhash_valid = false;
+        let mut sends = 0;
+        conn.on_send = Box::new(move |_| {
+            sends += 1;
+            match sends {
+                1 => SendOutcome::Drop("Blockhash not found".into()),
+                _ => SendOutcome::Confirm,
+            }
+        });
+
+        let executions = send_one_group(&mut conn).unwrap();
+
+        assert_eq!(conn.sent.len(), 2);
+        assert_eq!(executions[0].signature, conn.sent[1].signatures[0]);
+        assert_eq!(executions[0].attempts, 2);
+    }
+
+    #[test]
+    fn test_separate_fee_payer() {
+        let mut conn = MockConnection::new();
+        let payer = Keypair::new();
+        let fee_payer = Keypair::new();
+
+        execute_instruction_groups_with_config(
+            &mut conn,
+            &payer,
+            &[
+                signer_group(&[RESOLVER_PUBKEY_PAYER]),
+                writable_group(&[RESOLVER_PUBKEY_PAYER]),
+            ],
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &ExecuteConfig {
+                fee_payer: Some(&fee_payer),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The fee payer pays for both; the payer only signs where its
+        // signature is required
+        assert_eq!(conn.sent.len(), 2);
+        let signing = &conn.sent[0];
+        let keys = signing.message.static_account_keys();
+        assert_eq!(signing.message.header().num_required_signatures, 2);
+        assert_eq!(keys[..2], [fee_payer.pubkey(), payer.pubkey()]);
+        assert!(signing.verify_with_results().iter().all(|ok| *ok));
+
+        let writing = &conn.sent[1];
+        let keys = writing.message.static_account_keys();
+        assert_eq!(writing.message.header().num_required_signatures, 1);
+        assert_eq!(keys[0], fee_payer.pubkey());
+        assert!(keys.contains(&payer.pubkey()));
+        assert!(writing.verify_with_results().iter().all(|ok| *ok));
+    }
+
+    #[test]
+    fn test_estimate_group_compute() {
+        let light = Pubkey::new_unique();
+        let heavy = Pubkey::new_unique();
+        let unmetered = Pubkey::new_unique();
+        let guardian_set = Pubkey::new_unique();
+        let mut conn = MockConnection::new();
+        conn.on_simulate = Box::new(move |tx| {
+            // Fully substituted, signed and at the maximum limit
+            let keys = &tx.message.account_keys;
+            assert!(keys.iter().all(|key| placeholder_name(key).is_none()));
+            assert!(tx.verify().is_ok());
+            assert_eq!(
+                tx.message.instructions[0],
+                tx.message
+                    .compile_instruction(&ComputeBudgetInstruction::set_compute_unit_limit(
+                        MAX_COMPUTE_UNIT_LIMIT
+                    ))
+            );
+            let program = keys[tx.message.instructions[1].program_id_index as usize];
+            Ok(SimulationResult {
+                return_data: None,
+                post_accounts: vec![],
+                units_consumed: (program != unmetered).then_some(if program == heavy {
+                    200_000
+                } else {
+                    10_000
+                }),
+                logs: vec![],
+            })
+        });
+        let payer = Keypair::new();
+        let substitutions = Substitutions {
+            payer: &payer,
+            signatures_pubkey: Pubkey::new_unique(),
+            guardian_set,
+            placeholders: &[],
+        };
+
+        let units = estimate_group_compute(
+            &conn,
+            &[
+                group(light, &[(RESOLVER_PUBKEY_PAYER, true, true)]),
+                group(
+                    heavy,
+                    &[
+                        (RESOLVER_PUBKEY_KEYPAIR_00, true, true),
+                        (RESOLVER_PUBKEY_GUARDIAN_SET, false, false),
+                    ],
+                ),
+            ],
+            &substitutions,
+        )
+        .unwrap();
+
+        assert_eq!(units, [10_000, 200_000]);
+        assert!(conn.sent.is_empty());
+
+        // A simulation that doesn't report units fails the estimate
+        let err =
+            estimate_group_compute(&conn, &[group(unmetered, &[])], &substitutions).unwrap_err();
+        assert!(err.to_string().contains("group 0"));
+    }
+
+    #[test]
+    fn test_escalate_compute_unit_price() {
+        let escalation = FeeEscalation {
+            multiplier_percent: 200,
+            floor: 1_000,
+            cap: 5_000,
+        };
+
+        assert_eq!(escalate_compute_unit_price(None, &escalation), 1_000);
+        assert_eq!(escalate_compute_unit_price(Some(0), &escalation), 1_000);
+        assert_eq!(escalate_compute_unit_price(Some(1_000), &escalation), 2_000);
+        // Capped
+        assert_eq!(escalate_compute_unit_price(Some(4_000), &escalation), 5_000);
+    }
+
+    #[test]
+    fn test_missing_signer() {
+        let payer = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(payer, true), AccountMeta::new(other, true)],
+            data: vec![],
+        };
+
+        assert_eq!(missing_signer(&[ix.clone()], &[payer]), Some(other));
+        assert_eq!(missing_signer(&[ix], &[payer, other]), None);
+    }
+
+    #[test]
+    fn test_find_unresolved_placeholder() {
+        let custom = Pubkey::new_unique();
+        let ixs = [
+            Instruction {
+                program_id: Pubkey::new_unique(),
+                accounts: vec![AccountMeta::new(Pubkey::new_unique(), true)],
+                data: vec![],
+            },
+            Instruction {
+                program_id: Pubkey::new_unique(),
+                accounts: vec![
+                    AccountMeta::new_readonly(Pubkey::new_unique(), false),
+                    AccountMeta::new_readonly(RESOLVER_PUBKEY_GUARDIAN_SET, false),
+                ],
+                data: vec![],
+            },
+        ];
+
+        assert_eq!(
+            find_unresolved_placeholder(&ixs, &[]),
+            Some((1, 1, RESOLVER_PUBKEY_GUARDIAN_SET))
+        );
+        assert_eq!(find_unresolved_placeholder(&ixs[..1], &[]), None);
+
+        let with_custom = [Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(custom, false)],
+            data: vec![],
+        }];
+        assert_eq!(
+            find_unresolved_placeholder(&with_custom, &[(custom, Pubkey::new_unique())]),
+            Some((0, 0, custom))
+        );
+    }
+}
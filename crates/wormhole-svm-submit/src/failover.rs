@@ -0,0 +1,264 @@
+//! Failover across several connections to the same cluster.
+//!
+//! [`FailoverConnection`] sends every call to the endpoint that last
+//! answered, moving on to the next one when a call fails with an error the
+//! `should_failover` predicate considers transient (a timeout, an HTTP error,
+//! a rate limit). Other errors, e.g. a failed simulation, are returned as is:
+//! another endpoint would give the same answer.
+//!
+//! Resending a transaction to another endpoint after a failed
+//! `send_and_confirm` is safe: it carries the same signature, so it can land
+//! at most once.
+
+use std::cell::Cell;
+
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::connection::{SimulationResult, SolanaConnection, TransactionFailure};
+
+/// A [`SolanaConnection`] over several endpoints of the same cluster.
+pub struct FailoverConnection<C: SolanaConnection> {
+    endpoints: Vec<C>,
+    current: Cell<usize>,
+    should_failover: fn(&C::Error) -> bool,
+}
+
+impl<C: SolanaConnection> FailoverConnection<C> {
+    /// Fail over between `endpoints`, in order, on errors for which
+    /// `should_failover` returns true.
+    ///
+    /// # Panics
+    ///
+    /// If `endpoints` is empty.
+    pub fn new(endpoints: Vec<C>, should_failover: fn(&C::Error) -> bool) -> Self {
+        assert!(
+            !endpoints.is_empty(),
+            "FailoverConnection needs an endpoint"
+        );
+        Self {
+            endpoints,
+            current: Cell::new(0),
+            should_failover,
+        }
+    }
+
+    /// The endpoint calls currently go to.
+    pub fn current(&self) -> &C {
+        &self.endpoints[self.current.get()]
+    }
+
+    /// Index of the endpoint calls currently go to.
+    pub fn current_index(&self) -> usize {
+        self.current.get()
+    }
+
+    fn call<T>(&self, mut f: impl FnMut(&C) -> Result<T, C::Error>) -> Result<T, C::Error> {
+        let start = self.current.get();
+        for attempt in 0.. {
+            let index = (start + attempt) % self.endpoints.len();
+            match f(&self.endpoints[index]) {
+                Ok(value) => {
+                    self.current.set(index);
+                    return Ok(value);
+                }
+                Err(e) if self.fail_over(index, attempt, &e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!()
+    }
+
+    fn call_mut<T>(
+        &mut self,
+        mut f: impl FnMut(&mut C) -> Result<T, C::Error>,
+    ) -> Result<T, C::Error> {
+        let start = self.current.get();
+        for attempt in 0.. {
+            let index = (start + attempt) % self.endpoints.len();
+            match f(&mut self.endpoints[index]) {
+                Ok(value) => {
+                    self.current.set(index);
+                    return Ok(value);
+                }
+                Err(e) if self.fail_over(index, attempt, &e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!()
+    }
+
+    /// Whether to retry on the next endpoint after `error` from endpoint
+    /// `index` on the `attempt`th try of a call.
+    fn fail_over(&self, index: usize, attempt: usize, error: &C::Error) -> bool {
+        let count = self.endpoints.len();
+        if attempt + 1 >= count || !(self.should_failover)(error) {
+            return false;
+        }
+        log::warn!(
+            "RPC endpoint {} failed ({}); trying endpoint {}",
+            index,
+            error,
+            (index + 1) % count
+        );
+        true
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl FailoverConnection<solana_client::rpc_client::RpcClient> {
+    /// Fail over between RPC clients on transport errors (connection
+    /// failures, timeouts, HTTP error statuses such as 429 or 503).
+    pub fn rpc(endpoints: Vec<solana_client::rpc_client::RpcClient>) -> Self {
+        Self::new(endpoints, is_transport_error)
+    }
+}
+
+/// Whether an RPC error is a transport failure rather than an answer from
+/// the node.
+#[cfg(feature = "rpc")]
+pub fn is_transport_error(error: &solana_client::client_error::ClientError) -> bool {
+    use solana_client::client_error::ClientErrorKind;
+
+    matches!(
+        error.kind(),
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_)
+    )
+}
+
+impl<C: SolanaConnection> SolanaConnection for FailoverConnection<C> {
+    type Error = C::Error;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
+        self.call(|conn| conn.get_latest_blockhash())
+    }
+
+    fn simulate_with_post_accounts(
+        &self,
+        tx: &Transaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult, Self::Error> {
+        self.call(|conn| conn.simulate_with_post_accounts(tx, accounts))
+    }
+
+    fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+        self.call_mut(|conn| conn.send_and_confirm(tx))
+    }
+
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.call_mut(|conn| conn.send_and_confirm_versioned(tx))
+    }
+
+    fn supports_versioned_transactions(&self) -> bool {
+        self.endpoints
+            .iter()
+            .all(|conn| conn.supports_versioned_transactions())
+    }
+
+    fn transaction_failure(&self, error: &Self::Error) -> TransactionFailure {
+        self.endpoints[self.current.get()].transaction_failure(error)
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), String>>, Self::Error> {
+        self.call(|conn| conn.get_signature_status(signature))
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, Self::Error> {
+        self.call(|conn| conn.is_blockhash_valid(blockhash))
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
+        self.call(|conn| conn.get_account(pubkey))
+    }
+
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, Self::Error> {
+        self.call(|conn| conn.get_multiple_accounts(pubkeys))
+    }
+
+    fn advance_slot(&mut self) -> Result<(), Self::Error> {
+        self.call_mut(|conn| conn.advance_slot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{signature::Keypair, signer::Signer};
+
+    use super::*;
+    use crate::test_support::{MockConnection, MockError};
+
+    /// An endpoint whose calls fail with `error`, if any.
+    fn endpoint(error: Option<&str>) -> MockConnection {
+        let mut conn = MockConnection::new();
+        conn.error = error.map(str::to_string);
+        conn
+    }
+
+    fn is_transient(e: &MockError) -> bool {
+        e.0 == "unreachable"
+    }
+
+    #[test]
+    fn test_fails_over_on_transient_errors() {
+        let endpoints = vec![endpoint(Some("unreachable")), endpoint(None)];
+        let blockhash = endpoints[1].blockhash;
+        let conn = FailoverConnection::new(endpoints, is_transient);
+
+        assert_eq!(conn.get_latest_blockhash().unwrap(), blockhash);
+        assert_eq!(conn.current_index(), 1);
+    }
+
+    #[test]
+    fn test_returns_other_errors() {
+        let conn = FailoverConnection::new(
+            vec![endpoint(Some("rejected")), endpoint(None)],
+            is_transient,
+        );
+
+        assert_eq!(conn.get_latest_blockhash().unwrap_err().0, "rejected");
+        assert_eq!(conn.current_index(), 0);
+    }
+
+    #[test]
+    fn test_gives_up_after_every_endpoint() {
+        let conn = FailoverConnection::new(
+            vec![endpoint(Some("unreachable")), endpoint(Some("unreachable"))],
+            is_transient,
+        );
+
+        assert_eq!(conn.get_latest_blockhash().unwrap_err().0, "unreachable");
+    }
+
+    #[test]
+    fn test_sends_fail_over() {
+        let mut conn = FailoverConnection::new(
+            vec![endpoint(Some("unreachable")), endpoint(None)],
+            is_transient,
+        );
+        let payer = Keypair::new();
+        let tx = Transaction::new_signed_with_payer(
+            &[],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+
+        assert_eq!(conn.send_and_confirm(&tx).unwrap(), tx.signatures[0]);
+        assert!(conn.endpoints[0].sent.is_empty());
+        assert_eq!(conn.endpoints[1].sent.len(), 1);
+    }
+}
@@ -0,0 +1,259 @@
+//! Decoding of Core Bridge guardian set accounts.
+
+use sha3::{Digest, Keccak256};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::secp256k1_recover::secp256k1_recover;
+
+use crate::connection::SolanaConnection;
+use crate::network::WormholeNetwork;
+use crate::vaa::vaa_digest;
+use crate::SubmitError;
+
+/// A guardian set as stored by the Wormhole Core Bridge.
+///
+/// Account layout (borsh, no discriminator):
+/// - index: u32 (little-endian)
+/// - keys_len: u32 (little-endian)
+/// - keys: [[u8; 20]; keys_len] (guardian Ethereum addresses)
+/// - creation_time: u32 (little-endian)
+/// - expiration_time: u32 (little-endian) - 0 means never expires
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardianSet {
+    /// The guardian set index.
+    pub index: u32,
+    /// Guardian Ethereum addresses, in guardian index order.
+    pub keys: Vec<[u8; 20]>,
+    /// Unix timestamp when the set was created.
+    pub creation_time: u32,
+    /// Unix timestamp after which the set is no longer valid (0 = never expires).
+    pub expiration_time: u32,
+}
+
+impl GuardianSet {
+    /// Parse guardian set account data.
+    pub fn parse(data: &[u8]) -> Result<Self, SubmitError> {
+        let invalid = SubmitError::InvalidGuardianSet;
+
+        if data.len() < 8 {
+            return Err(invalid(format!(
+                "account too short: expected at least 8 bytes, got {}",
+                data.len()
+            )));
+        }
+        let index = u32::from_le_bytes(data[0..4].try_into().unwrap());
+        let keys_len = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+
+        let keys_end = keys_len
+            .checked_mul(20)
+            .and_then(|len| len.checked_add(8))
+            .ok_or_else(|| invalid(format!("invalid key count: {}", keys_len)))?;
+        let expected_len = keys_end + 8;
+        if data.len() < expected_len {
+            return Err(invalid(format!(
+                "account truncated: expected at least {} bytes for {} keys, got {}",
+                expected_len,
+                keys_len,
+                data.len()
+            )));
+        }
+
+        let keys = data[8..keys_end]
+            .chunks_exact(20)
+            .map(|chunk| chunk.try_into().unwrap())
+            .collect();
+
+        Ok(Self {
+            index,
+            keys,
+            creation_time: u32::from_le_bytes(data[keys_end..keys_end + 4].try_into().unwrap()),
+            expiration_time: u32::from_le_bytes(
+                data[keys_end + 4..keys_end + 8].try_into().unwrap(),
+            ),
+        })
+    }
+
+    /// Fetch and parse the guardian set with `index` on `network`.
+    pub fn fetch<C: SolanaConnection>(
+        conn: &C,
+        network: &WormholeNetwork,
+        index: u32,
+    ) -> Result<Self, SubmitError> {
+        let address = network.guardian_set_address(index);
+        Self::fetch_address(conn, &address)
+    }
+
+    /// Index of the current guardian set of `network`, from the core bridge
+    /// config.
+    pub fn current_index<C: SolanaConnection>(
+        conn: &C,
+        network: &WormholeNetwork,
+    ) -> Result<u32, SubmitError> {
+        let address = network.core_bridge_config_address();
+        let account = conn
+            .get_account(&address)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?
+            .ok_or_else(|| {
+                SubmitError::InvalidGuardianSet(format!("core bridge config {} not found", address))
+            })?;
+        let bytes = account.data.get(0..4).ok_or_else(|| {
+            SubmitError::InvalidGuardianSet(format!("core bridge config {} too short", address))
+        })?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Fetch and parse the guardian set account at `address`.
+    pub fn fetch_address<C: SolanaConnection>(
+        conn: &C,
+        address: &Pubkey,
+    ) -> Result<Self, SubmitError> {
+        let account = conn
+            .get_account(address)
+            .map_err(|e| SubmitError::Connection(e.to_string()))?
+            .ok_or_else(|| {
+                SubmitError::InvalidGuardianSet(format!("account {} not found", address))
+            })?;
+        Self::parse(&account.data)
+    }
+
+    /// Number of signatures required for quorum (more than two thirds).
+    pub fn quorum(&self) -> usize {
+        self.keys.len() * 2 / 3 + 1
+    }
+
+    /// Whether the set has expired at unix timestamp `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiration_time != 0 && now > self.expiration_time as i64
+    }
+
+    /// Recover the signer of each guardian signature (`[index, r, s, v]`) over
+    /// the VAA `body` and compare it with the guardian at that index.
+    pub fn check_signatures(&self, body: &[u8], signatures: &[[u8; 66]]) -> SignatureCheck {
+        let digest = vaa_digest(body);
+        let mut check = SignatureCheck {
+            valid: Vec::new(),
+            invalid: Vec::new(),
+            quorum: self.quorum(),
+        };
+        for signature in signatures {
+            let index = signature[0];
+            let signer = recover_guardian_address(&digest, signature[1..].try_into().unwrap());
+            let matches = signer.is_some() && self.keys.get(index as usize) == signer.as_ref();
+            if matches && !check.valid.contains(&index) {
+                check.valid.push(index);
+            } else if !matches {
+                check.invalid.push(index);
+            }
+        }
+        check
+    }
+}
+
+/// Result of [`GuardianSet::check_signatures`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureCheck {
+    /// Guardian indices with a signature recovering to their address (each
+    /// index once).
+    pub valid: Vec<u8>,
+    /// Guardian indices whose signature didn't recover to their address, or
+    /// that are out of range for the set.
+    pub invalid: Vec<u8>,
+    /// Signatures required for quorum.
+    pub quorum: usize,
+}
+
+impl SignatureCheck {
+    /// Whether enough distinct guardians signed.
+    pub fn has_quorum(&self) -> bool {
+        self.valid.len() >= self.quorum
+    }
+}
+
+/// Ethereum address of the key that produced the 65-byte `[r, s, v]`
+/// signature over `digest`, or `None` if it doesn't recover.
+pub fn recover_guardian_address(digest: &[u8; 32], signature: &[u8; 65]) -> Option<[u8; 20]> {
+    let pubkey = secp256k1_recover(digest, signature[64], &signature[..64]).ok()?;
+    let hash = Keccak256::digest(pubkey.to_bytes());
+    Some(hash[12..].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_data(index: u32, keys: &[[u8; 20]], expiration_time: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&index.to_le_bytes());
+        data.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+        for key in keys {
+            data.extend_from_slice(key);
+        }
+        data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        data.extend_from_slice(&expiration_time.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_guardian_set() {
+        let keys = [[1u8; 20], [2u8; 20], [3u8; 20]];
+        let set = GuardianSet::parse(&build_data(4, &keys, 0)).unwrap();
+
+        assert_eq!(set.index, 4);
+        assert_eq!(set.keys, keys);
+        assert_eq!(set.creation_time, 1_700_000_000);
+        assert_eq!(set.expiration_time, 0);
+        assert_eq!(set.quorum(), 3);
+        assert!(!set.is_expired(i64::MAX));
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_guardian_set() {
+        let data = build_data(0, &[[1u8; 20], [2u8; 20]], 0);
+
+        assert!(GuardianSet::parse(&data[..7]).is_err());
+        assert!(GuardianSet::parse(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_check_signatures() {
+        let secret_key = libsecp256k1::SecretKey::parse(&[7u8; 32]).unwrap();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+        let address: [u8; 20] = Keccak256::digest(&public_key.serialize()[1..])[12..]
+            .try_into()
+            .unwrap();
+        let body = b"vaa body";
+        let message = libsecp256k1::Message::parse(&vaa_digest(body));
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &secret_key);
+        let mut guardian_signature = [0u8; 66];
+        guardian_signature[0] = 1;
+        guardian_signature[1..65].copy_from_slice(&signature.serialize());
+        guardian_signature[65] = recovery_id.serialize();
+        let set = GuardianSet {
+            index: 0,
+            keys: vec![[0u8; 20], address],
+            creation_time: 0,
+            expiration_time: 0,
+        };
+
+        let check = set.check_signatures(body, &[guardian_signature, guardian_signature]);
+        assert_eq!(check.valid, vec![1]);
+        assert!(check.invalid.is_empty());
+        assert!(!check.has_quorum());
+
+        let check = set.check_signatures(b"other body", &[guardian_signature]);
+        assert_eq!(check.invalid, vec![1]);
+    }
+
+    #[test]
+    fn test_quorum_and_expiration() {
+        let set = GuardianSet {
+            index: 0,
+            keys: vec![[0u8; 20]; 19],
+            creation_time: 0,
+            expiration_time: 100,
+        };
+
+        assert_eq!(set.quorum(), 13);
+        assert!(!set.is_expired(100));
+        assert!(set.is_expired(101));
+    }
+}
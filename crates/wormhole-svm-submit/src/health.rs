@@ -0,0 +1,224 @@
+//! Startup checks of a Wormhole deployment and target program.
+//!
+//! [`check_environment`] verifies everything a broadcast relies on before the
+//! first VAA arrives: the core bridge and Verify VAA Shim are deployed, the
+//! target program exists and answers the resolver instruction, and the current
+//! guardian set is readable. Each check is reported separately, so a relayer
+//! can log the full picture instead of failing on the first problem.
+
+use solana_sdk::{account::Account, pubkey::Pubkey, signer::null_signer::NullSigner};
+
+use crate::connection::SolanaConnection;
+use crate::guardian_set::GuardianSet;
+use crate::network::WormholeNetwork;
+use crate::resolve;
+use crate::SubmitError;
+
+/// Outcome of one check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    /// Whether the check passed.
+    pub ok: bool,
+    /// What was found, e.g. why the check failed.
+    pub detail: String,
+}
+
+impl Check {
+    fn passed(detail: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn failed(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Result of [`check_environment`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentReport {
+    /// The core bridge program is deployed.
+    pub core_bridge: Check,
+    /// The Verify VAA Shim program is deployed.
+    pub verify_vaa_shim: Check,
+    /// The target program is deployed.
+    pub program: Check,
+    /// The target program answers `resolve_execute_vaa_v1`.
+    pub resolver: Check,
+    /// The current guardian set account is readable.
+    pub guardian_set: Check,
+    /// Index of the current guardian set, if the core bridge config could be
+    /// read.
+    pub guardian_set_index: Option<u32>,
+}
+
+impl EnvironmentReport {
+    /// Whether every check passed.
+    pub fn is_healthy(&self) -> bool {
+        self.checks().iter().all(|(_, check)| check.ok)
+    }
+
+    /// All checks with their names, in the order they were run.
+    pub fn checks(&self) -> [(&'static str, &Check); 5] {
+        [
+            ("core bridge", &self.core_bridge),
+            ("verify vaa shim", &self.verify_vaa_shim),
+            ("program", &self.program),
+            ("resolver", &self.resolver),
+            ("guardian set", &self.guardian_set),
+        ]
+    }
+}
+
+/// Check that `network` is deployed and `program_id` is ready to receive
+/// broadcasts over `conn`.
+///
+/// The resolver check simulates `resolve_execute_vaa_v1` with an empty VAA,
+/// paid by the core bridge fee collector, so no keypair is needed. It requires
+/// a connection that simulates without signature verification (like RPC).
+/// A program that rejects the empty VAA still passes, since it recognized the
+/// instruction; the rejection is reported in the detail.
+pub fn check_environment<C: SolanaConnection>(
+    conn: &C,
+    network: &WormholeNetwork,
+    program_id: &Pubkey,
+) -> EnvironmentReport {
+    let fetch = |pubkey: &Pubkey| conn.get_account(pubkey).map_err(|e| e.to_string());
+
+    let core_bridge = program_check(fetch(&network.core_bridge), &network.core_bridge);
+    let verify_vaa_shim = program_check(fetch(&network.verify_vaa_shim), &network.verify_vaa_shim);
+    let program = program_check(fetch(program_id), program_id);
+
+    let config_address = network.core_bridge_config_address();
+    let guardian_set_index = match fetch(&config_address) {
+        Ok(Some(account)) if account.data.len() >= 4 => {
+            Ok(u32::from_le_bytes(account.data[0..4].try_into().unwrap()))
+        }
+        Ok(_) => Err(format!("core bridge config {} not found", config_address)),
+        Err(e) => Err(format!("failed to fetch core bridge config: {}", e)),
+    };
+    let resolver = if program.ok {
+        let payer = NullSigner::new(&network.fee_collector_address());
+        resolver_check(resolve::resolve_execute_vaa_v1(
+            conn,
+            program_id,
+            &payer,
+            &PROBE_VAA_BODY,
+            &network.guardian_set_address(*guardian_set_index.as_ref().unwrap_or(&0)),
+            1,
+        ))
+    } else {
+        Check::failed("skipped: program not deployed")
+    };
+
+    let guardian_set = match &guardian_set_index {
+        Ok(index) => match GuardianSet::fetch(conn, network, *index) {
+            Ok(set) => Check::passed(format!(
+                "guardian set {} with {} guardians (quorum {})",
+                set.index,
+                set.keys.len(),
+                set.quorum()
+            )),
+            Err(e) => Check::failed(e.to_string()),
+        },
+        Err(e) => Check::failed(e.clone()),
+    };
+
+    EnvironmentReport {
+        core_bridge,
+        verify_vaa_shim,
+        program,
+        resolver,
+        guardian_set,
+        guardian_set_index: guardian_set_index.ok(),
+    }
+}
+
+/// A VAA body with zeroed header fields and an empty payload.
+const PROBE_VAA_BODY: [u8; 51] = [0; 51];
+
+fn program_check(account: Result<Option<Account>, String>, pubkey: &Pubkey) -> Check {
+    match account {
+        Ok(Some(account)) if account.executable => {
+            Check::passed(format!("{} deployed (owner {})", pubkey, account.owner))
+        }
+        Ok(Some(_)) => Check::failed(format!("{} exists but is not executable", pubkey)),
+        Ok(None) => Check::failed(format!("{} not found", pubkey)),
+        Err(e) => Check::failed(format!("failed to fetch {}: {}", pubkey, e)),
+    }
+}
+
+fn resolver_check<T>(result: Result<T, SubmitError>) -> Check {
+    match result {
+        Ok(_) => Check::passed("resolved the probe VAA"),
+        Err(SubmitError::ResolverNotConverged { .. }) => {
+            Check::passed("resolver requested accounts for the probe VAA")
+        }
+        Err(SubmitError::InvalidResolverResult(message)) => Check::failed(format!(
+            "program does not implement the resolver: {}",
+            message
+        )),
+        Err(SubmitError::ResolverSimulation(message)) => {
+            let unknown_instruction = message.contains("InstructionFallbackNotFound")
+                || message.contains("custom program error: 0x65")
+                || message.contains("invalid instruction data");
+            if unknown_instruction {
+                Check::failed(format!(
+                    "program does not implement the resolver: {}",
+                    message
+                ))
+            } else {
+                Check::passed(format!("resolver rejected the probe VAA: {}", message))
+            }
+        }
+        Err(e) => Check::failed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(executable: bool) -> Account {
+        Account {
+            lamports: 1,
+            data: vec![],
+            owner: Pubkey::new_unique(),
+            executable,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_program_check() {
+        let pubkey = Pubkey::new_unique();
+
+        assert!(program_check(Ok(Some(account(true))), &pubkey).ok);
+        assert!(!program_check(Ok(Some(account(false))), &pubkey).ok);
+        assert!(!program_check(Ok(None), &pubkey).ok);
+        assert!(!program_check(Err("timeout".to_string()), &pubkey).ok);
+    }
+
+    #[test]
+    fn test_resolver_check() {
+        let simulation = |message: &str| -> Result<(), SubmitError> {
+            Err(SubmitError::ResolverSimulation(message.to_string()))
+        };
+
+        assert!(resolver_check(Ok(())).ok);
+        assert!(resolver_check(simulation("custom program error: 0x1771")).ok);
+        assert!(!resolver_check(simulation("custom program error: 0x65")).ok);
+        assert!(
+            !resolver_check::<()>(Err(SubmitError::InvalidResolverResult(
+                "No return data from resolver on iteration 1".to_string()
+            )))
+            .ok
+        );
+        assert!(!resolver_check::<()>(Err(SubmitError::Connection("down".to_string()))).ok);
+    }
+}
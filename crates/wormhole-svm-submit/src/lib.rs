@@ -10,25 +10,137 @@
 //! 2. Resolve accounts via simulation
 //! 3. Execute the resolved instructions
 //! 4. Close the signatures account
+//!
+//! [`broadcast_vaa_signed`] does the same starting from complete signed VAA bytes.
+//!
+//! [`broadcast_vaa_with_config`] runs the flow over any [`SolanaConnection`] and
+//! takes a [`BroadcastConfig`], e.g. to target another SVM network.
+//!
+//! [`resolve_and_substitute`] runs the resolver and returns concrete
+//! instructions without executing anything, for composing custom transactions.
+//!
+//! [`export_broadcast`] resolves and substitutes everything but returns unsigned
+//! transactions for offline signing instead of sending them.
+//!
+//! [`export_multisig_transactions`] serializes resolved instruction groups as
+//! multisig (Squads) vault transactions with the vault as payer.
+//!
+//! With the `shadow` feature, [`broadcast_vaa_with_shadow`] rehearses the whole
+//! flow on a LiteSVM fork of the cluster before submitting for real.
+//!
+//! [`post_message`] posts a Wormhole message through the Post Message Shim,
+//! paying the core bridge fee in the same transaction.
+//!
+//! Set [`ExecuteConfig::lookup_tables`] to send instruction groups that exceed
+//! the legacy transaction size through temporary address lookup tables.
+//!
+//! [`FailoverConnection`] spreads calls over several endpoints of a cluster,
+//! moving to the next one on transport errors.
+//!
+//! Set [`BroadcastConfig::store`] (e.g. to a [`FileStore`]) to persist progress,
+//! so a restarted relayer resumes or cleans up in-flight broadcasts.
+//!
+//! With the `pipeline` feature, [`broadcast_stream`] submits a stream of signed
+//! VAAs concurrently, in order per emitter.
+//!
+//! With the `spy` feature, [`spy::relay_from_spy`] feeds the signed VAAs of a
+//! guardian spy subscription into that pipeline.
+//!
+//! With the `tpu` feature, [`TpuConnection`] sends transactions directly to
+//! leader TPUs over QUIC instead of RPC `sendTransaction`.
+//!
+//! With the `compat` feature, [`compat`] encodes instructions in the bincode
+//! form shared with the solana 1.x line, for handing them to 1.x code.
+//!
+//! # Minimal build
+//!
+//! Everything that talks to RPC (the [`SolanaConnection`] impl for `RpcClient`,
+//! [`broadcast_vaa`], [`broadcast_vaa_signed`] and
+//! [`signatures::find_signatures_accounts`]) is behind the default `rpc`
+//! feature. With `default-features = false` the crate builds without
+//! `solana-client`, and the resolver, execution and signature-building logic
+//! runs over any caller-provided [`SolanaConnection`].
 
+pub mod account_report;
+#[cfg(feature = "compat")]
+pub mod compat;
 pub mod connection;
+pub mod cost;
+pub mod dependencies;
+pub mod events;
 pub mod execute;
+pub mod failover;
+pub mod guardian_set;
+pub mod health;
+pub mod lookup_table;
+pub mod message;
+pub mod multisig;
+pub mod network;
+pub mod offline;
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
 pub mod resolve;
+#[cfg(feature = "shadow")]
+pub mod shadow;
 pub mod signatures;
+#[cfg(feature = "spy")]
+pub mod spy;
+pub mod store;
+#[cfg(test)]
+mod test_support;
+#[cfg(feature = "tpu")]
+pub mod tpu;
+pub mod vaa;
+pub mod verification;
 
-pub use connection::SolanaConnection;
+pub use account_report::{account_report, AccountReport, AccountStatus};
+#[cfg(feature = "rpc")]
+pub use connection::{rpc_client_with_config, RpcClientWithCommitment, RpcConnectionConfig};
+pub use connection::{SolanaConnection, TransactionFailure};
+pub use cost::{estimate_broadcast_cost, CostEstimate};
+pub use dependencies::{order_instruction_groups, GroupOrder};
+pub use events::{BroadcastEvent, EventSink, JsonLinesSink};
+pub use execute::{
+    estimate_group_compute, placeholder_name, ComputeUnitLimit, ExecuteConfig, FeeEscalation,
+    GroupExecution, SubstitutedGroups, Substitutions,
+};
+pub use failover::FailoverConnection;
+pub use guardian_set::{GuardianSet, SignatureCheck};
+pub use health::{check_environment, EnvironmentReport};
+pub use message::{post_message, Finality, MessageEvent, PostMessageArgs, PostedMessage};
+pub use multisig::{export_multisig_transactions, MultisigTransaction};
+pub use network::WormholeNetwork;
+pub use offline::{
+    export_broadcast, export_broadcast_with_nonces, DurableNonce, OfflineBroadcast,
+    UnsignedTransaction,
+};
+#[cfg(feature = "pipeline")]
+pub use pipeline::{broadcast_stream, PipelineConfig, PipelineResult};
 pub use resolve::{
-    InstructionGroup, ResolverResult, SerializableAccountMeta, SerializableInstruction,
-    RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+    InstructionGroup, ResolverIteration, ResolverResult, SerializableAccountMeta,
+    SerializableInstruction, RESOLVER_PUBKEY_SHIM_VAA_SIGS,
 };
-pub use signatures::{build_close_signatures_ix, build_post_signatures_ix, PostedSignatures};
+#[cfg(feature = "shadow")]
+pub use shadow::{broadcast_vaa_with_shadow, ForkedConnection};
+pub use signatures::{
+    build_close_signatures_ix, build_post_signatures_ix, signatures_account_rent, ClosedSignatures,
+    PostedSignatures, SignaturesAccount,
+};
+pub use store::{close_in_flight, BroadcastRecord, BroadcastStore, FileStore, PendingTransaction};
+#[cfg(feature = "tpu")]
+pub use tpu::TpuConnection;
+pub use vaa::{SignedVaa, VaaBody};
+pub use verification::{ShimBackend, VerificationBackend};
+
+// The solana-sdk line used in this crate's API, so dependents can name the
+// exact types without pinning the same version themselves.
+pub use solana_sdk;
 
 // Re-export placeholder constants at crate root for convenience.
 pub use executor_account_resolver_svm::{RESOLVER_PUBKEY_GUARDIAN_SET, RESOLVER_PUBKEY_PAYER};
 
 /// Maximum resolver iterations before giving up.
-#[cfg(feature = "rpc")]
-const MAX_RESOLVER_ITERATIONS: usize = 10;
+pub const MAX_RESOLVER_ITERATIONS: usize = 10;
 
 /// Errors that can occur during VAA submission.
 #[derive(thiserror::Error, Debug)]
@@ -39,8 +151,133 @@ pub enum SubmitError {
     #[error("Resolver simulation error: {0}")]
     ResolverSimulation(String),
 
+    /// The resolver ran, but its result (return data or result account) is
+    /// missing or can't be decoded, e.g. because the program doesn't
+    /// implement the resolver.
+    #[error("Invalid resolver result: {0}")]
+    InvalidResolverResult(String),
+
+    /// The resolver kept asking for more accounts. The trace records what was
+    /// supplied and requested on each iteration.
+    #[error("Resolver did not resolve after {iterations} iterations. Remaining accounts: {remaining_accounts:?}")]
+    ResolverNotConverged {
+        iterations: usize,
+        remaining_accounts: Vec<solana_sdk::pubkey::Pubkey>,
+        trace: Vec<resolve::ResolverIteration>,
+    },
+
+    #[error("Invalid VAA: {0}")]
+    InvalidVaa(String),
+
+    #[error("Invalid guardian set: {0}")]
+    InvalidGuardianSet(String),
+
     #[error("Execution error: {0}")]
     Execution(String),
+
+    /// A placeholder pubkey survived substitution, so the transaction would
+    /// reference a nonsense address. Nothing was sent for this group.
+    #[error("Instruction {instruction_index} (account {account_index}) of group {group_index} still references placeholder {placeholder}")]
+    UnresolvedPlaceholder {
+        group_index: usize,
+        instruction_index: usize,
+        account_index: usize,
+        placeholder: solana_sdk::pubkey::Pubkey,
+    },
+
+    /// A resolved instruction requires a signature that no available signer
+    /// (payer, generated keypair or caller-supplied signer) can provide.
+    #[error("Instruction group {group_index} requires a signature from {pubkey}, but no signer is available for it")]
+    MissingSigner {
+        group_index: usize,
+        pubkey: solana_sdk::pubkey::Pubkey,
+    },
+
+    /// A transaction was sent but failed or could not be confirmed. It may
+    /// still land later, so the signature is kept for investigation.
+    #[error("Transaction {signature} for instruction group {group_index} failed: {message}")]
+    TransactionFailed {
+        group_index: usize,
+        signature: solana_sdk::signature::Signature,
+        message: String,
+        cause: TransactionFailure,
+    },
+
+    /// The estimated cost of a broadcast exceeds
+    /// [`BroadcastConfig::max_cost_lamports`]. Nothing was sent.
+    #[error("Estimated cost of {estimated} lamports exceeds the cap of {cap} lamports")]
+    CostCapExceeded { estimated: u64, cap: u64 },
+
+    /// A wallet can't cover its share of the estimated broadcast cost (see
+    /// [`cost::required_balances`]). Nothing was sent.
+    #[error("{account} holds {available} lamports, but the broadcast needs {required}")]
+    InsufficientFunds {
+        account: solana_sdk::pubkey::Pubkey,
+        required: u64,
+        available: u64,
+    },
+
+    #[error("Broadcast store error: {0}")]
+    Store(String),
+
+    /// The account dependencies between instruction groups form a cycle, so
+    /// no execution order satisfies them.
+    #[error("Instruction groups {groups:?} have cyclic account dependencies")]
+    CyclicGroupDependencies { groups: Vec<usize> },
+
+    /// [`BroadcastConfig::confirm`] declined the plan. Nothing was sent.
+    #[error("Broadcast declined; nothing was sent")]
+    Declined,
+}
+
+impl SubmitError {
+    /// Stable numeric code of the failure class, for automation and wrappers
+    /// in other languages. Codes are never reused or renumbered.
+    ///
+    /// | Code | Failure |
+    /// |------|---------|
+    /// | 100 | [`Connection`](Self::Connection) |
+    /// | 200 | [`ResolverSimulation`](Self::ResolverSimulation) |
+    /// | 201 | [`InvalidResolverResult`](Self::InvalidResolverResult) |
+    /// | 202 | [`ResolverNotConverged`](Self::ResolverNotConverged) |
+    /// | 300 | [`InvalidVaa`](Self::InvalidVaa) |
+    /// | 301 | [`InvalidGuardianSet`](Self::InvalidGuardianSet) |
+    /// | 400 | [`Execution`](Self::Execution) |
+    /// | 401 | [`UnresolvedPlaceholder`](Self::UnresolvedPlaceholder) |
+    /// | 402 | [`MissingSigner`](Self::MissingSigner) |
+    /// | 403 | [`CyclicGroupDependencies`](Self::CyclicGroupDependencies) |
+    /// | 410 | `TransactionFailed`, [`Other`](TransactionFailure::Other) |
+    /// | 411 | `TransactionFailed`, [`Expired`](TransactionFailure::Expired) |
+    /// | 412 | `TransactionFailed`, [`ProgramError`](TransactionFailure::ProgramError) |
+    /// | 413 | `TransactionFailed`, [`InsufficientFunds`](TransactionFailure::InsufficientFunds) |
+    /// | 500 | [`CostCapExceeded`](Self::CostCapExceeded) |
+    /// | 501 | [`InsufficientFunds`](Self::InsufficientFunds) |
+    /// | 502 | [`Declined`](Self::Declined) |
+    /// | 600 | [`Store`](Self::Store) |
+    pub fn error_code(&self) -> u32 {
+        match self {
+            SubmitError::Connection(_) => 100,
+            SubmitError::ResolverSimulation(_) => 200,
+            SubmitError::InvalidResolverResult(_) => 201,
+            SubmitError::ResolverNotConverged { .. } => 202,
+            SubmitError::InvalidVaa(_) => 300,
+            SubmitError::InvalidGuardianSet(_) => 301,
+            SubmitError::Execution(_) => 400,
+            SubmitError::UnresolvedPlaceholder { .. } => 401,
+            SubmitError::MissingSigner { .. } => 402,
+            SubmitError::CyclicGroupDependencies { .. } => 403,
+            SubmitError::TransactionFailed { cause, .. } => match cause {
+                TransactionFailure::Other => 410,
+                TransactionFailure::Expired => 411,
+                TransactionFailure::ProgramError => 412,
+                TransactionFailure::InsufficientFunds => 413,
+            },
+            SubmitError::CostCapExceeded { .. } => 500,
+            SubmitError::InsufficientFunds { .. } => 501,
+            SubmitError::Declined => 502,
+            SubmitError::Store(_) => 600,
+        }
+    }
 }
 
 #[cfg(feature = "rpc")]
@@ -50,6 +287,57 @@ impl From<solana_client::client_error::ClientError> for SubmitError {
     }
 }
 
+/// Options for [`broadcast_vaa_with_config`].
+#[derive(Default)]
+pub struct BroadcastConfig<'a> {
+    /// Wormhole deployment addresses of the target network.
+    pub network: WormholeNetwork,
+    /// Options for executing the resolved instruction groups.
+    pub execute: ExecuteConfig<'a>,
+    /// Append an SPL Memo with the VAA emitter, sequence and digest (see
+    /// [`vaa::vaa_memo`]) to each execution transaction.
+    pub vaa_memo: bool,
+    /// Receives an event for each step of the flow (also used for execution
+    /// events unless `execute.event_sink` is set).
+    pub event_sink: Option<&'a dyn EventSink>,
+    /// Abort before posting signatures if the estimated cost (see
+    /// [`estimate_broadcast_cost`]) exceeds this many lamports.
+    pub max_cost_lamports: Option<u64>,
+    /// Records progress so that broadcasting the same VAA again resumes an
+    /// interrupted run (see [`store`]).
+    pub store: Option<&'a dyn BroadcastStore>,
+    /// Execute the resolved groups in the order derived from their account
+    /// dependencies (see [`dependencies`]) instead of the resolver order.
+    pub order_groups: bool,
+    /// Fetch every referenced account after resolution and warn about
+    /// writable accounts that don't exist (see [`account_report()`]).
+    pub report_accounts: bool,
+    /// Leave the guardian signatures account open instead of closing it
+    /// after execution. Its rent stays locked until it is closed, e.g. with
+    /// [`close_signatures`](signatures::close_signatures).
+    pub skip_close: bool,
+    /// Don't check before posting signatures that the payer (and fee payer)
+    /// can afford the broadcast (see [`SubmitError::InsufficientFunds`]),
+    /// e.g. when they are funded by an earlier instruction group.
+    pub skip_balance_check: bool,
+    /// Called with the resolved plan once everything has been checked and
+    /// before anything is sent; returning `false` aborts the broadcast with
+    /// [`SubmitError::Declined`]. Not called when resuming a broadcast.
+    pub confirm: Option<&'a dyn Fn(&BroadcastPlan) -> bool>,
+}
+
+/// What a broadcast is about to do, passed to [`BroadcastConfig::confirm`].
+pub struct BroadcastPlan<'a> {
+    pub program_id: &'a solana_sdk::pubkey::Pubkey,
+    /// The guardian set substituted for `RESOLVER_PUBKEY_GUARDIAN_SET`.
+    pub guardian_set: &'a solana_sdk::pubkey::Pubkey,
+    /// The instruction groups to execute, in execution order.
+    pub instruction_groups: &'a [InstructionGroup],
+    pub estimate: CostEstimate,
+    /// Core bridge message fees paid by the instruction groups.
+    pub bridge_fees: u64,
+}
+
 /// Submit a signed VAA to a program that implements `resolve_execute_vaa_v1`.
 ///
 /// This performs the complete broadcast flow:
@@ -58,14 +346,15 @@ impl From<solana_client::client_error::ClientError> for SubmitError {
 /// 3. Execute the resolved instructions (substituting placeholders)
 /// 4. Close the signatures account to reclaim rent
 ///
-/// Currently only supports programs that use the Verify VAA Shim (i.e. the
-/// resolved instructions reference `RESOLVER_PUBKEY_SHIM_VAA_SIGS`). Legacy
-/// programs that verify VAAs differently are not yet supported.
+/// Only supports programs that use the Verify VAA Shim (i.e. the resolved
+/// instructions reference `RESOLVER_PUBKEY_SHIM_VAA_SIGS`). Programs that
+/// verify VAAs differently can plug in a [`VerificationBackend`] with
+/// [`broadcast_vaa_with_backend`].
 ///
 /// # Arguments
 ///
 /// * `rpc_client` - Connected RPC client
-/// * `payer` - Keypair that pays for transactions
+/// * `payer` - Signer that pays for transactions (a keypair, or e.g. a hardware wallet)
 /// * `program_id` - The program implementing `resolve_execute_vaa_v1`
 /// * `guardian_set_index` - On-chain guardian set index
 /// * `vaa_body` - The VAA body bytes (without header/signatures)
@@ -74,37 +363,495 @@ impl From<solana_client::client_error::ClientError> for SubmitError {
 #[cfg(feature = "rpc")]
 pub fn broadcast_vaa(
     rpc_client: &mut solana_client::rpc_client::RpcClient,
-    payer: &solana_sdk::signature::Keypair,
+    payer: &dyn solana_sdk::signer::Signer,
     program_id: &solana_sdk::pubkey::Pubkey,
     guardian_set_index: u32,
     vaa_body: &[u8],
     guardian_signatures: &[[u8; 66]],
     core_bridge: &solana_sdk::pubkey::Pubkey,
 ) -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
-    use wormhole_svm_definitions::find_guardian_set_address;
+    let config = BroadcastConfig {
+        network: WormholeNetwork {
+            core_bridge: *core_bridge,
+            ..WormholeNetwork::SOLANA_MAINNET
+        },
+        ..Default::default()
+    };
+    broadcast_vaa_with_config(
+        rpc_client,
+        payer,
+        program_id,
+        guardian_set_index,
+        vaa_body,
+        guardian_signatures,
+        &config,
+    )
+}
+
+/// Submit a signed VAA over any [`SolanaConnection`], with additional options.
+///
+/// Runs the same flow as [`broadcast_vaa`], using the program addresses of
+/// `config.network` for the guardian set PDA and the Verify VAA Shim.
+pub fn broadcast_vaa_with_config<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn solana_sdk::signer::Signer,
+    program_id: &solana_sdk::pubkey::Pubkey,
+    guardian_set_index: u32,
+    vaa_body: &[u8],
+    guardian_signatures: &[[u8; 66]],
+    config: &BroadcastConfig,
+) -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
+    let vaa = SignedVaa {
+        guardian_set_index,
+        signatures: guardian_signatures.to_vec(),
+        body: vaa_body.to_vec(),
+    };
+    broadcast_vaa_with_backend(
+        conn,
+        payer,
+        program_id,
+        &vaa,
+        &ShimBackend::new(config.network.verify_vaa_shim),
+        config,
+    )
+}
+
+/// Submit a signed VAA, verifying it through `backend` instead of the
+/// Verify VAA Shim.
+///
+/// Runs the same flow as [`broadcast_vaa_with_config`], with `backend`
+/// posting the guardian signatures, providing the account substituted for
+/// `RESOLVER_PUBKEY_SHIM_VAA_SIGS` and cleaning up afterwards.
+pub fn broadcast_vaa_with_backend<C: SolanaConnection, B: VerificationBackend>(
+    conn: &mut C,
+    payer: &dyn solana_sdk::signer::Signer,
+    program_id: &solana_sdk::pubkey::Pubkey,
+    vaa: &SignedVaa,
+    backend: &B,
+    config: &BroadcastConfig,
+) -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
+    let vaa_body = vaa.body.as_slice();
+    let guardian_set = config.network.guardian_set_address(vaa.guardian_set_index);
+    let emit = |event: BroadcastEvent| {
+        if let Some(sink) = config.event_sink {
+            sink.emit(&event);
+        }
+    };
+    let emit_error = |e: SubmitError| {
+        emit(BroadcastEvent::Error {
+            code: e.error_code(),
+            message: e.to_string(),
+        });
+        e
+    };
+
+    let vaa_digest = vaa::vaa_digest(vaa_body);
+    let previous = match config.store {
+        Some(store) => store.load(&vaa_digest).map_err(emit_error)?,
+        None => None,
+    };
+    if let Some(record) = &previous {
+        if record.program_id != *program_id {
+            return Err(emit_error(SubmitError::Store(format!(
+                "VAA is already in flight for program {}",
+                record.program_id
+            ))));
+        }
+    }
+
+    // Step 1: Resolve accounts (no on-chain state needed yet), unless a
+    // previous run already did
+    let resumed = previous.is_some();
+    let (instruction_groups, stored_signatures_account, executed) = match previous {
+        Some(mut record) => {
+            if let Some(pending) = record.pending_group.take() {
+                // The previous run may have stopped between sending the next
+                // group and seeing it confirmed
+                log::info!(
+                    "Checking transaction {} of the interrupted run...",
+                    pending.signature
+                );
+                match execute::await_landed_or_expired(conn, &pending.signature, &pending.blockhash)
+                    .map_err(emit_error)?
+                {
+                    Some(Ok(())) => {
+                        log::info!("Transaction {} landed", pending.signature);
+                        if let Some(store) = config.store {
+                            store
+                                .group_executed(&vaa_digest, &pending.signature)
+                                .map_err(emit_error)?;
+                        }
+                        record.executed_groups.push(pending.signature);
+                    }
+                    Some(Err(message)) => log::info!(
+                        "Transaction {} failed ({}); sending its group again",
+                        pending.signature,
+                        message
+                    ),
+                    None => log::info!(
+                        "Transaction {} expired without landing; sending its group again",
+                        pending.signature
+                    ),
+                }
+            }
+            log::info!(
+                "Resuming broadcast ({} of {} groups executed)",
+                record.executed_groups.len(),
+                record.instruction_groups.len()
+            );
+            (
+                record.instruction_groups,
+                record.signatures_account,
+                record.executed_groups,
+            )
+        }
+        None => {
+            log::info!("Resolving accounts...");
+            let resolved = resolve::resolve_execute_vaa_v1_with_placeholders(
+                conn,
+                program_id,
+                payer,
+                vaa_body,
+                &guardian_set,
+                MAX_RESOLVER_ITERATIONS,
+                config.execute.placeholders,
+            )
+            .map_err(emit_error)?;
+            log::info!(
+                "Resolved in {} iterations ({} instruction groups)",
+                resolved.iterations,
+                resolved.instruction_groups.len()
+            );
+            emit(BroadcastEvent::Resolved {
+                program_id: program_id.to_string(),
+                iterations: resolved.iterations,
+                instruction_groups: resolved.instruction_groups.len(),
+            });
+            let mut instruction_groups = resolved.instruction_groups;
+            if config.order_groups {
+                let missing = dependencies::missing_accounts(
+                    conn,
+                    &instruction_groups,
+                    config.execute.placeholders,
+                )
+                .map_err(emit_error)?;
+                let order = dependencies::order_instruction_groups(&instruction_groups, &missing)
+                    .map_err(emit_error)?;
+                if order.is_reordered() {
+                    log::info!(
+                        "Reordered instruction groups by dependencies: {:?}",
+                        order.order
+                    );
+                    instruction_groups = order.apply(instruction_groups);
+                }
+            }
+            (instruction_groups, None, Vec::new())
+        }
+    };
 
-    let (guardian_set, _bump) =
-        find_guardian_set_address(guardian_set_index.to_be_bytes(), core_bridge);
+    backend
+        .check_groups(&instruction_groups)
+        .map_err(emit_error)?;
+    store::ensure_resumable(&instruction_groups, executed.len()).map_err(emit_error)?;
+    let remaining_groups = &instruction_groups[executed.len().min(instruction_groups.len())..];
 
-    // Step 1: Resolve accounts (no on-chain state needed yet)
-    eprintln!("Resolving accounts...");
-    let resolved = resolve::resolve_execute_vaa_v1(
+    let payer_pubkey = solana_sdk::signer::Signer::pubkey(payer);
+    if config.report_accounts {
+        let report = account_report::account_report(
+            conn,
+            remaining_groups,
+            &payer_pubkey,
+            &guardian_set,
+            config.execute.placeholders,
+        )
+        .map_err(emit_error)?;
+        log::info!("Referenced accounts: {}", report.accounts.len());
+        let missing: Vec<String> = report
+            .flagged()
+            .map(|account| {
+                log::warn!(
+                    "writable account {} (groups {:?}) doesn't exist",
+                    account.address,
+                    account.groups
+                );
+                account.address.to_string()
+            })
+            .collect();
+        if !missing.is_empty() {
+            emit(BroadcastEvent::AccountsMissing { accounts: missing });
+        }
+    }
+
+    // Validate the memo before anything is sent
+    let memo = if config.vaa_memo {
+        Some(vaa::vaa_memo(vaa_body).map_err(emit_error)?)
+    } else {
+        None
+    };
+
+    let estimate = cost::estimate_broadcast_cost(
+        remaining_groups,
+        &payer_pubkey,
+        vaa.signatures.len(),
+        &ExecuteConfig {
+            memo: memo.as_deref().or(config.execute.memo),
+            ..config.execute
+        },
+    );
+    if let Some(cap) = config.max_cost_lamports {
+        log::info!("Estimated cost: {} lamports", estimate.total());
+        if estimate.total() > cap {
+            return Err(emit_error(SubmitError::CostCapExceeded {
+                estimated: estimate.total(),
+                cap,
+            }));
+        }
+    }
+
+    let bridge_fees =
+        cost::estimate_bridge_fees(conn, &config.network, remaining_groups).map_err(emit_error)?;
+    if let (Some(confirm), false) = (config.confirm, resumed) {
+        let plan = BroadcastPlan {
+            program_id,
+            guardian_set: &guardian_set,
+            instruction_groups: remaining_groups,
+            estimate,
+            bridge_fees,
+        };
+        if !confirm(&plan) {
+            return Err(emit_error(SubmitError::Declined));
+        }
+    }
+
+    if let (Some(store), false) = (config.store, resumed) {
+        store
+            .resolved(&vaa_digest, program_id, &instruction_groups)
+            .map_err(emit_error)?;
+    }
+
+    // Step 2: Post guardian signatures, or reuse the account of a previous
+    // run if it is still open
+    let reusable_account = match stored_signatures_account {
+        Some(account) => conn
+            .get_account(&account)
+            .map_err(|e| emit_error(SubmitError::Connection(e.to_string())))?
+            .map(|_| account),
+        None => None,
+    };
+    // Fail before spending rent if the payer can't afford the broadcast
+    if !config.skip_balance_check {
+        let fee_payer = config
+            .execute
+            .fee_payer
+            .map(solana_sdk::signer::Signer::pubkey);
+        cost::ensure_sufficient_balances(
+            conn,
+            &CostEstimate {
+                signatures_rent: if reusable_account.is_some() {
+                    0
+                } else {
+                    estimate.signatures_rent
+                },
+                ..estimate
+            },
+            bridge_fees,
+            &payer_pubkey,
+            fee_payer.as_ref(),
+        )
+        .map_err(emit_error)?;
+    }
+
+    let signatures_pubkey = match reusable_account {
+        Some(account) => {
+            log::info!("Reusing signatures account: {}", account);
+            account
+        }
+        None => {
+            log::info!("Posting guardian signatures...");
+            let posted = backend.post(conn, payer, vaa).map_err(emit_error)?;
+            log::info!("Signatures posted: {}", posted);
+            emit(BroadcastEvent::SignaturesPosted {
+                signatures_account: posted.to_string(),
+            });
+            if let Some(store) = config.store {
+                if let Err(e) = store.signatures_posted(&vaa_digest, &posted) {
+                    log::warn!("failed to record signatures account: {}", e);
+                }
+            }
+            posted
+        }
+    };
+    let reference_account = backend.reference_account(&signatures_pubkey);
+
+    let event_sink = config.execute.event_sink.or(config.event_sink);
+    let recording_sink = config.store.map(|store| store::RecordingSink {
+        store,
+        vaa_digest,
+        skipped_groups: executed.len(),
+        inner: event_sink,
+    });
+    let record_sending = config.store.map(|store| {
+        move |group_index: usize,
+              signature: &solana_sdk::signature::Signature,
+              blockhash: &solana_sdk::hash::Hash| {
+            if let Some(before_send) = config.execute.before_send {
+                before_send(group_index, signature, blockhash)?;
+            }
+            store.group_sending(&vaa_digest, signature, blockhash)
+        }
+    });
+    let execute_config = ExecuteConfig {
+        event_sink: match &recording_sink {
+            Some(sink) => Some(sink as &dyn EventSink),
+            None => event_sink,
+        },
+        before_send: match &record_sending {
+            Some(hook) => Some(hook),
+            None => config.execute.before_send,
+        },
+        memo: memo.as_deref().or(config.execute.memo),
+        ..config.execute
+    };
+
+    // Step 3 wrapped so that step 4 also runs when execution fails
+    let result = (|| -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
+        // Step 3: Execute resolved instructions
+        log::info!("Executing resolved instructions...");
+        let tx_sigs = execute::execute_instruction_groups_with_config(
+            conn,
+            payer,
+            remaining_groups,
+            &reference_account,
+            &guardian_set,
+            &execute_config,
+        )?;
+        for sig in &tx_sigs {
+            log::info!("Executed: {}", sig);
+        }
+
+        Ok(executed.iter().copied().chain(tx_sigs).collect())
+    })()
+    .map_err(emit_error);
+
+    // Step 4: Close the signatures account to reclaim rent, whether or not
+    // execution succeeded, unless `skip_close` leaves it open
+    if config.skip_close {
+        log::info!("Leaving signatures account {} open", signatures_pubkey);
+        if let (Some(store), Ok(_)) = (config.store, &result) {
+            if let Err(e) = store.finished(&vaa_digest) {
+                log::warn!("failed to remove broadcast record: {}", e);
+            }
+        }
+        log::info!("Done.");
+        return result;
+    }
+    log::info!("Closing signatures account...");
+    match backend.cleanup(conn, payer, &signatures_pubkey) {
+        Ok(closed) => {
+            if closed.already_closed {
+                log::info!("Signatures account already closed.");
+            } else {
+                log::info!("Reclaimed {} lamports of rent", closed.reclaimed_lamports);
+            }
+            emit(BroadcastEvent::Closed {
+                signatures_account: signatures_pubkey.to_string(),
+                signature: closed.signature.map(|sig| sig.to_string()),
+                reclaimed_lamports: closed.reclaimed_lamports,
+            });
+            if let (Some(store), Ok(_)) = (config.store, &result) {
+                if let Err(e) = store.finished(&vaa_digest) {
+                    log::warn!("failed to remove broadcast record: {}", e);
+                }
+            }
+        }
+        Err(e) => {
+            log::warn!("failed to close signatures account: {}", e);
+            emit(BroadcastEvent::Error {
+                code: e.error_code(),
+                message: e.to_string(),
+            });
+        }
+    }
+    log::info!("Done.");
+
+    result
+}
+
+/// Submit complete signed VAA bytes to a program that implements `resolve_execute_vaa_v1`.
+///
+/// Parses the VAA header (version, guardian set index, signatures) and then
+/// runs the same flow as [`broadcast_vaa`].
+#[cfg(feature = "rpc")]
+pub fn broadcast_vaa_signed(
+    rpc_client: &mut solana_client::rpc_client::RpcClient,
+    payer: &dyn solana_sdk::signer::Signer,
+    program_id: &solana_sdk::pubkey::Pubkey,
+    signed_vaa: &[u8],
+    core_bridge: &solana_sdk::pubkey::Pubkey,
+) -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
+    let vaa = SignedVaa::parse(signed_vaa)?;
+    broadcast_vaa(
         rpc_client,
-        program_id,
         payer,
+        program_id,
+        vaa.guardian_set_index,
+        &vaa.body,
+        &vaa.signatures,
+        core_bridge,
+    )
+}
+
+/// Run the resolver and substitute all placeholders, without executing anything.
+///
+/// Returns concrete instructions that can be composed into custom transactions
+/// alongside unrelated instructions. The guardian signatures must already be
+/// posted (or be posted before the instructions execute) at `signatures_pubkey`,
+/// e.g. with [`signatures::post_signatures`].
+///
+/// # Arguments
+///
+/// * `conn` - Connection used to simulate the resolver
+/// * `program_id` - The program implementing `resolve_execute_vaa_v1`
+/// * `payer` - Pubkey substituted for `RESOLVER_PUBKEY_PAYER`
+/// * `guardian_set_index` - On-chain guardian set index
+/// * `vaa_body` - The VAA body bytes (without header/signatures)
+/// * `signatures_pubkey` - Guardian signatures account
+/// * `network` - Wormhole deployment (for guardian set PDA derivation)
+/// * `placeholders` - Additional `(placeholder, pubkey)` substitutions, used
+///   during resolution and substitution
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_and_substitute<C: SolanaConnection>(
+    conn: &C,
+    program_id: &solana_sdk::pubkey::Pubkey,
+    payer: &solana_sdk::pubkey::Pubkey,
+    guardian_set_index: u32,
+    vaa_body: &[u8],
+    signatures_pubkey: &solana_sdk::pubkey::Pubkey,
+    network: &WormholeNetwork,
+    placeholders: &[(solana_sdk::pubkey::Pubkey, solana_sdk::pubkey::Pubkey)],
+) -> Result<SubstitutedGroups, SubmitError> {
+    let guardian_set = network.guardian_set_address(guardian_set_index);
+    let resolved = resolve::resolve_execute_vaa_v1_with_placeholders(
+        conn,
+        program_id,
+        &solana_sdk::signer::null_signer::NullSigner::new(payer),
         vaa_body,
         &guardian_set,
         MAX_RESOLVER_ITERATIONS,
+        placeholders,
     )?;
-    eprintln!(
-        "Resolved in {} iterations ({} instruction groups)",
-        resolved.iterations,
-        resolved.instruction_groups.len()
-    );
+    Ok(execute::substitute_instruction_groups(
+        &resolved.instruction_groups,
+        payer,
+        signatures_pubkey,
+        &guardian_set,
+        placeholders,
+    ))
+}
 
-    // Check that the program uses the Verify VAA Shim.
-    // TODO: support legacy programs that verify VAAs without the shim
-    let uses_shim = resolved.instruction_groups.iter().any(|group| {
+/// Check that the resolved instructions use the Verify VAA Shim.
+pub(crate) fn ensure_uses_verify_vaa_shim(groups: &[InstructionGroup]) -> Result<(), SubmitError> {
+    let uses_shim = groups.iter().any(|group| {
         group.instructions.iter().any(|ix| {
             ix.accounts
                 .iter()
@@ -114,51 +861,169 @@ pub fn broadcast_vaa(
     if !uses_shim {
         return Err(SubmitError::Execution(
             "Program does not use the Verify VAA Shim (no RESOLVER_PUBKEY_SHIM_VAA_SIGS in \
-             resolved instructions). Legacy VAA verification is not yet supported."
+             resolved instructions). Other verification programs need a different \
+             VerificationBackend."
                 .to_string(),
         ));
     }
+    Ok(())
+}
 
-    // Step 2: Post guardian signatures
-    // TODO: solana::* addresses are all mainnet. it's fine for the shim because
-    // it has the same address everywhere.
-    let verify_vaa_shim = wormhole_svm_definitions::solana::VERIFY_VAA_SHIM_PROGRAM_ID;
-    eprintln!("Posting guardian signatures...");
-    let posted = signatures::post_signatures(
-        rpc_client,
-        payer,
-        &verify_vaa_shim,
-        guardian_set_index,
-        guardian_signatures,
-    )?;
-    eprintln!("Signatures posted: {}", posted.pubkey);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Steps 3-4 wrapped so we always close signatures even on failure
-    let result = (|| -> Result<Vec<solana_sdk::signature::Signature>, SubmitError> {
-        // Step 3: Execute resolved instructions
-        eprintln!("Executing resolved instructions...");
-        let tx_sigs = execute::execute_instruction_groups(
-            rpc_client,
-            payer,
-            &resolved.instruction_groups,
-            &posted.pubkey,
-            &guardian_set,
-        )?;
-        for sig in &tx_sigs {
-            eprintln!("Executed: {}", sig);
-        }
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use crate::test_support::{group, MockConnection};
 
-        Ok(tx_sigs)
-    })();
+    #[test]
+    fn test_broadcast_on_custom_network() {
+        let network = WormholeNetwork::new(
+            51,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let program_id = Pubkey::new_unique();
+        let payer = Keypair::new();
+        let mut conn = MockConnection::new();
+        conn.resolve_to(vec![group(
+            program_id,
+            &[
+                (RESOLVER_PUBKEY_PAYER, true, true),
+                (RESOLVER_PUBKEY_SHIM_VAA_SIGS, false, false),
+                (RESOLVER_PUBKEY_GUARDIAN_SET, false, false),
+            ],
+        )]);
 
-    // Step 4: Always close signatures account to reclaim rent
-    eprintln!("Closing signatures account...");
-    if let Err(e) =
-        signatures::close_signatures(rpc_client, payer, &verify_vaa_shim, &posted.pubkey)
-    {
-        eprintln!("Warning: failed to close signatures account: {}", e);
+        let signatures = broadcast_vaa_with_config(
+            &mut conn,
+            &payer,
+            &program_id,
+            4,
+            b"body",
+            &[[0; 66]],
+            &BroadcastConfig {
+                network,
+                skip_balance_check: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Signatures are posted to the network's Verify VAA Shim...
+        assert_eq!(conn.sent.len(), 2);
+        let post = &conn.sent[0].message;
+        let post_keys = post.static_account_keys();
+        assert_eq!(
+            *post.instructions()[0].program_id(post_keys),
+            network.verify_vaa_shim
+        );
+        let signatures_account = post_keys[1];
+
+        // ...and the group runs against the guardian set of its core bridge.
+        let execute = &conn.sent[1].message;
+        let execute_keys = execute.static_account_keys();
+        assert_eq!(signatures, [conn.sent[1].signatures[0]]);
+        assert_eq!(execute_keys[0], payer.pubkey());
+        assert!(execute_keys.contains(&signatures_account));
+        assert!(execute_keys.contains(&network.guardian_set_address(4)));
+        assert!(!execute_keys.contains(&WormholeNetwork::SOLANA_MAINNET.guardian_set_address(4)));
     }
-    eprintln!("Done.");
 
-    result
+    #[test]
+    fn test_error_codes() {
+        let transaction_failed = |cause| SubmitError::TransactionFailed {
+            group_index: 0,
+            signature: solana_sdk::signature::Signature::default(),
+            message: "failed".to_string(),
+            cause,
+        };
+
+        assert_eq!(SubmitError::Connection("down".into()).error_code(), 100);
+        assert_eq!(
+            SubmitError::ResolverSimulation("simulation failed".into()).error_code(),
+            200
+        );
+        assert_eq!(
+            SubmitError::InvalidResolverResult("No return data from resolver".into()).error_code(),
+            201
+        );
+        assert_eq!(
+            transaction_failed(TransactionFailure::Expired).error_code(),
+            411
+        );
+        assert_eq!(
+            transaction_failed(TransactionFailure::ProgramError).error_code(),
+            412
+        );
+        assert_eq!(
+            transaction_failed(TransactionFailure::InsufficientFunds).error_code(),
+            413
+        );
+        assert_eq!(
+            transaction_failed(TransactionFailure::Other).error_code(),
+            410
+        );
+        assert_eq!(
+            SubmitError::CostCapExceeded {
+                estimated: 2,
+                cap: 1
+            }
+            .error_code(),
+            500
+        );
+        assert_eq!(SubmitError::Declined.error_code(), 502);
+    }
+
+    #[test]
+    fn test_resolve_and_substitute_custom_placeholder() {
+        use borsh::BorshSerialize;
+        use executor_account_resolver_svm::{InstructionGroups, MissingAccounts, Resolver};
+
+        let custom = Pubkey::new_unique();
+        let value = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let mut conn = MockConnection::new();
+        // The resolver asks for the custom placeholder before resolving to a
+        // group that references it.
+        conn.on_simulate = Box::new(move |tx| {
+            let resolver = if tx.message.account_keys.contains(&value) {
+                Resolver::Resolved(InstructionGroups(vec![group(
+                    program_id,
+                    &[(RESOLVER_PUBKEY_PAYER, true, true), (custom, false, false)],
+                )]))
+            } else {
+                Resolver::Missing(MissingAccounts {
+                    accounts: vec![custom],
+                    address_lookup_tables: vec![],
+                })
+            };
+            Ok(connection::SimulationResult {
+                return_data: Some(resolver.try_to_vec().unwrap()),
+                post_accounts: vec![],
+                units_consumed: None,
+                logs: vec![],
+            })
+        });
+        let payer = Pubkey::new_unique();
+
+        let substituted = resolve_and_substitute(
+            &conn,
+            &program_id,
+            &payer,
+            0,
+            b"body",
+            &Pubkey::new_unique(),
+            &WormholeNetwork::SOLANA_DEVNET,
+            &[(custom, value)],
+        )
+        .unwrap();
+
+        let accounts = &substituted.groups[0][0].accounts;
+        assert_eq!(accounts[0].pubkey, payer);
+        assert_eq!(accounts[1].pubkey, value);
+    }
 }
@@ -0,0 +1,330 @@
+//! Temporary address lookup tables for oversized instruction groups.
+//!
+//! A legacy transaction must fit in a single packet, which limits a group to
+//! roughly 35 accounts. With
+//! [`ExecuteConfig::lookup_tables`](crate::ExecuteConfig::lookup_tables) set,
+//! a group that doesn't fit is sent as a v0 transaction instead: its
+//! non-signer accounts are stored in a freshly created lookup table, which is
+//! deactivated once the group has executed.
+//!
+//! A deactivated table can only be closed (returning its rent) after it has
+//! cooled down for about 512 slots, so execution never closes the tables it
+//! used: that is the caller's job. Collect them from
+//! [`GroupExecution::lookup_table`](crate::GroupExecution::lookup_table) and
+//! pass them to [`close_lookup_tables`] later, e.g. from a periodic cleanup
+//! job.
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use solana_address_lookup_table_interface::instruction as alt_instruction;
+use solana_sdk::{
+    clock::{Clock, Slot},
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    message::{AddressLookupTableAccount, Message},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    sysvar,
+    transaction::Transaction,
+};
+
+use crate::connection::SolanaConnection;
+use crate::SubmitError;
+
+/// Addresses added per extend transaction (keeps each under the packet size).
+const EXTEND_CHUNK_SIZE: usize = 20;
+
+/// How often to check whether a new table is usable.
+const ACTIVATION_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// How many times to check before giving up on activation.
+const ACTIVATION_POLL_ATTEMPTS: usize = 50;
+
+/// Whether `instructions`, together with compute budget instructions, are too
+/// large for a legacy transaction paid by `payer`.
+pub fn exceeds_legacy_transaction_size(instructions: &[Instruction], payer: &Pubkey) -> bool {
+    let mut with_budget = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(0),
+        ComputeBudgetInstruction::set_compute_unit_price(0),
+    ];
+    with_budget.extend_from_slice(instructions);
+    let message = Message::new(&with_budget, Some(payer));
+
+    // Signature count (compact-u16, one byte below 128) + signatures + message
+    let size = 1 + message.header.num_required_signatures as usize * 64 + message.serialize().len();
+    size > PACKET_DATA_SIZE
+}
+
+/// The accounts of `instructions` that can be loaded from a lookup table:
+/// everything except signers and invoked programs.
+pub fn lookup_table_addresses(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let mut addresses: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        let is_program = instructions.iter().any(|ix| ix.program_id == meta.pubkey);
+        if !meta.is_signer && !is_program && !addresses.contains(&meta.pubkey) {
+            addresses.push(meta.pubkey);
+        }
+    }
+    addresses
+}
+
+/// Create a lookup table holding `addresses` with `authority` as authority
+/// and payer, and wait until it can be used.
+///
+/// If the table can't be filled or doesn't activate, it is deactivated
+/// again (see [`discard_lookup_table`]) before the error is returned.
+pub fn create_lookup_table<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    addresses: &[Pubkey],
+) -> Result<AddressLookupTableAccount, SubmitError> {
+    let (create_ix, key) = alt_instruction::create_lookup_table(
+        authority.pubkey(),
+        authority.pubkey(),
+        current_slot(conn)?,
+    );
+    let signature = send(conn, authority, create_ix)?;
+    log::info!("Created address lookup table {} ({})", key, signature);
+
+    match extend_and_activate(conn, authority, &key, addresses) {
+        Ok(()) => Ok(AddressLookupTableAccount {
+            key,
+            addresses: addresses.to_vec(),
+        }),
+        Err(e) => {
+            discard_lookup_table(conn, authority, &key);
+            Err(e)
+        }
+    }
+}
+
+/// Add `addresses` to the table and wait until they can be used.
+fn extend_and_activate<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    key: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<(), SubmitError> {
+    for chunk in addresses.chunks(EXTEND_CHUNK_SIZE) {
+        let extend_ix = alt_instruction::extend_lookup_table(
+            *key,
+            authority.pubkey(),
+            Some(authority.pubkey()),
+            chunk.to_vec(),
+        );
+        send(conn, authority, extend_ix)?;
+    }
+
+    // Addresses become usable in the slot after they were added.
+    let extended_slot = current_slot(conn)?;
+    for _ in 0..ACTIVATION_POLL_ATTEMPTS {
+        conn.advance_slot()
+            .map_err(|e| SubmitError::Connection(e.to_string()))?;
+        if current_slot(conn)? > extended_slot {
+            return Ok(());
+        }
+        sleep(ACTIVATION_POLL_INTERVAL);
+    }
+
+    Err(SubmitError::Execution(format!(
+        "Address lookup table {} did not activate (slot stuck at {})",
+        key, extended_slot
+    )))
+}
+
+/// Deactivate a table that won't be used and try to close it, returning its
+/// rent to `authority`.
+///
+/// Closing only succeeds once the deactivation has cooled down, so on a live
+/// cluster the table is usually left deactivated; failures are logged, and
+/// the table can be closed later with [`close_lookup_table`].
+pub fn discard_lookup_table<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    table: &Pubkey,
+) {
+    if let Err(e) = deactivate_lookup_table(conn, authority, table) {
+        log::warn!("failed to deactivate address lookup table {}: {}", table, e);
+        return;
+    }
+    match close_lookup_table(conn, authority, table, &authority.pubkey()) {
+        Ok(_) => log::info!("Closed address lookup table {}", table),
+        Err(e) => log::warn!(
+            "Deactivated address lookup table {}, but could not close it yet ({}); \
+             close it after the cool-down",
+            table,
+            e
+        ),
+    }
+}
+
+/// Deactivate a lookup table so it can later be closed.
+pub fn deactivate_lookup_table<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    table: &Pubkey,
+) -> Result<Signature, SubmitError> {
+    send(
+        conn,
+        authority,
+        alt_instruction::deactivate_lookup_table(*table, authority.pubkey()),
+    )
+}
+
+/// Close a deactivated lookup table whose cool-down has passed, sending its
+/// rent to `recipient`.
+pub fn close_lookup_table<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    table: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Signature, SubmitError> {
+    send(
+        conn,
+        authority,
+        alt_instruction::close_lookup_table(*table, authority.pubkey(), *recipient),
+    )
+}
+
+/// Close each of the deactivated `tables`, sending their rent to
+/// `recipient`, and return those that couldn't be closed, usually because
+/// their cool-down hasn't passed yet. Failures are logged.
+pub fn close_lookup_tables<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    tables: &[Pubkey],
+    recipient: &Pubkey,
+) -> Vec<Pubkey> {
+    let mut open = Vec::new();
+    for table in tables {
+        match close_lookup_table(conn, authority, table, recipient) {
+            Ok(_) => log::info!("Closed address lookup table {}", table),
+            Err(e) => {
+                log::warn!("Could not close address lookup table {}: {}", table, e);
+                open.push(*table);
+            }
+        }
+    }
+    open
+}
+
+/// The current slot, from the clock sysvar.
+fn current_slot<C: SolanaConnection>(conn: &C) -> Result<Slot, SubmitError> {
+    let account = conn
+        .get_account(&sysvar::clock::ID)
+        .map_err(|e| SubmitError::Connection(e.to_string()))?
+        .ok_or_else(|| SubmitError::Connection("clock sysvar not found".to_string()))?;
+    solana_sdk::account::from_account::<Clock, _>(&account)
+        .map(|clock| clock.slot)
+        .ok_or_else(|| SubmitError::Connection("invalid clock sysvar".to_string()))
+}
+
+fn send<C: SolanaConnection>(
+    conn: &mut C,
+    authority: &dyn Signer,
+    ix: Instruction,
+) -> Result<Signature, SubmitError> {
+    let blockhash = conn
+        .get_latest_blockhash()
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        blockhash,
+    );
+    conn.send_and_confirm(&tx)
+        .map_err(|e| SubmitError::Connection(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockConnection, SendOutcome};
+    use solana_sdk::instruction::AccountMeta;
+
+    fn instruction(program_id: Pubkey, payer: Pubkey, accounts: usize) -> Instruction {
+        let mut metas = vec![AccountMeta::new(payer, true)];
+        metas.extend((0..accounts).map(|_| AccountMeta::new(Pubkey::new_unique(), false)));
+        Instruction {
+            program_id,
+            accounts: metas,
+            data: vec![0; 8],
+        }
+    }
+
+    #[test]
+    fn test_exceeds_legacy_transaction_size() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+
+        assert!(!exceeds_legacy_transaction_size(
+            &[instruction(program_id, payer, 10)],
+            &payer
+        ));
+        assert!(exceeds_legacy_transaction_size(
+            &[instruction(program_id, payer, 40)],
+            &payer
+        ));
+    }
+
+    #[test]
+    fn test_failed_table_is_deactivated_and_closed() {
+        // ProgramInstruction tags: create 0, extend 2, deactivate 3, close 4
+        let mut conn = MockConnection::new();
+        conn.on_send = Box::new(|tx| match tx.message.instructions()[0].data[0] {
+            2 => SendOutcome::Drop("extend failed".to_string()),
+            _ => SendOutcome::Confirm,
+        });
+        let authority = solana_sdk::signature::Keypair::new();
+
+        let result = create_lookup_table(&mut conn, &authority, &[Pubkey::new_unique()]);
+
+        assert!(result.is_err());
+        let tags: Vec<u8> = conn
+            .sent
+            .iter()
+            .map(|tx| tx.message.instructions()[0].data[0])
+            .collect();
+        assert_eq!(tags, vec![0, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_close_lookup_tables_returns_open_tables() {
+        let tables = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut conn = MockConnection::new();
+        let cooling = tables[1];
+        conn.on_send = Box::new(move |tx| {
+            if tx.message.static_account_keys().contains(&cooling) {
+                SendOutcome::Drop("table is not deactivated long enough".to_string())
+            } else {
+                SendOutcome::Confirm
+            }
+        });
+        let authority = solana_sdk::signature::Keypair::new();
+
+        let open = close_lookup_tables(&mut conn, &authority, &tables, &authority.pubkey());
+
+        assert_eq!(open, vec![tables[1]]);
+        assert_eq!(conn.sent.len(), 2);
+    }
+
+    #[test]
+    fn test_lookup_table_addresses() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let mut ix = instruction(program_id, payer, 2);
+        ix.accounts
+            .push(AccountMeta::new_readonly(program_id, false));
+        ix.accounts.push(ix.accounts[1].clone());
+
+        let addresses = lookup_table_addresses(&[ix.clone()]);
+
+        assert_eq!(
+            addresses,
+            vec![ix.accounts[1].pubkey, ix.accounts[2].pubkey]
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Posting Wormhole messages through the Post Message Shim.
+//!
+//! The shim doesn't collect the core bridge message fee itself: the fee must
+//! reach the fee collector earlier in the same transaction. [`post_message`]
+//! reads the current fee and prepends the transfer.
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    system_program, sysvar,
+    transaction::Transaction,
+};
+use wormhole_svm_definitions::MESSAGE_EVENT_DISCRIMINATOR;
+use wormhole_svm_shim::post_message::PostMessageShimInstruction;
+
+use crate::connection::SolanaConnection;
+use crate::cost::bridge_fee;
+use crate::network::WormholeNetwork;
+use crate::SubmitError;
+
+/// Consistency level of a posted message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Finality {
+    Confirmed,
+    Finalized,
+}
+
+impl Finality {
+    fn as_u8(self) -> u8 {
+        match self {
+            Finality::Confirmed => 0,
+            Finality::Finalized => 1,
+        }
+    }
+}
+
+/// Result of [`post_message`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostedMessage {
+    /// Signature of the posting transaction.
+    pub signature: Signature,
+    /// The emitter address.
+    pub emitter: Pubkey,
+    /// Sequence assigned to the message.
+    pub sequence: u64,
+}
+
+/// Tag of Anchor's event self-CPI instruction, `sha256("anchor:event")[..8]`
+/// in little-endian order, which precedes the event discriminator.
+pub const EVENT_CPI_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// Length of the event self-CPI data: the tag, the discriminator, then the
+/// emitter, sequence and submission time.
+const MESSAGE_EVENT_LEN: usize = 8 + 8 + 32 + 8 + 4;
+
+/// A `MessageEvent` emitted by the Post Message Shim (through a self-CPI)
+/// for each posted message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageEvent {
+    /// The emitter address.
+    pub emitter: Pubkey,
+    /// Sequence assigned to the message.
+    pub sequence: u64,
+    /// Unix timestamp at which the message was posted.
+    pub submission_time: u32,
+}
+
+impl MessageEvent {
+    /// Decode an instruction to `program_id` with `data`, if it is the event
+    /// self-CPI of `network`'s Post Message Shim. Returns `None` for any
+    /// other instruction.
+    pub fn parse(network: &WormholeNetwork, program_id: &Pubkey, data: &[u8]) -> Option<Self> {
+        if *program_id != network.post_message_shim {
+            return None;
+        }
+        Self::parse_data(data)
+    }
+
+    /// Decode the data of the shim's event self-CPI instruction: the event
+    /// CPI tag, the event discriminator, then the event fields (borsh).
+    /// Returns `None` for any other instruction data.
+    ///
+    /// Prefer [`MessageEvent::parse`] when the invoked program is known:
+    /// another program could emit the same bytes.
+    pub fn parse_data(data: &[u8]) -> Option<Self> {
+        if data.len() != MESSAGE_EVENT_LEN
+            || data[..8] != EVENT_CPI_TAG
+            || data[8..16] != MESSAGE_EVENT_DISCRIMINATOR
+        {
+            return None;
+        }
+        let event = &data[16..];
+        Some(Self {
+            emitter: Pubkey::new_from_array(event[..32].try_into().unwrap()),
+            sequence: u64::from_le_bytes(event[32..40].try_into().unwrap()),
+            submission_time: u32::from_le_bytes(event[40..44].try_into().unwrap()),
+        })
+    }
+}
+
+/// The arguments of a Post Message Shim `post_message` instruction, as built
+/// by [`build_post_message_ix`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostMessageArgs {
+    pub nonce: u32,
+    /// Consistency level: 0 for confirmed, 1 for finalized.
+    pub consistency_level: u8,
+    pub payload: Vec<u8>,
+}
+
+impl PostMessageArgs {
+    /// Decode `post_message` instruction data: the selector, then the nonce,
+    /// the consistency level and the payload (borsh). Returns `None` for any
+    /// other instruction data.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 17 || data[..8] != PostMessageShimInstruction::<u8>::POST_MESSAGE_SELECTOR {
+            return None;
+        }
+        let payload_len = u32::from_le_bytes(data[13..17].try_into().unwrap()) as usize;
+        Some(Self {
+            nonce: u32::from_le_bytes(data[8..12].try_into().unwrap()),
+            consistency_level: data[12],
+            payload: data.get(17..17usize.checked_add(payload_len)?)?.to_vec(),
+        })
+    }
+}
+
+/// Build a Post Message Shim `post_message` instruction. `emitter` must sign
+/// (directly, or through `invoke_signed` for a program PDA).
+pub fn build_post_message_ix(
+    network: &WormholeNetwork,
+    payer: &Pubkey,
+    emitter: &Pubkey,
+    nonce: u32,
+    finality: Finality,
+    payload: &[u8],
+) -> Instruction {
+    let mut data = Vec::with_capacity(8 + 4 + 1 + 4 + payload.len());
+    data.extend_from_slice(&PostMessageShimInstruction::<u8>::POST_MESSAGE_SELECTOR);
+    data.extend_from_slice(&nonce.to_le_bytes());
+    data.push(finality.as_u8());
+    data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    data.extend_from_slice(payload);
+
+    Instruction {
+        program_id: network.post_message_shim,
+        accounts: vec![
+            AccountMeta::new(network.core_bridge_config_address(), false),
+            AccountMeta::new(network.shim_message_address(emitter), false),
+            AccountMeta::new_readonly(*emitter, true),
+            AccountMeta::new(network.emitter_sequence_address(emitter), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(network.fee_collector_address(), false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(network.core_bridge, false),
+            AccountMeta::new_readonly(network.post_message_event_authority(), false),
+            AccountMeta::new_readonly(network.post_message_shim, false),
+        ],
+        data,
+    }
+}
+
+/// The sequence the next message of `emitter` will get.
+pub fn next_sequence<C: SolanaConnection>(
+    conn: &C,
+    network: &WormholeNetwork,
+    emitter: &Pubkey,
+) -> Result<u64, SubmitError> {
+    let account = conn
+        .get_account(&network.emitter_sequence_address(emitter))
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    Ok(account
+        .and_then(|account| {
+            let bytes = account.data.get(0..8)?;
+            Some(u64::from_le_bytes(bytes.try_into().unwrap()))
+        })
+        .unwrap_or(0))
+}
+
+/// Post a message from the `emitter` signer (which may be `payer` itself),
+/// paying the message fee and rent from `payer`.
+pub fn post_message<C: SolanaConnection>(
+    conn: &mut C,
+    network: &WormholeNetwork,
+    payer: &dyn Signer,
+    emitter: &dyn Signer,
+    nonce: u32,
+    finality: Finality,
+    payload: &[u8],
+) -> Result<PostedMessage, SubmitError> {
+    let sequence = next_sequence(conn, network, &emitter.pubkey())?;
+    let ixs = [
+        solana_sdk::system_instruction::transfer(
+            &payer.pubkey(),
+            &network.fee_collector_address(),
+            bridge_fee(conn, network)?,
+        ),
+        build_post_message_ix(
+            network,
+            &payer.pubkey(),
+            &emitter.pubkey(),
+            nonce,
+            finality,
+            payload,
+        ),
+    ];
+
+    let blockhash = conn
+        .get_latest_blockhash()
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    let mut signers = vec![payer];
+    if emitter.pubkey() != payer.pubkey() {
+        signers.push(emitter);
+    }
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &signers, blockhash);
+    let signature = conn
+        .send_and_confirm(&tx)
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+
+    Ok(PostedMessage {
+        signature,
+        emitter: emitter.pubkey(),
+        sequence,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_post_message_ix() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        let payer = Pubkey::new_unique();
+        let emitter = Pubkey::new_unique();
+
+        let ix = build_post_message_ix(&network, &payer, &emitter, 7, Finality::Finalized, &[9, 9]);
+
+        assert_eq!(ix.program_id, network.post_message_shim);
+        assert_eq!(ix.accounts.len(), 11);
+        assert!(ix.accounts[2].is_signer && ix.accounts[2].pubkey == emitter);
+        assert!(ix.accounts[4].is_signer && ix.accounts[4].pubkey == payer);
+        assert_eq!(&ix.data[8..12], &7u32.to_le_bytes());
+        assert_eq!(ix.data[12], 1);
+        assert_eq!(&ix.data[13..], &[2, 0, 0, 0, 9, 9]);
+    }
+
+    #[test]
+    fn test_parse_post_message_args() {
+        let ix = build_post_message_ix(
+            &WormholeNetwork::SOLANA_MAINNET,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            7,
+            Finality::Confirmed,
+            &[1, 2, 3],
+        );
+        assert_eq!(
+            PostMessageArgs::parse(&ix.data),
+            Some(PostMessageArgs {
+                nonce: 7,
+                consistency_level: 0,
+                payload: vec![1, 2, 3],
+            })
+        );
+        assert_eq!(PostMessageArgs::parse(&ix.data[..19]), None);
+        assert_eq!(PostMessageArgs::parse(&ix.data[8..]), None);
+    }
+
+    fn message_event_data(emitter: &Pubkey) -> Vec<u8> {
+        let mut data = EVENT_CPI_TAG.to_vec();
+        data.extend_from_slice(&MESSAGE_EVENT_DISCRIMINATOR);
+        data.extend_from_slice(emitter.as_ref());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_message_event() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        let emitter = Pubkey::new_unique();
+        let data = message_event_data(&emitter);
+
+        assert_eq!(
+            MessageEvent::parse(&network, &network.post_message_shim, &data),
+            Some(MessageEvent {
+                emitter,
+                sequence: 42,
+                submission_time: 1_700_000_000,
+            })
+        );
+        assert_eq!(MessageEvent::parse_data(&data[..59]), None);
+        let mut trailing = data.clone();
+        trailing.push(0);
+        assert_eq!(MessageEvent::parse_data(&trailing), None);
+        for byte in [0, 8] {
+            let mut corrupted = data.clone();
+            corrupted[byte] ^= 1;
+            assert_eq!(MessageEvent::parse_data(&corrupted), None);
+        }
+    }
+
+    #[test]
+    fn test_message_event_from_unrelated_program_is_rejected() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        let data = message_event_data(&Pubkey::new_unique());
+
+        assert!(MessageEvent::parse_data(&data).is_some());
+        assert_eq!(
+            MessageEvent::parse(&network, &Pubkey::new_unique(), &data),
+            None
+        );
+        let other_shim = WormholeNetwork::new(
+            network.chain_id,
+            network.core_bridge,
+            network.verify_vaa_shim,
+            Pubkey::new_unique(),
+        );
+        assert_eq!(
+            MessageEvent::parse(&other_shim, &network.post_message_shim, &data),
+            None
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Export of resolved instruction groups as multisig vault transactions.
+//!
+//! Lets a protocol redeem a VAA (e.g. a governance VAA) through a Squads
+//! multisig instead of a hot key. The multisig vault is substituted for the
+//! payer placeholder, and each instruction group becomes one Squads v4
+//! `TransactionMessage`, the `transaction_message` argument of the
+//! `vault_transaction_create` instruction.
+//!
+//! Typical flow:
+//! 1. Resolve with the vault as payer, e.g. via
+//!    [`resolve_execute_vaa_v1`](crate::resolve::resolve_execute_vaa_v1) with
+//!    `NullSigner::new(&vault)`
+//! 2. Post guardian signatures with any hot key
+//!    ([`post_signatures`](crate::signatures::post_signatures))
+//! 3. [`export_multisig_transactions`] and propose/approve/execute them in Squads
+//! 4. Close the signatures account with the hot key
+
+use base64::Engine;
+use solana_sdk::{instruction::Instruction, message::Message, pubkey::Pubkey};
+
+use crate::execute::{convert_instruction, KEYPAIR_PLACEHOLDERS};
+use crate::resolve::InstructionGroup;
+use crate::SubmitError;
+
+/// One instruction group ready to import as a multisig vault transaction.
+pub struct MultisigTransaction {
+    /// The instructions with all placeholders substituted.
+    pub instructions: Vec<Instruction>,
+    /// Squads v4 `TransactionMessage` with the vault as fee payer,
+    /// base58-encoded.
+    pub message_base58: String,
+    /// The same `TransactionMessage`, base64-encoded.
+    pub message_base64: String,
+}
+
+/// Substitute placeholders in resolved instruction groups with `vault` as the
+/// payer and serialize one Squads v4 `TransactionMessage` per group.
+///
+/// A `TransactionMessage` has no blockhash: the transaction that executes
+/// the vault transaction brings its own.
+///
+/// `placeholders` are additional `(placeholder, pubkey)` substitutions, as in
+/// [`ExecuteConfig::placeholders`](crate::ExecuteConfig::placeholders).
+///
+/// Fails if any group uses a `RESOLVER_PUBKEY_KEYPAIR_*` placeholder, since a
+/// vault transaction can't be co-signed by freshly generated keypairs.
+pub fn export_multisig_transactions(
+    groups: &[InstructionGroup],
+    vault: &Pubkey,
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+) -> Result<Vec<MultisigTransaction>, SubmitError> {
+    groups
+        .iter()
+        .enumerate()
+        .map(|(group_index, group)| {
+            let uses_keypair = group.instructions.iter().any(|ix| {
+                ix.accounts
+                    .iter()
+                    .any(|a| KEYPAIR_PLACEHOLDERS.contains(&a.pubkey))
+            });
+            if uses_keypair {
+                return Err(SubmitError::Execution(format!(
+                    "Instruction group {} requires a generated keypair signer, which a multisig \
+                     vault transaction cannot provide",
+                    group_index
+                )));
+            }
+
+            let instructions: Vec<Instruction> = group
+                .instructions
+                .iter()
+                .map(|si| {
+                    convert_instruction(si, vault, signatures_pubkey, guardian_set, placeholders)
+                })
+                .collect();
+
+            let serialized = squads_transaction_message(&Message::new(&instructions, Some(vault)))
+                .map_err(|e| {
+                    SubmitError::Execution(format!("Instruction group {}: {}", group_index, e))
+                })?;
+
+            Ok(MultisigTransaction {
+                instructions,
+                message_base58: bs58::encode(&serialized).into_string(),
+                message_base64: base64::engine::general_purpose::STANDARD.encode(&serialized),
+            })
+        })
+        .collect()
+}
+
+/// Serialize `message` as a Squads v4 `TransactionMessage`: the signer and
+/// writable counts, then the account keys, the instructions and the address
+/// table lookups (none for a legacy message). Collections have a `u8`
+/// length prefix, except instruction data which has a little-endian `u16`.
+fn squads_transaction_message(message: &Message) -> Result<Vec<u8>, String> {
+    fn len<T: TryFrom<usize>>(len: usize, what: &str) -> Result<T, String> {
+        T::try_from(len).map_err(|_| format!("too many {} for a vault transaction", what))
+    }
+
+    let header = &message.header;
+    let num_keys = message.account_keys.len();
+    let mut bytes = vec![
+        header.num_required_signatures,
+        header.num_required_signatures - header.num_readonly_signed_accounts,
+        len::<u8>(
+            num_keys
+                - usize::from(header.num_required_signatures)
+                - usize::from(header.num_readonly_unsigned_accounts),
+            "accounts",
+        )?,
+    ];
+    bytes.push(len::<u8>(num_keys, "accounts")?);
+    for key in &message.account_keys {
+        bytes.extend_from_slice(key.as_ref());
+    }
+    bytes.push(len::<u8>(message.instructions.len(), "instructions")?);
+    for ix in &message.instructions {
+        bytes.push(ix.program_id_index);
+        bytes.push(len::<u8>(ix.accounts.len(), "instruction accounts")?);
+        bytes.extend_from_slice(&ix.accounts);
+        bytes.extend_from_slice(
+            &len::<u16>(ix.data.len(), "bytes of instruction data")?.to_le_bytes(),
+        );
+        bytes.extend_from_slice(&ix.data);
+    }
+    // No address table lookups.
+    bytes.push(0);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::group;
+    use crate::{RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_SHIM_VAA_SIGS};
+    use executor_account_resolver_svm::RESOLVER_PUBKEY_KEYPAIR_00;
+
+    #[test]
+    fn test_vault_substituted_as_payer() {
+        let vault = Pubkey::new_unique();
+        let signatures = Pubkey::new_unique();
+        let groups = [group(
+            Pubkey::new_unique(),
+            &[
+                (RESOLVER_PUBKEY_PAYER, true, true),
+                (RESOLVER_PUBKEY_SHIM_VAA_SIGS, true, false),
+            ],
+        )];
+
+        let exported =
+            export_multisig_transactions(&groups, &vault, &signatures, &Pubkey::new_unique(), &[])
+                .unwrap();
+
+        assert_eq!(exported.len(), 1);
+        let accounts = &exported[0].instructions[0].accounts;
+        assert_eq!(accounts[0].pubkey, vault);
+        assert_eq!(accounts[1].pubkey, signatures);
+
+        let program_id = exported[0].instructions[0].program_id;
+        let mut expected = vec![1, 1, 1, 3];
+        for key in [vault, signatures, program_id] {
+            expected.extend_from_slice(key.as_ref());
+        }
+        // One instruction: program, its two accounts, no data; no lookups.
+        expected.extend_from_slice(&[1, 2, 2, 0, 1, 0, 0, 0]);
+        assert_eq!(
+            bs58::decode(&exported[0].message_base58)
+                .into_vec()
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            base64::engine::general_purpose::STANDARD
+                .decode(&exported[0].message_base64)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_rejects_generated_keypairs() {
+        let groups = [group(
+            Pubkey::new_unique(),
+            &[
+                (RESOLVER_PUBKEY_PAYER, true, true),
+                (RESOLVER_PUBKEY_KEYPAIR_00, true, false),
+            ],
+        )];
+
+        let result = export_multisig_transactions(
+            &groups,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &[],
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_custom_placeholder_substituted() {
+        let custom = Pubkey::new_unique();
+        let value = Pubkey::new_unique();
+        let groups = [group(
+            Pubkey::new_unique(),
+            &[(RESOLVER_PUBKEY_PAYER, true, true), (custom, false, false)],
+        )];
+
+        let exported = export_multisig_transactions(
+            &groups,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &[(custom, value)],
+        )
+        .unwrap();
+
+        assert_eq!(exported[0].instructions[0].accounts[1].pubkey, value);
+    }
+}
@@ -0,0 +1,123 @@
+//! Wormhole deployment addresses for SVM networks.
+//!
+//! Solana mainnet and devnet are provided as presets. Other SVM chains
+//! (Pythnet, Fogo, ...) run the same programs at different addresses; describe
+//! them with [`WormholeNetwork::new`].
+
+use solana_sdk::pubkey::Pubkey;
+use wormhole_svm_definitions::{
+    find_emitter_sequence_address, find_event_authority_address, find_guardian_set_address,
+    find_shim_message_address, solana,
+};
+
+/// Wormhole chain ID of Solana.
+pub const CHAIN_ID_SOLANA: u16 = 1;
+
+/// Wormhole chain ID of Pythnet.
+pub const CHAIN_ID_PYTHNET: u16 = 26;
+
+/// Program addresses of a Wormhole deployment on an SVM network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WormholeNetwork {
+    /// Wormhole chain ID of the network (not the SVM cluster).
+    pub chain_id: u16,
+    /// Wormhole Core Bridge program ID.
+    pub core_bridge: Pubkey,
+    /// Wormhole Verify VAA Shim program ID.
+    pub verify_vaa_shim: Pubkey,
+    /// Wormhole Post Message Shim program ID.
+    pub post_message_shim: Pubkey,
+}
+
+impl WormholeNetwork {
+    /// Solana mainnet-beta.
+    pub const SOLANA_MAINNET: Self = Self {
+        chain_id: CHAIN_ID_SOLANA,
+        core_bridge: solana::mainnet::CORE_BRIDGE_PROGRAM_ID,
+        verify_vaa_shim: solana::mainnet::VERIFY_VAA_SHIM_PROGRAM_ID,
+        post_message_shim: solana::mainnet::POST_MESSAGE_SHIM_PROGRAM_ID,
+    };
+
+    /// Solana devnet.
+    ///
+    /// The shims are deployed at the same addresses as on mainnet; only the
+    /// core bridge differs.
+    pub const SOLANA_DEVNET: Self = Self {
+        chain_id: CHAIN_ID_SOLANA,
+        core_bridge: solana::devnet::CORE_BRIDGE_PROGRAM_ID,
+        verify_vaa_shim: solana::mainnet::VERIFY_VAA_SHIM_PROGRAM_ID,
+        post_message_shim: solana::mainnet::POST_MESSAGE_SHIM_PROGRAM_ID,
+    };
+
+    /// Describe a custom deployment.
+    pub const fn new(
+        chain_id: u16,
+        core_bridge: Pubkey,
+        verify_vaa_shim: Pubkey,
+        post_message_shim: Pubkey,
+    ) -> Self {
+        Self {
+            chain_id,
+            core_bridge,
+            verify_vaa_shim,
+            post_message_shim,
+        }
+    }
+
+    /// Derive the guardian set PDA for `index` under this network's core bridge.
+    pub fn guardian_set_address(&self, index: u32) -> Pubkey {
+        find_guardian_set_address(index.to_be_bytes(), &self.core_bridge).0
+    }
+
+    /// Derive the core bridge config PDA, which holds the current guardian
+    /// set index.
+    pub fn core_bridge_config_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"Bridge"], &self.core_bridge).0
+    }
+
+    /// Derive the core bridge fee collector PDA, a system account receiving
+    /// message fees.
+    pub fn fee_collector_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"fee_collector"], &self.core_bridge).0
+    }
+
+    /// Derive the core bridge sequence PDA of `emitter`, which holds its next
+    /// message sequence.
+    pub fn emitter_sequence_address(&self, emitter: &Pubkey) -> Pubkey {
+        find_emitter_sequence_address(emitter, &self.core_bridge).0
+    }
+
+    /// Derive the Post Message Shim message PDA of `emitter`.
+    pub fn shim_message_address(&self, emitter: &Pubkey) -> Pubkey {
+        find_shim_message_address(emitter, &self.post_message_shim).0
+    }
+
+    /// Derive the Post Message Shim event authority PDA.
+    pub fn post_message_event_authority(&self) -> Pubkey {
+        find_event_authority_address(&self.post_message_shim).0
+    }
+}
+
+impl Default for WormholeNetwork {
+    fn default() -> Self {
+        Self::SOLANA_MAINNET
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_bridge_addresses() {
+        let network = WormholeNetwork::SOLANA_MAINNET;
+        assert_eq!(
+            network.core_bridge_config_address(),
+            solana::mainnet::CORE_BRIDGE_CONFIG
+        );
+        assert_eq!(
+            network.fee_collector_address(),
+            solana::mainnet::CORE_BRIDGE_FEE_COLLECTOR
+        );
+    }
+}
@@ -0,0 +1,467 @@
+//! Offline export of unsigned transactions.
+//!
+//! Runs resolution and placeholder substitution like [`broadcast_vaa`](crate::broadcast_vaa),
+//! but returns the transactions instead of sending them, so they can be signed
+//! on a cold wallet or air-gapped machine and broadcast later.
+//!
+//! Keypairs generated locally (the guardian signatures account and
+//! `RESOLVER_PUBKEY_KEYPAIR_*` placeholders) sign before export; only the
+//! remaining signers are listed in [`UnsignedTransaction::required_signers`].
+//!
+//! The transactions use a recent blockhash, so they expire after about a
+//! minute. With [`export_broadcast_with_nonces`], each uses a durable nonce
+//! instead and stays valid until its nonce account is advanced.
+
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    signer::null_signer::NullSigner,
+    transaction::Transaction,
+};
+
+use crate::connection::SolanaConnection;
+use crate::execute::substitute_instruction_groups;
+use crate::network::WormholeNetwork;
+use crate::resolve::InstructionGroup;
+use crate::signatures::{build_close_signatures_ix, build_post_signatures_ix};
+use crate::SubmitError;
+
+/// A transaction awaiting signatures from keys that aren't available locally.
+pub struct UnsignedTransaction {
+    /// The transaction, partially signed by any locally generated keypairs.
+    ///
+    /// Sign with [`Transaction::partial_sign`] using the same recent blockhash;
+    /// a different blockhash discards the existing signatures.
+    pub transaction: Transaction,
+    /// Pubkeys that still need to sign, in message order.
+    pub required_signers: Vec<Pubkey>,
+    /// The durable nonce account the transaction advances, if it uses one
+    /// instead of a recent blockhash.
+    pub nonce_account: Option<Pubkey>,
+}
+
+/// A durable nonce, used as the blockhash of a transaction whose first
+/// instruction advances it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurableNonce {
+    /// The nonce account.
+    pub account: Pubkey,
+    /// The account's nonce authority, which must sign the transaction.
+    pub authority: Pubkey,
+    /// The nonce currently stored in the account.
+    pub nonce: Hash,
+}
+
+impl UnsignedTransaction {
+    fn new(
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        local_signers: &[&Keypair],
+        blockhash: Hash,
+        nonce: Option<&DurableNonce>,
+    ) -> Self {
+        let mut transaction = match nonce {
+            Some(nonce) => {
+                let advance = solana_sdk::system_instruction::advance_nonce_account(
+                    &nonce.account,
+                    &nonce.authority,
+                );
+                let instructions: Vec<Instruction> = std::iter::once(advance)
+                    .chain(instructions.iter().cloned())
+                    .collect();
+                Transaction::new_with_payer(&instructions, Some(payer))
+            }
+            None => Transaction::new_with_payer(instructions, Some(payer)),
+        };
+        let blockhash = nonce.map_or(blockhash, |nonce| nonce.nonce);
+        transaction.message.recent_blockhash = blockhash;
+
+        let signer_keys = signer_keys(&transaction);
+        let local_signers: Vec<&Keypair> = local_signers
+            .iter()
+            .copied()
+            .filter(|kp| signer_keys.contains(&kp.pubkey()))
+            .collect();
+        transaction.partial_sign(&local_signers, blockhash);
+
+        let required_signers = signer_keys
+            .iter()
+            .zip(&transaction.signatures)
+            .filter(|(_, sig)| **sig == Signature::default())
+            .map(|(pubkey, _)| *pubkey)
+            .collect();
+
+        Self {
+            transaction,
+            required_signers,
+            nonce_account: nonce.map(|nonce| nonce.account),
+        }
+    }
+}
+
+fn signer_keys(transaction: &Transaction) -> Vec<Pubkey> {
+    let num_signers = transaction.message.header.num_required_signatures as usize;
+    transaction.message.account_keys[..num_signers].to_vec()
+}
+
+/// The complete broadcast flow as unsigned transactions.
+pub struct OfflineBroadcast {
+    /// The guardian signatures account created by the first transaction.
+    pub signatures_pubkey: Pubkey,
+    /// How many resolver iterations resolution took.
+    pub resolver_iterations: usize,
+    /// Transactions in submission order: post signatures, one per resolved
+    /// instruction group, then close signatures.
+    pub transactions: Vec<UnsignedTransaction>,
+}
+
+/// Resolve a VAA and export the broadcast flow as unsigned transactions.
+///
+/// Resolution only simulates, so only the payer's pubkey is needed. All
+/// transactions use the connection's latest blockhash and must be signed and
+/// submitted before it expires.
+///
+/// `placeholders` are additional `(placeholder, pubkey)` substitutions, used
+/// during resolution and substitution as in
+/// [`ExecuteConfig::placeholders`](crate::ExecuteConfig::placeholders).
+#[allow(clippy::too_many_arguments)]
+pub fn export_broadcast<C: SolanaConnection>(
+    conn: &C,
+    payer: &Pubkey,
+    program_id: &Pubkey,
+    guardian_set_index: u32,
+    vaa_body: &[u8],
+    guardian_signatures: &[[u8; 66]],
+    network: &WormholeNetwork,
+    placeholders: &[(Pubkey, Pubkey)],
+) -> Result<OfflineBroadcast, SubmitError> {
+    export_broadcast_with_nonces(
+        conn,
+        payer,
+        program_id,
+        guardian_set_index,
+        vaa_body,
+        guardian_signatures,
+        network,
+        placeholders,
+        &[],
+    )
+}
+
+/// Like [`export_broadcast`], but each transaction uses one of `nonces`, in
+/// order, instead of the latest blockhash, so they don't expire.
+///
+/// Nonces are consumed one per transaction: post signatures, each
+/// instruction group, then close signatures. Fails without exporting
+/// anything if there are fewer nonces than transactions. With no nonces,
+/// this is [`export_broadcast`].
+#[allow(clippy::too_many_arguments)]
+pub fn export_broadcast_with_nonces<C: SolanaConnection>(
+    conn: &C,
+    payer: &Pubkey,
+    program_id: &Pubkey,
+    guardian_set_index: u32,
+    vaa_body: &[u8],
+    guardian_signatures: &[[u8; 66]],
+    network: &WormholeNetwork,
+    placeholders: &[(Pubkey, Pubkey)],
+    nonces: &[DurableNonce],
+) -> Result<OfflineBroadcast, SubmitError> {
+    let guardian_set = network.guardian_set_address(guardian_set_index);
+
+    let resolved = crate::resolve::resolve_execute_vaa_v1_with_placeholders(
+        conn,
+        program_id,
+        &NullSigner::new(payer),
+        vaa_body,
+        &guardian_set,
+        crate::MAX_RESOLVER_ITERATIONS,
+        placeholders,
+    )?;
+    crate::ensure_uses_verify_vaa_shim(&resolved.instruction_groups)?;
+
+    let count = resolved.instruction_groups.len() + 2;
+    if !nonces.is_empty() && nonces.len() < count {
+        return Err(SubmitError::Execution(format!(
+            "The broadcast has {} transactions, but only {} durable nonces were given",
+            count,
+            nonces.len()
+        )));
+    }
+
+    let blockhash = conn
+        .get_latest_blockhash()
+        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+
+    let signatures_keypair = Keypair::new();
+    let signatures_pubkey = signatures_keypair.pubkey();
+
+    let mut transactions = Vec::with_capacity(resolved.instruction_groups.len() + 2);
+
+    let post_ix = build_post_signatures_ix(
+        payer,
+        &signatures_pubkey,
+        &network.verify_vaa_shim,
+        guardian_set_index,
+        guardian_signatures,
+    );
+    transactions.push(UnsignedTransaction::new(
+        &[post_ix],
+        payer,
+        &[&signatures_keypair],
+        blockhash,
+        nonces.first(),
+    ));
+
+    transactions.extend(group_transactions(
+        &resolved.instruction_groups,
+        payer,
+        &signatures_pubkey,
+        &guardian_set,
+        placeholders,
+        blockhash,
+        nonces.get(1..).unwrap_or_default(),
+    ));
+
+    let close_ix = build_close_signatures_ix(&network.verify_vaa_shim, &signatures_pubkey, payer);
+    transactions.push(UnsignedTransaction::new(
+        &[close_ix],
+        payer,
+        &[],
+        blockhash,
+        nonces.get(count - 1),
+    ));
+
+    Ok(OfflineBroadcast {
+        signatures_pubkey,
+        resolver_iterations: resolved.iterations,
+        transactions,
+    })
+}
+
+/// Substitute placeholders in resolved instruction groups and build one
+/// unsigned transaction per group, without sending anything.
+///
+/// Placeholder keypairs are generated here and sign immediately; they are not
+/// returned, since nothing else needs to sign with them. `placeholders` are
+/// the additional substitutions, as in
+/// [`ExecuteConfig::placeholders`](crate::ExecuteConfig::placeholders).
+pub fn build_unsigned_transactions(
+    groups: &[InstructionGroup],
+    payer: &Pubkey,
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+    blockhash: Hash,
+) -> Vec<UnsignedTransaction> {
+    group_transactions(
+        groups,
+        payer,
+        signatures_pubkey,
+        guardian_set,
+        placeholders,
+        blockhash,
+        &[],
+    )
+}
+
+/// [`build_unsigned_transactions`], with the `i`th group using `nonces[i]`
+/// if there is one.
+fn group_transactions(
+    groups: &[InstructionGroup],
+    payer: &Pubkey,
+    signatures_pubkey: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+    blockhash: Hash,
+    nonces: &[DurableNonce],
+) -> Vec<UnsignedTransaction> {
+    let substituted =
+        substitute_instruction_groups(groups, payer, signatures_pubkey, guardian_set, placeholders);
+    let local_signers: Vec<&Keypair> = substituted.keypairs.iter().collect();
+
+    substituted
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(index, instructions)| {
+            UnsignedTransaction::new(
+                instructions,
+                payer,
+                &local_signers,
+                blockhash,
+                nonces.get(index),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use executor_account_resolver_svm::{
+        RESOLVER_PUBKEY_GUARDIAN_SET, RESOLVER_PUBKEY_KEYPAIR_00, RESOLVER_PUBKEY_PAYER,
+        RESOLVER_PUBKEY_SHIM_VAA_SIGS,
+    };
+    use solana_sdk::system_program;
+
+    use crate::test_support::{group, MockConnection};
+
+    fn resolving_connection() -> MockConnection {
+        let mut conn = MockConnection::new();
+        conn.resolve_to(vec![group(
+            Pubkey::new_unique(),
+            &[
+                (RESOLVER_PUBKEY_PAYER, true, true),
+                (RESOLVER_PUBKEY_KEYPAIR_00, true, true),
+                (RESOLVER_PUBKEY_SHIM_VAA_SIGS, false, false),
+                (RESOLVER_PUBKEY_GUARDIAN_SET, false, false),
+            ],
+        )]);
+        conn
+    }
+
+    #[test]
+    fn test_export_broadcast() {
+        let conn = resolving_connection();
+        let payer = Pubkey::new_unique();
+        let network = WormholeNetwork::SOLANA_DEVNET;
+
+        let exported = export_broadcast(
+            &conn,
+            &payer,
+            &Pubkey::new_unique(),
+            0,
+            b"body",
+            &[[0; 66]],
+            &network,
+            &[],
+        )
+        .unwrap();
+
+        // Nothing is sent; everything waits for the payer's signature
+        assert!(conn.sent.is_empty());
+        assert_eq!(exported.resolver_iterations, 1);
+        assert_eq!(exported.transactions.len(), 3);
+        for unsigned in &exported.transactions {
+            let message = &unsigned.transaction.message;
+            assert_eq!(message.recent_blockhash, conn.blockhash);
+            assert_eq!(message.account_keys[0], payer);
+            assert_eq!(unsigned.required_signers, vec![payer]);
+            assert_eq!(unsigned.nonce_account, None);
+            let program_index = message.instructions[0].program_id_index as usize;
+            let program_id = message.account_keys[program_index];
+            assert!(message.account_keys.contains(&exported.signatures_pubkey));
+            if program_id != network.verify_vaa_shim {
+                assert!(message
+                    .account_keys
+                    .contains(&network.guardian_set_address(0)));
+            }
+        }
+
+        // The signatures account and placeholder keypairs have signed
+        let post = &exported.transactions[0].transaction;
+        assert_eq!(post.message.account_keys[1], exported.signatures_pubkey);
+        assert_ne!(post.signatures[1], Signature::default());
+        let execute = &exported.transactions[1].transaction;
+        assert_eq!(execute.message.header.num_required_signatures, 2);
+        assert_ne!(execute.signatures[1], Signature::default());
+    }
+
+    #[test]
+    fn test_export_needs_a_nonce_per_transaction() {
+        let conn = resolving_connection();
+        let nonce = || DurableNonce {
+            account: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            nonce: Hash::new_unique(),
+        };
+        let export = |nonces: &[DurableNonce]| {
+            export_broadcast_with_nonces(
+                &conn,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                0,
+                b"body",
+                &[[0; 66]],
+                &WormholeNetwork::SOLANA_DEVNET,
+                &[],
+                nonces,
+            )
+        };
+
+        assert!(matches!(
+            export(&[nonce(), nonce()]),
+            Err(SubmitError::Execution(_))
+        ));
+
+        let nonces = [nonce(), nonce(), nonce()];
+        let exported = export(&nonces).unwrap();
+        for (unsigned, nonce) in exported.transactions.iter().zip(&nonces) {
+            assert_eq!(unsigned.transaction.message.recent_blockhash, nonce.nonce);
+            assert_eq!(unsigned.nonce_account, Some(nonce.account));
+        }
+    }
+
+    #[test]
+    fn test_export_substitutes_custom_placeholders() {
+        let custom = Pubkey::new_unique();
+        let value = Pubkey::new_unique();
+        let mut conn = MockConnection::new();
+        conn.resolve_to(vec![group(
+            Pubkey::new_unique(),
+            &[
+                (RESOLVER_PUBKEY_PAYER, true, true),
+                (RESOLVER_PUBKEY_SHIM_VAA_SIGS, false, false),
+                (custom, false, false),
+            ],
+        )]);
+
+        let exported = export_broadcast(
+            &conn,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            0,
+            b"body",
+            &[[0; 66]],
+            &WormholeNetwork::SOLANA_DEVNET,
+            &[(custom, value)],
+        )
+        .unwrap();
+
+        let keys = &exported.transactions[1].transaction.message.account_keys;
+        assert!(keys.contains(&value));
+        assert!(!keys.contains(&custom));
+    }
+
+    #[test]
+    fn test_durable_nonce_replaces_blockhash() {
+        let payer = Pubkey::new_unique();
+        let nonce = DurableNonce {
+            account: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            nonce: Hash::new_unique(),
+        };
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![]);
+
+        let unsigned =
+            UnsignedTransaction::new(&[ix], &payer, &[], Hash::new_unique(), Some(&nonce));
+
+        let message = &unsigned.transaction.message;
+        assert_eq!(message.recent_blockhash, nonce.nonce);
+        assert_eq!(message.instructions.len(), 2);
+        let advance = &message.instructions[0];
+        assert_eq!(
+            message.account_keys[advance.program_id_index as usize],
+            system_program::ID
+        );
+        assert_eq!(
+            message.account_keys[advance.accounts[0] as usize],
+            nonce.account
+        );
+        assert_eq!(unsigned.required_signers, vec![payer, nonce.authority]);
+        assert_eq!(unsigned.nonce_account, Some(nonce.account));
+    }
+}
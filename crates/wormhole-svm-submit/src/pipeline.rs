@@ -0,0 +1,353 @@
+//! Async submission of a stream of signed VAAs.
+//!
+//! [`broadcast_stream`] consumes a [`Stream`] of signed VAAs (e.g. from a
+//! guardian spy subscription), runs up to a configurable number of broadcasts
+//! at once and yields one [`PipelineResult`] per VAA as broadcasts complete.
+//! VAAs from the same emitter are submitted one at a time in arrival order,
+//! since protocols commonly require sequences to be redeemed in order.
+//!
+//! At most [`PipelineConfig::max_queued`] received VAAs wait for a slot; the
+//! input stream isn't polled while that buffer is full, so a source that
+//! produces faster than the cluster accepts is slowed down instead of piling
+//! up VAAs in memory.
+//!
+//! Broadcasts are blocking, so each one runs on the tokio blocking thread
+//! pool; the stream must be polled inside a tokio runtime.
+//!
+//! Requires the `pipeline` feature.
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::future::{self, Either};
+use futures::stream::{self, FuturesUnordered, Stream, StreamExt};
+use solana_sdk::signature::Signature;
+
+use crate::vaa::{SignedVaa, VaaBody};
+use crate::SubmitError;
+
+/// Options for [`broadcast_stream`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PipelineConfig {
+    /// Maximum number of broadcasts in flight.
+    pub concurrency: usize,
+    /// Submit VAAs of the same emitter one at a time, in arrival order.
+    pub order_per_emitter: bool,
+    /// Maximum number of received VAAs waiting to start. The input stream
+    /// isn't polled while this many are waiting.
+    pub max_queued: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            order_per_emitter: true,
+            max_queued: 64,
+        }
+    }
+}
+
+/// Outcome of submitting one VAA.
+#[derive(Debug)]
+pub struct PipelineResult {
+    /// The signed VAA as received.
+    pub vaa: Vec<u8>,
+    /// Transaction signatures of the broadcast, or why it failed.
+    pub result: Result<Vec<Signature>, SubmitError>,
+}
+
+/// Emitter chain and address.
+type EmitterKey = (u16, [u8; 32]);
+
+type Task = Pin<Box<dyn Future<Output = (Option<EmitterKey>, PipelineResult)> + Send>>;
+
+struct State<S, F> {
+    vaas: Pin<Box<S>>,
+    vaas_done: bool,
+    submit: Arc<F>,
+    config: PipelineConfig,
+    /// VAAs waiting for a free slot (or for their emitter), in arrival order.
+    queued: VecDeque<(Option<EmitterKey>, Vec<u8>)>,
+    /// Emitters with a broadcast in flight.
+    busy: HashSet<EmitterKey>,
+    running: FuturesUnordered<Task>,
+    /// Results that don't need a broadcast (unparseable VAAs).
+    ready: VecDeque<PipelineResult>,
+}
+
+/// Submit each signed VAA of `vaas` with `submit` and yield the results as
+/// they complete.
+///
+/// `submit` is called on the blocking thread pool with the signed VAA bytes,
+/// e.g. a closure around [`broadcast_vaa_signed`](crate::broadcast_vaa_signed)
+/// or [`rpc_broadcaster`]. VAAs that can't be parsed are reported as
+/// [`SubmitError::InvalidVaa`] without calling `submit`.
+pub fn broadcast_stream<S, F>(
+    vaas: S,
+    config: PipelineConfig,
+    submit: F,
+) -> impl Stream<Item = PipelineResult>
+where
+    S: Stream<Item = Vec<u8>> + Send + 'static,
+    F: Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static,
+{
+    let state = State {
+        vaas: Box::pin(vaas),
+        vaas_done: false,
+        submit: Arc::new(submit),
+        config: PipelineConfig {
+            concurrency: config.concurrency.max(1),
+            max_queued: config.max_queued.max(1),
+            ..config
+        },
+        queued: VecDeque::new(),
+        busy: HashSet::new(),
+        running: FuturesUnordered::new(),
+        ready: VecDeque::new(),
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(result) = state.ready.pop_front() {
+                return Some((result, state));
+            }
+            state.start_queued();
+
+            // With nothing running, start_queued has emptied the queue, so
+            // only an exhausted input stream stops receiving.
+            let receiving = !state.vaas_done && state.queued.len() < state.config.max_queued;
+            let completed = match (receiving, state.running.is_empty()) {
+                (false, true) => return None,
+                (false, false) => state.running.next().await,
+                (true, true) => {
+                    let vaa = state.vaas.next().await;
+                    state.receive(vaa);
+                    continue;
+                }
+                (true, false) => {
+                    let next = future::select(state.running.next(), state.vaas.next()).await;
+                    let next = match next {
+                        Either::Left((completed, _)) => Either::Left(completed),
+                        Either::Right((vaa, _)) => Either::Right(vaa),
+                    };
+                    match next {
+                        Either::Left(completed) => completed,
+                        Either::Right(vaa) => {
+                            state.receive(vaa);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            if let Some((key, result)) = completed {
+                if let Some(key) = key {
+                    state.busy.remove(&key);
+                }
+                return Some((result, state));
+            }
+        }
+    })
+}
+
+impl<S, F> State<S, F>
+where
+    F: Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static,
+{
+    fn receive(&mut self, vaa: Option<Vec<u8>>) {
+        let Some(vaa) = vaa else {
+            self.vaas_done = true;
+            return;
+        };
+        match emitter_key(&vaa) {
+            Ok(key) => {
+                let key = Some(key).filter(|_| self.config.order_per_emitter);
+                self.queued.push_back((key, vaa));
+            }
+            Err(e) => self.ready.push_back(PipelineResult {
+                vaa,
+                result: Err(e),
+            }),
+        }
+    }
+
+    /// Start queued VAAs while there are free slots, skipping emitters that
+    /// already have a broadcast in flight.
+    fn start_queued(&mut self) {
+        let mut index = 0;
+        while self.running.len() < self.config.concurrency && index < self.queued.len() {
+            let key = self.queued[index].0;
+            if key.is_some_and(|key| self.busy.contains(&key)) {
+                index += 1;
+                continue;
+            }
+            let (key, vaa) = self.queued.remove(index).unwrap();
+            if let Some(key) = key {
+                self.busy.insert(key);
+            }
+
+            let submit = self.submit.clone();
+            self.running.push(Box::pin(async move {
+                let task_vaa = vaa.clone();
+                let result = tokio::task::spawn_blocking(move || submit(&task_vaa))
+                    .await
+                    .unwrap_or_else(|e| {
+                        Err(SubmitError::Execution(format!(
+                            "Submission task failed: {}",
+                            e
+                        )))
+                    });
+                (key, PipelineResult { vaa, result })
+            }));
+        }
+    }
+}
+
+fn emitter_key(vaa: &[u8]) -> Result<EmitterKey, SubmitError> {
+    let body = VaaBody::parse(&SignedVaa::parse(vaa)?.body)?;
+    Ok((body.emitter_chain, body.emitter_address))
+}
+
+/// A `submit` function for [`broadcast_stream`] that broadcasts over RPC
+/// with [`broadcast_vaa_with_config`](crate::broadcast_vaa_with_config) on
+/// `network`, using a new client for each VAA.
+#[cfg(feature = "rpc")]
+pub fn rpc_broadcaster(
+    rpc_url: String,
+    payer: Arc<solana_sdk::signature::Keypair>,
+    program_id: solana_sdk::pubkey::Pubkey,
+    network: crate::WormholeNetwork,
+) -> impl Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static {
+    move |signed_vaa| {
+        let vaa = SignedVaa::parse(signed_vaa)?;
+        let mut rpc_client = solana_client::rpc_client::RpcClient::new(rpc_url.clone());
+        crate::broadcast_vaa_with_config(
+            &mut rpc_client,
+            &payer,
+            &program_id,
+            vaa.guardian_set_index,
+            &vaa.body,
+            &vaa.signatures,
+            &crate::BroadcastConfig {
+                network,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    /// A signed VAA with no signatures from `emitter_chain` with `sequence`.
+    fn signed_vaa(emitter_chain: u16, sequence: u64) -> Vec<u8> {
+        let mut vaa = vec![1];
+        vaa.extend_from_slice(&0u32.to_be_bytes());
+        vaa.push(0);
+        vaa.extend_from_slice(&0u32.to_be_bytes()); // timestamp
+        vaa.extend_from_slice(&0u32.to_be_bytes()); // nonce
+        vaa.extend_from_slice(&emitter_chain.to_be_bytes());
+        vaa.extend_from_slice(&[7u8; 32]);
+        vaa.extend_from_slice(&sequence.to_be_bytes());
+        vaa.push(1); // consistency level
+        vaa
+    }
+
+    fn sequence(vaa: &[u8]) -> u64 {
+        VaaBody::parse(&SignedVaa::parse(vaa).unwrap().body)
+            .unwrap()
+            .sequence
+    }
+
+    #[test]
+    fn test_orders_per_emitter() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let submitted = Arc::new(Mutex::new(Vec::new()));
+
+        let vaas = vec![
+            signed_vaa(2, 1),
+            signed_vaa(2, 2),
+            vec![0xff],
+            signed_vaa(5, 1),
+            signed_vaa(2, 3),
+        ];
+        let recorded = submitted.clone();
+        let results: Vec<PipelineResult> = runtime.block_on(
+            broadcast_stream(
+                stream::iter(vaas),
+                PipelineConfig::default(),
+                move |vaa: &[u8]| {
+                    let key = emitter_key(vaa).unwrap();
+                    // Later sequences finish faster, so only ordering keeps them in order.
+                    sleep(Duration::from_millis(40 / sequence(vaa)));
+                    recorded.lock().unwrap().push((key.0, sequence(vaa)));
+                    Ok(vec![])
+                },
+            )
+            .collect(),
+        );
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(
+            results
+                .iter()
+                .filter(|r| matches!(r.result, Err(SubmitError::InvalidVaa(_))))
+                .count(),
+            1
+        );
+
+        let submitted = submitted.lock().unwrap();
+        let chain_2: Vec<u64> = submitted
+            .iter()
+            .filter(|(chain, _)| *chain == 2)
+            .map(|(_, seq)| *seq)
+            .collect();
+        assert_eq!(chain_2, vec![1, 2, 3]);
+        assert!(submitted.contains(&(5, 1)));
+    }
+
+    #[test]
+    fn test_input_not_drained_past_queue_limit() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let received = Arc::new(AtomicUsize::new(0));
+        let submitted = Arc::new(AtomicUsize::new(0));
+        let config = PipelineConfig {
+            concurrency: 1,
+            max_queued: 2,
+            ..Default::default()
+        };
+
+        let counter = received.clone();
+        let vaas = stream::iter((0..10).map(|chain| signed_vaa(chain, 1))).inspect(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let (received_in_submit, submitted_in_submit) = (received.clone(), submitted.clone());
+        let results: Vec<PipelineResult> = runtime.block_on(
+            broadcast_stream(vaas, config, move |_: &[u8]| {
+                // Give the pipeline time to read ahead as far as it will.
+                sleep(Duration::from_millis(20));
+                let started = submitted_in_submit.fetch_add(1, Ordering::SeqCst) + 1;
+                // The VAAs started so far, plus at most a full queue.
+                assert!(received_in_submit.load(Ordering::SeqCst) <= started + 2);
+                Ok(vec![])
+            })
+            .collect(),
+        );
+
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|r| r.result.is_ok()));
+        assert_eq!(received.load(Ordering::SeqCst), 10);
+    }
+}
@@ -11,7 +11,7 @@ use executor_account_resolver_svm::{
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::Signer,
     transaction::Transaction,
 };
 
@@ -29,6 +29,24 @@ pub struct ResolverResult {
     pub instruction_groups: Vec<InstructionGroup>,
     /// How many iterations it took to resolve.
     pub iterations: usize,
+    /// What happened on each iteration, for debugging.
+    pub trace: Vec<ResolverIteration>,
+}
+
+/// One round of the resolver loop.
+#[derive(Clone, Debug)]
+pub struct ResolverIteration {
+    /// 1-based iteration number.
+    pub iteration: usize,
+    /// Remaining accounts passed to the resolver instruction.
+    pub accounts_supplied: Vec<Pubkey>,
+    /// Accounts the resolver asked for (as returned, before placeholder
+    /// substitution). Empty once resolved.
+    pub accounts_requested: Vec<Pubkey>,
+    /// Raw program return data.
+    pub return_data: Vec<u8>,
+    /// Raw result account data, if the resolver answered with `Account()`.
+    pub result_account_data: Option<Vec<u8>>,
 }
 
 /// Run the executor-account-resolver `resolve_execute_vaa_v1` loop.
@@ -41,15 +59,45 @@ pub struct ResolverResult {
 /// - `RESOLVER_PUBKEY_PAYER` -> `payer.pubkey()`
 /// - `RESOLVER_PUBKEY_GUARDIAN_SET` -> `guardian_set`
 /// - `RESOLVER_PUBKEY_SHIM_VAA_SIGS` -> left as-is (substituted at execution time)
+///
+/// The payer only signs simulations, so a
+/// [`NullSigner`](solana_sdk::signer::null_signer::NullSigner) works against RPC
+/// when the keypair isn't available (e.g. offline export).
 pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
     conn: &C,
     program_id: &Pubkey,
-    payer: &Keypair,
+    payer: &dyn Signer,
+    vaa_body: &[u8],
+    guardian_set: &Pubkey,
+    max_iterations: usize,
+) -> Result<ResolverResult, SubmitError> {
+    resolve_execute_vaa_v1_with_placeholders(
+        conn,
+        program_id,
+        payer,
+        vaa_body,
+        guardian_set,
+        max_iterations,
+        &[],
+    )
+}
+
+/// Run the resolver loop with additional `(placeholder, pubkey)` substitutions.
+///
+/// For integrators extending the protocol with their own well-known
+/// placeholders. Built-in placeholders take precedence. Pass the same table to
+/// execution ([`ExecuteConfig::placeholders`](crate::ExecuteConfig::placeholders)).
+pub fn resolve_execute_vaa_v1_with_placeholders<C: SolanaConnection>(
+    conn: &C,
+    program_id: &Pubkey,
+    payer: &dyn Signer,
     vaa_body: &[u8],
     guardian_set: &Pubkey,
     max_iterations: usize,
+    placeholders: &[(Pubkey, Pubkey)],
 ) -> Result<ResolverResult, SubmitError> {
     let mut remaining_accounts: Vec<AccountMeta> = Vec::new();
+    let mut trace: Vec<ResolverIteration> = Vec::new();
 
     // Derive the result account PDA for the Account() flow.
     let (result_account_pubkey, _) =
@@ -68,6 +116,13 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
             accounts: remaining_accounts.clone(),
             data: ix_data,
         };
+        let mut step = ResolverIteration {
+            iteration,
+            accounts_supplied: remaining_accounts.iter().map(|a| a.pubkey).collect(),
+            accounts_requested: Vec::new(),
+            return_data: Vec::new(),
+            result_account_data: None,
+        };
 
         let blockhash = conn
             .get_latest_blockhash()
@@ -86,7 +141,7 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
             })?;
 
         let return_data = sim_result.return_data.ok_or_else(|| {
-            SubmitError::ResolverSimulation(format!(
+            SubmitError::InvalidResolverResult(format!(
                 "No return data from resolver on iteration {}",
                 iteration
             ))
@@ -94,25 +149,35 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
 
         let resolver: Resolver<InstructionGroups> =
             BorshDeserialize::deserialize(&mut return_data.as_slice()).map_err(|e| {
-                SubmitError::ResolverSimulation(format!(
+                SubmitError::InvalidResolverResult(format!(
                     "Failed to deserialize resolver return data: {}",
                     e
                 ))
             })?;
+        step.return_data = return_data;
 
         match resolver {
             Resolver::Resolved(groups) => {
+                trace.push(step);
                 return Ok(ResolverResult {
                     instruction_groups: groups.0,
                     iterations: iteration,
+                    trace,
                 });
             }
             Resolver::Missing(MissingAccounts {
                 accounts: missing,
                 address_lookup_tables: _,
             }) => {
+                step.accounts_requested = missing.clone();
+                trace.push(step);
                 for pubkey in &missing {
-                    let actual = substitute_placeholder(*pubkey, &payer.pubkey(), guardian_set);
+                    let actual = substitute_placeholder(
+                        *pubkey,
+                        &payer.pubkey(),
+                        guardian_set,
+                        placeholders,
+                    );
                     if actual == result_account_pubkey {
                         // Result account needs to be writable for Account() flow
                         remaining_accounts.push(AccountMeta::new(actual, false));
@@ -133,7 +198,7 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
                     .find(|(pk, _)| *pk == result_account_pubkey)
                     .map(|(_, data)| data.as_slice())
                     .ok_or_else(|| {
-                        SubmitError::ResolverSimulation(
+                        SubmitError::InvalidResolverResult(
                             "Resolver returned Account() but result account not found in simulation"
                                 .to_string(),
                         )
@@ -141,15 +206,16 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
 
                 // Skip the 8-byte Anchor discriminator.
                 if account_data.len() <= 8 {
-                    return Err(SubmitError::ResolverSimulation(
+                    return Err(SubmitError::InvalidResolverResult(
                         "Result account data too short".to_string(),
                     ));
                 }
                 let payload = &account_data[8..];
+                step.result_account_data = Some(account_data.to_vec());
 
                 let resolver: Resolver<InstructionGroups> =
                     BorshDeserialize::deserialize(&mut &payload[..]).map_err(|e| {
-                        SubmitError::ResolverSimulation(format!(
+                        SubmitError::InvalidResolverResult(format!(
                             "Failed to deserialize result account: {}",
                             e
                         ))
@@ -157,18 +223,26 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
 
                 match resolver {
                     Resolver::Resolved(groups) => {
+                        trace.push(step);
                         return Ok(ResolverResult {
                             instruction_groups: groups.0,
                             iterations: iteration,
+                            trace,
                         });
                     }
                     Resolver::Missing(MissingAccounts {
                         accounts: missing,
                         address_lookup_tables: _,
                     }) => {
+                        step.accounts_requested = missing.clone();
+                        trace.push(step);
                         for pubkey in &missing {
-                            let actual =
-                                substitute_placeholder(*pubkey, &payer.pubkey(), guardian_set);
+                            let actual = substitute_placeholder(
+                                *pubkey,
+                                &payer.pubkey(),
+                                guardian_set,
+                                placeholders,
+                            );
                             if actual == result_account_pubkey {
                                 remaining_accounts.push(AccountMeta::new(actual, false));
                             } else if *pubkey == RESOLVER_PUBKEY_PAYER {
@@ -179,7 +253,7 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
                         }
                     }
                     Resolver::Account() => {
-                        return Err(SubmitError::ResolverSimulation(
+                        return Err(SubmitError::InvalidResolverResult(
                             "Result account itself returned Account() -- recursive not supported"
                                 .to_string(),
                         ));
@@ -189,26 +263,57 @@ pub fn resolve_execute_vaa_v1<C: SolanaConnection>(
         }
     }
 
-    Err(SubmitError::ResolverSimulation(format!(
-        "Resolver did not resolve after {} iterations. \
-         Remaining accounts: {:?}",
-        max_iterations,
-        remaining_accounts
-            .iter()
-            .map(|a| a.pubkey.to_string())
-            .collect::<Vec<_>>()
-    )))
+    Err(SubmitError::ResolverNotConverged {
+        iterations: max_iterations,
+        remaining_accounts: remaining_accounts.iter().map(|a| a.pubkey).collect(),
+        trace,
+    })
 }
 
-/// Substitute well-known placeholder pubkeys with actual values.
-fn substitute_placeholder(pubkey: Pubkey, payer: &Pubkey, guardian_set: &Pubkey) -> Pubkey {
+/// Substitute well-known and caller-registered placeholder pubkeys with actual values.
+fn substitute_placeholder(
+    pubkey: Pubkey,
+    payer: &Pubkey,
+    guardian_set: &Pubkey,
+    placeholders: &[(Pubkey, Pubkey)],
+) -> Pubkey {
     if pubkey == RESOLVER_PUBKEY_PAYER {
         *payer
     } else if pubkey == RESOLVER_PUBKEY_GUARDIAN_SET {
         *guardian_set
+    } else if let Some((_, actual)) = placeholders.iter().find(|(ph, _)| *ph == pubkey) {
+        *actual
     } else {
         // RESOLVER_PUBKEY_SHIM_VAA_SIGS and others are left as-is;
         // they are substituted at execution time, not resolve time.
         pubkey
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_custom_placeholder() {
+        let payer = Pubkey::new_unique();
+        let guardian_set = Pubkey::new_unique();
+        let custom = Pubkey::new_unique();
+        let actual = Pubkey::new_unique();
+        let placeholders = [(custom, actual), (RESOLVER_PUBKEY_PAYER, actual)];
+
+        assert_eq!(
+            substitute_placeholder(custom, &payer, &guardian_set, &placeholders),
+            actual
+        );
+        // Built-in placeholders take precedence
+        assert_eq!(
+            substitute_placeholder(RESOLVER_PUBKEY_PAYER, &payer, &guardian_set, &placeholders),
+            payer
+        );
+        assert_eq!(
+            substitute_placeholder(RESOLVER_PUBKEY_SHIM_VAA_SIGS, &payer, &guardian_set, &[]),
+            RESOLVER_PUBKEY_SHIM_VAA_SIGS
+        );
+    }
+}
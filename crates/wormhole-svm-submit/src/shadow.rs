@@ -0,0 +1,374 @@
+//! Shadow execution against a LiteSVM fork of the target cluster.
+//!
+//! [`broadcast_vaa_with_shadow`] first runs the complete broadcast flow in a
+//! local LiteSVM instance whose accounts and programs are cloned on demand
+//! from the real connection, and only submits for real if that run succeeds.
+//! This catches state-dependent failures (e.g. a later group depending on an
+//! account created by an earlier one) that simulating a single transaction
+//! against the cluster misses.
+//!
+//! Requires the `shadow` feature.
+
+use std::cell::RefCell;
+
+use litesvm::LiteSVM;
+use solana_address_lookup_table_interface::state::LOOKUP_TABLE_META_SIZE;
+use solana_sdk::{
+    account::{Account, ReadableAccount},
+    bpf_loader_upgradeable,
+    clock::Clock,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::Signer,
+    sysvar,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::connection::{SimulationResult, SolanaConnection};
+use crate::{BroadcastConfig, ExecuteConfig, SubmitError};
+
+/// A LiteSVM instance that clones missing accounts from `source` when a
+/// transaction first touches them.
+///
+/// Writes only affect the fork; `source` is never sent anything.
+pub struct ForkedConnection<'a, S: SolanaConnection> {
+    source: &'a S,
+    svm: RefCell<LiteSVM>,
+}
+
+impl<'a, S: SolanaConnection> ForkedConnection<'a, S> {
+    /// Create a fork of `source`, with its clock copied from the cluster.
+    pub fn new(source: &'a S) -> Result<Self, SubmitError> {
+        let mut svm = LiteSVM::new();
+
+        if let Some(account) = fetch(source, &sysvar::clock::ID)? {
+            if let Some(clock) = solana_sdk::account::from_account::<Clock, _>(&account) {
+                svm.set_sysvar(&clock);
+            }
+        }
+
+        Ok(Self {
+            source,
+            svm: RefCell::new(svm),
+        })
+    }
+
+    /// Clone any of `pubkeys` that the fork doesn't have yet.
+    ///
+    /// Upgradeable programs are cloned together with their program data account.
+    fn ensure_accounts(&self, pubkeys: &[Pubkey]) -> Result<(), SubmitError> {
+        for pubkey in pubkeys {
+            if self.svm.borrow().get_account(pubkey).is_some() {
+                continue;
+            }
+            let Some(account) = fetch(self.source, pubkey)? else {
+                continue;
+            };
+
+            if let Some(programdata) = upgradeable_programdata_address(&account) {
+                self.ensure_accounts(&[programdata])?;
+            }
+
+            self.svm
+                .borrow_mut()
+                .set_account(*pubkey, account)
+                .map_err(|e| {
+                    SubmitError::Connection(format!("failed to clone account {}: {:?}", pubkey, e))
+                })?;
+        }
+        Ok(())
+    }
+}
+
+fn fetch<S: SolanaConnection>(source: &S, pubkey: &Pubkey) -> Result<Option<Account>, SubmitError> {
+    source
+        .get_account(pubkey)
+        .map_err(|e| SubmitError::Connection(format!("failed to fetch {}: {}", pubkey, e)))
+}
+
+/// The addresses stored in an address lookup table account.
+fn lookup_table_addresses(account: &Account) -> Vec<Pubkey> {
+    // The addresses follow the 56-byte table metadata.
+    account
+        .data()
+        .get(LOOKUP_TABLE_META_SIZE..)
+        .unwrap_or_default()
+        .chunks_exact(32)
+        .map(|chunk| Pubkey::new_from_array(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// The program data address of an upgradeable program account, if it is one.
+fn upgradeable_programdata_address(account: &Account) -> Option<Pubkey> {
+    // UpgradeableLoaderState::Program is tag 2 (u32 LE) followed by the address.
+    let data = account.data();
+    if account.owner != bpf_loader_upgradeable::ID || data.len() < 36 || data[..4] != [2, 0, 0, 0] {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data[4..36].try_into().unwrap()))
+}
+
+impl<S: SolanaConnection> SolanaConnection for ForkedConnection<'_, S> {
+    type Error = SubmitError;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
+        Ok(self.svm.borrow().latest_blockhash())
+    }
+
+    fn simulate_with_post_accounts(
+        &self,
+        tx: &Transaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult, Self::Error> {
+        self.ensure_accounts(&tx.message.account_keys)?;
+        self.ensure_accounts(accounts)?;
+
+        let result = self
+            .svm
+            .borrow()
+            .simulate_transaction(tx.clone())
+            .map_err(|e| {
+                SubmitError::Execution(format!(
+                    "Shadow simulation failed: {:?}\n{}",
+                    e.err,
+                    e.meta.logs.join("\n")
+                ))
+            })?;
+
+        let return_data = Some(result.meta.return_data.data).filter(|data| !data.is_empty());
+        let post_accounts = accounts
+            .iter()
+            .filter_map(|pubkey| {
+                result
+                    .post_accounts
+                    .iter()
+                    .find(|(pk, _)| pk == pubkey)
+                    .map(|(_, account)| (*pubkey, account.data().to_vec()))
+            })
+            .collect();
+
+        Ok(SimulationResult {
+            return_data,
+            post_accounts,
+            units_consumed: Some(result.meta.compute_units_consumed),
+            logs: result.meta.logs,
+        })
+    }
+
+    fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+        self.ensure_accounts(&tx.message.account_keys)?;
+
+        self.svm
+            .get_mut()
+            .send_transaction(tx.clone())
+            .map(|_| tx.signatures[0])
+            .map_err(|e| {
+                SubmitError::Execution(format!(
+                    "Shadow transaction failed: {:?}\n{}",
+                    e.err,
+                    e.meta.logs.join("\n")
+                ))
+            })
+    }
+
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.ensure_accounts(tx.message.static_account_keys())?;
+        for lookup in tx.message.address_table_lookups().unwrap_or_default() {
+            self.ensure_accounts(&[lookup.account_key])?;
+            let addresses = self
+                .svm
+                .borrow()
+                .get_account(&lookup.account_key)
+                .map(|table| lookup_table_addresses(&table))
+                .unwrap_or_default();
+            self.ensure_accounts(&addresses)?;
+        }
+
+        self.svm
+            .get_mut()
+            .send_transaction(tx.clone())
+            .map(|_| tx.signatures[0])
+            .map_err(|e| {
+                SubmitError::Execution(format!(
+                    "Shadow transaction failed: {:?}\n{}",
+                    e.err,
+                    e.meta.logs.join("\n")
+                ))
+            })
+    }
+
+    fn supports_versioned_transactions(&self) -> bool {
+        true
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
+        self.ensure_accounts(&[*pubkey])?;
+        Ok(self.svm.borrow().get_account(pubkey))
+    }
+
+    fn advance_slot(&mut self) -> Result<(), Self::Error> {
+        let svm = self.svm.get_mut();
+        let slot = svm.get_sysvar::<Clock>().slot;
+        svm.warp_to_slot(slot + 1);
+        Ok(())
+    }
+}
+
+/// Run the broadcast flow on a fork of `conn` first, and only submit for real
+/// if the shadow run succeeds.
+///
+/// The shadow run uses the same options, but the hooks (both event sinks,
+/// `before_send`, the store and `confirm`) only see the real submission.
+/// Returns the signatures of the real submission.
+pub fn broadcast_vaa_with_shadow<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn Signer,
+    program_id: &Pubkey,
+    guardian_set_index: u32,
+    vaa_body: &[u8],
+    guardian_signatures: &[[u8; 66]],
+    config: &BroadcastConfig,
+) -> Result<Vec<Signature>, SubmitError> {
+    log::info!("Running shadow execution on a local fork...");
+    let shadow_config = shadow_config(config);
+    {
+        let mut fork = ForkedConnection::new(&*conn)?;
+        crate::broadcast_vaa_with_config(
+            &mut fork,
+            payer,
+            program_id,
+            guardian_set_index,
+            vaa_body,
+            guardian_signatures,
+            &shadow_config,
+        )
+        .map_err(|e| SubmitError::Execution(format!("Shadow execution failed: {}", e)))?;
+    }
+    log::info!("Shadow execution succeeded; submitting.");
+
+    crate::broadcast_vaa_with_config(
+        conn,
+        payer,
+        program_id,
+        guardian_set_index,
+        vaa_body,
+        guardian_signatures,
+        config,
+    )
+}
+
+/// `config` without its hooks, for the shadow run.
+fn shadow_config<'a>(config: &BroadcastConfig<'a>) -> BroadcastConfig<'a> {
+    BroadcastConfig {
+        network: config.network,
+        execute: ExecuteConfig {
+            event_sink: None,
+            before_send: None,
+            ..config.execute
+        },
+        vaa_memo: config.vaa_memo,
+        event_sink: None,
+        max_cost_lamports: config.max_cost_lamports,
+        store: None,
+        order_groups: config.order_groups,
+        report_accounts: config.report_accounts,
+        skip_close: config.skip_close,
+        skip_balance_check: config.skip_balance_check,
+        confirm: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Keypair, system_instruction};
+
+    use crate::test_support::MockConnection;
+
+    #[test]
+    fn test_fork_clones_accounts_and_keeps_writes() {
+        let mut conn = MockConnection::new();
+        conn.slot = 42;
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        conn.accounts.insert(
+            payer.pubkey(),
+            Account::new(LAMPORTS_PER_SOL, 0, &solana_sdk::system_program::ID),
+        );
+
+        let mut fork = ForkedConnection::new(&conn).unwrap();
+        let clock = fork.get_account(&sysvar::clock::ID).unwrap().unwrap();
+        let clock: Clock = solana_sdk::account::from_account(&clock).unwrap();
+        assert_eq!(clock.slot, 42);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &recipient,
+                LAMPORTS_PER_SOL / 10,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            fork.get_latest_blockhash().unwrap(),
+        );
+        fork.send_and_confirm(&tx).unwrap();
+
+        let received = fork.get_account(&recipient).unwrap().unwrap();
+        assert_eq!(received.lamports, LAMPORTS_PER_SOL / 10);
+        assert!(conn.sent.is_empty());
+        assert!(!conn.accounts.contains_key(&recipient));
+    }
+
+    #[test]
+    fn test_failed_shadow_run_submits_nothing() {
+        // The resolver program doesn't exist on the fork, so resolution fails
+        let mut conn = MockConnection::new();
+
+        let err = broadcast_vaa_with_shadow(
+            &mut conn,
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            0,
+            b"body",
+            &[[0; 66]],
+            &BroadcastConfig::default(),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Shadow execution failed"));
+        assert!(conn.sent.is_empty());
+    }
+
+    #[test]
+    fn test_shadow_config_drops_hooks() {
+        fn before_send(_: usize, _: &Signature, _: &Hash) -> Result<(), SubmitError> {
+            Ok(())
+        }
+        let sink = crate::JsonLinesSink::new(Vec::new());
+        let confirm = |_: &crate::BroadcastPlan| true;
+        let config = BroadcastConfig {
+            execute: ExecuteConfig {
+                event_sink: Some(&sink),
+                before_send: Some(&before_send),
+                max_retries: 7,
+                ..Default::default()
+            },
+            event_sink: Some(&sink),
+            confirm: Some(&confirm),
+            ..Default::default()
+        };
+
+        let shadow = shadow_config(&config);
+
+        assert!(shadow.execute.event_sink.is_none());
+        assert!(shadow.execute.before_send.is_none());
+        assert!(shadow.event_sink.is_none());
+        assert!(shadow.store.is_none());
+        assert!(shadow.confirm.is_none());
+        assert_eq!(shadow.execute.max_retries, 7);
+    }
+}
@@ -6,11 +6,17 @@
 //!
 //! Instruction builders ([`build_post_signatures_ix`], [`build_close_signatures_ix`])
 //! are also provided for callers that want to compose transactions manually.
+//!
+//! [`signatures_account_rent`] gives the rent locked by a signatures account,
+//! and [`find_signatures_accounts`] (RPC only) finds accounts that were never
+//! closed.
 
 use solana_sdk::{
+    account::Account,
     instruction::Instruction,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    rent::Rent,
+    signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
 use wormhole_svm_shim::verify_vaa::{
@@ -29,6 +35,22 @@ pub struct PostedSignatures {
     pub pubkey: Pubkey,
 }
 
+/// Offset of the refund recipient in a guardian signatures account (after the
+/// account discriminator).
+const REFUND_RECIPIENT_OFFSET: usize = 8;
+
+/// Size of a guardian signatures account holding `num_signatures` signatures:
+/// discriminator, refund recipient, guardian set index and the signature vector.
+pub fn signatures_account_len(num_signatures: usize) -> usize {
+    8 + 32 + 4 + 4 + num_signatures * 66
+}
+
+/// Lamports locked by [`post_signatures`] for `num_signatures` signatures
+/// (the rent-exempt balance of the account, returned by [`close_signatures`]).
+pub fn signatures_account_rent(rent: &Rent, num_signatures: usize) -> u64 {
+    rent.minimum_balance(signatures_account_len(num_signatures))
+}
+
 /// Build a `PostSignatures` instruction without sending it.
 pub fn build_post_signatures_ix(
     payer: &Pubkey,
@@ -70,7 +92,7 @@ pub fn build_close_signatures_ix(
 /// which is then used during resolver execution for VAA verification.
 pub fn post_signatures<C: SolanaConnection>(
     conn: &mut C,
-    payer: &Keypair,
+    payer: &dyn Signer,
     verify_vaa_shim: &Pubkey,
     guardian_set_index: u32,
     signatures: &[[u8; 66]],
@@ -105,13 +127,37 @@ pub fn post_signatures<C: SolanaConnection>(
     })
 }
 
+/// Result of closing a guardian signatures account.
+pub struct ClosedSignatures {
+    /// Signature of the close transaction, if one landed.
+    pub signature: Option<Signature>,
+    /// The account did not exist (already closed or never created).
+    pub already_closed: bool,
+    /// Lamports returned to the refund recipient (0 if already closed).
+    pub reclaimed_lamports: u64,
+}
+
 /// Close a guardian signatures account to reclaim rent.
+///
+/// Idempotent: an account that no longer exists is reported as
+/// `already_closed` instead of an error, so retries and cleanup paths can call
+/// this unconditionally.
 pub fn close_signatures<C: SolanaConnection>(
     conn: &mut C,
-    payer: &Keypair,
+    payer: &dyn Signer,
     verify_vaa_shim: &Pubkey,
     signatures_pubkey: &Pubkey,
-) -> Result<(), SubmitError> {
+) -> Result<ClosedSignatures, SubmitError> {
+    let already_closed = ClosedSignatures {
+        signature: None,
+        already_closed: true,
+        reclaimed_lamports: 0,
+    };
+
+    let Some(account) = fetch_account(conn, signatures_pubkey)? else {
+        return Ok(already_closed);
+    };
+
     let ix = build_close_signatures_ix(verify_vaa_shim, signatures_pubkey, &payer.pubkey());
 
     let blockhash = conn
@@ -119,8 +165,128 @@ pub fn close_signatures<C: SolanaConnection>(
         .map_err(|e| SubmitError::Connection(e.to_string()))?;
     let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
 
-    conn.send_and_confirm(&tx)
-        .map_err(|e| SubmitError::Connection(e.to_string()))?;
+    match conn.send_and_confirm(&tx) {
+        Ok(signature) => Ok(ClosedSignatures {
+            signature: Some(signature),
+            already_closed: false,
+            reclaimed_lamports: account.lamports,
+        }),
+        // Someone else (or an earlier attempt that landed late) closed it first.
+        Err(_) if fetch_account(conn, signatures_pubkey)?.is_none() => Ok(already_closed),
+        Err(e) => Err(SubmitError::Connection(e.to_string())),
+    }
+}
 
-    Ok(())
+fn fetch_account<C: SolanaConnection>(
+    conn: &C,
+    pubkey: &Pubkey,
+) -> Result<Option<Account>, SubmitError> {
+    conn.get_account(pubkey)
+        .map_err(|e| SubmitError::Connection(e.to_string()))
+}
+
+/// A guardian signatures account found by [`find_signatures_accounts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignaturesAccount {
+    /// Address of the account.
+    pub pubkey: Pubkey,
+    /// Receives the rent when the account is closed, and must sign the close.
+    pub refund_recipient: Pubkey,
+    /// Guardian set index the signatures were posted for.
+    pub guardian_set_index: u32,
+    /// Number of signatures stored in the account.
+    pub num_signatures: usize,
+    /// Lamports held by the account (reclaimed when it is closed).
+    pub lamports: u64,
+}
+
+impl SignaturesAccount {
+    /// Decode a guardian signatures account. Returns `None` if `data` is too
+    /// short to be one.
+    pub fn parse(pubkey: Pubkey, lamports: u64, data: &[u8]) -> Option<Self> {
+        let header = data.get(REFUND_RECIPIENT_OFFSET + 32..REFUND_RECIPIENT_OFFSET + 40)?;
+        Some(Self {
+            pubkey,
+            refund_recipient: Pubkey::new_from_array(
+                data[REFUND_RECIPIENT_OFFSET..REFUND_RECIPIENT_OFFSET + 32]
+                    .try_into()
+                    .unwrap(),
+            ),
+            guardian_set_index: u32::from_be_bytes(header[0..4].try_into().unwrap()),
+            num_signatures: u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize,
+            lamports,
+        })
+    }
+}
+
+/// Find guardian signatures accounts of `verify_vaa_shim` whose refund
+/// recipient is `refund_recipient`, e.g. accounts left behind by a crashed
+/// relayer that can still be closed with [`close_signatures`].
+#[cfg(feature = "rpc")]
+pub fn find_signatures_accounts(
+    rpc_client: &solana_client::rpc_client::RpcClient,
+    verify_vaa_shim: &Pubkey,
+    refund_recipient: &Pubkey,
+) -> Result<Vec<SignaturesAccount>, SubmitError> {
+    use solana_account_decoder_client_types::UiAccountEncoding;
+    use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+    use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            REFUND_RECIPIENT_OFFSET,
+            refund_recipient.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let accounts = rpc_client.get_program_accounts_with_config(verify_vaa_shim, config)?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            SignaturesAccount::parse(pubkey, account.lamports, &account.data)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signatures_account_rent() {
+        let rent = Rent::default();
+
+        assert_eq!(signatures_account_len(0), 48);
+        assert_eq!(signatures_account_len(13), 48 + 13 * 66);
+        assert_eq!(
+            signatures_account_rent(&rent, 13),
+            rent.minimum_balance(48 + 13 * 66)
+        );
+        assert!(signatures_account_rent(&rent, 19) > signatures_account_rent(&rent, 13));
+    }
+
+    #[test]
+    fn test_parse_signatures_account() {
+        let refund_recipient = Pubkey::new_unique();
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(refund_recipient.as_ref());
+        data.extend_from_slice(&4u32.to_be_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[0u8; 2 * 66]);
+
+        let pubkey = Pubkey::new_unique();
+        let account = SignaturesAccount::parse(pubkey, 1_000, &data).unwrap();
+
+        assert_eq!(account.pubkey, pubkey);
+        assert_eq!(account.refund_recipient, refund_recipient);
+        assert_eq!(account.guardian_set_index, 4);
+        assert_eq!(account.num_signatures, 2);
+        assert_eq!(account.lamports, 1_000);
+        assert!(SignaturesAccount::parse(pubkey, 0, &data[..47]).is_none());
+    }
 }
@@ -0,0 +1,174 @@
+//! Signed VAA subscription from a Wormhole guardian spy.
+//!
+//! [`subscribe_signed_vaas`] connects to the gRPC endpoint of a spy (or a
+//! guardian's public spy service) and streams the signed VAAs of the given
+//! emitters. [`relay_from_spy`] feeds that stream straight into
+//! [`broadcast_stream`], so a relayer doesn't need a separate listener.
+//!
+//! The subscription ends when the connection drops; reconnecting is up to the
+//! caller.
+//!
+//! Requires the `spy` feature.
+
+use futures::stream::{self, Stream};
+use solana_sdk::signature::Signature;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::Endpoint;
+
+use crate::pipeline::{broadcast_stream, PipelineConfig, PipelineResult};
+use crate::SubmitError;
+
+/// gRPC method of `spy.v1.SpyRPCService` that streams signed VAAs.
+const SUBSCRIBE_SIGNED_VAA: &str = "/spy.v1.SpyRPCService/SubscribeSignedVAA";
+
+/// Only receive VAAs from this emitter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmitterFilter {
+    /// Wormhole chain ID of the emitter.
+    pub chain: u16,
+    /// Emitter address (32 bytes, left-padded).
+    pub address: [u8; 32],
+}
+
+/// Subscribe to the signed VAAs of `filters` (all VAAs if empty) from the
+/// spy at `endpoint`, e.g. `http://localhost:7073`.
+///
+/// Yields the signed VAA bytes; the stream ends when the subscription fails
+/// or the spy closes it.
+pub async fn subscribe_signed_vaas(
+    endpoint: &str,
+    filters: &[EmitterFilter],
+) -> Result<impl Stream<Item = Vec<u8>> + Send + 'static, SubmitError> {
+    let connection_error =
+        |e: &dyn std::fmt::Display| SubmitError::Connection(format!("spy {}: {}", endpoint, e));
+
+    let channel = Endpoint::from_shared(endpoint.to_string())
+        .map_err(|e| connection_error(&e))?
+        .connect()
+        .await
+        .map_err(|e| connection_error(&e))?;
+    let mut grpc = tonic::client::Grpc::new(channel);
+    grpc.ready().await.map_err(|e| connection_error(&e))?;
+
+    let request = proto::SubscribeSignedVaaRequest {
+        filters: filters.iter().map(proto::FilterEntry::from).collect(),
+    };
+    let codec: ProstCodec<proto::SubscribeSignedVaaRequest, proto::SubscribeSignedVaaResponse> =
+        ProstCodec::default();
+    let responses = grpc
+        .server_streaming(
+            tonic::Request::new(request),
+            PathAndQuery::from_static(SUBSCRIBE_SIGNED_VAA),
+            codec,
+        )
+        .await
+        .map_err(|e| connection_error(&e))?
+        .into_inner();
+
+    Ok(stream::unfold(responses, |mut responses| async move {
+        match responses.message().await {
+            Ok(Some(response)) => Some((response.vaa_bytes, responses)),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("Spy subscription ended: {}", e);
+                None
+            }
+        }
+    }))
+}
+
+/// Subscribe to the signed VAAs of `filters` and submit each with `submit`
+/// through [`broadcast_stream`].
+pub async fn relay_from_spy<F>(
+    endpoint: &str,
+    filters: &[EmitterFilter],
+    config: PipelineConfig,
+    submit: F,
+) -> Result<impl Stream<Item = PipelineResult>, SubmitError>
+where
+    F: Fn(&[u8]) -> Result<Vec<Signature>, SubmitError> + Send + Sync + 'static,
+{
+    let vaas = subscribe_signed_vaas(endpoint, filters).await?;
+    Ok(broadcast_stream(vaas, config, submit))
+}
+
+/// Messages of the `spy.v1` protobuf package, limited to what
+/// `SubscribeSignedVAA` needs.
+mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EmitterFilter {
+        /// `publicrpc.v1.ChainID`; values equal the Wormhole chain ID.
+        #[prost(int32, tag = "1")]
+        pub chain_id: i32,
+        /// Hex-encoded emitter address, without `0x`.
+        #[prost(string, tag = "2")]
+        pub emitter_address: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FilterEntry {
+        #[prost(oneof = "filter_entry::Filter", tags = "1")]
+        pub filter: Option<filter_entry::Filter>,
+    }
+
+    pub mod filter_entry {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Filter {
+            #[prost(message, tag = "1")]
+            EmitterFilter(super::EmitterFilter),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeSignedVaaRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub filters: Vec<FilterEntry>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeSignedVaaResponse {
+        #[prost(bytes = "vec", tag = "1")]
+        pub vaa_bytes: Vec<u8>,
+    }
+
+    impl From<&super::EmitterFilter> for FilterEntry {
+        fn from(filter: &super::EmitterFilter) -> Self {
+            Self {
+                filter: Some(filter_entry::Filter::EmitterFilter(EmitterFilter {
+                    chain_id: filter.chain as i32,
+                    emitter_address: hex::encode(filter.address),
+                })),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_subscribe_request_encoding() {
+        let filter = EmitterFilter {
+            chain: 2,
+            address: [0xab; 32],
+        };
+        let request = proto::SubscribeSignedVaaRequest {
+            filters: vec![proto::FilterEntry::from(&filter)],
+        };
+
+        let encoded = request.encode_to_vec();
+        // filters (field 1, length-delimited) > emitter_filter (field 1) > chain_id (field 1) = 2
+        assert_eq!(&encoded[..6], &[0x0a, 70, 0x0a, 68, 0x08, 2]);
+
+        let decoded = proto::SubscribeSignedVaaRequest::decode(encoded.as_slice()).unwrap();
+        let Some(proto::filter_entry::Filter::EmitterFilter(emitter)) = &decoded.filters[0].filter
+        else {
+            panic!("expected an emitter filter");
+        };
+        assert_eq!(emitter.chain_id, 2);
+        assert_eq!(emitter.emitter_address, "ab".repeat(32));
+    }
+}
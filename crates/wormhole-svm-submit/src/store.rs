@@ -0,0 +1,477 @@
+//! Persistence of broadcast progress for crash recovery.
+//!
+//! Set [`BroadcastConfig::store`](crate::BroadcastConfig::store) to record each
+//! state transition of a broadcast (resolved plan, posted signatures account,
+//! sent and executed groups), keyed by the VAA digest. Broadcasting the same
+//! VAA again with the same store resumes where the previous run stopped: the
+//! stored plan is reused, a still-open signatures account is reused and
+//! executed groups are skipped. A group whose transaction was sent but not
+//! confirmed is only sent again once that transaction can no longer land.
+//! [`close_in_flight`] instead abandons unfinished broadcasts and reclaims
+//! their signatures account rent.
+//!
+//! [`FileStore`] keeps one JSON file per in-flight broadcast in a directory.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::Engine;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature, signer::Signer};
+
+use crate::connection::SolanaConnection;
+use crate::events::{BroadcastEvent, EventSink};
+use crate::execute::KEYPAIR_PLACEHOLDERS;
+use crate::resolve::InstructionGroup;
+use crate::signatures::{close_signatures, ClosedSignatures};
+use crate::SubmitError;
+
+/// Recorded progress of one broadcast.
+pub struct BroadcastRecord {
+    /// Digest of the VAA body (see [`vaa_digest`](crate::vaa::vaa_digest)).
+    pub vaa_digest: [u8; 32],
+    /// The program the VAA is submitted to.
+    pub program_id: Pubkey,
+    /// The resolved instruction groups.
+    pub instruction_groups: Vec<InstructionGroup>,
+    /// The guardian signatures account, once posted.
+    pub signatures_account: Option<Pubkey>,
+    /// Signatures of the executed groups, in execution order.
+    pub executed_groups: Vec<Signature>,
+    /// The last transaction sent for the next group, which may have landed
+    /// after the run stopped.
+    pub pending_group: Option<PendingTransaction>,
+}
+
+/// A sent transaction that was not confirmed yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingTransaction {
+    pub signature: Signature,
+    /// The transaction's blockhash; once it expires, the transaction can no
+    /// longer land.
+    pub blockhash: Hash,
+}
+
+/// Records broadcast state transitions.
+///
+/// A record is created by [`resolved`](Self::resolved), updated by the
+/// following transitions and removed by [`finished`](Self::finished).
+pub trait BroadcastStore {
+    /// The resolver returned `instruction_groups`; starts a new record.
+    fn resolved(
+        &self,
+        vaa_digest: &[u8; 32],
+        program_id: &Pubkey,
+        instruction_groups: &[InstructionGroup],
+    ) -> Result<(), SubmitError>;
+
+    /// Guardian signatures were posted to `signatures_account`.
+    fn signatures_posted(
+        &self,
+        vaa_digest: &[u8; 32],
+        signatures_account: &Pubkey,
+    ) -> Result<(), SubmitError>;
+
+    /// A transaction for the next instruction group is about to be sent.
+    ///
+    /// Until [`group_executed`](Self::group_executed), the transaction may
+    /// land without the broadcast noticing, so a resumed broadcast checks on
+    /// it before sending the group again.
+    fn group_sending(
+        &self,
+        vaa_digest: &[u8; 32],
+        signature: &Signature,
+        blockhash: &Hash,
+    ) -> Result<(), SubmitError>;
+
+    /// The next instruction group was executed with `signature`.
+    fn group_executed(
+        &self,
+        vaa_digest: &[u8; 32],
+        signature: &Signature,
+    ) -> Result<(), SubmitError>;
+
+    /// The broadcast completed or was abandoned; removes the record.
+    fn finished(&self, vaa_digest: &[u8; 32]) -> Result<(), SubmitError>;
+
+    /// The record of the broadcast of `vaa_digest`, if it is in flight.
+    fn load(&self, vaa_digest: &[u8; 32]) -> Result<Option<BroadcastRecord>, SubmitError>;
+
+    /// All in-flight broadcasts.
+    fn in_flight(&self) -> Result<Vec<BroadcastRecord>, SubmitError>;
+}
+
+/// A [`BroadcastStore`] that keeps one JSON file per in-flight broadcast,
+/// named after the hex VAA digest.
+///
+/// Files are replaced atomically (written to a temporary file, then renamed),
+/// so a crash mid-write leaves the previous state intact.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Use `dir` for the records, creating it if needed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, SubmitError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| store_error(&dir, e))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, vaa_digest: &[u8; 32]) -> PathBuf {
+        self.dir.join(format!("{}.json", hex::encode(vaa_digest)))
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<StoredRecord>, SubmitError> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|e| store_error(path, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(store_error(path, e)),
+        }
+    }
+
+    fn write(&self, path: &Path, record: &StoredRecord) -> Result<(), SubmitError> {
+        let json = serde_json::to_vec_pretty(record).map_err(|e| store_error(path, e))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|e| store_error(&tmp, e))?;
+        fs::rename(&tmp, path).map_err(|e| store_error(path, e))
+    }
+
+    /// Apply `update` to an existing record.
+    fn update(
+        &self,
+        vaa_digest: &[u8; 32],
+        update: impl FnOnce(&mut StoredRecord),
+    ) -> Result<(), SubmitError> {
+        let path = self.path(vaa_digest);
+        let mut record = self.read(&path)?.ok_or_else(|| {
+            SubmitError::Store(format!("no record for VAA {}", hex::encode(vaa_digest)))
+        })?;
+        update(&mut record);
+        self.write(&path, &record)
+    }
+}
+
+impl BroadcastStore for FileStore {
+    fn resolved(
+        &self,
+        vaa_digest: &[u8; 32],
+        program_id: &Pubkey,
+        instruction_groups: &[InstructionGroup],
+    ) -> Result<(), SubmitError> {
+        let path = self.path(vaa_digest);
+        let groups = instruction_groups
+            .try_to_vec()
+            .map_err(|e| store_error(&path, e))?;
+        let record = StoredRecord {
+            program_id: program_id.to_string(),
+            instruction_groups: base64::engine::general_purpose::STANDARD.encode(groups),
+            signatures_account: None,
+            executed_groups: Vec::new(),
+            pending_group: None,
+        };
+        self.write(&path, &record)
+    }
+
+    fn signatures_posted(
+        &self,
+        vaa_digest: &[u8; 32],
+        signatures_account: &Pubkey,
+    ) -> Result<(), SubmitError> {
+        self.update(vaa_digest, |record| {
+            record.signatures_account = Some(signatures_account.to_string());
+        })
+    }
+
+    fn group_sending(
+        &self,
+        vaa_digest: &[u8; 32],
+        signature: &Signature,
+        blockhash: &Hash,
+    ) -> Result<(), SubmitError> {
+        self.update(vaa_digest, |record| {
+            record.pending_group = Some(StoredPending {
+                signature: signature.to_string(),
+                blockhash: blockhash.to_string(),
+            });
+        })
+    }
+
+    fn group_executed(
+        &self,
+        vaa_digest: &[u8; 32],
+        signature: &Signature,
+    ) -> Result<(), SubmitError> {
+        self.update(vaa_digest, |record| {
+            record.executed_groups.push(signature.to_string());
+            record.pending_group = None;
+        })
+    }
+
+    fn finished(&self, vaa_digest: &[u8; 32]) -> Result<(), SubmitError> {
+        let path = self.path(vaa_digest);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(store_error(&path, e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn load(&self, vaa_digest: &[u8; 32]) -> Result<Option<BroadcastRecord>, SubmitError> {
+        let path = self.path(vaa_digest);
+        self.read(&path)?
+            .map(|record| record.decode(*vaa_digest, &path))
+            .transpose()
+    }
+
+    fn in_flight(&self) -> Result<Vec<BroadcastRecord>, SubmitError> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| store_error(&self.dir, e))?;
+        let mut records = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| store_error(&self.dir, e))?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(vaa_digest) = path
+                .file_stem()
+                .and_then(|stem| hex::decode(stem.to_string_lossy().as_ref()).ok())
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            else {
+                continue;
+            };
+            if let Some(record) = self.read(&path)? {
+                records.push(record.decode(vaa_digest, &path)?);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// On-disk form of a [`BroadcastRecord`]. Addresses and signatures are
+/// base58, the instruction groups borsh-serialized and base64-encoded.
+#[derive(Serialize, Deserialize)]
+struct StoredRecord {
+    program_id: String,
+    instruction_groups: String,
+    signatures_account: Option<String>,
+    executed_groups: Vec<String>,
+    #[serde(default)]
+    pending_group: Option<StoredPending>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredPending {
+    signature: String,
+    blockhash: String,
+}
+
+impl StoredRecord {
+    fn decode(self, vaa_digest: [u8; 32], path: &Path) -> Result<BroadcastRecord, SubmitError> {
+        let invalid = |what: &str| store_error(path, format!("invalid {}", what));
+
+        let groups = base64::engine::general_purpose::STANDARD
+            .decode(&self.instruction_groups)
+            .map_err(|_| invalid("instruction groups"))?;
+        Ok(BroadcastRecord {
+            vaa_digest,
+            program_id: Pubkey::from_str(&self.program_id).map_err(|_| invalid("program id"))?,
+            instruction_groups: Vec::<InstructionGroup>::try_from_slice(&groups)
+                .map_err(|_| invalid("instruction groups"))?,
+            signatures_account: self
+                .signatures_account
+                .as_deref()
+                .map(Pubkey::from_str)
+                .transpose()
+                .map_err(|_| invalid("signatures account"))?,
+            executed_groups: self
+                .executed_groups
+                .iter()
+                .map(|sig| Signature::from_str(sig))
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid("group signature"))?,
+            pending_group: self
+                .pending_group
+                .map(|pending| {
+                    Ok::<_, SubmitError>(PendingTransaction {
+                        signature: Signature::from_str(&pending.signature)
+                            .map_err(|_| invalid("pending signature"))?,
+                        blockhash: Hash::from_str(&pending.blockhash)
+                            .map_err(|_| invalid("pending blockhash"))?,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+fn store_error(path: &Path, e: impl std::fmt::Display) -> SubmitError {
+    SubmitError::Store(format!("{}: {}", path.display(), e))
+}
+
+/// Check that the groups still to execute don't share a generated keypair
+/// with the groups a previous run executed, since those keypairs are lost.
+pub(crate) fn ensure_resumable(
+    groups: &[InstructionGroup],
+    executed: usize,
+) -> Result<(), SubmitError> {
+    let uses = |groups: &[InstructionGroup], placeholder: &Pubkey| {
+        groups.iter().any(|group| {
+            group
+                .instructions
+                .iter()
+                .any(|ix| ix.accounts.iter().any(|a| a.pubkey == *placeholder))
+        })
+    };
+    let (done, remaining) = groups.split_at(executed.min(groups.len()));
+    if let Some(placeholder) = KEYPAIR_PLACEHOLDERS
+        .iter()
+        .find(|placeholder| uses(done, placeholder) && uses(remaining, placeholder))
+    {
+        return Err(SubmitError::Store(format!(
+            "Cannot resume after {} executed groups: keypair placeholder {} is shared with a \
+             remaining group, and the generated keypair was not persisted",
+            executed, placeholder
+        )));
+    }
+    Ok(())
+}
+
+/// Forwards execution events to another sink and records executed groups,
+/// correcting group indices for groups skipped on resume.
+pub(crate) struct RecordingSink<'a> {
+    pub store: &'a dyn BroadcastStore,
+    pub vaa_digest: [u8; 32],
+    pub skipped_groups: usize,
+    pub inner: Option<&'a dyn EventSink>,
+}
+
+impl EventSink for RecordingSink<'_> {
+    fn emit(&self, event: &BroadcastEvent) {
+        let event = match event {
+            BroadcastEvent::GroupExecuted {
+                group_index,
+                signature,
+                attempts,
+            } => {
+                // A signature from the event always parses; a store failure must
+                // not abort a group that already landed.
+                if let Ok(sig) = Signature::from_str(signature) {
+                    if let Err(e) = self.store.group_executed(&self.vaa_digest, &sig) {
+                        log::warn!("failed to record executed group: {}", e);
+                    }
+                }
+                BroadcastEvent::GroupExecuted {
+                    group_index: group_index + self.skipped_groups,
+                    signature: signature.clone(),
+                    attempts: *attempts,
+                }
+            }
+            other => other.clone(),
+        };
+        if let Some(sink) = self.inner {
+            sink.emit(&event);
+        }
+    }
+}
+
+/// Abandon every in-flight broadcast in `store`: close its signatures account
+/// (if still open) and remove the record.
+///
+/// Returns the digests of the abandoned broadcasts with the outcome of closing
+/// their signatures accounts.
+pub fn close_in_flight<C: SolanaConnection>(
+    conn: &mut C,
+    payer: &dyn Signer,
+    verify_vaa_shim: &Pubkey,
+    store: &dyn BroadcastStore,
+) -> Result<Vec<([u8; 32], Option<ClosedSignatures>)>, SubmitError> {
+    let mut abandoned = Vec::new();
+    for record in store.in_flight()? {
+        let closed = match &record.signatures_account {
+            Some(account) => Some(close_signatures(conn, payer, verify_vaa_shim, account)?),
+            None => None,
+        };
+        store.finished(&record.vaa_digest)?;
+        abandoned.push((record.vaa_digest, closed));
+    }
+    Ok(abandoned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::writable_group;
+    use executor_account_resolver_svm::RESOLVER_PUBKEY_KEYPAIR_00;
+
+    #[test]
+    fn test_file_store_transitions() {
+        let dir = std::env::temp_dir().join(format!("wormhole-svm-store-{}", Pubkey::new_unique()));
+        let store = FileStore::open(&dir).unwrap();
+        let digest = [3u8; 32];
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let groups = [writable_group(&[account]), writable_group(&[])];
+
+        assert!(store.load(&digest).unwrap().is_none());
+
+        store.resolved(&digest, &program_id, &groups).unwrap();
+        store.signatures_posted(&digest, &account).unwrap();
+
+        let pending = PendingTransaction {
+            signature: Signature::from([1u8; 64]),
+            blockhash: Hash::new_from_array([2; 32]),
+        };
+        store
+            .group_sending(&digest, &pending.signature, &pending.blockhash)
+            .unwrap();
+        assert_eq!(
+            store.load(&digest).unwrap().unwrap().pending_group,
+            Some(pending)
+        );
+
+        store
+            .group_executed(&digest, &Signature::from([1u8; 64]))
+            .unwrap();
+
+        let records = store.in_flight().unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.vaa_digest, digest);
+        assert_eq!(record.program_id, program_id);
+        assert_eq!(record.instruction_groups.len(), 2);
+        assert_eq!(
+            record.instruction_groups[0].instructions[0].accounts[0].pubkey,
+            account
+        );
+        assert_eq!(record.signatures_account, Some(account));
+        assert_eq!(record.executed_groups, vec![Signature::from([1u8; 64])]);
+        assert_eq!(record.pending_group, None);
+
+        store.finished(&digest).unwrap();
+        assert!(store.load(&digest).unwrap().is_none());
+        assert!(store.in_flight().unwrap().is_empty());
+        store.finished(&digest).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_ensure_resumable() {
+        let groups = [
+            writable_group(&[RESOLVER_PUBKEY_KEYPAIR_00]),
+            writable_group(&[]),
+            writable_group(&[RESOLVER_PUBKEY_KEYPAIR_00]),
+        ];
+
+        assert!(ensure_resumable(&groups, 0).is_ok());
+        assert!(matches!(
+            ensure_resumable(&groups, 1),
+            Err(SubmitError::Store(_))
+        ));
+        assert!(ensure_resumable(&groups, 3).is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+//! Fixtures shared by the unit tests.
+
+use std::collections::HashMap;
+
+use borsh::BorshSerialize;
+use executor_account_resolver_svm::{InstructionGroups, Resolver};
+use solana_sdk::{
+    account::Account,
+    clock::{Clock, Slot},
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    sysvar,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::connection::{SimulationResult, SolanaConnection};
+use crate::resolve::{InstructionGroup, SerializableAccountMeta, SerializableInstruction};
+
+/// A group with one instruction to `program_id`; `accounts` are
+/// `(pubkey, writable, signer)`.
+pub(crate) fn group(program_id: Pubkey, accounts: &[(Pubkey, bool, bool)]) -> InstructionGroup {
+    InstructionGroup {
+        instructions: vec![SerializableInstruction {
+            program_id,
+            accounts: accounts
+                .iter()
+                .map(
+                    |&(pubkey, is_writable, is_signer)| SerializableAccountMeta {
+                        pubkey,
+                        is_signer,
+                        is_writable,
+                    },
+                )
+                .collect(),
+            data: vec![],
+        }],
+    }
+}
+
+/// A group with one instruction to a fresh program, writing `accounts`
+/// without signing for them.
+pub(crate) fn writable_group(accounts: &[Pubkey]) -> InstructionGroup {
+    let accounts: Vec<_> = accounts
+        .iter()
+        .map(|&pubkey| (pubkey, true, false))
+        .collect();
+    group(Pubkey::new_unique(), &accounts)
+}
+
+/// A group with one instruction to a fresh program, writing `accounts` and
+/// signing for each of them.
+pub(crate) fn signer_group(accounts: &[Pubkey]) -> InstructionGroup {
+    let accounts: Vec<_> = accounts
+        .iter()
+        .map(|&pubkey| (pubkey, true, true))
+        .collect();
+    group(Pubkey::new_unique(), &accounts)
+}
+
+/// What [`MockConnection`] does with a sent transaction.
+pub(crate) enum SendOutcome {
+    /// Lands and is confirmed.
+    Confirm,
+    /// Never lands; the send fails with this message.
+    Drop(String),
+    /// Lands, but the send fails with this message anyway (e.g. a timeout
+    /// while waiting for confirmation).
+    LandUnconfirmed(String),
+}
+
+#[derive(Debug)]
+pub(crate) struct MockError(pub String);
+
+impl std::fmt::Display for MockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for MockError {}
+
+/// A cluster that records what is sent to it and answers from canned state.
+pub(crate) struct MockConnection {
+    pub blockhash: Hash,
+    /// Whether `blockhash` (and any earlier one) can still land.
+    pub blockhash_valid: bool,
+    pub slot: Slot,
+    pub accounts: HashMap<Pubkey, Account>,
+    /// Every transaction passed to a send method, in order.
+    pub sent: Vec<VersionedTransaction>,
+    /// Transactions that landed, by signature.
+    pub landed: HashMap<Signature, Result<(), String>>,
+    /// Decides the fate of each sent transaction; confirms all by default.
+    pub on_send: Box<dyn FnMut(&VersionedTransaction) -> SendOutcome>,
+    /// Answers simulations; fails them by default.
+    pub on_simulate: Box<dyn Fn(&Transaction) -> Result<SimulationResult, String>>,
+    /// When set, every call fails with this message, like an unreachable
+    /// endpoint.
+    pub error: Option<String>,
+}
+
+impl MockConnection {
+    pub(crate) fn new() -> Self {
+        Self {
+            blockhash: Hash::new_unique(),
+            blockhash_valid: true,
+            slot: 1,
+            accounts: HashMap::new(),
+            sent: Vec::new(),
+            landed: HashMap::new(),
+            on_send: Box::new(|_| SendOutcome::Confirm),
+            on_simulate: Box::new(|_| Err("simulation not mocked".to_string())),
+            error: None,
+        }
+    }
+
+    fn check_available(&self) -> Result<(), MockError> {
+        match &self.error {
+            Some(message) => Err(MockError(message.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Answer resolver simulations with `groups`, resolved on the first
+    /// iteration.
+    pub(crate) fn resolve_to(&mut self, groups: Vec<InstructionGroup>) {
+        let return_data = Resolver::Resolved(InstructionGroups(groups))
+            .try_to_vec()
+            .unwrap();
+        self.on_simulate = Box::new(move |_| {
+            Ok(SimulationResult {
+                return_data: Some(return_data.clone()),
+                post_accounts: vec![],
+                units_consumed: None,
+                logs: vec![],
+            })
+        });
+    }
+
+    fn send(&mut self, tx: VersionedTransaction) -> Result<Signature, MockError> {
+        self.check_available()?;
+        let signature = tx.signatures[0];
+        let outcome = (self.on_send)(&tx);
+        self.sent.push(tx);
+        match outcome {
+            SendOutcome::Confirm => {
+                self.landed.insert(signature, Ok(()));
+                Ok(signature)
+            }
+            SendOutcome::Drop(message) => Err(MockError(message)),
+            SendOutcome::LandUnconfirmed(message) => {
+                self.landed.insert(signature, Ok(()));
+                Err(MockError(message))
+            }
+        }
+    }
+}
+
+impl SolanaConnection for MockConnection {
+    type Error = MockError;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
+        self.check_available()?;
+        Ok(self.blockhash)
+    }
+
+    fn simulate_with_post_accounts(
+        &self,
+        tx: &Transaction,
+        _accounts: &[Pubkey],
+    ) -> Result<SimulationResult, Self::Error> {
+        self.check_available()?;
+        (self.on_simulate)(tx).map_err(MockError)
+    }
+
+    fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+        self.send(tx.clone().into())
+    }
+
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.send(tx.clone())
+    }
+
+    fn supports_versioned_transactions(&self) -> bool {
+        true
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), String>>, Self::Error> {
+        self.check_available()?;
+        Ok(self.landed.get(signature).cloned())
+    }
+
+    fn is_blockhash_valid(&self, _blockhash: &Hash) -> Result<bool, Self::Error> {
+        self.check_available()?;
+        Ok(self.blockhash_valid)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
+        self.check_available()?;
+        if *pubkey == sysvar::clock::ID {
+            return Ok(Some(solana_sdk::account::create_account_for_test(&Clock {
+                slot: self.slot,
+                ..Clock::default()
+            })));
+        }
+        Ok(self.accounts.get(pubkey).cloned())
+    }
+
+    fn advance_slot(&mut self) -> Result<(), Self::Error> {
+        self.slot += 1;
+        Ok(())
+    }
+}
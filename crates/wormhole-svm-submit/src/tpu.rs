@@ -0,0 +1,268 @@
+//! Direct-to-leader transaction submission over QUIC.
+//!
+//! [`TpuConnection`] sends transactions straight to the TPU ports of the
+//! current and upcoming leaders instead of through RPC `sendTransaction`,
+//! which lands more reliably on congested slots. Blockhashes, simulation,
+//! account reads and confirmation polling still go through RPC.
+//!
+//! Requires the `tpu` feature.
+
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcError;
+use solana_client::tpu_client::{TpuClient, TpuClientConfig};
+use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use crate::connection::{SimulationResult, SolanaConnection, TransactionFailure};
+use crate::SubmitError;
+
+/// How long to wait for confirmation before resending to the leaders.
+const RESEND_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often to poll the signature status while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A [`SolanaConnection`] that sends transactions to leader TPUs over QUIC.
+pub struct TpuConnection {
+    rpc_client: Arc<RpcClient>,
+    tpu_client: TpuClient<QuicPool, QuicConnectionManager, QuicConfig>,
+}
+
+impl TpuConnection {
+    /// Connect to the leader schedule via `rpc_client` and `websocket_url`
+    /// (the RPC node's pubsub endpoint, used to track the current slot).
+    pub fn new(rpc_client: Arc<RpcClient>, websocket_url: &str) -> Result<Self, SubmitError> {
+        let tpu_client = TpuClient::new(
+            "wormhole-svm-submit",
+            rpc_client.clone(),
+            websocket_url,
+            TpuClientConfig::default(),
+        )
+        .map_err(|e| SubmitError::Connection(format!("failed to create TPU client: {}", e)))?;
+        Ok(Self {
+            rpc_client,
+            tpu_client,
+        })
+    }
+
+    /// The RPC client used for everything other than sending.
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_client
+    }
+
+    fn send_wire_and_confirm<T: serde::Serialize>(
+        &mut self,
+        tx: &T,
+        signature: Signature,
+        recent_blockhash: &Hash,
+    ) -> Result<Signature, ClientError> {
+        let wire_transaction = bincode::serialize(tx).map_err(|e| {
+            ClientError::from(RpcError::ForUser(format!(
+                "Failed to serialize transaction: {}",
+                e
+            )))
+        })?;
+        let commitment = CommitmentConfig::confirmed();
+        let rpc_client = &self.rpc_client;
+        let tpu_client = &self.tpu_client;
+
+        let landed = resend_until_landed(
+            || {
+                tpu_client
+                    .try_send_wire_transaction(wire_transaction.clone())
+                    .map_err(ClientError::from)
+            },
+            || {
+                Ok(rpc_client
+                    .get_signature_status_with_commitment(&signature, commitment)?
+                    .map(|status| status.map_err(ClientError::from)))
+            },
+            || rpc_client.is_blockhash_valid(recent_blockhash, CommitmentConfig::processed()),
+            RESEND_INTERVAL,
+            POLL_INTERVAL,
+        )?;
+        if !landed {
+            return Err(ClientError::from(RpcError::ForUser(format!(
+                "Transaction {} expired before confirmation",
+                signature
+            ))));
+        }
+        Ok(signature)
+    }
+}
+
+/// Send with `send` every `resend_interval`, polling `status` every
+/// `poll_interval` in between, until the transaction is confirmed (`true`)
+/// or `blockhash_valid` reports its blockhash expired (`false`).
+fn resend_until_landed<E>(
+    mut send: impl FnMut() -> Result<(), E>,
+    mut status: impl FnMut() -> Result<Option<Result<(), E>>, E>,
+    mut blockhash_valid: impl FnMut() -> Result<bool, E>,
+    resend_interval: Duration,
+    poll_interval: Duration,
+) -> Result<bool, E> {
+    loop {
+        send()?;
+
+        let sent_at = Instant::now();
+        while sent_at.elapsed() < resend_interval {
+            match status()? {
+                Some(Ok(())) => return Ok(true),
+                Some(Err(e)) => return Err(e),
+                None => sleep(poll_interval),
+            }
+        }
+
+        if !blockhash_valid()? {
+            return Ok(false);
+        }
+    }
+}
+
+impl SolanaConnection for TpuConnection {
+    type Error = ClientError;
+
+    fn get_latest_blockhash(&self) -> Result<Hash, Self::Error> {
+        self.rpc_client.get_latest_blockhash()
+    }
+
+    fn simulate_with_post_accounts(
+        &self,
+        tx: &Transaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult, Self::Error> {
+        SolanaConnection::simulate_with_post_accounts(self.rpc_client.as_ref(), tx, accounts)
+    }
+
+    /// Send to the leaders, resending every few seconds until the transaction
+    /// is confirmed or its blockhash expires.
+    fn send_and_confirm(&mut self, tx: &Transaction) -> Result<Signature, Self::Error> {
+        self.send_wire_and_confirm(tx, tx.signatures[0], &tx.message.recent_blockhash)
+    }
+
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.send_wire_and_confirm(tx, tx.signatures[0], tx.message.recent_blockhash())
+    }
+
+    fn supports_versioned_transactions(&self) -> bool {
+        true
+    }
+
+    fn transaction_failure(&self, error: &ClientError) -> TransactionFailure {
+        SolanaConnection::transaction_failure(self.rpc_client.as_ref(), error)
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<Result<(), String>>, Self::Error> {
+        SolanaConnection::get_signature_status(self.rpc_client.as_ref(), signature)
+    }
+
+    fn is_blockhash_valid(&self, blockhash: &Hash) -> Result<bool, Self::Error> {
+        SolanaConnection::is_blockhash_valid(self.rpc_client.as_ref(), blockhash)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
+        SolanaConnection::get_account(self.rpc_client.as_ref(), pubkey)
+    }
+
+    fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>, Self::Error> {
+        SolanaConnection::get_multiple_accounts(self.rpc_client.as_ref(), pubkeys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+    use crate::test_support::MockConnection;
+
+    /// Resend a transaction whose status and blockhash come from `conn`,
+    /// counting the sends; `landing` is which send (if any) lands it, and
+    /// with what result.
+    fn resend(
+        conn: &RefCell<MockConnection>,
+        landing: Option<(usize, Result<(), String>)>,
+    ) -> (Result<bool, String>, usize) {
+        let signature = Signature::new_unique();
+        let blockhash = conn.borrow().blockhash;
+        let sends = Cell::new(0);
+        let landed = resend_until_landed(
+            || {
+                sends.set(sends.get() + 1);
+                if let Some((send, result)) = &landing {
+                    if *send == sends.get() {
+                        conn.borrow_mut().landed.insert(signature, result.clone());
+                    }
+                }
+                Ok(())
+            },
+            || {
+                conn.borrow()
+                    .get_signature_status(&signature)
+                    .map_err(|e| e.to_string())
+            },
+            || {
+                conn.borrow()
+                    .is_blockhash_valid(&blockhash)
+                    .map_err(|e| e.to_string())
+            },
+            Duration::from_millis(5),
+            Duration::from_millis(1),
+        );
+        (landed, sends.get())
+    }
+
+    #[test]
+    fn test_confirmed_transaction_is_not_resent() {
+        let conn = RefCell::new(MockConnection::new());
+
+        assert_eq!(resend(&conn, Some((1, Ok(())))), (Ok(true), 1));
+    }
+
+    #[test]
+    fn test_unconfirmed_transaction_is_resent() {
+        let conn = RefCell::new(MockConnection::new());
+
+        assert_eq!(resend(&conn, Some((3, Ok(())))), (Ok(true), 3));
+    }
+
+    #[test]
+    fn test_failed_transaction_is_not_resent() {
+        let conn = RefCell::new(MockConnection::new());
+        let failed = Err("custom program error: 0x1".to_string());
+
+        assert_eq!(
+            resend(&conn, Some((1, failed.clone()))),
+            (failed.map(|_| true), 1)
+        );
+    }
+
+    #[test]
+    fn test_resending_stops_when_blockhash_expires() {
+        let conn = RefCell::new(MockConnection::new());
+        conn.borrow_mut().blockhash_valid = false;
+
+        assert_eq!(resend(&conn, None), (Ok(false), 1));
+    }
+}
@@ -0,0 +1,216 @@
+//! Signed VAA parsing.
+//!
+//! Splits a signed VAA into the pieces the submission flow needs: the guardian
+//! set index, the guardian signatures (posted to the Verify VAA Shim), and the
+//! body (passed to the resolver and the target program).
+
+use sha3::{Digest, Keccak256};
+
+use crate::SubmitError;
+
+/// Length of the fixed VAA header: version (1) + guardian set index (4) + signature count (1).
+const HEADER_LEN: usize = 6;
+
+/// Length of a single guardian signature: guardian index (1) + signature (65).
+const SIGNATURE_LEN: usize = 66;
+
+/// Length of the VAA body before the payload: timestamp (4) + nonce (4) +
+/// emitter chain (2) + emitter address (32) + sequence (8) + consistency level (1).
+const BODY_HEADER_LEN: usize = 51;
+
+/// A signed VAA split into header fields and body.
+#[derive(Clone, Debug)]
+pub struct SignedVaa {
+    /// The guardian set index that signed the VAA.
+    pub guardian_set_index: u32,
+    /// Guardian signatures (66 bytes each: [index, r, s, v]).
+    pub signatures: Vec<[u8; 66]>,
+    /// The VAA body bytes (without header/signatures).
+    pub body: Vec<u8>,
+}
+
+impl SignedVaa {
+    /// Parse a signed VAA.
+    ///
+    /// Only version 1 VAAs are supported.
+    pub fn parse(raw: &[u8]) -> Result<Self, SubmitError> {
+        if raw.is_empty() {
+            return Err(SubmitError::InvalidVaa("empty VAA".to_string()));
+        }
+        if raw[0] != 1 {
+            return Err(SubmitError::InvalidVaa(format!(
+                "unsupported VAA version: {}",
+                raw[0]
+            )));
+        }
+        if raw.len() < HEADER_LEN {
+            return Err(SubmitError::InvalidVaa(
+                "VAA too short to contain header".to_string(),
+            ));
+        }
+
+        let guardian_set_index = u32::from_be_bytes(raw[1..5].try_into().unwrap());
+        let sig_count = raw[5] as usize;
+        let body_offset = HEADER_LEN + sig_count * SIGNATURE_LEN;
+
+        if raw.len() < body_offset {
+            return Err(SubmitError::InvalidVaa(format!(
+                "VAA truncated: expected at least {} bytes for {} signatures, got {}",
+                body_offset,
+                sig_count,
+                raw.len()
+            )));
+        }
+
+        let signatures = raw[HEADER_LEN..body_offset]
+            .chunks_exact(SIGNATURE_LEN)
+            .map(|chunk| chunk.try_into().unwrap())
+            .collect();
+
+        Ok(Self {
+            guardian_set_index,
+            signatures,
+            body: raw[body_offset..].to_vec(),
+        })
+    }
+}
+
+/// The fields of a VAA body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaaBody {
+    pub timestamp: u32,
+    pub nonce: u32,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub consistency_level: u8,
+    pub payload: Vec<u8>,
+}
+
+impl VaaBody {
+    /// Parse a VAA body (the bytes after the signatures).
+    pub fn parse(body: &[u8]) -> Result<Self, SubmitError> {
+        if body.len() < BODY_HEADER_LEN {
+            return Err(SubmitError::InvalidVaa(format!(
+                "VAA body too short: expected at least {} bytes, got {}",
+                BODY_HEADER_LEN,
+                body.len()
+            )));
+        }
+
+        Ok(Self {
+            timestamp: u32::from_be_bytes(body[0..4].try_into().unwrap()),
+            nonce: u32::from_be_bytes(body[4..8].try_into().unwrap()),
+            emitter_chain: u16::from_be_bytes(body[8..10].try_into().unwrap()),
+            emitter_address: body[10..42].try_into().unwrap(),
+            sequence: u64::from_be_bytes(body[42..50].try_into().unwrap()),
+            consistency_level: body[50],
+            payload: body[BODY_HEADER_LEN..].to_vec(),
+        })
+    }
+}
+
+/// Compute the message hash (keccak256 of the body), which the Core Bridge
+/// uses to derive posted VAA accounts.
+pub fn vaa_message_hash(body: &[u8]) -> [u8; 32] {
+    Keccak256::digest(body).into()
+}
+
+/// Compute the VAA digest (double keccak256 of the body), as signed by the guardians.
+pub fn vaa_digest(body: &[u8]) -> [u8; 32] {
+    Keccak256::digest(vaa_message_hash(body)).into()
+}
+
+/// Memo text identifying a VAA: `wormhole:<chain>/<emitter>/<sequence> digest:<digest>`,
+/// with the emitter address and digest hex-encoded.
+pub fn vaa_memo(body: &[u8]) -> Result<String, SubmitError> {
+    let parsed = VaaBody::parse(body)?;
+    Ok(format!(
+        "wormhole:{}/{}/{} digest:{}",
+        parsed.emitter_chain,
+        hex::encode(parsed.emitter_address),
+        parsed.sequence,
+        hex::encode(vaa_digest(body))
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_signed_vaa(guardian_set_index: u32, sig_count: u8, body: &[u8]) -> Vec<u8> {
+        let mut raw = vec![1];
+        raw.extend_from_slice(&guardian_set_index.to_be_bytes());
+        raw.push(sig_count);
+        for i in 0..sig_count {
+            let mut sig = [0xAA; 66];
+            sig[0] = i;
+            raw.extend_from_slice(&sig);
+        }
+        raw.extend_from_slice(body);
+        raw
+    }
+
+    #[test]
+    fn test_parse_signed_vaa() {
+        let raw = build_signed_vaa(4, 2, b"body bytes");
+
+        let vaa = SignedVaa::parse(&raw).unwrap();
+
+        assert_eq!(vaa.guardian_set_index, 4);
+        assert_eq!(vaa.signatures.len(), 2);
+        assert_eq!(vaa.signatures[0][0], 0);
+        assert_eq!(vaa.signatures[1][0], 1);
+        assert_eq!(vaa.body, b"body bytes");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_vaa() {
+        assert!(SignedVaa::parse(&[]).is_err());
+
+        // Wrong version
+        let mut raw = build_signed_vaa(0, 1, b"body");
+        raw[0] = 2;
+        assert!(SignedVaa::parse(&raw).is_err());
+
+        // Truncated header
+        assert!(SignedVaa::parse(&[1, 0, 0]).is_err());
+
+        // Truncated signatures
+        let raw = build_signed_vaa(0, 2, b"");
+        assert!(SignedVaa::parse(&raw[..raw.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_vaa_body_and_memo() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&1234567890u32.to_be_bytes());
+        body.extend_from_slice(&7u32.to_be_bytes());
+        body.extend_from_slice(&2u16.to_be_bytes());
+        body.extend_from_slice(&[0xAB; 32]);
+        body.extend_from_slice(&42u64.to_be_bytes());
+        body.push(1);
+        body.extend_from_slice(b"payload");
+
+        let parsed = VaaBody::parse(&body).unwrap();
+        assert_eq!(parsed.timestamp, 1234567890);
+        assert_eq!(parsed.nonce, 7);
+        assert_eq!(parsed.emitter_chain, 2);
+        assert_eq!(parsed.emitter_address, [0xAB; 32]);
+        assert_eq!(parsed.sequence, 42);
+        assert_eq!(parsed.consistency_level, 1);
+        assert_eq!(parsed.payload, b"payload");
+
+        let memo = vaa_memo(&body).unwrap();
+        assert_eq!(
+            memo,
+            format!(
+                "wormhole:2/{}/42 digest:{}",
+                "ab".repeat(32),
+                hex::encode(vaa_digest(&body))
+            )
+        );
+
+        assert!(VaaBody::parse(&body[..50]).is_err());
+    }
+}
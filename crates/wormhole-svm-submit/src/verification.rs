@@ -0,0 +1,95 @@
+//! How a broadcast makes the guardian signatures of a VAA available on chain.
+//!
+//! The broadcast flow only needs three things from VAA verification: posting
+//! the guardian signatures before execution, the account the resolved
+//! instructions reference in place of `RESOLVER_PUBKEY_SHIM_VAA_SIGS`, and
+//! cleaning up afterwards. [`VerificationBackend`] captures those steps, so
+//! [`broadcast_vaa_with_backend`](crate::broadcast_vaa_with_backend) works
+//! unchanged with other verification programs (legacy core bridge
+//! verification, future shim versions or custom programs).
+//!
+//! [`ShimBackend`] implements them for the Verify VAA Shim and is what
+//! [`broadcast_vaa_with_config`](crate::broadcast_vaa_with_config) uses.
+
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use crate::connection::SolanaConnection;
+use crate::resolve::InstructionGroup;
+use crate::signatures::{self, ClosedSignatures};
+use crate::vaa::SignedVaa;
+use crate::SubmitError;
+
+/// Verification steps of the broadcast flow.
+pub trait VerificationBackend {
+    /// Check, before anything is sent, that the resolved instructions can be
+    /// verified with this backend.
+    fn check_groups(&self, groups: &[InstructionGroup]) -> Result<(), SubmitError>;
+
+    /// Post the guardian signatures of `vaa` and return the created account.
+    fn post<C: SolanaConnection>(
+        &self,
+        conn: &mut C,
+        payer: &dyn Signer,
+        vaa: &SignedVaa,
+    ) -> Result<Pubkey, SubmitError>;
+
+    /// The account substituted for `RESOLVER_PUBKEY_SHIM_VAA_SIGS`, given the
+    /// account returned by [`post`](Self::post).
+    fn reference_account(&self, posted: &Pubkey) -> Pubkey {
+        *posted
+    }
+
+    /// Reclaim the posted account once the instructions have executed (or
+    /// failed). Must succeed if the account no longer exists.
+    fn cleanup<C: SolanaConnection>(
+        &self,
+        conn: &mut C,
+        payer: &dyn Signer,
+        posted: &Pubkey,
+    ) -> Result<ClosedSignatures, SubmitError>;
+}
+
+/// Verification through the Wormhole Verify VAA Shim: signatures are posted
+/// to a guardian signatures account, which is closed afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShimBackend {
+    /// Verify VAA Shim program ID.
+    pub verify_vaa_shim: Pubkey,
+}
+
+impl ShimBackend {
+    pub fn new(verify_vaa_shim: Pubkey) -> Self {
+        Self { verify_vaa_shim }
+    }
+}
+
+impl VerificationBackend for ShimBackend {
+    fn check_groups(&self, groups: &[InstructionGroup]) -> Result<(), SubmitError> {
+        crate::ensure_uses_verify_vaa_shim(groups)
+    }
+
+    fn post<C: SolanaConnection>(
+        &self,
+        conn: &mut C,
+        payer: &dyn Signer,
+        vaa: &SignedVaa,
+    ) -> Result<Pubkey, SubmitError> {
+        signatures::post_signatures(
+            conn,
+            payer,
+            &self.verify_vaa_shim,
+            vaa.guardian_set_index,
+            &vaa.signatures,
+        )
+        .map(|posted| posted.pubkey)
+    }
+
+    fn cleanup<C: SolanaConnection>(
+        &self,
+        conn: &mut C,
+        payer: &dyn Signer,
+        posted: &Pubkey,
+    ) -> Result<ClosedSignatures, SubmitError> {
+        signatures::close_signatures(conn, payer, &self.verify_vaa_shim, posted)
+    }
+}
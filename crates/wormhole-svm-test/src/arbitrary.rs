@@ -0,0 +1,203 @@
+//! proptest support: [`Arbitrary`] for [`TestVaa`] and [`TestGuardianSet`],
+//! and the strategies behind them.
+//!
+//! ```ignore
+//! use proptest::prelude::*;
+//! use wormhole_svm_test::{TestGuardianSet, TestVaa, TestVaaParams};
+//!
+//! proptest! {
+//!     #[test]
+//!     fn parses_any_vaa(vaa: TestVaa, guardians: TestGuardianSet) {
+//!         let signed = vaa.sign(&guardians);
+//!         prop_assert!(my_program::parse(&signed).is_ok());
+//!     }
+//!
+//!     #[test]
+//!     fn rejects_other_emitters(
+//!         vaa in any_with::<TestVaa>(TestVaaParams {
+//!             emitter_chain: Some(2),
+//!             ..Default::default()
+//!         }),
+//!     ) {
+//!         // ...
+//!     }
+//! }
+//! ```
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::{emitter_address_from_20, TestGuardianSet, TestVaa};
+
+/// Chain IDs [`arb_chain_id`] favors: Solana, Ethereum, BSC, Polygon, Avalanche,
+/// Fantom, Sui, Aptos, Arbitrum, Optimism and Base.
+const KNOWN_CHAINS: &[u16] = &[1, 2, 4, 5, 6, 10, 21, 22, 23, 24, 30];
+
+/// Consistency levels seen in practice: confirmed, finalized and instant.
+const KNOWN_CONSISTENCY_LEVELS: &[u8] = &[1, 32, 200];
+
+/// Wormhole chain IDs: mostly well-known chains, sometimes any u16.
+pub fn arb_chain_id() -> impl Strategy<Value = u16> {
+    prop_oneof![
+        3 => select(KNOWN_CHAINS),
+        1 => any::<u16>(),
+    ]
+}
+
+/// Emitter addresses: EVM-style (20 bytes, left-padded) or full 32 bytes.
+pub fn arb_emitter_address() -> impl Strategy<Value = [u8; 32]> {
+    prop_oneof![
+        any::<[u8; 20]>().prop_map(emitter_address_from_20),
+        any::<[u8; 32]>(),
+    ]
+}
+
+/// Sequences: small ones, as a fresh emitter has, or any u64.
+pub fn arb_sequence() -> impl Strategy<Value = u64> {
+    prop_oneof![0..1_000u64, any::<u64>()]
+}
+
+/// Payloads of up to `max_len` bytes, biased towards empty and short ones.
+pub fn arb_payload(max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(Vec::new()),
+        vec(any::<u8>(), 0..=max_len.min(64)),
+        vec(any::<u8>(), 0..=max_len),
+    ]
+}
+
+/// Consistency levels: mostly known ones, sometimes any u8.
+pub fn arb_consistency_level() -> impl Strategy<Value = u8> {
+    prop_oneof![
+        3 => select(KNOWN_CONSISTENCY_LEVELS),
+        1 => any::<u8>(),
+    ]
+}
+
+/// Parameters of the [`TestVaa`] strategy.
+#[derive(Clone, Debug)]
+pub struct TestVaaParams {
+    /// Longest payload to generate (defaults to 1024 bytes).
+    pub max_payload_len: usize,
+    /// Fix the emitter chain instead of generating it.
+    pub emitter_chain: Option<u16>,
+    /// Fix the emitter address instead of generating it.
+    pub emitter_address: Option<[u8; 32]>,
+}
+
+impl Default for TestVaaParams {
+    fn default() -> Self {
+        Self {
+            max_payload_len: 1024,
+            emitter_chain: None,
+            emitter_address: None,
+        }
+    }
+}
+
+impl Arbitrary for TestVaa {
+    type Parameters = TestVaaParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    /// VAAs with generated header fields and payload. The guardian set index
+    /// and `with_vaa` checks keep their defaults.
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        let emitter_chain = match params.emitter_chain {
+            Some(chain) => Just(chain).boxed(),
+            None => arb_chain_id().boxed(),
+        };
+        let emitter_address = match params.emitter_address {
+            Some(address) => Just(address).boxed(),
+            None => arb_emitter_address().boxed(),
+        };
+        (
+            emitter_chain,
+            emitter_address,
+            arb_sequence(),
+            arb_payload(params.max_payload_len),
+            any::<u32>(),
+            any::<u32>(),
+            arb_consistency_level(),
+        )
+            .prop_map(
+                |(chain, address, sequence, payload, timestamp, nonce, consistency_level)| {
+                    TestVaa::new(chain, address, sequence, payload)
+                        .with_timestamp(timestamp)
+                        .with_nonce(nonce)
+                        .with_consistency_level(consistency_level)
+                },
+            )
+            .boxed()
+    }
+}
+
+/// Parameters of the [`TestGuardianSet`] strategy.
+#[derive(Clone, Debug)]
+pub struct TestGuardianSetParams {
+    /// Fewest guardians to generate (defaults to 1).
+    pub min_guardians: usize,
+    /// Most guardians to generate (defaults to 19, mainnet's size).
+    pub max_guardians: usize,
+}
+
+impl Default for TestGuardianSetParams {
+    fn default() -> Self {
+        Self {
+            min_guardians: 1,
+            max_guardians: crate::MAINNET_GUARDIAN_COUNT,
+        }
+    }
+}
+
+impl Arbitrary for TestGuardianSet {
+    type Parameters = TestGuardianSetParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Deterministically generated guardian sets of a generated size and seed.
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        (params.min_guardians..=params.max_guardians, any::<u64>())
+            .prop_map(|(count, seed)| TestGuardianSet::generate(count, seed))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_arbitrary_vaa_body(vaa: TestVaa) {
+            // Header is 51 bytes, then the payload
+            let body = vaa.body();
+            prop_assert_eq!(body.len(), 51 + vaa.payload.len());
+            prop_assert!(vaa.payload.len() <= 1024);
+        }
+
+        #[test]
+        fn test_fixed_emitter(
+            vaa in any_with::<TestVaa>(TestVaaParams {
+                emitter_chain: Some(2),
+                emitter_address: Some([0xAB; 32]),
+                max_payload_len: 8,
+            }),
+        ) {
+            prop_assert_eq!(vaa.emitter_chain, 2);
+            prop_assert_eq!(vaa.emitter_address, [0xAB; 32]);
+            prop_assert!(vaa.payload.len() <= 8);
+        }
+
+        #[test]
+        fn test_arbitrary_guardian_set_signs(
+            guardians in any_with::<TestGuardianSet>(TestGuardianSetParams {
+                min_guardians: 1,
+                max_guardians: 5,
+            }),
+        ) {
+            prop_assert!((1..=5).contains(&guardians.len()));
+            let signatures = guardians.sign_vaa_body(b"body");
+            prop_assert_eq!(signatures.len(), guardians.len());
+        }
+    }
+}
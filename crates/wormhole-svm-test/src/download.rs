@@ -0,0 +1,223 @@
+//! Downloading of program binaries that are neither given nor bundled.
+//!
+//! With the `fixture-download` feature, [`load_wormhole_programs`] falls back
+//! to fetching a missing program from an RPC node, as `solana program dump`
+//! would, and keeps it in a cache directory so that later runs (and other
+//! tests of the same run) don't fetch it again. Opt in per config with
+//! [`WormholeProgramsConfig::download`], or for every test with environment
+//! variables:
+//!
+//! - `WORMHOLE_FIXTURES_RPC_URL`: RPC URL to download from
+//! - `WORMHOLE_FIXTURES_CACHE_DIR`: cache directory (default: a
+//!   `wormhole-svm-test-fixtures` directory in the system temp directory)
+//!
+//! Program hashes are the sha256 of the program without its trailing zero
+//! padding, as printed by `solana-verify get-program-hash` and
+//! `svm-vaa program-info`. Cached binaries with another hash than the pinned
+//! one are downloaded again; downloads with another hash are rejected.
+//!
+//! [`load_wormhole_programs`]: crate::load_wormhole_programs
+//! [`WormholeProgramsConfig::download`]: crate::WormholeProgramsConfig
+
+use std::path::PathBuf;
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{hash::hash, pubkey::Pubkey};
+
+use crate::litesvm::WormholeTestError;
+
+/// Where and how to download missing program binaries.
+#[derive(Clone, Debug)]
+pub struct FixtureDownload {
+    /// RPC URL to fetch programs from.
+    pub rpc_url: String,
+    /// Directory keeping downloaded binaries, one subdirectory per RPC URL.
+    pub cache_dir: PathBuf,
+    /// Expected program hashes (hex), by program ID.
+    pub expected_sha256: Vec<(Pubkey, String)>,
+}
+
+impl FixtureDownload {
+    /// Download from `rpc_url` into the default cache directory, without
+    /// pinned hashes.
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            cache_dir: std::env::temp_dir().join("wormhole-svm-test-fixtures"),
+            expected_sha256: Vec::new(),
+        }
+    }
+
+    /// The download configured by `WORMHOLE_FIXTURES_RPC_URL` and
+    /// `WORMHOLE_FIXTURES_CACHE_DIR`; `None` if no RPC URL is set.
+    pub fn from_env() -> Option<Self> {
+        let rpc_url = std::env::var("WORMHOLE_FIXTURES_RPC_URL").ok()?;
+        let download = Self::new(rpc_url);
+        match std::env::var("WORMHOLE_FIXTURES_CACHE_DIR") {
+            Ok(dir) => Some(download.cache_dir(dir)),
+            Err(_) => Some(download),
+        }
+    }
+
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = dir.into();
+        self
+    }
+
+    /// Only accept the program at `program_id` with hash `sha256` (hex).
+    pub fn expect_sha256(mut self, program_id: Pubkey, sha256: impl Into<String>) -> Self {
+        self.expected_sha256.push((program_id, sha256.into()));
+        self
+    }
+
+    /// The binary of `program_id`, from the cache or downloaded into it.
+    pub(crate) fn program(&self, program_id: &Pubkey) -> Result<Vec<u8>, WormholeTestError> {
+        let expected = self
+            .expected_sha256
+            .iter()
+            .find(|(id, _)| id == program_id)
+            .map(|(_, sha256)| sha256.as_str());
+        let matches = |bytes: &[u8]| {
+            expected.is_none_or(|expected| program_hash(bytes).eq_ignore_ascii_case(expected))
+        };
+
+        let path = self.cache_path(program_id);
+        if let Ok(bytes) = std::fs::read(&path) {
+            if matches(&bytes) {
+                return Ok(bytes);
+            }
+        }
+
+        let bytes = self.fetch(program_id)?;
+        if !matches(&bytes) {
+            return Err(WormholeTestError::LoadError(format!(
+                "{} from {} has sha256 {}, expected {}",
+                program_id,
+                self.rpc_url,
+                program_hash(&bytes),
+                expected.unwrap_or_default(),
+            )));
+        }
+
+        // Write to a temporary file first, so that tests running in parallel
+        // never read a partial binary.
+        std::fs::create_dir_all(path.parent().expect("cache path has a parent"))?;
+        let partial = path.with_extension(format!("so.{}", std::process::id()));
+        std::fs::write(&partial, &bytes)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(bytes)
+    }
+
+    /// `<cache dir>/<RPC URL>/<program ID>.so`. The shims have the same
+    /// addresses on mainnet and devnet, so binaries are kept apart by source.
+    fn cache_path(&self, program_id: &Pubkey) -> PathBuf {
+        let source: String = self
+            .rpc_url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.cache_dir
+            .join(source)
+            .join(format!("{}.so", program_id))
+    }
+
+    fn fetch(&self, program_id: &Pubkey) -> Result<Vec<u8>, WormholeTestError> {
+        let rpc_client = RpcClient::new(self.rpc_url.clone());
+        crate::rpc::fetch_program(&rpc_client, program_id).map_err(|e| {
+            WormholeTestError::LoadError(format!(
+                "downloading {} from {}: {}",
+                program_id, self.rpc_url, e
+            ))
+        })
+    }
+}
+
+/// The sha256 (hex) of a program binary without its trailing zero padding.
+pub fn program_hash(code: &[u8]) -> String {
+    let len = code
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    hex::encode(hash(&code[..len]).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An RPC URL nothing listens on, so any download attempt fails.
+    const UNREACHABLE_RPC_URL: &str = "http://127.0.0.1:1";
+
+    fn temp_cache(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wormhole-svm-test-download-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_program_hash_ignores_padding() {
+        let code = [0x7f, b'E', b'L', b'F', 1, 2, 3];
+        let mut padded = code.to_vec();
+        padded.extend_from_slice(&[0; 64]);
+
+        assert_eq!(program_hash(&code), program_hash(&padded));
+        assert_eq!(
+            program_hash(&code),
+            hex::encode(hash(&code).as_ref()),
+            "hash of unpadded code is the plain sha256"
+        );
+    }
+
+    #[test]
+    fn test_cached_program_is_used() {
+        let program_id = Pubkey::new_unique();
+        let download = FixtureDownload::new(UNREACHABLE_RPC_URL).cache_dir(temp_cache("cached"));
+        let path = download.cache_path(&program_id);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"cached program").unwrap();
+
+        assert_eq!(download.program(&program_id).unwrap(), b"cached program");
+
+        // Still used when its hash is the pinned one
+        let download = download.expect_sha256(program_id, program_hash(b"cached program"));
+        assert_eq!(download.program(&program_id).unwrap(), b"cached program");
+
+        let _ = std::fs::remove_dir_all(&download.cache_dir);
+    }
+
+    #[test]
+    fn test_cached_program_with_other_hash_is_downloaded_again() {
+        let program_id = Pubkey::new_unique();
+        let download = FixtureDownload::new(UNREACHABLE_RPC_URL)
+            .cache_dir(temp_cache("mismatch"))
+            .expect_sha256(program_id, program_hash(b"expected program"));
+        let path = download.cache_path(&program_id);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"tampered program").unwrap();
+
+        let error = download.program(&program_id).unwrap_err();
+        assert!(
+            error.to_string().contains("downloading"),
+            "expected a download attempt, got: {}",
+            error
+        );
+
+        let _ = std::fs::remove_dir_all(&download.cache_dir);
+    }
+
+    #[test]
+    fn test_cache_path_separates_sources() {
+        let program_id = Pubkey::new_unique();
+        let mainnet = FixtureDownload::new("https://api.mainnet-beta.solana.com");
+        let devnet = FixtureDownload::new("https://api.devnet.solana.com");
+
+        assert_ne!(
+            mainnet.cache_path(&program_id),
+            devnet.cache_path(&program_id)
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! A ready-made Wormhole test environment.
+//!
+//! [`WormholeTestEnv`] bundles what almost every test sets up by hand: a
+//! LiteSVM instance with the Wormhole programs and accounts, a funded payer
+//! and the guardian set signing VAAs. Its methods are the free functions of
+//! this crate with those arguments filled in.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use wormhole_svm_test::{TestVaa, WormholeTestEnv};
+//!
+//! let mut env = WormholeTestEnv::builder().guardians(3).build()?;
+//! env.svm.add_program_from_file(my_program::ID, "target/deploy/my_program.so")?;
+//!
+//! let vaa = TestVaa::new(1, [0xAB; 32], 42, b"hello".to_vec());
+//! env.with_vaa(&vaa, |svm, sigs_pubkey, vaa_body| {
+//!     let tx = Transaction::new_signed_with_payer(...);
+//!     svm.send_transaction(tx).map_err(|e| format!("{:?}", e))
+//! })?;
+//! ```
+
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::litesvm::{
+    close_signatures, create_bridge_config_for_network, create_fee_collector_for_network,
+    create_guardian_set_account_for_network, load_wormhole_programs, post_signatures,
+    read_emitter_sequence_for_network, with_posted_signatures, with_vaa_for_network,
+    with_vaa_unchecked, CheckReport, PostedSignatures, WormholeAccounts, WormholeNetwork,
+    WormholeProgramsConfig, WormholeTestError, DEFAULT_BRIDGE_FEE,
+};
+use crate::rotation::{rotate_guardian_set_for_network, GuardianSetRotation};
+use crate::{TestGuardian, TestGuardianSet, TestVaa};
+
+/// Lamports airdropped to the payer by default (10 SOL).
+const DEFAULT_PAYER_LAMPORTS: u64 = 10_000_000_000;
+
+/// A LiteSVM instance with Wormhole set up and a funded payer.
+///
+/// Fields are public so tests can still reach for the free functions (or
+/// LiteSVM itself) where the convenience methods don't fit.
+pub struct WormholeTestEnv {
+    pub svm: LiteSVM,
+    /// Funded fee payer; also receives the rent of closed signatures accounts.
+    pub payer: Keypair,
+    pub guardians: TestGuardianSet,
+    pub guardian_set_index: u32,
+    /// The guardian set account and its bump.
+    pub accounts: WormholeAccounts,
+    /// The bridge's message fee, in lamports.
+    pub fee: u64,
+    /// Addresses the programs and accounts were created at.
+    pub network: WormholeNetwork,
+}
+
+impl WormholeTestEnv {
+    /// Start configuring an environment. The defaults match the usual
+    /// hand-written setup: the single default guardian, guardian set 0,
+    /// [`DEFAULT_BRIDGE_FEE`], a payer with 10 SOL and the program search of
+    /// [`WormholeProgramsConfig::default`].
+    pub fn builder() -> WormholeTestEnvBuilder {
+        WormholeTestEnvBuilder::default()
+    }
+
+    /// Sign `vaa` with the environment's guardians.
+    pub fn sign(&self, vaa: &TestVaa) -> Vec<u8> {
+        vaa.sign(&self.guardians)
+    }
+
+    /// [`post_signatures`] with the environment's payer and guardian set.
+    pub fn post_signatures(
+        &mut self,
+        signatures: &[[u8; 66]],
+    ) -> Result<PostedSignatures, WormholeTestError> {
+        post_signatures(
+            &mut self.svm,
+            &self.payer,
+            self.guardian_set_index,
+            signatures,
+        )
+    }
+
+    /// [`close_signatures`], refunding the payer.
+    pub fn close_signatures(
+        &mut self,
+        signatures_pubkey: &Pubkey,
+    ) -> Result<(), WormholeTestError> {
+        close_signatures(
+            &mut self.svm,
+            &self.payer,
+            signatures_pubkey,
+            &self.payer.pubkey(),
+        )
+    }
+
+    /// [`with_posted_signatures`] with the environment's payer and guardian set.
+    pub fn with_posted_signatures<F, T, E>(
+        &mut self,
+        signatures: &[[u8; 66]],
+        f: F,
+    ) -> Result<T, WormholeTestError>
+    where
+        F: FnOnce(&mut LiteSVM, &Pubkey) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        with_posted_signatures(
+            &mut self.svm,
+            &self.payer,
+            self.guardian_set_index,
+            signatures,
+            f,
+        )
+    }
+
+    /// [`with_vaa`](crate::with_vaa) with the environment's payer, guardians,
+    /// guardian set and network.
+    pub fn with_vaa<F, T, E>(
+        &mut self,
+        vaa: &TestVaa,
+        f: F,
+    ) -> Result<(T, CheckReport), WormholeTestError>
+    where
+        F: FnMut(&mut LiteSVM, &Pubkey, &[u8]) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        with_vaa_for_network(
+            &mut self.svm,
+            &self.payer,
+            &self.guardians,
+            self.guardian_set_index,
+            &self.network,
+            vaa,
+            f,
+        )
+    }
+
+    /// [`with_vaa_unchecked`] with the environment's payer, guardians and
+    /// guardian set.
+    pub fn with_vaa_unchecked<F, T, E>(
+        &mut self,
+        vaa: &TestVaa,
+        f: F,
+    ) -> Result<T, WormholeTestError>
+    where
+        F: FnOnce(&mut LiteSVM, &Pubkey, &[u8]) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        with_vaa_unchecked(
+            &mut self.svm,
+            &self.payer,
+            &self.guardians,
+            self.guardian_set_index,
+            vaa,
+            f,
+        )
+    }
+
+    /// A transfer of the bridge fee from the payer to the fee collector; like
+    /// [`build_bridge_fee_ix`](crate::build_bridge_fee_ix), but for the
+    /// environment's fee.
+    pub fn bridge_fee_ix(&self) -> Instruction {
+        solana_sdk::system_instruction::transfer(
+            &self.payer.pubkey(),
+            &self.network.fee_collector_address(),
+            self.fee,
+        )
+    }
+
+    /// [`rotate_guardian_set`](crate::rotate_guardian_set) to `guardians`,
+    /// which become the environment's guardians. Clone the old ones first to
+    /// sign VAAs for the old set during its grace period.
+    pub fn rotate_guardian_set(
+        &mut self,
+        guardians: TestGuardianSet,
+    ) -> Result<GuardianSetRotation, WormholeTestError> {
+        let rotation = rotate_guardian_set_for_network(&mut self.svm, &self.network, &guardians)?;
+        self.guardians = guardians;
+        self.guardian_set_index = rotation.index;
+        self.accounts = WormholeAccounts {
+            guardian_set: rotation.guardian_set,
+            guardian_set_bump: rotation.guardian_set_bump,
+        };
+        Ok(rotation)
+    }
+
+    /// [`read_emitter_sequence`] in the environment's LiteSVM instance.
+    pub fn read_emitter_sequence(&self, emitter: &Pubkey) -> Option<u64> {
+        read_emitter_sequence_for_network(&self.svm, &self.network, emitter)
+    }
+}
+
+/// Builder for [`WormholeTestEnv`], from [`WormholeTestEnv::builder`].
+pub struct WormholeTestEnvBuilder {
+    guardians: TestGuardianSet,
+    guardian_set_index: u32,
+    fee: u64,
+    payer: Option<Keypair>,
+    payer_lamports: u64,
+    programs: WormholeProgramsConfig,
+}
+
+impl Default for WormholeTestEnvBuilder {
+    fn default() -> Self {
+        Self {
+            guardians: TestGuardianSet::single(TestGuardian::default()),
+            guardian_set_index: 0,
+            fee: DEFAULT_BRIDGE_FEE,
+            payer: None,
+            payer_lamports: DEFAULT_PAYER_LAMPORTS,
+            programs: WormholeProgramsConfig::default(),
+        }
+    }
+}
+
+impl WormholeTestEnvBuilder {
+    /// Use `count` guardians generated with [`TestGuardianSet::generate`]
+    /// (seed 0).
+    pub fn guardians(self, count: usize) -> Self {
+        self.guardian_set(TestGuardianSet::generate(count, 0))
+    }
+
+    /// Use the given guardian set.
+    pub fn guardian_set(mut self, guardians: TestGuardianSet) -> Self {
+        self.guardians = guardians;
+        self
+    }
+
+    pub fn guardian_set_index(mut self, index: u32) -> Self {
+        self.guardian_set_index = index;
+        self
+    }
+
+    /// The bridge's message fee, in lamports.
+    pub fn fee(mut self, lamports: u64) -> Self {
+        self.fee = lamports;
+        self
+    }
+
+    /// Use `payer` instead of a new keypair.
+    pub fn payer(mut self, payer: Keypair) -> Self {
+        self.payer = Some(payer);
+        self
+    }
+
+    /// Lamports airdropped to the payer.
+    pub fn payer_lamports(mut self, lamports: u64) -> Self {
+        self.payer_lamports = lamports;
+        self
+    }
+
+    /// Where to load the Wormhole programs from.
+    pub fn programs(mut self, config: WormholeProgramsConfig) -> Self {
+        self.programs = config;
+        self
+    }
+
+    /// Set up Wormhole at the addresses of `network` (default: Solana mainnet).
+    pub fn network(mut self, network: WormholeNetwork) -> Self {
+        self.programs.network = network;
+        self
+    }
+
+    /// Create the LiteSVM instance, load the programs, create the guardian
+    /// set, bridge config and fee collector, and fund the payer.
+    pub fn build(self) -> Result<WormholeTestEnv, WormholeTestError> {
+        let network = self.programs.network;
+        let mut svm = LiteSVM::new();
+        load_wormhole_programs(&mut svm, self.programs)?;
+
+        let (guardian_set, guardian_set_bump) = create_guardian_set_account_for_network(
+            &mut svm,
+            &network,
+            &self.guardians,
+            self.guardian_set_index,
+        );
+        create_bridge_config_for_network(&mut svm, &network, self.guardian_set_index, self.fee);
+        create_fee_collector_for_network(&mut svm, &network);
+
+        let payer = self.payer.unwrap_or_else(Keypair::new);
+        svm.airdrop(&payer.pubkey(), self.payer_lamports)
+            .map_err(|e| WormholeTestError::LoadError(format!("airdrop failed: {:?}", e)))?;
+
+        Ok(WormholeTestEnv {
+            svm,
+            payer,
+            guardians: self.guardians,
+            guardian_set_index: self.guardian_set_index,
+            accounts: WormholeAccounts {
+                guardian_set,
+                guardian_set_bump,
+            },
+            fee: self.fee,
+            network,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "bundled-fixtures"))]
+mod tests {
+    use super::*;
+    use wormhole_svm_definitions::solana::mainnet::CORE_BRIDGE_CONFIG;
+
+    #[test]
+    fn test_builder_defaults() {
+        let env = WormholeTestEnv::builder().build().unwrap();
+
+        assert_eq!(env.guardians.len(), 1);
+        assert_eq!(env.guardian_set_index, 0);
+        assert_eq!(env.fee, DEFAULT_BRIDGE_FEE);
+        assert_eq!(
+            env.svm.get_balance(&env.payer.pubkey()),
+            Some(DEFAULT_PAYER_LAMPORTS)
+        );
+        assert!(env.svm.get_account(&env.accounts.guardian_set).is_some());
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let env = WormholeTestEnv::builder()
+            .guardians(3)
+            .guardian_set_index(2)
+            .fee(100)
+            .build()
+            .unwrap();
+
+        assert_eq!(env.guardians.len(), 3);
+
+        // Guardian set: index (4) + len (4) + 3 addresses
+        let guardian_set = env.svm.get_account(&env.accounts.guardian_set).unwrap();
+        assert_eq!(
+            u32::from_le_bytes(guardian_set.data[0..4].try_into().unwrap()),
+            2
+        );
+        assert_eq!(
+            u32::from_le_bytes(guardian_set.data[4..8].try_into().unwrap()),
+            3
+        );
+
+        // Bridge config: guardian set index first, fee last
+        let config = env.svm.get_account(&CORE_BRIDGE_CONFIG).unwrap();
+        assert_eq!(u32::from_le_bytes(config.data[0..4].try_into().unwrap()), 2);
+        assert_eq!(
+            u64::from_le_bytes(config.data[16..24].try_into().unwrap()),
+            100
+        );
+    }
+
+    #[test]
+    fn test_post_and_close_signatures() {
+        let mut env = WormholeTestEnv::builder().guardians(3).build().unwrap();
+
+        let vaa = TestVaa::new(1, [0xAB; 32], 42, vec![1, 2, 3, 4]);
+        let signatures = vaa.guardian_signatures(&env.guardians);
+
+        let posted = env.post_signatures(&signatures).unwrap();
+        assert!(env.svm.get_account(&posted.pubkey).is_some());
+
+        env.close_signatures(&posted.pubkey).unwrap();
+        assert!(env.svm.get_account(&posted.pubkey).is_none());
+    }
+}
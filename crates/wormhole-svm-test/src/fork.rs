@@ -0,0 +1,146 @@
+//! Copying live cluster state into LiteSVM.
+//!
+//! Tests that need real state — a token bridge config, registered emitters,
+//! the current guardian set — can fork those accounts from mainnet (or any
+//! cluster) instead of rebuilding them by hand:
+//!
+//! ```ignore
+//! use wormhole_svm_test::{fork_accounts, fork_programs};
+//!
+//! let rpc_url = "https://api.mainnet-beta.solana.com";
+//! fork_programs(&mut svm, rpc_url, &[TOKEN_BRIDGE_PROGRAM_ID])?;
+//! fork_accounts(&mut svm, rpc_url, &[token_bridge_config, registered_emitter])?;
+//! ```
+//!
+//! The accounts are copied as of the time of the call. A forked guardian set
+//! holds the real guardians' keys, so VAAs for it can't be signed in tests;
+//! overwrite it with [`create_guardian_set_account`](crate::create_guardian_set_account)
+//! to sign with test guardians instead.
+
+use litesvm::LiteSVM;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use crate::litesvm::WormholeTestError;
+
+/// Accounts per `getMultipleAccounts` request (the RPC limit).
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Copy the accounts at `addresses` from the cluster at `rpc_url` into `svm`.
+///
+/// Fails if any account doesn't exist, or is a program (use
+/// [`fork_programs`] for those); nothing is copied then.
+pub fn fork_accounts(
+    svm: &mut LiteSVM,
+    rpc_url: &str,
+    addresses: &[Pubkey],
+) -> Result<(), WormholeTestError> {
+    let rpc_client = RpcClient::new(rpc_url.to_string());
+    let accounts = fetch_accounts(&rpc_client, rpc_url, addresses)?;
+
+    let programs: Vec<String> = accounts
+        .iter()
+        .filter(|(_, account)| account.executable)
+        .map(|(address, _)| address.to_string())
+        .collect();
+    if !programs.is_empty() {
+        return Err(WormholeTestError::LoadError(format!(
+            "{} are programs; fork them with fork_programs",
+            programs.join(", ")
+        )));
+    }
+
+    for (address, account) in accounts {
+        svm.set_account(address, account).map_err(|e| {
+            WormholeTestError::LoadError(format!("setting forked account {}: {:?}", address, e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Copy the programs at `program_ids` from the cluster at `rpc_url` into
+/// `svm`, at the same addresses.
+///
+/// Upgradeable programs are loaded from their program data account. The
+/// program's own accounts (config, state) are not copied; fork them with
+/// [`fork_accounts`].
+pub fn fork_programs(
+    svm: &mut LiteSVM,
+    rpc_url: &str,
+    program_ids: &[Pubkey],
+) -> Result<(), WormholeTestError> {
+    let rpc_client = RpcClient::new(rpc_url.to_string());
+    let programs = fetch_accounts(&rpc_client, rpc_url, program_ids)?;
+
+    let mut bytes = Vec::with_capacity(programs.len());
+    for (program_id, account) in &programs {
+        let program = crate::rpc::program_bytes(&rpc_client, account).map_err(|e| {
+            WormholeTestError::LoadError(format!(
+                "forking program {} from {}: {}",
+                program_id, rpc_url, e
+            ))
+        })?;
+        bytes.push((*program_id, program));
+    }
+
+    for (program_id, program) in bytes {
+        svm.add_program(program_id, &program).map_err(|e| {
+            WormholeTestError::LoadError(format!("loading forked program {}: {}", program_id, e))
+        })?;
+    }
+    Ok(())
+}
+
+/// Fetch `addresses`, failing if any of them doesn't exist.
+fn fetch_accounts(
+    rpc_client: &RpcClient,
+    rpc_url: &str,
+    addresses: &[Pubkey],
+) -> Result<Vec<(Pubkey, Account)>, WormholeTestError> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    let mut missing = Vec::new();
+    for chunk in addresses.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        let fetched = rpc_client.get_multiple_accounts(chunk).map_err(|e| {
+            WormholeTestError::LoadError(format!("fetching accounts from {}: {}", rpc_url, e))
+        })?;
+        for (address, account) in chunk.iter().zip(fetched) {
+            match account {
+                Some(account) => accounts.push((*address, account)),
+                None => missing.push(address.to_string()),
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(WormholeTestError::LoadError(format!(
+            "not found on {}: {}",
+            rpc_url,
+            missing.join(", ")
+        )));
+    }
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fork_nothing_needs_no_rpc() {
+        let mut svm = LiteSVM::new();
+
+        // Nothing listens here; an empty fork must not make a request
+        fork_accounts(&mut svm, "http://127.0.0.1:1", &[]).unwrap();
+        fork_programs(&mut svm, "http://127.0.0.1:1", &[]).unwrap();
+    }
+
+    #[test]
+    fn test_fork_unreachable_rpc_fails() {
+        let mut svm = LiteSVM::new();
+        let address = Pubkey::new_unique();
+
+        let error = fork_accounts(&mut svm, "http://127.0.0.1:1", &[address]).unwrap_err();
+        assert!(error.to_string().contains("fetching accounts"));
+        assert!(svm.get_account(&address).is_none());
+    }
+}
@@ -0,0 +1,333 @@
+//! Fuzzing helpers: VAA parsers, ready-made fuzz target bodies, and a
+//! [`VaaFuzzer`] that feeds fuzzer input to a program as VAA payloads.
+//!
+//! The helpers take plain byte slices, so they drop into any fuzzer. With
+//! cargo-fuzz, a target is one line:
+//!
+//! ```ignore
+//! // fuzz/fuzz_targets/vaa_body.rs
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| wormhole_svm_test::fuzz_vaa_body(data));
+//! ```
+//!
+//! To fuzz a program's payload parser, keep one [`VaaFuzzer`] per fuzzing
+//! thread; each input runs against a fresh copy of the environment:
+//!
+//! ```ignore
+//! // fuzz/fuzz_targets/my_program.rs
+//! #![no_main]
+//! use std::cell::RefCell;
+//! use wormhole_svm_test::{TestVaa, VaaFuzzer, WormholeTestEnv};
+//!
+//! thread_local! {
+//!     static FUZZER: RefCell<VaaFuzzer> = RefCell::new({
+//!         let mut env = WormholeTestEnv::builder().build().unwrap();
+//!         env.svm.add_program_from_file(my_program::ID, "my_program.so").unwrap();
+//!         VaaFuzzer::new(env, TestVaa::new(2, EMITTER, 0, vec![]))
+//!     });
+//! }
+//!
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+//!     FUZZER.with(|fuzzer| {
+//!         fuzzer
+//!             .borrow_mut()
+//!             .run(data, |svm, sigs, body| send_my_instruction(svm, sigs, body))
+//!             .unwrap();
+//!     })
+//! });
+//! ```
+//!
+//! A program that rejects an input is fine; the fuzzer is after panics in
+//! off-chain code and inputs the program wrongly accepts, which the closure
+//! can assert on.
+
+use sha3::{Digest, Keccak256};
+
+use crate::TestVaa;
+
+/// Length of the VAA body header (everything before the payload).
+pub const VAA_BODY_HEADER_LEN: usize = 51;
+
+/// A signed VAA split into its parts.
+#[derive(Clone, Debug)]
+pub struct ParsedSignedVaa {
+    pub guardian_set_index: u32,
+    pub signatures: Vec<[u8; 66]>,
+    /// The body; its `guardian_set_index` is set from the envelope.
+    pub vaa: TestVaa,
+}
+
+/// Parse a VAA body (header and payload), or `None` if it is too short.
+pub fn parse_vaa_body(body: &[u8]) -> Option<TestVaa> {
+    if body.len() < VAA_BODY_HEADER_LEN {
+        return None;
+    }
+    let u32_at = |offset: usize| u32::from_be_bytes(body[offset..offset + 4].try_into().unwrap());
+    Some(
+        TestVaa::new(
+            u16::from_be_bytes(body[8..10].try_into().unwrap()),
+            body[10..42].try_into().unwrap(),
+            u64::from_be_bytes(body[42..50].try_into().unwrap()),
+            body[VAA_BODY_HEADER_LEN..].to_vec(),
+        )
+        .with_timestamp(u32_at(0))
+        .with_nonce(u32_at(4))
+        .with_consistency_level(body[50]),
+    )
+}
+
+/// Parse a signed version 1 VAA, or `None` if it is malformed.
+pub fn parse_signed_vaa(bytes: &[u8]) -> Option<ParsedSignedVaa> {
+    let (&version, rest) = bytes.split_first()?;
+    if version != 1 || rest.len() < 5 {
+        return None;
+    }
+    let guardian_set_index = u32::from_be_bytes(rest[..4].try_into().unwrap());
+    let count = rest[4] as usize;
+    let rest = &rest[5..];
+    if rest.len() < count * 66 {
+        return None;
+    }
+    let (signatures, body) = rest.split_at(count * 66);
+    let vaa = parse_vaa_body(body)?.with_guardian_set_index(guardian_set_index);
+    Some(ParsedSignedVaa {
+        guardian_set_index,
+        signatures: signatures
+            .chunks_exact(66)
+            .map(|signature| signature.try_into().unwrap())
+            .collect(),
+        vaa,
+    })
+}
+
+/// Fuzz target: parse `data` as a VAA body and check that it re-encodes to
+/// the same bytes and digests as the core bridge does.
+///
+/// # Panics
+///
+/// If an invariant breaks (which is the finding).
+pub fn fuzz_vaa_body(data: &[u8]) {
+    let Some(vaa) = parse_vaa_body(data) else {
+        return;
+    };
+    assert_eq!(vaa.body(), data, "VAA body does not round-trip");
+    let expected: [u8; 32] = Keccak256::digest(Keccak256::digest(data)).into();
+    assert_eq!(vaa.digest(), expected, "VAA digest mismatch");
+}
+
+/// Fuzz target: parse `data` as a signed VAA and check that it re-encodes to
+/// the same bytes.
+///
+/// # Panics
+///
+/// If an invariant breaks (which is the finding).
+pub fn fuzz_signed_vaa(data: &[u8]) {
+    let Some(parsed) = parse_signed_vaa(data) else {
+        return;
+    };
+    let body = parsed.vaa.body();
+    assert_eq!(
+        parsed.vaa.build_signed_vaa(&body, &parsed.signatures),
+        data,
+        "signed VAA does not round-trip"
+    );
+}
+
+/// Counts of what a [`VaaFuzzer`] has run.
+#[cfg(feature = "litesvm")]
+#[derive(Clone, Debug, Default)]
+pub struct FuzzStats {
+    pub runs: u64,
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Rejections by error message.
+    pub rejections: std::collections::BTreeMap<String, u64>,
+}
+
+/// What a program did with one fuzz input.
+#[cfg(feature = "litesvm")]
+#[derive(Debug)]
+pub enum FuzzOutcome<T> {
+    Accepted(T),
+    Rejected(String),
+}
+
+/// Runs fuzz inputs through a program as the payload of a validly signed
+/// VAA, like [`with_vaa_unchecked`](crate::with_vaa_unchecked), each on a
+/// fresh copy of the environment.
+///
+/// The same input always makes the same VAA, so crashes reproduce.
+#[cfg(feature = "litesvm")]
+pub struct VaaFuzzer {
+    env: crate::WormholeTestEnv,
+    baseline: litesvm::LiteSVM,
+    template: TestVaa,
+    stats: FuzzStats,
+}
+
+#[cfg(feature = "litesvm")]
+impl VaaFuzzer {
+    /// Fuzz with `env` as it is now, sending VAAs like `template` with the
+    /// fuzz input as payload.
+    pub fn new(env: crate::WormholeTestEnv, template: TestVaa) -> Self {
+        Self {
+            baseline: env.svm.clone(),
+            env,
+            template,
+            stats: FuzzStats::default(),
+        }
+    }
+
+    /// Sign a VAA carrying `payload`, post its signatures and run `f`, as
+    /// `with_vaa_unchecked` does. The environment is reset afterwards.
+    ///
+    /// Errors are failures of the harness itself (posting or closing the
+    /// signatures), not of the program.
+    pub fn run<F, T, E>(
+        &mut self,
+        payload: &[u8],
+        f: F,
+    ) -> Result<FuzzOutcome<T>, crate::WormholeTestError>
+    where
+        F: FnOnce(&mut litesvm::LiteSVM, &solana_sdk::pubkey::Pubkey, &[u8]) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        let vaa = TestVaa {
+            payload: payload.to_vec(),
+            ..self.template.clone()
+        };
+        let result = self.run_vaa(&vaa, f);
+        self.env.svm = self.baseline.clone();
+
+        let outcome = result?;
+        self.stats.runs += 1;
+        match &outcome {
+            FuzzOutcome::Accepted(_) => self.stats.accepted += 1,
+            FuzzOutcome::Rejected(error) => {
+                self.stats.rejected += 1;
+                *self.stats.rejections.entry(error.clone()).or_default() += 1;
+            }
+        }
+        Ok(outcome)
+    }
+
+    fn run_vaa<F, T, E>(
+        &mut self,
+        vaa: &TestVaa,
+        f: F,
+    ) -> Result<FuzzOutcome<T>, crate::WormholeTestError>
+    where
+        F: FnOnce(&mut litesvm::LiteSVM, &solana_sdk::pubkey::Pubkey, &[u8]) -> Result<T, E>,
+        E: std::fmt::Display,
+    {
+        let signatures = vaa.guardian_signatures(&self.env.guardians);
+        let posted = self.env.post_signatures(&signatures)?;
+        let outcome = match f(&mut self.env.svm, &posted.pubkey, &vaa.body()) {
+            Ok(value) => FuzzOutcome::Accepted(value),
+            Err(error) => FuzzOutcome::Rejected(error.to_string()),
+        };
+        self.env.close_signatures(&posted.pubkey)?;
+        Ok(outcome)
+    }
+
+    /// What has run so far.
+    pub fn stats(&self) -> &FuzzStats {
+        &self.stats
+    }
+
+    /// The environment.
+    pub fn env(&self) -> &crate::WormholeTestEnv {
+        &self.env
+    }
+
+    /// The environment, to change between runs; changes to `svm` are
+    /// discarded by the next run unless saved with
+    /// [`save_baseline`](Self::save_baseline).
+    pub fn env_mut(&mut self) -> &mut crate::WormholeTestEnv {
+        &mut self.env
+    }
+
+    /// Make the environment's current state the one every run starts from.
+    pub fn save_baseline(&mut self) {
+        self.baseline = self.env.svm.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestGuardian, TestGuardianSet};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let guardians = TestGuardianSet::generate(3, 1);
+        let vaa = TestVaa::new(2, [0xAB; 32], 77, b"payload".to_vec())
+            .with_timestamp(1_700_000_000)
+            .with_nonce(5)
+            .with_consistency_level(32)
+            .with_guardian_set_index(4);
+
+        let parsed = parse_vaa_body(&vaa.body()).unwrap();
+        assert_eq!(parsed.body(), vaa.body());
+
+        let signed = vaa.sign(&guardians);
+        let parsed = parse_signed_vaa(&signed).unwrap();
+        assert_eq!(parsed.guardian_set_index, 4);
+        assert_eq!(parsed.signatures.len(), 3);
+        assert_eq!(parsed.vaa.payload, b"payload");
+        assert_eq!(parsed.vaa.guardian_set_index, 4);
+
+        fuzz_vaa_body(&vaa.body());
+        fuzz_signed_vaa(&signed);
+    }
+
+    #[test]
+    fn test_fuzz_targets_on_malformed_input() {
+        let signed = TestVaa::new(1, [1; 32], 1, vec![])
+            .sign(&TestGuardianSet::single(TestGuardian::default()));
+
+        for input in [
+            &[][..],
+            &[1],
+            &[0; 50],
+            &[0xFF; 51],
+            &signed[..signed.len() - 1],
+            &[2, 0, 0, 0, 0, 1],
+            &[1, 0, 0, 0, 0, 255, 0],
+        ] {
+            fuzz_vaa_body(input);
+            fuzz_signed_vaa(input);
+        }
+        assert!(parse_signed_vaa(&signed[..signed.len() - 1]).is_none());
+        assert!(parse_vaa_body(&[0; 50]).is_none());
+    }
+
+    #[cfg(feature = "bundled-fixtures")]
+    #[test]
+    fn test_vaa_fuzzer_resets_and_counts() {
+        let env = crate::WormholeTestEnv::builder().build().unwrap();
+        let marker = solana_sdk::pubkey::Pubkey::new_unique();
+        let mut fuzzer = VaaFuzzer::new(env, TestVaa::new(2, [0xAB; 32], 0, vec![]));
+
+        for input in [&b"\x01ok"[..], b"\x02bad", b"", b"\x01again"] {
+            fuzzer
+                .run(input, |svm, _sigs, body| {
+                    // Any state change must not leak into the next run
+                    assert!(svm.get_account(&marker).is_none());
+                    svm.airdrop(&marker, 1).unwrap();
+                    match body.get(VAA_BODY_HEADER_LEN) {
+                        Some(1) => Ok(()),
+                        _ => Err("unknown payload type"),
+                    }
+                })
+                .unwrap();
+        }
+
+        let stats = fuzzer.stats();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.accepted, 2);
+        assert_eq!(stats.rejected, 2);
+        assert_eq!(stats.rejections["unknown payload type"], 2);
+        assert!(fuzzer.env().svm.get_account(&marker).is_none());
+    }
+}
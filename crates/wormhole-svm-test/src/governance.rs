@@ -0,0 +1,165 @@
+//! Core bridge governance VAAs.
+//!
+//! Governance payloads are `module (32) | action (1) | chain (2) | body`,
+//! big-endian, where `module` is `"Core"` left-padded with zeros and `chain`
+//! is the target chain (0 for all chains). They are emitted by the governance
+//! emitter (address 4) on Solana.
+
+use crate::{TestGuardianSet, TestVaa};
+
+/// Wormhole chain ID of the governance emitter (Solana).
+pub const GOVERNANCE_CHAIN: u16 = 1;
+
+/// Address of the governance emitter.
+pub const GOVERNANCE_EMITTER: [u8; 32] = {
+    let mut address = [0u8; 32];
+    address[31] = 4;
+    address
+};
+
+/// Governance module of the core bridge: `"Core"`, left-padded to 32 bytes.
+pub const CORE_MODULE: [u8; 32] = {
+    let mut module = [0u8; 32];
+    module[28] = b'C';
+    module[29] = b'o';
+    module[30] = b'r';
+    module[31] = b'e';
+    module
+};
+
+/// Core bridge governance actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CoreGovernanceAction {
+    ContractUpgrade = 1,
+    GuardianSetUpgrade = 2,
+    SetMessageFee = 3,
+    TransferFees = 4,
+}
+
+/// Build a core governance payload: module, `action`, target `chain`, then
+/// the action's `body`.
+pub fn core_governance_payload(action: CoreGovernanceAction, chain: u16, body: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(35 + body.len());
+    payload.extend_from_slice(&CORE_MODULE);
+    payload.push(action as u8);
+    payload.extend_from_slice(&chain.to_be_bytes());
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// A u64 as the 32-byte big-endian integer (uint256) of governance payloads.
+pub(crate) fn uint256(value: u64) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&value.to_be_bytes());
+    bytes
+}
+
+impl TestVaa {
+    /// A VAA from the governance emitter carrying `payload`.
+    pub fn governance(sequence: u64, payload: Vec<u8>) -> Self {
+        Self::new(GOVERNANCE_CHAIN, GOVERNANCE_EMITTER, sequence, payload)
+    }
+
+    /// Upgrade the core bridge on `chain` to `new_contract` (on Solana, the
+    /// buffer holding the new program).
+    pub fn contract_upgrade(sequence: u64, chain: u16, new_contract: [u8; 32]) -> Self {
+        Self::governance(
+            sequence,
+            core_governance_payload(CoreGovernanceAction::ContractUpgrade, chain, &new_contract),
+        )
+    }
+
+    /// Replace the guardian set with `guardians` as set `new_index`, on all
+    /// chains.
+    pub fn guardian_set_upgrade(
+        sequence: u64,
+        new_index: u32,
+        guardians: &TestGuardianSet,
+    ) -> Self {
+        let keys = guardians.eth_addresses();
+        let mut body = Vec::with_capacity(5 + 20 * keys.len());
+        body.extend_from_slice(&new_index.to_be_bytes());
+        body.push(keys.len() as u8);
+        for key in keys {
+            body.extend_from_slice(&key);
+        }
+        Self::governance(
+            sequence,
+            core_governance_payload(CoreGovernanceAction::GuardianSetUpgrade, 0, &body),
+        )
+    }
+
+    /// Set the message fee on `chain` to `fee` (in the chain's native unit).
+    pub fn set_message_fee(sequence: u64, chain: u16, fee: u64) -> Self {
+        Self::governance(
+            sequence,
+            core_governance_payload(CoreGovernanceAction::SetMessageFee, chain, &uint256(fee)),
+        )
+    }
+
+    /// Transfer `amount` of the fees collected on `chain` to `recipient`.
+    pub fn transfer_fees(sequence: u64, chain: u16, amount: u64, recipient: [u8; 32]) -> Self {
+        let mut body = Vec::with_capacity(64);
+        body.extend_from_slice(&uint256(amount));
+        body.extend_from_slice(&recipient);
+        Self::governance(
+            sequence,
+            core_governance_payload(CoreGovernanceAction::TransferFees, chain, &body),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_governance_header() {
+        let vaa = TestVaa::set_message_fee(7, 1, 1000);
+
+        assert_eq!(vaa.emitter_chain, GOVERNANCE_CHAIN);
+        assert_eq!(vaa.emitter_address, GOVERNANCE_EMITTER);
+        assert_eq!(vaa.sequence, 7);
+
+        // Module is "Core" left-padded
+        assert_eq!(&vaa.payload[..28], &[0u8; 28]);
+        assert_eq!(&vaa.payload[28..32], b"Core");
+        // Action and chain (big-endian)
+        assert_eq!(vaa.payload[32], 3);
+        assert_eq!(&vaa.payload[33..35], &[0, 1]);
+        // Fee as uint256
+        assert_eq!(vaa.payload.len(), 35 + 32);
+        assert_eq!(&vaa.payload[35..59], &[0u8; 24]);
+        assert_eq!(&vaa.payload[59..], &1000u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_guardian_set_upgrade_payload() {
+        let guardians = TestGuardianSet::generate(3, 5);
+        let vaa = TestVaa::guardian_set_upgrade(1, 4, &guardians);
+        let payload = &vaa.payload;
+
+        assert_eq!(payload[32], CoreGovernanceAction::GuardianSetUpgrade as u8);
+        // Applies to all chains
+        assert_eq!(&payload[33..35], &[0, 0]);
+        assert_eq!(&payload[35..39], &4u32.to_be_bytes());
+        assert_eq!(payload[39], 3);
+        assert_eq!(payload.len(), 40 + 3 * 20);
+        for (i, key) in guardians.eth_addresses().iter().enumerate() {
+            assert_eq!(&payload[40 + 20 * i..60 + 20 * i], key);
+        }
+    }
+
+    #[test]
+    fn test_transfer_fees_and_contract_upgrade_payloads() {
+        let vaa = TestVaa::transfer_fees(2, 1, 500, [0xCD; 32]);
+        assert_eq!(vaa.payload[32], 4);
+        assert_eq!(&vaa.payload[59..67], &500u64.to_be_bytes());
+        assert_eq!(&vaa.payload[67..], &[0xCD; 32]);
+
+        let vaa = TestVaa::contract_upgrade(3, 1, [0xEF; 32]);
+        assert_eq!(vaa.payload[32], 1);
+        assert_eq!(&vaa.payload[35..], &[0xEF; 32]);
+    }
+}
@@ -21,6 +21,19 @@ pub struct TestGuardian {
     pub index: u8,
 }
 
+impl std::fmt::Debug for TestGuardian {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The secret key stays out of test output
+        f.debug_struct("TestGuardian")
+            .field("index", &self.index)
+            .field(
+                "eth_address",
+                &format_args!("0x{}", hex::encode(self.eth_address)),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
 impl TestGuardian {
     /// Create a new test guardian with the given secret key and index.
     pub fn new(secret_key: [u8; 32], index: u8) -> Self {
@@ -83,8 +96,14 @@ impl Default for TestGuardian {
     }
 }
 
+/// Number of guardians in the mainnet guardian set.
+pub const MAINNET_GUARDIAN_COUNT: usize = 19;
+
+/// Seed of [`TestGuardianSet::mainnet_like`].
+const MAINNET_LIKE_SEED: u64 = 19;
+
 /// A set of test guardians for quorum testing.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct TestGuardianSet {
     guardians: Vec<TestGuardian>,
 }
@@ -116,6 +135,20 @@ impl TestGuardianSet {
         Self { guardians }
     }
 
+    /// A set the size of mainnet's: 19 deterministic guardians, of which 13
+    /// make quorum.
+    ///
+    /// Use it with [`sign_vaa_body_quorum`](Self::sign_vaa_body_quorum) to
+    /// exercise programs and compute budgets with realistic signature counts.
+    pub fn mainnet_like() -> Self {
+        Self::generate(MAINNET_GUARDIAN_COUNT, MAINNET_LIKE_SEED)
+    }
+
+    /// Number of signatures required for quorum (more than two thirds).
+    pub fn quorum(&self) -> usize {
+        self.guardians.len() * 2 / 3 + 1
+    }
+
     /// Sign a VAA body with all guardians in the set.
     pub fn sign_vaa_body(&self, vaa_body: &[u8]) -> Vec<[u8; 66]> {
         self.guardians
@@ -133,6 +166,20 @@ impl TestGuardianSet {
             .collect()
     }
 
+    /// Indices of the first [`quorum`](Self::quorum) guardians.
+    pub fn quorum_indices(&self) -> Vec<u8> {
+        (0..self.quorum().min(self.guardians.len()) as u8).collect()
+    }
+
+    /// Sign a VAA body with exactly a quorum of guardians (the first ones).
+    pub fn sign_vaa_body_quorum(&self, vaa_body: &[u8]) -> Vec<[u8; 66]> {
+        self.guardians
+            .iter()
+            .take(self.quorum())
+            .map(|g| g.sign_vaa_body(vaa_body))
+            .collect()
+    }
+
     /// Get the Ethereum addresses of all guardians.
     pub fn eth_addresses(&self) -> Vec<[u8; 20]> {
         self.guardians.iter().map(|g| g.eth_address).collect()
@@ -210,6 +257,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quorum() {
+        assert_eq!(TestGuardianSet::generate(1, 0).quorum(), 1);
+        assert_eq!(TestGuardianSet::generate(2, 0).quorum(), 2);
+        assert_eq!(TestGuardianSet::generate(3, 0).quorum(), 3);
+        assert_eq!(TestGuardianSet::generate(4, 0).quorum(), 3);
+        assert_eq!(TestGuardianSet::generate(19, 0).quorum(), 13);
+    }
+
+    #[test]
+    fn test_mainnet_like() {
+        let set = TestGuardianSet::mainnet_like();
+        assert_eq!(set.len(), 19);
+        assert_eq!(set.quorum(), 13);
+        assert_eq!(set.quorum_indices(), (0..13).collect::<Vec<u8>>());
+
+        // Deterministic
+        assert_eq!(
+            set.eth_addresses(),
+            TestGuardianSet::mainnet_like().eth_addresses()
+        );
+
+        let sigs = set.sign_vaa_body_quorum(b"test");
+        assert_eq!(sigs.len(), 13);
+        assert_eq!(sigs[12][0], 12);
+    }
+
     #[test]
     fn test_sign_with_subset() {
         let set = TestGuardianSet::generate(5, 999);
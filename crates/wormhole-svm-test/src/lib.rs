@@ -20,18 +20,63 @@
 //! let signed_vaa = vaa.sign(&guardians);
 //! ```
 
+mod fuzz;
+mod governance;
 mod guardian;
+mod ntt;
 mod vaa;
 
+pub use fuzz::*;
+pub use governance::*;
 pub use guardian::*;
+pub use ntt::*;
 pub use vaa::*;
 
+#[cfg(feature = "proptest")]
+mod arbitrary;
+
+#[cfg(feature = "proptest")]
+pub use arbitrary::*;
+
 #[cfg(feature = "litesvm")]
 mod litesvm;
 
 #[cfg(feature = "litesvm")]
 pub use crate::litesvm::*;
 
+#[cfg(feature = "litesvm")]
+mod env;
+
+#[cfg(feature = "litesvm")]
+pub use env::*;
+
+#[cfg(feature = "litesvm")]
+mod rotation;
+
+#[cfg(feature = "litesvm")]
+pub use rotation::*;
+
+#[cfg(feature = "fixture-download")]
+mod token_bridge;
+
+#[cfg(feature = "fixture-download")]
+pub use token_bridge::*;
+
+#[cfg(any(feature = "fixture-download", feature = "fork"))]
+mod rpc;
+
+#[cfg(feature = "fixture-download")]
+mod download;
+
+#[cfg(feature = "fixture-download")]
+pub use download::*;
+
+#[cfg(feature = "fork")]
+mod fork;
+
+#[cfg(feature = "fork")]
+pub use fork::*;
+
 #[cfg(feature = "resolver")]
 mod resolver;
 
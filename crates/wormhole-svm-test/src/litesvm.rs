@@ -10,19 +10,16 @@ use solana_sdk::{
     pubkey::Pubkey,
     rent::Rent,
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use thiserror::Error;
 use wormhole_svm_definitions::{
-    find_guardian_set_address,
-    solana::mainnet::{
-        CORE_BRIDGE_CONFIG, CORE_BRIDGE_PROGRAM_ID, POST_MESSAGE_SHIM_PROGRAM_ID,
-        VERIFY_VAA_SHIM_PROGRAM_ID,
-    },
+    find_guardian_set_address, solana::mainnet::VERIFY_VAA_SHIM_PROGRAM_ID,
 };
-use wormhole_svm_submit::SolanaConnection;
+use wormhole_svm_submit::{MessageEvent, PostMessageArgs, SolanaConnection};
 
 pub use wormhole_svm_submit::signatures::PostedSignatures;
+pub use wormhole_svm_submit::WormholeNetwork;
 
 use crate::TestGuardianSet;
 
@@ -57,6 +54,16 @@ pub enum WormholeTestError {
     EmitterChainBypass(String),
     #[error("Emitter address check missing: {0}")]
     EmitterAddressBypass(String),
+    #[error("Quorum check missing: {0}")]
+    QuorumBypass(String),
+    #[error("Guardian set index check missing: {0}")]
+    GuardianSetIndexBypass(String),
+    #[error("Guardian set expiration check missing: {0}")]
+    ExpiredGuardianSetBypass(String),
+    #[error("Payload validation missing: {0}")]
+    PayloadValidationMissing(String),
+    #[error("Mutated VAA accepted: {0}")]
+    MutationAccepted(String),
     #[error("Replay protection missing: {0}")]
     ReplayProtectionMissing(String),
     #[error("Submit error: {0}")]
@@ -65,6 +72,71 @@ pub enum WormholeTestError {
 
 // ReplayProtection is defined in vaa.rs and re-exported from the crate root.
 
+/// The outcome of one of [`with_vaa`]'s automatic negative tests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The check ran and the program rejected the VAA with this error.
+    Rejected(String),
+    /// The check didn't run, for this reason.
+    Skipped(String),
+}
+
+/// Which negative tests [`with_vaa`] ran and how the program rejected each,
+/// in the order they ran.
+///
+/// Its `Display` prints one line per check, for test logs:
+///
+/// ```text
+/// signatures: rejected (tx failed: ...)
+/// emitter_chain: skipped (disabled in VaaChecks)
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CheckReport {
+    checks: Vec<(String, CheckOutcome)>,
+}
+
+impl CheckReport {
+    /// The checks, by name, in the order they ran.
+    pub fn checks(&self) -> &[(String, CheckOutcome)] {
+        &self.checks
+    }
+
+    /// The outcome of the check `name` (e.g. `"replay"`), if it was reached.
+    pub fn outcome(&self, name: &str) -> Option<&CheckOutcome> {
+        self.checks
+            .iter()
+            .find(|(check, _)| check == name)
+            .map(|(_, outcome)| outcome)
+    }
+
+    /// Whether the check `name` ran (and the program rejected the VAA).
+    pub fn ran(&self, name: &str) -> bool {
+        matches!(self.outcome(name), Some(CheckOutcome::Rejected(_)))
+    }
+
+    fn rejected(&mut self, name: impl Into<String>, error: impl std::fmt::Display) {
+        self.checks
+            .push((name.into(), CheckOutcome::Rejected(error.to_string())));
+    }
+
+    fn skipped(&mut self, name: &str, reason: &str) {
+        self.checks
+            .push((name.to_string(), CheckOutcome::Skipped(reason.to_string())));
+    }
+}
+
+impl std::fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, outcome) in &self.checks {
+            match outcome {
+                CheckOutcome::Rejected(error) => writeln!(f, "{}: rejected ({})", name, error)?,
+                CheckOutcome::Skipped(reason) => writeln!(f, "{}: skipped ({})", name, reason)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Configuration for loading Wormhole programs.
 #[derive(Default)]
 pub struct WormholeProgramsConfig {
@@ -74,6 +146,17 @@ pub struct WormholeProgramsConfig {
     pub core_bridge: Option<PathBuf>,
     /// Path to post_message_shim.so (or None to search default locations).
     pub post_message_shim: Option<PathBuf>,
+    /// Addresses to load the programs at, and to create the core bridge
+    /// accounts under (default: Solana mainnet).
+    ///
+    /// The bundled binaries are mainnet builds. The shims have the core bridge
+    /// address compiled in, so tests of a devnet build should load binaries
+    /// dumped from devnet (`solana program dump -ud ...`).
+    pub network: WormholeNetwork,
+    /// Download programs that are neither given, bundled nor found (default:
+    /// from `WORMHOLE_FIXTURES_RPC_URL`, if set).
+    #[cfg(feature = "fixture-download")]
+    pub download: Option<crate::FixtureDownload>,
 }
 
 /// Accounts created by setup_wormhole.
@@ -104,7 +187,10 @@ Or dump them from mainnet yourself:
         EtZMZM22ViKMo4r5y4Anovs3wKQ2owUmDpjygnMMcdEX \
         fixtures/post_message_shim.so
 
-Or set WORMHOLE_FIXTURES_DIR environment variable to point to existing binaries."#;
+Or set WORMHOLE_FIXTURES_DIR environment variable to point to existing binaries.
+
+Or enable the `fixture-download` feature and set WORMHOLE_FIXTURES_RPC_URL to
+download them once into a cache directory."#;
 
 /// Search paths for program binaries.
 fn search_paths() -> Vec<PathBuf> {
@@ -144,10 +230,18 @@ fn find_program_file(filename: &str) -> Result<PathBuf, WormholeTestError> {
 ///
 /// With the `bundled-fixtures` feature enabled, programs are loaded from
 /// bundled binaries by default. You can still override with explicit paths.
+/// Programs are loaded at the addresses of `config.network`.
 pub fn load_wormhole_programs(
     svm: &mut LiteSVM,
     config: WormholeProgramsConfig,
 ) -> Result<(), WormholeTestError> {
+    let network = config.network;
+    #[cfg(feature = "fixture-download")]
+    let download = config
+        .download
+        .clone()
+        .or_else(crate::FixtureDownload::from_env);
+
     // Load Verify VAA Shim
     let shim_bytes = get_program_bytes(
         "verify_vaa_shim.so",
@@ -156,8 +250,12 @@ pub fn load_wormhole_programs(
         Some(VERIFY_VAA_SHIM_BYTES),
         #[cfg(not(feature = "bundled-fixtures"))]
         None,
+        #[cfg(feature = "fixture-download")]
+        download
+            .as_ref()
+            .map(|download| (download, &network.verify_vaa_shim)),
     )?;
-    svm.add_program(VERIFY_VAA_SHIM_PROGRAM_ID, &shim_bytes)
+    svm.add_program(network.verify_vaa_shim, &shim_bytes)
         .map_err(|e| WormholeTestError::LoadError(format!("verify_vaa_shim: {}", e)))?;
 
     // Load Core Bridge
@@ -168,8 +266,12 @@ pub fn load_wormhole_programs(
         Some(CORE_BRIDGE_BYTES),
         #[cfg(not(feature = "bundled-fixtures"))]
         None,
+        #[cfg(feature = "fixture-download")]
+        download
+            .as_ref()
+            .map(|download| (download, &network.core_bridge)),
     )?;
-    svm.add_program(CORE_BRIDGE_PROGRAM_ID, &bridge_bytes)
+    svm.add_program(network.core_bridge, &bridge_bytes)
         .map_err(|e| WormholeTestError::LoadError(format!("core_bridge: {}", e)))?;
 
     // Load Post Message Shim
@@ -180,18 +282,24 @@ pub fn load_wormhole_programs(
         Some(POST_MESSAGE_SHIM_BYTES),
         #[cfg(not(feature = "bundled-fixtures"))]
         None,
+        #[cfg(feature = "fixture-download")]
+        download
+            .as_ref()
+            .map(|download| (download, &network.post_message_shim)),
     )?;
-    svm.add_program(POST_MESSAGE_SHIM_PROGRAM_ID, &post_shim_bytes)
+    svm.add_program(network.post_message_shim, &post_shim_bytes)
         .map_err(|e| WormholeTestError::LoadError(format!("post_message_shim: {}", e)))?;
 
     Ok(())
 }
 
-/// Get program bytes from explicit path, bundled bytes, or file search.
-fn get_program_bytes(
+/// Get program bytes from explicit path, bundled bytes, file search, or
+/// (with the `fixture-download` feature) the download cache.
+pub(crate) fn get_program_bytes(
     filename: &str,
     explicit_path: Option<&PathBuf>,
     bundled: Option<&'static [u8]>,
+    #[cfg(feature = "fixture-download")] download: Option<(&crate::FixtureDownload, &Pubkey)>,
 ) -> Result<Vec<u8>, WormholeTestError> {
     // Explicit path takes priority
     if let Some(path) = explicit_path {
@@ -210,7 +318,14 @@ fn get_program_bytes(
         return Ok(bytes.to_vec());
     }
 
-    // Fall back to file search
+    // Fall back to file search, then to downloading
+    #[cfg(feature = "fixture-download")]
+    if let Some((download, program_id)) = download {
+        if let Ok(path) = find_program_file(filename) {
+            return Ok(std::fs::read(&path)?);
+        }
+        return download.program(program_id);
+    }
     let path = find_program_file(filename)?;
     Ok(std::fs::read(&path)?)
 }
@@ -223,7 +338,17 @@ pub fn create_guardian_set_account(
     guardians: &TestGuardianSet,
     index: u32,
 ) -> (Pubkey, u8) {
-    let (address, bump) = find_guardian_set_address(index.to_be_bytes(), &CORE_BRIDGE_PROGRAM_ID);
+    create_guardian_set_account_for_network(svm, &WormholeNetwork::SOLANA_MAINNET, guardians, index)
+}
+
+/// Create a guardian set account under the core bridge of `network`.
+pub fn create_guardian_set_account_for_network(
+    svm: &mut LiteSVM,
+    network: &WormholeNetwork,
+    guardians: &TestGuardianSet,
+    index: u32,
+) -> (Pubkey, u8) {
+    let (address, bump) = find_guardian_set_address(index.to_be_bytes(), &network.core_bridge);
     let data = build_guardian_set_data(guardians, index);
 
     let rent = Rent::default();
@@ -232,7 +357,7 @@ pub fn create_guardian_set_account(
     let account = Account {
         lamports,
         data,
-        owner: CORE_BRIDGE_PROGRAM_ID,
+        owner: network.core_bridge,
         executable: false,
         rent_epoch: 0,
     };
@@ -245,34 +370,69 @@ pub fn create_guardian_set_account(
 /// Create a bridge config account in LiteSVM.
 ///
 /// This creates a full bridge config that supports both VAA verification and message posting.
-///
-/// Bridge config data structure (BridgeData, borsh-serialized):
-/// - guardian_set_index: u32 (4 bytes, little-endian)
-/// - last_lamports: u64 (8 bytes) - required for post_message fee tracking
-/// - guardian_set_expiration_time: u32 (4 bytes) - BridgeConfig.guardian_set_expiration_time
-/// - fee: u64 (8 bytes) - BridgeConfig.fee
+/// See [`build_bridge_config_data`] for the account data.
 pub fn create_bridge_config(svm: &mut LiteSVM, guardian_set_index: u32) {
-    // Match the fee collector's initial balance so the core bridge fee check works.
-    let rent = Rent::default();
-    let fee_collector_lamports = rent.minimum_balance(0);
+    create_bridge_config_with_fee(svm, guardian_set_index, DEFAULT_BRIDGE_FEE);
+}
 
-    let mut data = Vec::new();
-    data.extend_from_slice(&guardian_set_index.to_le_bytes());
-    data.extend_from_slice(&fee_collector_lamports.to_le_bytes()); // last_lamports
-    data.extend_from_slice(&86400u32.to_le_bytes()); // 24 hour expiration
-    data.extend_from_slice(&10u64.to_le_bytes()); // 10 lamport fee
+/// Create a bridge config account in LiteSVM with a message fee of `fee` lamports.
+pub fn create_bridge_config_with_fee(svm: &mut LiteSVM, guardian_set_index: u32, fee: u64) {
+    create_bridge_config_for_network(
+        svm,
+        &WormholeNetwork::SOLANA_MAINNET,
+        guardian_set_index,
+        fee,
+    );
+}
 
+/// Create the bridge config account of `network`'s core bridge, with a
+/// message fee of `fee` lamports.
+pub fn create_bridge_config_for_network(
+    svm: &mut LiteSVM,
+    network: &WormholeNetwork,
+    guardian_set_index: u32,
+    fee: u64,
+) {
+    let rent = Rent::default();
+    let data = build_bridge_config_data_with_fee(guardian_set_index, fee);
     let lamports = rent.minimum_balance(data.len());
 
     let account = Account {
         lamports,
         data,
-        owner: CORE_BRIDGE_PROGRAM_ID,
+        owner: network.core_bridge,
         executable: false,
         rent_epoch: 0,
     };
 
-    svm.set_account(CORE_BRIDGE_CONFIG, account).unwrap();
+    svm.set_account(network.core_bridge_config_address(), account)
+        .unwrap();
+}
+
+/// Build bridge config account data.
+///
+/// Bridge config data structure (BridgeData, borsh-serialized):
+/// - guardian_set_index: u32 (4 bytes, little-endian)
+/// - last_lamports: u64 (8 bytes) - required for post_message fee tracking
+/// - guardian_set_expiration_time: u32 (4 bytes) - BridgeConfig.guardian_set_expiration_time
+/// - fee: u64 (8 bytes) - BridgeConfig.fee
+///
+/// `last_lamports` matches the initial balance of the fee collector created by
+/// [`create_fee_collector`], so the core bridge fee check works.
+pub fn build_bridge_config_data(guardian_set_index: u32) -> Vec<u8> {
+    build_bridge_config_data_with_fee(guardian_set_index, DEFAULT_BRIDGE_FEE)
+}
+
+/// Build bridge config account data with a message fee of `fee` lamports.
+pub fn build_bridge_config_data_with_fee(guardian_set_index: u32, fee: u64) -> Vec<u8> {
+    let fee_collector_lamports = Rent::default().minimum_balance(0);
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&guardian_set_index.to_le_bytes());
+    data.extend_from_slice(&fee_collector_lamports.to_le_bytes()); // last_lamports
+    data.extend_from_slice(&86400u32.to_le_bytes()); // 24 hour expiration
+    data.extend_from_slice(&fee.to_le_bytes());
+    data
 }
 
 /// The default bridge fee set by [`create_bridge_config`] (in lamports).
@@ -294,8 +454,11 @@ pub fn build_bridge_fee_ix(payer: &Pubkey) -> Instruction {
 /// The fee collector is needed for posting Wormhole messages.
 /// It's a simple system-owned account that receives bridge fees.
 pub fn create_fee_collector(svm: &mut LiteSVM) {
-    use wormhole_svm_definitions::solana::mainnet::CORE_BRIDGE_FEE_COLLECTOR;
+    create_fee_collector_for_network(svm, &WormholeNetwork::SOLANA_MAINNET);
+}
 
+/// Create the fee collector account of `network`'s core bridge.
+pub fn create_fee_collector_for_network(svm: &mut LiteSVM, network: &WormholeNetwork) {
     let rent = Rent::default();
     let account = Account {
         lamports: rent.minimum_balance(0),
@@ -305,7 +468,8 @@ pub fn create_fee_collector(svm: &mut LiteSVM) {
         rent_epoch: 0,
     };
 
-    svm.set_account(CORE_BRIDGE_FEE_COLLECTOR, account).unwrap();
+    svm.set_account(network.fee_collector_address(), account)
+        .unwrap();
 }
 
 /// Set up Wormhole in an existing LiteSVM instance.
@@ -315,19 +479,24 @@ pub fn create_fee_collector(svm: &mut LiteSVM) {
 /// 2. Creates a guardian set account
 /// 3. Creates a bridge config account (with full support for message posting)
 /// 4. Creates the fee collector account
+///
+/// Everything is created at the addresses of `config.network`, Solana mainnet
+/// by default. With [`WormholeNetwork::SOLANA_DEVNET`], the accounts match
+/// what a devnet build of the program under test derives.
 pub fn setup_wormhole(
     svm: &mut LiteSVM,
     guardians: &TestGuardianSet,
     guardian_set_index: u32,
     config: WormholeProgramsConfig,
 ) -> Result<WormholeAccounts, WormholeTestError> {
+    let network = config.network;
     load_wormhole_programs(svm, config)?;
 
     let (guardian_set, guardian_set_bump) =
-        create_guardian_set_account(svm, guardians, guardian_set_index);
+        create_guardian_set_account_for_network(svm, &network, guardians, guardian_set_index);
 
-    create_bridge_config(svm, guardian_set_index);
-    create_fee_collector(svm);
+    create_bridge_config_for_network(svm, &network, guardian_set_index, DEFAULT_BRIDGE_FEE);
+    create_fee_collector_for_network(svm, &network);
 
     Ok(WormholeAccounts {
         guardian_set,
@@ -418,6 +587,8 @@ impl SolanaConnection for LiteSvmConnection<'_> {
         Ok(wormhole_svm_submit::connection::SimulationResult {
             return_data,
             post_accounts,
+            units_consumed: Some(result.meta.compute_units_consumed),
+            logs: result.meta.logs,
         })
     }
 
@@ -428,9 +599,29 @@ impl SolanaConnection for LiteSvmConnection<'_> {
             .map_err(|e| LiteSvmError(format!("Transaction failed: {:?}", e)))
     }
 
+    fn send_and_confirm_versioned(
+        &mut self,
+        tx: &VersionedTransaction,
+    ) -> Result<Signature, Self::Error> {
+        self.0
+            .send_transaction(tx.clone())
+            .map(|_| tx.signatures[0])
+            .map_err(|e| LiteSvmError(format!("Transaction failed: {:?}", e)))
+    }
+
+    fn supports_versioned_transactions(&self) -> bool {
+        true
+    }
+
     fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, Self::Error> {
         Ok(self.0.get_account(pubkey))
     }
+
+    fn advance_slot(&mut self) -> Result<(), Self::Error> {
+        let slot = self.0.get_sysvar::<solana_sdk::clock::Clock>().slot;
+        self.0.warp_to_slot(slot + 1);
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -538,6 +729,17 @@ where
 /// 1. **Negative test (on cloned SVM)**: Clones the SVM, posts mismatched signatures,
 ///    and runs your closure. If it succeeds, your program doesn't verify VAAs -
 ///    returns `VerificationBypass` error. The clone is discarded, so no state persists.
+///    The other enabled [`VaaChecks`](crate::VaaChecks) run the same way, each on its
+///    own clone:
+///    - wrong emitter chain / address (`EmitterChainBypass`, `EmitterAddressBypass`)
+///    - signatures from one guardian short of quorum, for sets of more than one
+///      guardian (`QuorumBypass`)
+///    - signatures posted under the next guardian set index (`GuardianSetIndexBypass`)
+///    - the guardian set expired, with the clock past the expiration
+///      (`ExpiredGuardianSetBypass`)
+///    - opt-in: a validly signed VAA with its payload's first byte flipped
+///      (`PayloadValidationMissing`)
+///    - each of the VAA's [`mutations`](crate::TestVaa::mutations) (`MutationAccepted`)
 ///
 /// 2. **Positive test (on original SVM)**: Posts correct signatures and runs your
 ///    closure, committing state changes.
@@ -550,6 +752,9 @@ where
 /// is just the body bytes (used for digest calculation). Using clone + discard
 /// ensures the negative test behaves identically to real execution.
 ///
+/// Returns the closure's result from the positive test, with a [`CheckReport`] of
+/// which negative tests ran and the error the program rejected each with.
+///
 /// # Arguments
 ///
 /// * `replay_protection` - Whether to verify replay protection:
@@ -563,23 +768,24 @@ where
 ///
 /// let vaa = TestVaa::new(1, emitter_address_from_20([0xAB; 20]), 42, payload);
 ///
-/// let result = with_vaa(
+/// let (result, report) = with_vaa(
 ///     &mut svm,
 ///     &payer,
 ///     &guardians,
 ///     0, // guardian_set_index
 ///     &vaa,
-///     ReplayProtection::NonReplayable, // Verify replay protection
 ///     |svm, sigs_pubkey, vaa_body| {
 ///         let ix = build_my_verify_instruction(sigs_pubkey, vaa_body);
 ///         let tx = Transaction::new_signed_with_payer(...);
 ///         svm.send_transaction(tx).map_err(|e| format!("{:?}", e))
 ///     },
 /// )?;
+/// println!("{}", report);
 /// ```
 ///
 /// # See Also
 ///
+/// - [`with_vaa_for_network`] - For Wormhole set up at another network's addresses
 /// - [`with_vaa_unchecked`] - Skip all automatic tests (use sparingly)
 pub fn with_vaa<F, T, E>(
     svm: &mut LiteSVM,
@@ -587,14 +793,42 @@ pub fn with_vaa<F, T, E>(
     guardians: &TestGuardianSet,
     guardian_set_index: u32,
     vaa: &crate::TestVaa,
+    f: F,
+) -> Result<(T, CheckReport), WormholeTestError>
+where
+    F: FnMut(&mut LiteSVM, &Pubkey, &[u8]) -> Result<T, E>,
+    E: std::fmt::Display,
+{
+    with_vaa_for_network(
+        svm,
+        payer,
+        guardians,
+        guardian_set_index,
+        &WormholeNetwork::SOLANA_MAINNET,
+        vaa,
+        f,
+    )
+}
+
+/// [`with_vaa`] for Wormhole set up at the addresses of `network` (the
+/// [`WormholeProgramsConfig::network`] given to [`setup_wormhole`]). The
+/// expired guardian set check expires the guardian set under its core bridge.
+pub fn with_vaa_for_network<F, T, E>(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    guardians: &TestGuardianSet,
+    guardian_set_index: u32,
+    network: &WormholeNetwork,
+    vaa: &crate::TestVaa,
     mut f: F,
-) -> Result<T, WormholeTestError>
+) -> Result<(T, CheckReport), WormholeTestError>
 where
     F: FnMut(&mut LiteSVM, &Pubkey, &[u8]) -> Result<T, E>,
     E: std::fmt::Display,
 {
     // Get just the body bytes - this is what the program needs for digest calculation
     let vaa_body = vaa.body();
+    let mut report = CheckReport::default();
 
     // === NEGATIVE TEST (on cloned SVM - discarded after) ===
     // Clone the SVM so any state changes from the negative test don't persist
@@ -618,14 +852,15 @@ where
     // Clone is discarded here (dropped) - no state changes persist
 
     // If negative test succeeded, the program doesn't verify VAAs!
-    if negative_result.is_ok() {
+    let Err(error) = negative_result else {
         return Err(WormholeTestError::VerificationBypass(
             "SECURITY: Program accepted VAA with mismatched signatures! \
              This means your program is not actually verifying VAAs. \
              Ensure you call verify_hash CPI before processing the VAA."
                 .to_string(),
         ));
-    }
+    };
+    report.rejected("signatures", error);
 
     // === NEGATIVE TEST: Wrong emitter chain (on cloned SVM) ===
     if vaa.checks.emitter_chain {
@@ -638,13 +873,16 @@ where
         let wrong_chain_sigs = wrong_chain_vaa.guardian_signatures(guardians);
         let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &wrong_chain_sigs)?;
         let result = f(&mut svm_clone, &posted.pubkey, &wrong_chain_body);
-        if result.is_ok() {
+        let Err(error) = result else {
             return Err(WormholeTestError::EmitterChainBypass(
                 "SECURITY: Program accepted VAA with wrong emitter chain! \
                  Ensure you validate the emitter_chain field before processing."
                     .to_string(),
             ));
-        }
+        };
+        report.rejected("emitter_chain", error);
+    } else {
+        report.skipped("emitter_chain", "disabled in VaaChecks");
     }
 
     // === NEGATIVE TEST: Wrong emitter address (on cloned SVM) ===
@@ -660,13 +898,134 @@ where
         let wrong_addr_sigs = wrong_addr_vaa.guardian_signatures(guardians);
         let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &wrong_addr_sigs)?;
         let result = f(&mut svm_clone, &posted.pubkey, &wrong_addr_body);
-        if result.is_ok() {
+        let Err(error) = result else {
             return Err(WormholeTestError::EmitterAddressBypass(
                 "SECURITY: Program accepted VAA with wrong emitter address! \
                  Ensure you validate the emitter_address field before processing."
                     .to_string(),
             ));
-        }
+        };
+        report.rejected("emitter_address", error);
+    } else {
+        report.skipped("emitter_address", "disabled in VaaChecks");
+    }
+
+    // === NEGATIVE TEST: Fewer signatures than quorum (on cloned SVM) ===
+    if vaa.checks.quorum && guardians.len() > 1 {
+        let mut svm_clone = svm.clone();
+        let indices: Vec<u8> = (0..guardians.quorum() as u8 - 1).collect();
+        let under_signed = guardians.sign_vaa_body_with(&vaa_body, &indices);
+        let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &under_signed)?;
+        let result = f(&mut svm_clone, &posted.pubkey, &vaa_body);
+        let Err(error) = result else {
+            return Err(WormholeTestError::QuorumBypass(format!(
+                "SECURITY: Program accepted VAA signed by {} of {} guardians (quorum is {})! \
+                 Ensure you verify signatures with the core bridge's verify_hash CPI, \
+                 which enforces quorum.",
+                indices.len(),
+                guardians.len(),
+                guardians.quorum()
+            )));
+        };
+        report.rejected("quorum", error);
+    } else {
+        report.skipped(
+            "quorum",
+            if vaa.checks.quorum {
+                "single guardian"
+            } else {
+                "disabled in VaaChecks"
+            },
+        );
+    }
+
+    // === NEGATIVE TEST: Signatures under another guardian set index (on cloned SVM) ===
+    if vaa.checks.guardian_set_index {
+        let mut svm_clone = svm.clone();
+        let wrong_index = guardian_set_index.wrapping_add(1);
+        let signatures = vaa.guardian_signatures(guardians);
+        let posted = post_signatures(&mut svm_clone, payer, wrong_index, &signatures)?;
+        let result = f(&mut svm_clone, &posted.pubkey, &vaa_body);
+        let Err(error) = result else {
+            return Err(WormholeTestError::GuardianSetIndexBypass(format!(
+                "SECURITY: Program accepted signatures posted for guardian set {} \
+                 while verifying against guardian set {}! Ensure the signatures account \
+                 is bound to the guardian set (verify_hash checks this).",
+                wrong_index, guardian_set_index
+            )));
+        };
+        report.rejected("guardian_set_index", error);
+    } else {
+        report.skipped("guardian_set_index", "disabled in VaaChecks");
+    }
+
+    // === NEGATIVE TEST: Expired guardian set (on cloned SVM) ===
+    if vaa.checks.expired_guardian_set {
+        let mut svm_clone = svm.clone();
+        crate::expire_guardian_set_now_for_network(&mut svm_clone, network, guardian_set_index)?;
+        let signatures = vaa.guardian_signatures(guardians);
+        let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &signatures)?;
+        let result = f(&mut svm_clone, &posted.pubkey, &vaa_body);
+        let Err(error) = result else {
+            return Err(WormholeTestError::ExpiredGuardianSetBypass(format!(
+                "SECURITY: Program accepted VAA signed by expired guardian set {}! \
+                 Ensure you verify signatures with the core bridge's verify_hash CPI, \
+                 which rejects expired guardian sets.",
+                guardian_set_index
+            )));
+        };
+        report.rejected("expired_guardian_set", error);
+    } else {
+        report.skipped("expired_guardian_set", "disabled in VaaChecks");
+    }
+
+    // === NEGATIVE TEST: Mutated payload, validly signed (on cloned SVM) ===
+    if vaa.checks.payload_mutation && !vaa.payload.is_empty() {
+        let mut svm_clone = svm.clone();
+        let mut mutated_payload = vaa.payload.clone();
+        mutated_payload[0] ^= 0xFF;
+        let mutated_vaa = crate::TestVaa {
+            payload: mutated_payload,
+            ..vaa.clone()
+        };
+        let mutated_body = mutated_vaa.body();
+        let mutated_sigs = mutated_vaa.guardian_signatures(guardians);
+        let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &mutated_sigs)?;
+        let result = f(&mut svm_clone, &posted.pubkey, &mutated_body);
+        let Err(error) = result else {
+            return Err(WormholeTestError::PayloadValidationMissing(
+                "Program accepted a VAA with a mutated payload (first byte flipped)! \
+                 Ensure you validate the payload type and format before processing."
+                    .to_string(),
+            ));
+        };
+        report.rejected("payload_mutation", error);
+    } else {
+        report.skipped(
+            "payload_mutation",
+            if vaa.checks.payload_mutation {
+                "empty payload"
+            } else {
+                "disabled in VaaChecks"
+            },
+        );
+    }
+
+    // === NEGATIVE TESTS: Caller-registered mutations (on cloned SVMs) ===
+    for mutation in &vaa.mutations {
+        let mut svm_clone = svm.clone();
+        let mutated_vaa = mutation.mutate(vaa);
+        let mutated_body = mutated_vaa.body();
+        let mutated_sigs = mutated_vaa.guardian_signatures(guardians);
+        let posted = post_signatures(&mut svm_clone, payer, guardian_set_index, &mutated_sigs)?;
+        let result = f(&mut svm_clone, &posted.pubkey, &mutated_body);
+        let Err(error) = result else {
+            return Err(WormholeTestError::MutationAccepted(format!(
+                "Program accepted a mutated VAA; expected it to fail with: {}",
+                mutation.expected_failure()
+            )));
+        };
+        report.rejected(format!("mutation: {}", mutation.expected_failure()), error);
     }
 
     // === POSITIVE TEST (on original SVM - commits state) ===
@@ -698,7 +1057,7 @@ where
         // Clone is discarded regardless - we only care about the result
 
         // If replay succeeded, the program lacks replay protection!
-        if replay_result.is_ok() {
+        let Err(error) = replay_result else {
             return Err(WormholeTestError::ReplayProtectionMissing(
                 "SECURITY: Program accepted the same VAA twice! \
                  This means your program lacks replay protection. \
@@ -706,12 +1065,15 @@ where
                  before processing them."
                     .to_string(),
             ));
-        }
+        };
+        report.rejected("replay", error);
         // Replay failed as expected - replay protection is working
         // The clone is dropped, original SVM state (after first successful call) is preserved
+    } else {
+        report.skipped("replay", "Replayable");
     }
 
-    Ok(result)
+    Ok((result, report))
 }
 
 /// Execute a closure that verifies a VAA, WITHOUT automatic verification check.
@@ -793,66 +1155,6 @@ pub fn build_close_signatures_ix(
 // Posted Message Capture and VAA Construction
 // =============================================================================
 
-/// Discriminator for the MessageEvent anchor event.
-/// This is sha256("event:MessageEvent")[0..8]
-const MESSAGE_EVENT_DISCRIMINATOR: [u8; 8] = wormhole_svm_definitions::MESSAGE_EVENT_DISCRIMINATOR;
-
-/// Discriminator for the post_message instruction.
-/// This is sha256("global:post_message")[0..8]
-const POST_MESSAGE_SELECTOR: [u8; 8] =
-    wormhole_svm_shim::post_message::PostMessageShimInstruction::<u8>::POST_MESSAGE_SELECTOR;
-
-// Internal: Parsed post_message instruction data.
-#[derive(Clone, Debug)]
-struct PostMessageData {
-    nonce: u32,
-    finality: u8,
-    payload: Vec<u8>,
-}
-
-impl PostMessageData {
-    fn parse(data: &[u8]) -> Option<Self> {
-        // Format: 8 (disc) + 4 (nonce) + 1 (finality) + 4 (len) + payload
-        if data.len() < 17 || data[..8] != POST_MESSAGE_SELECTOR {
-            return None;
-        }
-        let nonce = u32::from_le_bytes(data[8..12].try_into().ok()?);
-        let finality = data[12];
-        let payload_len = u32::from_le_bytes(data[13..17].try_into().ok()?) as usize;
-        if data.len() < 17 + payload_len {
-            return None;
-        }
-        Some(Self {
-            nonce,
-            finality,
-            payload: data[17..17 + payload_len].to_vec(),
-        })
-    }
-}
-
-// Internal: Parsed MessageEvent from the Post Message Shim's self-CPI.
-#[derive(Clone, Debug)]
-struct MessageEvent {
-    emitter: Pubkey,
-    sequence: u64,
-    submission_time: u32,
-}
-
-impl MessageEvent {
-    fn parse(data: &[u8]) -> Option<Self> {
-        // Format: 8 (cpi disc) + 8 (event disc) + 32 (emitter) + 8 (seq) + 4 (time)
-        if data.len() < 60 || data[8..16] != MESSAGE_EVENT_DISCRIMINATOR {
-            return None;
-        }
-        let event_data = &data[16..];
-        Some(Self {
-            emitter: Pubkey::new_from_array(event_data[..32].try_into().ok()?),
-            sequence: u64::from_le_bytes(event_data[32..40].try_into().ok()?),
-            submission_time: u32::from_le_bytes(event_data[40..44].try_into().ok()?),
-        })
-    }
-}
-
 /// Information about a posted Wormhole message.
 ///
 /// This struct captures all the data needed to construct a VAA from a
@@ -901,17 +1203,15 @@ impl PostedMessageInfo {
     ///
     /// The resulting TestVaa can be signed by guardians to produce a verifiable VAA.
     pub fn to_test_vaa(&self) -> crate::TestVaa {
-        crate::TestVaa {
-            emitter_chain: self.emitter_chain,
-            emitter_address: self.emitter.to_bytes(),
-            sequence: self.sequence,
-            payload: self.payload.clone(),
-            timestamp: self.timestamp,
-            nonce: self.nonce,
-            consistency_level: self.consistency_level,
-            guardian_set_index: 0,
-            checks: Default::default(),
-        }
+        crate::TestVaa::new(
+            self.emitter_chain,
+            self.emitter.to_bytes(),
+            self.sequence,
+            self.payload.clone(),
+        )
+        .with_timestamp(self.timestamp)
+        .with_nonce(self.nonce)
+        .with_consistency_level(self.consistency_level)
     }
 }
 
@@ -923,6 +1223,8 @@ impl PostedMessageInfo {
 /// - The `MessageEvent` self-CPI events (for emitter, sequence, timestamp)
 ///
 /// These are paired up in order - the Nth post_message corresponds to the Nth MessageEvent.
+/// The metadata doesn't carry the transaction's account keys, so events are
+/// recognized by their data alone (see `MessageEvent::parse_data`).
 ///
 /// # Example
 ///
@@ -949,10 +1251,10 @@ pub fn extract_posted_message_info_from_tx(
 
     for inner_list in &meta.inner_instructions {
         for inner in inner_list {
-            if let Some(data) = PostMessageData::parse(&inner.instruction.data) {
+            if let Some(data) = PostMessageArgs::parse(&inner.instruction.data) {
                 post_messages.push(data);
             }
-            if let Some(event) = MessageEvent::parse(&inner.instruction.data) {
+            if let Some(event) = MessageEvent::parse_data(&inner.instruction.data) {
                 events.push(event);
             }
         }
@@ -967,7 +1269,7 @@ pub fn extract_posted_message_info_from_tx(
                 &event,
                 post_msg.payload,
                 post_msg.nonce,
-                post_msg.finality,
+                post_msg.consistency_level,
             )
         })
         .collect()
@@ -977,9 +1279,16 @@ pub fn extract_posted_message_info_from_tx(
 ///
 /// Returns `None` if the sequence account doesn't exist yet (first message not posted).
 pub fn read_emitter_sequence(svm: &LiteSVM, emitter: &Pubkey) -> Option<u64> {
-    use wormhole_svm_definitions::find_emitter_sequence_address;
+    read_emitter_sequence_for_network(svm, &WormholeNetwork::SOLANA_MAINNET, emitter)
+}
 
-    let (sequence_addr, _) = find_emitter_sequence_address(emitter, &CORE_BRIDGE_PROGRAM_ID);
+/// Read the current sequence number for an emitter under `network`'s core bridge.
+pub fn read_emitter_sequence_for_network(
+    svm: &LiteSVM,
+    network: &WormholeNetwork,
+    emitter: &Pubkey,
+) -> Option<u64> {
+    let sequence_addr = network.emitter_sequence_address(emitter);
     let account = svm.get_account(&sequence_addr)?;
 
     // Sequence account data is just a u64 (little-endian)
@@ -995,6 +1304,9 @@ mod tests {
     use super::*;
     use crate::TestGuardian;
     use std::path::Path;
+    use wormhole_svm_definitions::solana::mainnet::{
+        CORE_BRIDGE_CONFIG, CORE_BRIDGE_PROGRAM_ID, POST_MESSAGE_SHIM_PROGRAM_ID,
+    };
 
     #[test]
     fn test_guardian_set_data_structure() {
@@ -1072,6 +1384,35 @@ mod tests {
         assert!(post_shim_account.is_some(), "Post message shim not loaded");
     }
 
+    #[cfg(feature = "bundled-fixtures")]
+    #[test]
+    fn test_setup_wormhole_on_devnet() {
+        let mut svm = LiteSVM::new();
+        let guardians = TestGuardianSet::single(TestGuardian::default());
+        let network = WormholeNetwork::SOLANA_DEVNET;
+        let config = WormholeProgramsConfig {
+            network,
+            ..Default::default()
+        };
+
+        let accounts = setup_wormhole(&mut svm, &guardians, 0, config).unwrap();
+
+        assert_eq!(accounts.guardian_set, network.guardian_set_address(0));
+        let guardian_set_account = svm.get_account(&accounts.guardian_set).unwrap();
+        assert_eq!(guardian_set_account.owner, network.core_bridge);
+
+        let bridge_account = svm.get_account(&network.core_bridge).unwrap();
+        assert!(bridge_account.executable, "Devnet core bridge not loaded");
+        assert!(svm
+            .get_account(&network.core_bridge_config_address())
+            .is_some());
+        assert!(svm.get_account(&network.fee_collector_address()).is_some());
+
+        // Nothing is created at the mainnet core bridge addresses
+        assert!(svm.get_account(&CORE_BRIDGE_PROGRAM_ID).is_none());
+        assert!(svm.get_account(&CORE_BRIDGE_CONFIG).is_none());
+    }
+
     #[cfg(feature = "bundled-fixtures")]
     #[test]
     fn test_bundled_fixtures_are_valid_elf() {
@@ -1183,45 +1524,11 @@ mod tests {
     // Note: with_vaa, with_vaa_unchecked, and message emission are tested in
     // integration tests (tests/verify_vaa_example.rs and tests/emit_message_example.rs).
 
-    #[test]
-    fn test_message_event_parsing() {
-        // Test parsing MessageEvent from raw bytes
-        let emitter = Pubkey::new_unique();
-        let sequence = 42u64;
-        let submission_time = 1234567890u32;
-
-        // Build the event data manually in the Anchor self-CPI format:
-        // - 8 bytes: Anchor self-CPI instruction discriminator (any value)
-        // - 8 bytes: MessageEvent event discriminator
-        // - borsh-encoded event data
-        let mut data = Vec::new();
-        // Outer CPI discriminator (any 8 bytes - Anchor's internal discriminator)
-        data.extend_from_slice(&[228, 69, 165, 46, 81, 203, 154, 29]);
-        // MessageEvent discriminator
-        data.extend_from_slice(&MESSAGE_EVENT_DISCRIMINATOR);
-        // Event data
-        data.extend_from_slice(&emitter.to_bytes());
-        data.extend_from_slice(&sequence.to_le_bytes());
-        data.extend_from_slice(&submission_time.to_le_bytes());
-
-        // Parse it back
-        let event = MessageEvent::parse(&data).expect("should parse");
-        assert_eq!(event.emitter, emitter);
-        assert_eq!(event.sequence, sequence);
-        assert_eq!(event.submission_time, submission_time);
-
-        // Test with wrong event discriminator (corrupt bytes 8-15)
-        let mut bad_data = data.clone();
-        bad_data[8] = 0xFF;
-        assert!(MessageEvent::parse(&bad_data).is_none());
-
-        // Test with truncated data
-        assert!(MessageEvent::parse(&data[..20]).is_none());
-    }
-
     #[test]
     fn test_post_message_data_parsing() {
-        // Test parsing PostMessageData from raw instruction data
+        use wormhole_svm_shim::post_message::PostMessageShimInstruction;
+
+        // Test parsing post_message instruction data
         let nonce = 12345u32;
         let finality = 1u8;
         let payload = b"Test payload for parsing";
@@ -1233,27 +1540,27 @@ mod tests {
         // - 4 bytes: payload_len (LE)
         // - N bytes: payload
         let mut data = Vec::new();
-        data.extend_from_slice(&POST_MESSAGE_SELECTOR);
+        data.extend_from_slice(&PostMessageShimInstruction::<u8>::POST_MESSAGE_SELECTOR);
         data.extend_from_slice(&nonce.to_le_bytes());
         data.push(finality);
         data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
         data.extend_from_slice(payload);
 
         // Parse it back
-        let parsed = PostMessageData::parse(&data).expect("should parse");
+        let parsed = PostMessageArgs::parse(&data).expect("should parse");
         assert_eq!(parsed.nonce, nonce);
-        assert_eq!(parsed.finality, finality);
+        assert_eq!(parsed.consistency_level, finality);
         assert_eq!(parsed.payload, payload);
 
         // Test with wrong discriminator
         let mut bad_data = data.clone();
         bad_data[0] = 0xFF;
-        assert!(PostMessageData::parse(&bad_data).is_none());
+        assert!(PostMessageArgs::parse(&bad_data).is_none());
 
         // Test with truncated data (no payload)
-        assert!(PostMessageData::parse(&data[..17]).is_none());
+        assert!(PostMessageArgs::parse(&data[..17]).is_none());
 
         // Test with truncated header
-        assert!(PostMessageData::parse(&data[..10]).is_none());
+        assert!(PostMessageArgs::parse(&data[..10]).is_none());
     }
 }
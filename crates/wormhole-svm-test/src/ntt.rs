@@ -0,0 +1,362 @@
+//! Native Token Transfers (NTT) test harness: transceiver and manager
+//! messages, trimmed amounts, and rate limit clock helpers.
+//!
+//! An NTT transfer arrives as a VAA from the peer chain's Wormhole
+//! transceiver, whose payload nests three messages (all big-endian):
+//!
+//! ```text
+//! WormholeTransceiverMessage  0x9945FF10 | source manager | recipient manager
+//!                             | manager message | transceiver payload
+//! └─ NttManagerMessage        id | sender | payload
+//!    └─ NativeTokenTransfer   0x994E5454 | trimmed amount | source token | to | to chain
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use wormhole_svm_test::*;
+//!
+//! let transfer = NativeTokenTransfer {
+//!     amount: TrimmedAmount::trim(1_000_000_000_000_000_000, 18, 9), // 1 token, 18 -> 9 decimals
+//!     source_token: emitter_address_from_20(evm_token),
+//!     to: recipient.to_bytes(),
+//!     to_chain: 1,
+//!     additional_payload: None,
+//! };
+//! let message = WormholeTransceiverMessage {
+//!     source_ntt_manager: emitter_address_from_20(evm_manager),
+//!     recipient_ntt_manager: ntt_manager_program_id.to_bytes(),
+//!     message: NttManagerMessage::transfer([0; 32], sender, &transfer),
+//!     transceiver_payload: vec![],
+//! };
+//! let vaa = TestVaa::ntt(2, emitter_address_from_20(evm_transceiver), 0, &message);
+//! ```
+
+/// Prefix of a `WormholeTransceiverMessage`.
+pub const WORMHOLE_TRANSCEIVER_MESSAGE_PREFIX: [u8; 4] = [0x99, 0x45, 0xFF, 0x10];
+
+/// Prefix of a `NativeTokenTransfer`.
+pub const NATIVE_TOKEN_TRANSFER_PREFIX: [u8; 4] = [0x99, 0x4E, 0x54, 0x54];
+
+/// Amounts are carried with at most this many decimals.
+pub const TRIMMED_DECIMALS: u8 = 8;
+
+/// Length of the rate limit window: a limit's full capacity refills over
+/// this many seconds, and queued inbound transfers are released after it.
+pub const RATE_LIMIT_DURATION: i64 = 24 * 60 * 60;
+
+/// An amount with the decimals it is carried with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrimmedAmount {
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TrimmedAmount {
+    /// Trim `amount` of a token with `from_decimals` for a transfer to a
+    /// chain where it has `to_decimals`, as the NTT manager does: to the
+    /// smallest of the two and [`TRIMMED_DECIMALS`], dropping the remainder.
+    pub fn trim(amount: u64, from_decimals: u8, to_decimals: u8) -> Self {
+        let decimals = from_decimals.min(to_decimals).min(TRIMMED_DECIMALS);
+        Self {
+            amount: scale(amount, from_decimals, decimals),
+            decimals,
+        }
+    }
+
+    /// The amount in units of a token with `decimals`.
+    pub fn untrim(&self, decimals: u8) -> u64 {
+        scale(self.amount, self.decimals, decimals)
+    }
+}
+
+/// `amount` with `from` decimals in units with `to` decimals.
+///
+/// # Panics
+///
+/// If scaling up overflows a u64.
+fn scale(amount: u64, from: u8, to: u8) -> u64 {
+    if from > to {
+        amount / 10u64.pow(u32::from(from - to))
+    } else {
+        amount
+            .checked_mul(10u64.pow(u32::from(to - from)))
+            .expect("amount overflows u64")
+    }
+}
+
+/// A token transfer, the payload of an [`NttManagerMessage`].
+#[derive(Clone, Debug, Default)]
+pub struct NativeTokenTransfer {
+    pub amount: TrimmedAmount,
+    /// The token on the sending chain.
+    pub source_token: [u8; 32],
+    /// The recipient: a wallet address on Solana, or an EVM address
+    /// left-padded with [`emitter_address_from_20`](crate::emitter_address_from_20).
+    pub to: [u8; 32],
+    pub to_chain: u16,
+    /// Payload for the recipient, for managers that support one.
+    pub additional_payload: Option<Vec<u8>>,
+}
+
+impl NativeTokenTransfer {
+    /// The encoded transfer.
+    pub fn payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(79);
+        payload.extend_from_slice(&NATIVE_TOKEN_TRANSFER_PREFIX);
+        payload.push(self.amount.decimals);
+        payload.extend_from_slice(&self.amount.amount.to_be_bytes());
+        payload.extend_from_slice(&self.source_token);
+        payload.extend_from_slice(&self.to);
+        payload.extend_from_slice(&self.to_chain.to_be_bytes());
+        if let Some(additional) = &self.additional_payload {
+            push_prefixed(&mut payload, additional);
+        }
+        payload
+    }
+}
+
+/// A message between NTT managers.
+#[derive(Clone, Debug, Default)]
+pub struct NttManagerMessage {
+    /// Unique message ID (the sending manager's sequence, on EVM).
+    pub id: [u8; 32],
+    /// The original sender on the source chain.
+    pub sender: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl NttManagerMessage {
+    /// A manager message carrying `transfer`.
+    pub fn transfer(id: [u8; 32], sender: [u8; 32], transfer: &NativeTokenTransfer) -> Self {
+        Self {
+            id,
+            sender,
+            payload: transfer.payload(),
+        }
+    }
+
+    /// The encoded message.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(66 + self.payload.len());
+        encoded.extend_from_slice(&self.id);
+        encoded.extend_from_slice(&self.sender);
+        push_prefixed(&mut encoded, &self.payload);
+        encoded
+    }
+}
+
+/// The payload of a VAA emitted by a Wormhole transceiver.
+#[derive(Clone, Debug, Default)]
+pub struct WormholeTransceiverMessage {
+    pub source_ntt_manager: [u8; 32],
+    pub recipient_ntt_manager: [u8; 32],
+    pub message: NttManagerMessage,
+    /// Transceiver-specific payload; usually empty.
+    pub transceiver_payload: Vec<u8>,
+}
+
+impl WormholeTransceiverMessage {
+    /// The encoded message.
+    pub fn payload(&self) -> Vec<u8> {
+        let message = self.message.encode();
+        let mut payload =
+            Vec::with_capacity(72 + message.len() + 2 + self.transceiver_payload.len());
+        payload.extend_from_slice(&WORMHOLE_TRANSCEIVER_MESSAGE_PREFIX);
+        payload.extend_from_slice(&self.source_ntt_manager);
+        payload.extend_from_slice(&self.recipient_ntt_manager);
+        push_prefixed(&mut payload, &message);
+        push_prefixed(&mut payload, &self.transceiver_payload);
+        payload
+    }
+}
+
+/// Append `bytes` with a big-endian u16 length prefix.
+///
+/// # Panics
+///
+/// If `bytes` is longer than a u16 can describe.
+fn push_prefixed(out: &mut Vec<u8>, bytes: &[u8]) {
+    let len = u16::try_from(bytes.len()).expect("payload longer than u16::MAX");
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+impl crate::TestVaa {
+    /// A VAA from the Wormhole transceiver at `transceiver` on
+    /// `emitter_chain` carrying `message`.
+    pub fn ntt(
+        emitter_chain: u16,
+        transceiver: [u8; 32],
+        sequence: u64,
+        message: &WormholeTransceiverMessage,
+    ) -> Self {
+        Self::new(emitter_chain, transceiver, sequence, message.payload())
+    }
+}
+
+/// Seconds until a rate limit of `limit` regains `amount` of capacity,
+/// rounded up. A limit of 0 never refills.
+pub fn rate_limit_refill_time(limit: u64, amount: u64) -> i64 {
+    if amount == 0 {
+        return 0;
+    }
+    if limit == 0 {
+        return i64::MAX;
+    }
+    let seconds =
+        (u128::from(amount.min(limit)) * RATE_LIMIT_DURATION as u128).div_ceil(u128::from(limit));
+    seconds as i64
+}
+
+/// The capacity of a rate limit of `limit` at `now`, given its capacity at
+/// `last_tx_timestamp`: it refills linearly over [`RATE_LIMIT_DURATION`].
+pub fn rate_limit_capacity(
+    limit: u64,
+    capacity_at_last_tx: u64,
+    last_tx_timestamp: i64,
+    now: i64,
+) -> u64 {
+    let elapsed = now.saturating_sub(last_tx_timestamp).max(0) as u128;
+    let refilled = u128::from(limit) * elapsed / RATE_LIMIT_DURATION as u128;
+    (u128::from(capacity_at_last_tx) + refilled).min(u128::from(limit)) as u64
+}
+
+/// Move LiteSVM's clock forward by `seconds` and return the new unix
+/// timestamp.
+///
+/// Also expires the blockhash, so a transaction that failed on the rate limit
+/// can be sent again unchanged.
+#[cfg(feature = "litesvm")]
+pub fn advance_clock(svm: &mut litesvm::LiteSVM, seconds: i64) -> i64 {
+    let now = svm
+        .get_sysvar::<solana_sdk::clock::Clock>()
+        .unix_timestamp
+        .saturating_add(seconds);
+    set_unix_timestamp(svm, now);
+    now
+}
+
+/// Set LiteSVM's clock to `unix_timestamp` (and expire the blockhash, as
+/// [`advance_clock`] does).
+#[cfg(feature = "litesvm")]
+pub fn set_unix_timestamp(svm: &mut litesvm::LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<solana_sdk::clock::Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+}
+
+/// Move LiteSVM's clock past a full rate limit window: every limit is back at
+/// full capacity and every queued transfer can be released.
+#[cfg(feature = "litesvm")]
+pub fn refill_rate_limits(svm: &mut litesvm::LiteSVM) -> i64 {
+    advance_clock(svm, RATE_LIMIT_DURATION + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emitter_address_from_20;
+
+    #[test]
+    fn test_trimmed_amount() {
+        // 1.5 tokens with 18 decimals, to a chain with 9
+        let amount = TrimmedAmount::trim(1_500_000_000_000_000_123, 18, 9);
+        assert_eq!(
+            amount,
+            TrimmedAmount {
+                amount: 150_000_000,
+                decimals: 8
+            }
+        );
+        assert_eq!(amount.untrim(9), 1_500_000_000);
+        assert_eq!(amount.untrim(18), 1_500_000_000_000_000_000);
+
+        // Fewer decimals than the trim are kept
+        let amount = TrimmedAmount::trim(1_234_567, 6, 9);
+        assert_eq!(amount.decimals, 6);
+        assert_eq!(amount.untrim(9), 1_234_567_000);
+    }
+
+    #[test]
+    fn test_transceiver_message_layout() {
+        let transfer = NativeTokenTransfer {
+            amount: TrimmedAmount {
+                amount: 42,
+                decimals: 8,
+            },
+            source_token: [0xAA; 32],
+            to: emitter_address_from_20([0xBB; 20]),
+            to_chain: 2,
+            additional_payload: None,
+        };
+        let encoded = transfer.payload();
+        assert_eq!(encoded.len(), 79);
+        assert_eq!(&encoded[..4], &NATIVE_TOKEN_TRANSFER_PREFIX);
+        assert_eq!(encoded[4], 8);
+        assert_eq!(&encoded[5..13], &42u64.to_be_bytes());
+        assert_eq!(&encoded[45..57], &[0u8; 12]);
+        assert_eq!(&encoded[57..77], &[0xBB; 20]);
+        assert_eq!(&encoded[77..], &[0, 2]);
+
+        let message = WormholeTransceiverMessage {
+            source_ntt_manager: [0x11; 32],
+            recipient_ntt_manager: [0x22; 32],
+            message: NttManagerMessage::transfer([0x33; 32], [0x44; 32], &transfer),
+            transceiver_payload: vec![],
+        };
+        let vaa = crate::TestVaa::ntt(2, [0x55; 32], 7, &message);
+        let payload = &vaa.payload;
+        assert_eq!(vaa.emitter_address, [0x55; 32]);
+        assert_eq!(&payload[..4], &WORMHOLE_TRANSCEIVER_MESSAGE_PREFIX);
+        assert_eq!(&payload[4..36], &[0x11; 32]);
+        assert_eq!(&payload[36..68], &[0x22; 32]);
+        // Manager message length, then id, sender and the prefixed transfer
+        assert_eq!(&payload[68..70], &(66u16 + 79).to_be_bytes());
+        assert_eq!(&payload[70..102], &[0x33; 32]);
+        assert_eq!(&payload[134..136], &79u16.to_be_bytes());
+        assert_eq!(&payload[136..215], &encoded[..]);
+        // Empty transceiver payload
+        assert_eq!(&payload[215..], &[0, 0]);
+    }
+
+    #[test]
+    fn test_additional_payload() {
+        let transfer = NativeTokenTransfer {
+            additional_payload: Some(vec![9, 9, 9]),
+            ..Default::default()
+        };
+        let encoded = transfer.payload();
+        assert_eq!(&encoded[79..], &[0, 3, 9, 9, 9]);
+    }
+
+    #[test]
+    fn test_rate_limit_math() {
+        assert_eq!(rate_limit_refill_time(1000, 500), RATE_LIMIT_DURATION / 2);
+        assert_eq!(rate_limit_refill_time(1000, 5000), RATE_LIMIT_DURATION);
+        assert_eq!(rate_limit_refill_time(0, 1), i64::MAX);
+        assert_eq!(rate_limit_refill_time(3, 1), 28_800);
+
+        assert_eq!(rate_limit_capacity(1000, 0, 100, 100), 0);
+        assert_eq!(
+            rate_limit_capacity(1000, 0, 0, RATE_LIMIT_DURATION / 4),
+            250
+        );
+        assert_eq!(rate_limit_capacity(1000, 900, 0, RATE_LIMIT_DURATION), 1000);
+    }
+
+    #[cfg(feature = "litesvm")]
+    #[test]
+    fn test_clock_helpers() {
+        let mut svm = litesvm::LiteSVM::new();
+        set_unix_timestamp(&mut svm, 1_000);
+        assert_eq!(advance_clock(&mut svm, 500), 1_500);
+        assert_eq!(
+            refill_rate_limits(&mut svm),
+            1_500 + RATE_LIMIT_DURATION + 1
+        );
+        let clock = svm.get_sysvar::<solana_sdk::clock::Clock>();
+        assert_eq!(clock.unix_timestamp, 1_500 + RATE_LIMIT_DURATION + 1);
+    }
+}
@@ -132,4 +132,5 @@ pub fn broadcast_vaa(
             .map_err(|e| e.to_string())
         },
     )
+    .map(|(signatures, _report)| signatures)
 }
@@ -0,0 +1,250 @@
+//! Guardian set rotation, for testing programs across guardian set
+//! transitions.
+//!
+//! [`rotate_guardian_set`] does what a guardian set upgrade does on chain:
+//! it creates the next guardian set, makes it current in the bridge config,
+//! and lets the old set expire after the bridge's guardian set TTL (24 hours
+//! as created by [`setup_wormhole`](crate::setup_wormhole)). VAAs signed by
+//! the old set keep verifying until then.
+//!
+//! To test rejection after expiry, either expire the old set right away with
+//! [`expire_guardian_set`], or move LiteSVM's clock past the expiration:
+//!
+//! ```ignore
+//! let rotation = rotate_guardian_set(&mut svm, &new_guardians)?;
+//!
+//! let mut clock = svm.get_sysvar::<Clock>();
+//! clock.unix_timestamp = rotation.old_set_expiration as i64 + 1;
+//! svm.set_sysvar(&clock);
+//! ```
+
+use litesvm::LiteSVM;
+use solana_sdk::{clock::Clock, pubkey::Pubkey};
+use wormhole_svm_definitions::find_guardian_set_address;
+
+use crate::litesvm::{create_guardian_set_account_for_network, WormholeNetwork, WormholeTestError};
+use crate::TestGuardianSet;
+
+/// Offset of the guardian set TTL (`guardian_set_expiration_time`) in the
+/// bridge config data.
+const CONFIG_GUARDIAN_SET_TTL_OFFSET: usize = 12;
+
+/// The outcome of [`rotate_guardian_set`].
+#[derive(Debug, Clone, Copy)]
+pub struct GuardianSetRotation {
+    /// Index of the new, now current, guardian set.
+    pub index: u32,
+    /// The new guardian set PDA address.
+    pub guardian_set: Pubkey,
+    /// The new guardian set PDA bump seed.
+    pub guardian_set_bump: u8,
+    /// Index of the replaced guardian set.
+    pub old_index: u32,
+    /// When the replaced guardian set expires (unix timestamp).
+    pub old_set_expiration: u32,
+}
+
+/// Replace the current guardian set with `guardians` at the next index.
+///
+/// Reads the current index and guardian set TTL from the bridge config, so
+/// Wormhole must be set up first (see [`setup_wormhole`](crate::setup_wormhole)).
+pub fn rotate_guardian_set(
+    svm: &mut LiteSVM,
+    guardians: &TestGuardianSet,
+) -> Result<GuardianSetRotation, WormholeTestError> {
+    rotate_guardian_set_for_network(svm, &WormholeNetwork::SOLANA_MAINNET, guardians)
+}
+
+/// [`rotate_guardian_set`] under the core bridge of `network`.
+pub fn rotate_guardian_set_for_network(
+    svm: &mut LiteSVM,
+    network: &WormholeNetwork,
+    guardians: &TestGuardianSet,
+) -> Result<GuardianSetRotation, WormholeTestError> {
+    let config_address = network.core_bridge_config_address();
+    let mut config = svm.get_account(&config_address).ok_or_else(|| {
+        WormholeTestError::LoadError(format!(
+            "bridge config {} not found; set up Wormhole first",
+            config_address
+        ))
+    })?;
+    if config.data.len() < CONFIG_GUARDIAN_SET_TTL_OFFSET + 4 {
+        return Err(WormholeTestError::LoadError(format!(
+            "bridge config {} is too short",
+            config_address
+        )));
+    }
+
+    let old_index = u32::from_le_bytes(config.data[0..4].try_into().unwrap());
+    let ttl = u32::from_le_bytes(
+        config.data[CONFIG_GUARDIAN_SET_TTL_OFFSET..CONFIG_GUARDIAN_SET_TTL_OFFSET + 4]
+            .try_into()
+            .unwrap(),
+    );
+    let index = old_index + 1;
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let old_set_expiration = u32::try_from(now).unwrap_or(0).saturating_add(ttl);
+    expire_guardian_set_for_network(svm, network, old_index, old_set_expiration)?;
+
+    let (guardian_set, guardian_set_bump) =
+        create_guardian_set_account_for_network(svm, network, guardians, index);
+
+    config.data[0..4].copy_from_slice(&index.to_le_bytes());
+    svm.set_account(config_address, config)
+        .map_err(|e| WormholeTestError::LoadError(format!("updating bridge config: {:?}", e)))?;
+
+    Ok(GuardianSetRotation {
+        index,
+        guardian_set,
+        guardian_set_bump,
+        old_index,
+        old_set_expiration,
+    })
+}
+
+/// Set the expiration time (unix timestamp) of the guardian set at `index`;
+/// 0 means it never expires.
+///
+/// A time in the past expires the set immediately.
+pub fn expire_guardian_set(
+    svm: &mut LiteSVM,
+    index: u32,
+    expiration_time: u32,
+) -> Result<(), WormholeTestError> {
+    expire_guardian_set_for_network(
+        svm,
+        &WormholeNetwork::SOLANA_MAINNET,
+        index,
+        expiration_time,
+    )
+}
+
+/// [`expire_guardian_set`] under the core bridge of `network`.
+pub fn expire_guardian_set_for_network(
+    svm: &mut LiteSVM,
+    network: &WormholeNetwork,
+    index: u32,
+    expiration_time: u32,
+) -> Result<(), WormholeTestError> {
+    let (address, _) = find_guardian_set_address(index.to_be_bytes(), &network.core_bridge);
+    let mut account = svm.get_account(&address).ok_or_else(|| {
+        WormholeTestError::LoadError(format!("guardian set {} not found at {}", index, address))
+    })?;
+
+    // index (4), keys_len (4), keys (20 each), creation_time (4), expiration_time (4)
+    let keys_len = account
+        .data
+        .get(4..8)
+        .map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize);
+    let offset = keys_len.map(|len| 8 + 20 * len + 4);
+    let Some(offset) = offset.filter(|offset| account.data.len() >= offset + 4) else {
+        return Err(WormholeTestError::LoadError(format!(
+            "guardian set {} at {} is malformed",
+            index, address
+        )));
+    };
+    account.data[offset..offset + 4].copy_from_slice(&expiration_time.to_le_bytes());
+
+    svm.set_account(address, account).map_err(|e| {
+        WormholeTestError::LoadError(format!("updating guardian set {}: {:?}", index, e))
+    })
+}
+
+/// Expire the guardian set at `index` and move LiteSVM's clock past the
+/// expiration, so verification against it fails from the next transaction.
+/// Returns the expiration time.
+pub fn expire_guardian_set_now(svm: &mut LiteSVM, index: u32) -> Result<u32, WormholeTestError> {
+    expire_guardian_set_now_for_network(svm, &WormholeNetwork::SOLANA_MAINNET, index)
+}
+
+/// [`expire_guardian_set_now`] under the core bridge of `network`.
+pub fn expire_guardian_set_now_for_network(
+    svm: &mut LiteSVM,
+    network: &WormholeNetwork,
+    index: u32,
+) -> Result<u32, WormholeTestError> {
+    let mut clock = svm.get_sysvar::<Clock>();
+    // 0 would mean "never expires"
+    let expiration_time = u32::try_from(clock.unix_timestamp).unwrap_or(0).max(1);
+    expire_guardian_set_for_network(svm, network, index, expiration_time)?;
+
+    clock.unix_timestamp = i64::from(expiration_time) + 1;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+    Ok(expiration_time)
+}
+
+#[cfg(all(test, feature = "bundled-fixtures"))]
+mod tests {
+    use super::*;
+    use crate::litesvm::{setup_wormhole, WormholeProgramsConfig};
+    use crate::TestGuardian;
+    use wormhole_svm_definitions::solana::mainnet::CORE_BRIDGE_CONFIG;
+
+    fn expiration_time(svm: &LiteSVM, guardian_set: &Pubkey) -> u32 {
+        let data = svm.get_account(guardian_set).unwrap().data;
+        u32::from_le_bytes(data[data.len() - 4..].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_rotate_guardian_set() {
+        let mut svm = LiteSVM::new();
+        let guardians = TestGuardianSet::single(TestGuardian::default());
+        let old =
+            setup_wormhole(&mut svm, &guardians, 0, WormholeProgramsConfig::default()).unwrap();
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = 1_700_000_000;
+        svm.set_sysvar(&clock);
+
+        let new_guardians = TestGuardianSet::generate(3, 42);
+        let rotation = rotate_guardian_set(&mut svm, &new_guardians).unwrap();
+
+        assert_eq!(rotation.old_index, 0);
+        assert_eq!(rotation.index, 1);
+        assert_eq!(rotation.old_set_expiration, 1_700_000_000 + 86400);
+
+        // Old set expires after the TTL, the new one never
+        assert_eq!(
+            expiration_time(&svm, &old.guardian_set),
+            rotation.old_set_expiration
+        );
+        assert_eq!(expiration_time(&svm, &rotation.guardian_set), 0);
+
+        // Bridge config points at the new set
+        let config = svm.get_account(&CORE_BRIDGE_CONFIG).unwrap();
+        assert_eq!(u32::from_le_bytes(config.data[0..4].try_into().unwrap()), 1);
+
+        // And the next rotation continues from there
+        let rotation = rotate_guardian_set(&mut svm, &guardians).unwrap();
+        assert_eq!(rotation.old_index, 1);
+        assert_eq!(rotation.index, 2);
+    }
+
+    #[test]
+    fn test_expire_guardian_set() {
+        let mut svm = LiteSVM::new();
+        let guardians = TestGuardianSet::generate(2, 7);
+        let accounts =
+            setup_wormhole(&mut svm, &guardians, 0, WormholeProgramsConfig::default()).unwrap();
+
+        expire_guardian_set(&mut svm, 0, 12345).unwrap();
+        assert_eq!(expiration_time(&svm, &accounts.guardian_set), 12345);
+
+        assert!(expire_guardian_set(&mut svm, 9, 12345).is_err());
+    }
+
+    #[test]
+    fn test_expire_guardian_set_now() {
+        let mut svm = LiteSVM::new();
+        let guardians = TestGuardianSet::single(TestGuardian::default());
+        let accounts =
+            setup_wormhole(&mut svm, &guardians, 0, WormholeProgramsConfig::default()).unwrap();
+
+        let expiration = expire_guardian_set_now(&mut svm, 0).unwrap();
+        assert!(expiration > 0);
+        assert_eq!(expiration_time(&svm, &accounts.guardian_set), expiration);
+        assert!(svm.get_sysvar::<Clock>().unix_timestamp > i64::from(expiration));
+    }
+}
@@ -0,0 +1,69 @@
+//! Reading programs from a live cluster, for fixture downloads and forks.
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, bpf_loader_upgradeable, pubkey::Pubkey};
+
+/// Size of the `UpgradeableLoaderState::ProgramData` header, which
+/// `solana program dump` strips.
+const PROGRAMDATA_METADATA_LEN: usize = 45;
+
+/// The executable bytes of the program at `program_id`, as `solana program
+/// dump` writes them.
+#[cfg(feature = "fixture-download")]
+pub(crate) fn fetch_program(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Vec<u8>, String> {
+    let account = rpc_client
+        .get_account(program_id)
+        .map_err(|e| e.to_string())?;
+    program_bytes(rpc_client, &account)
+}
+
+/// The executable bytes of the program `account`, fetching its program data
+/// account if it is upgradeable.
+pub(crate) fn program_bytes(rpc_client: &RpcClient, account: &Account) -> Result<Vec<u8>, String> {
+    if !account.executable {
+        return Err("not a program".to_string());
+    }
+    if account.owner != bpf_loader_upgradeable::ID {
+        return Ok(account.data.clone());
+    }
+
+    let programdata_address = programdata_address(&account.data)
+        .ok_or_else(|| "not an upgradeable program account".to_string())?;
+    let programdata = rpc_client
+        .get_account(&programdata_address)
+        .map_err(|e| format!("program data {}: {}", programdata_address, e))?;
+    if programdata.data.len() < PROGRAMDATA_METADATA_LEN {
+        return Err("program data account too short".to_string());
+    }
+    Ok(programdata.data[PROGRAMDATA_METADATA_LEN..].to_vec())
+}
+
+/// The program data address of an upgradeable program account:
+/// `UpgradeableLoaderState::Program` is tag 2 (u32 LE) followed by it.
+fn programdata_address(data: &[u8]) -> Option<Pubkey> {
+    if data.len() < 36 || data[..4] != [2, 0, 0, 0] {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data[4..36].try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_programdata_address() {
+        let programdata = Pubkey::new_unique();
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(programdata.as_ref());
+        assert_eq!(programdata_address(&data), Some(programdata));
+
+        // ProgramData (tag 3) and truncated accounts are not programs
+        data[0] = 3;
+        assert_eq!(programdata_address(&data), None);
+        assert_eq!(programdata_address(&[2, 0, 0, 0]), None);
+    }
+}
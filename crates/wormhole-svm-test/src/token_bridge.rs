@@ -0,0 +1,491 @@
+//! Token bridge test environment: program setup, foreign emitter
+//! registration, attestation and transfer VAAs, and wrapped mint checks.
+//!
+//! Requires the `fixture-download` feature: the token bridge binary is not
+//! bundled, so [`setup_token_bridge`] downloads it into the fixture cache
+//! unless a `token_bridge.so` is given or found next to the core programs.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use wormhole_svm_test::*;
+//!
+//! setup_wormhole(&mut svm, &guardians, 0, WormholeProgramsConfig::default())?;
+//! let token_bridge = setup_token_bridge(&mut svm, TokenBridgeProgramConfig::default())?;
+//! register_foreign_emitter(&mut svm, &token_bridge.program_id, 2, ETH_TOKEN_BRIDGE);
+//!
+//! let vaa = TestVaa::token_transfer(2, ETH_TOKEN_BRIDGE, 1, &TokenTransfer { ... });
+//! ```
+
+use std::path::PathBuf;
+
+use litesvm::LiteSVM;
+use solana_sdk::{account::Account, pubkey::Pubkey, rent::Rent};
+
+use crate::governance::uint256;
+use crate::litesvm::{get_program_bytes, WormholeNetwork, WormholeTestError};
+use crate::{FixtureDownload, TestVaa};
+
+/// Token bridge program ID on Solana mainnet.
+pub const TOKEN_BRIDGE_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb");
+
+/// Token bridge program ID on Solana devnet.
+pub const DEVNET_TOKEN_BRIDGE_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("DZnkkTmCiFWfYTfT41X3Rd1kDgozqzxWaHqsw6W4x2oe");
+
+/// SPL Token program ID (owner of wrapped mints).
+const SPL_TOKEN_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// Governance module of the token bridge: `"TokenBridge"`, left-padded to
+/// 32 bytes.
+pub const TOKEN_BRIDGE_MODULE: [u8; 32] = {
+    let name = b"TokenBridge";
+    let mut module = [0u8; 32];
+    let mut i = 0;
+    while i < name.len() {
+        module[32 - name.len() + i] = name[i];
+        i += 1;
+    }
+    module
+};
+
+/// Token bridge amounts are normalized to at most 8 decimals.
+const MAX_DECIMALS: u8 = 8;
+
+/// Public RPC URLs the token bridge is downloaded from by default.
+const MAINNET_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+
+/// Configuration for loading the token bridge.
+pub struct TokenBridgeProgramConfig {
+    /// Path to token_bridge.so (or None to search default locations).
+    pub token_bridge: Option<PathBuf>,
+    /// Address to load the program at.
+    pub program_id: Pubkey,
+    /// The Wormhole deployment the token bridge talks to.
+    pub network: WormholeNetwork,
+    /// Where to download the program if it isn't found (default: from
+    /// `WORMHOLE_FIXTURES_RPC_URL` if set, else from the public RPC of the
+    /// cluster `program_id` is deployed on).
+    pub download: Option<FixtureDownload>,
+}
+
+impl Default for TokenBridgeProgramConfig {
+    fn default() -> Self {
+        Self {
+            token_bridge: None,
+            program_id: TOKEN_BRIDGE_PROGRAM_ID,
+            network: WormholeNetwork::SOLANA_MAINNET,
+            download: None,
+        }
+    }
+}
+
+impl TokenBridgeProgramConfig {
+    /// The devnet token bridge, talking to the devnet core bridge.
+    pub fn devnet() -> Self {
+        Self {
+            token_bridge: None,
+            program_id: DEVNET_TOKEN_BRIDGE_PROGRAM_ID,
+            network: WormholeNetwork::SOLANA_DEVNET,
+            download: None,
+        }
+    }
+
+    /// The download to fall back to for the program binary.
+    fn resolve_download(&self) -> FixtureDownload {
+        self.download
+            .clone()
+            .or_else(FixtureDownload::from_env)
+            .unwrap_or_else(|| {
+                let rpc_url = if self.program_id == DEVNET_TOKEN_BRIDGE_PROGRAM_ID {
+                    DEVNET_RPC_URL
+                } else {
+                    MAINNET_RPC_URL
+                };
+                FixtureDownload::new(rpc_url)
+            })
+    }
+}
+
+/// Accounts created (or derived) by [`setup_token_bridge`].
+pub struct TokenBridgeAccounts {
+    pub program_id: Pubkey,
+    /// The config PDA, holding the core bridge address.
+    pub config: Pubkey,
+    /// The token bridge's emitter PDA (its messages' emitter address).
+    pub emitter: Pubkey,
+    /// The PDA minting wrapped tokens.
+    pub mint_authority: Pubkey,
+}
+
+/// Load the token bridge into `svm` and create its config account.
+///
+/// The program is read from `config.token_bridge`, found in the fixture
+/// search paths or downloaded (see [`TokenBridgeProgramConfig::download`]).
+/// Wormhole itself must be set up separately, with [`setup_wormhole`](crate::setup_wormhole)
+/// on the same network.
+pub fn setup_token_bridge(
+    svm: &mut LiteSVM,
+    config: TokenBridgeProgramConfig,
+) -> Result<TokenBridgeAccounts, WormholeTestError> {
+    let download = config.resolve_download();
+    let bytes = get_program_bytes(
+        "token_bridge.so",
+        config.token_bridge.as_ref(),
+        None,
+        Some((&download, &config.program_id)),
+    )?;
+    svm.add_program(config.program_id, &bytes)
+        .map_err(|e| WormholeTestError::LoadError(format!("token_bridge: {}", e)))?;
+
+    let program_id = config.program_id;
+    let config_address = create_token_bridge_config(svm, &program_id, &config.network);
+    Ok(TokenBridgeAccounts {
+        program_id,
+        config: config_address,
+        emitter: Pubkey::find_program_address(&[b"emitter"], &program_id).0,
+        mint_authority: Pubkey::find_program_address(&[b"mint_signer"], &program_id).0,
+    })
+}
+
+/// Create the token bridge config account (`Config { wormhole_bridge }`)
+/// and return its address.
+pub fn create_token_bridge_config(
+    svm: &mut LiteSVM,
+    program_id: &Pubkey,
+    network: &WormholeNetwork,
+) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    set_program_account(
+        svm,
+        address,
+        program_id,
+        network.core_bridge.to_bytes().to_vec(),
+    );
+    address
+}
+
+/// Register the token bridge of `chain` at `emitter_address`, as a
+/// `RegisterChain` governance VAA would, and return the endpoint address.
+pub fn register_foreign_emitter(
+    svm: &mut LiteSVM,
+    program_id: &Pubkey,
+    chain: u16,
+    emitter_address: [u8; 32],
+) -> Pubkey {
+    let address = endpoint_address(program_id, chain, &emitter_address);
+
+    // EndpointRegistration { chain: u16, contract: [u8; 32] }, borsh
+    let mut data = Vec::with_capacity(34);
+    data.extend_from_slice(&chain.to_le_bytes());
+    data.extend_from_slice(&emitter_address);
+    set_program_account(svm, address, program_id, data);
+    address
+}
+
+/// The endpoint PDA registering the token bridge of `chain` at
+/// `emitter_address`.
+pub fn endpoint_address(program_id: &Pubkey, chain: u16, emitter_address: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[&chain.to_be_bytes(), emitter_address], program_id).0
+}
+
+/// The wrapped mint of the token at `token_address` on `token_chain`.
+pub fn wrapped_mint_address(
+    program_id: &Pubkey,
+    token_chain: u16,
+    token_address: &[u8; 32],
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"wrapped", &token_chain.to_be_bytes(), token_address],
+        program_id,
+    )
+    .0
+}
+
+/// Assert that the wrapped mint of `token_chain`/`token_address` exists, is
+/// minted by the token bridge and has the decimals the token bridge gives a
+/// token with `original_decimals` (at most 8). Returns the mint address.
+///
+/// # Panics
+///
+/// If any of this doesn't hold.
+pub fn assert_wrapped_mint(
+    svm: &LiteSVM,
+    program_id: &Pubkey,
+    token_chain: u16,
+    token_address: &[u8; 32],
+    original_decimals: u8,
+) -> Pubkey {
+    let mint = wrapped_mint_address(program_id, token_chain, token_address);
+    let account = svm
+        .get_account(&mint)
+        .unwrap_or_else(|| panic!("wrapped mint {} does not exist", mint));
+    assert_eq!(
+        account.owner, SPL_TOKEN_PROGRAM_ID,
+        "wrapped mint {} is not an SPL token mint",
+        mint
+    );
+
+    // Mint: mint_authority (COption<Pubkey>: 4 + 32), supply (8), decimals (1), ...
+    let data = &account.data;
+    assert!(data.len() >= 46, "wrapped mint {} is too short", mint);
+    let mint_authority = (data[0..4] == [1, 0, 0, 0])
+        .then(|| Pubkey::new_from_array(data[4..36].try_into().unwrap()));
+    let (expected_authority, _) = Pubkey::find_program_address(&[b"mint_signer"], program_id);
+    assert_eq!(
+        mint_authority,
+        Some(expected_authority),
+        "wrapped mint {} is not minted by the token bridge",
+        mint
+    );
+    assert_eq!(
+        data[44],
+        original_decimals.min(MAX_DECIMALS),
+        "wrapped mint {} decimals",
+        mint
+    );
+    mint
+}
+
+/// The balance of the SPL token account `token_account`, if it exists.
+pub fn token_balance(svm: &LiteSVM, token_account: &Pubkey) -> Option<u64> {
+    // Account: mint (32), owner (32), amount (8), ...
+    let account = svm.get_account(token_account)?;
+    Some(u64::from_le_bytes(
+        account.data.get(64..72)?.try_into().ok()?,
+    ))
+}
+
+fn set_program_account(svm: &mut LiteSVM, address: Pubkey, owner: &Pubkey, data: Vec<u8>) {
+    let account = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(address, account).unwrap();
+}
+
+/// A token bridge transfer (payload 1), or, with a sender and payload,
+/// transfer with payload (payload 3).
+#[derive(Clone, Debug, Default)]
+pub struct TokenTransfer {
+    /// Amount, normalized to at most 8 decimals.
+    pub amount: u64,
+    /// Address of the token on its native chain.
+    pub token_address: [u8; 32],
+    /// Native chain of the token.
+    pub token_chain: u16,
+    /// Recipient: a token account, or for transfers with payload the
+    /// redeeming program.
+    pub recipient: [u8; 32],
+    pub recipient_chain: u16,
+    /// Relayer fee (transfers without payload only).
+    pub fee: u64,
+    /// Sender and payload of a transfer with payload.
+    pub with_payload: Option<([u8; 32], Vec<u8>)>,
+}
+
+impl TokenTransfer {
+    /// The payload bytes.
+    pub fn payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(133);
+        payload.push(if self.with_payload.is_some() { 3 } else { 1 });
+        payload.extend_from_slice(&uint256(self.amount));
+        payload.extend_from_slice(&self.token_address);
+        payload.extend_from_slice(&self.token_chain.to_be_bytes());
+        payload.extend_from_slice(&self.recipient);
+        payload.extend_from_slice(&self.recipient_chain.to_be_bytes());
+        match &self.with_payload {
+            Some((sender, extra)) => {
+                payload.extend_from_slice(sender);
+                payload.extend_from_slice(extra);
+            }
+            None => payload.extend_from_slice(&uint256(self.fee)),
+        }
+        payload
+    }
+}
+
+/// A token attestation (payload 2), which creates a wrapped mint.
+#[derive(Clone, Debug, Default)]
+pub struct TokenAttestation {
+    /// Address of the token on its native chain.
+    pub token_address: [u8; 32],
+    /// Native chain of the token.
+    pub token_chain: u16,
+    pub decimals: u8,
+    /// Up to 32 bytes; longer values are truncated.
+    pub symbol: String,
+    /// Up to 32 bytes; longer values are truncated.
+    pub name: String,
+}
+
+impl TokenAttestation {
+    /// The payload bytes.
+    pub fn payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(100);
+        payload.push(2);
+        payload.extend_from_slice(&self.token_address);
+        payload.extend_from_slice(&self.token_chain.to_be_bytes());
+        payload.push(self.decimals);
+        payload.extend_from_slice(&padded(&self.symbol));
+        payload.extend_from_slice(&padded(&self.name));
+        payload
+    }
+}
+
+/// `value` right-padded with zeros to 32 bytes.
+fn padded(value: &str) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let len = value.len().min(32);
+    bytes[..len].copy_from_slice(&value.as_bytes()[..len]);
+    bytes
+}
+
+impl TestVaa {
+    /// A token bridge transfer from the token bridge of `emitter_chain` at
+    /// `emitter_address`.
+    pub fn token_transfer(
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+        sequence: u64,
+        transfer: &TokenTransfer,
+    ) -> Self {
+        Self::new(emitter_chain, emitter_address, sequence, transfer.payload())
+    }
+
+    /// A token attestation from the token bridge of `emitter_chain` at
+    /// `emitter_address`.
+    pub fn token_attestation(
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+        sequence: u64,
+        attestation: &TokenAttestation,
+    ) -> Self {
+        Self::new(
+            emitter_chain,
+            emitter_address,
+            sequence,
+            attestation.payload(),
+        )
+    }
+
+    /// The token bridge governance VAA registering the token bridge of
+    /// `chain` at `emitter_address`.
+    pub fn register_chain(sequence: u64, chain: u16, emitter_address: [u8; 32]) -> Self {
+        let mut payload = Vec::with_capacity(69);
+        payload.extend_from_slice(&TOKEN_BRIDGE_MODULE);
+        payload.push(1);
+        payload.extend_from_slice(&0u16.to_be_bytes());
+        payload.extend_from_slice(&chain.to_be_bytes());
+        payload.extend_from_slice(&emitter_address);
+        Self::governance(sequence, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_payloads() {
+        let transfer = TokenTransfer {
+            amount: 1_000,
+            token_address: [0xAA; 32],
+            token_chain: 2,
+            recipient: [0xBB; 32],
+            recipient_chain: 1,
+            fee: 5,
+            with_payload: None,
+        };
+        let payload = transfer.payload();
+        assert_eq!(payload.len(), 133);
+        assert_eq!(payload[0], 1);
+        assert_eq!(&payload[25..33], &1_000u64.to_be_bytes());
+        assert_eq!(&payload[33..65], &[0xAA; 32]);
+        assert_eq!(&payload[65..67], &[0, 2]);
+        assert_eq!(&payload[67..99], &[0xBB; 32]);
+        assert_eq!(&payload[99..101], &[0, 1]);
+        assert_eq!(&payload[125..133], &5u64.to_be_bytes());
+
+        let with_payload = TokenTransfer {
+            with_payload: Some(([0xCC; 32], vec![1, 2, 3])),
+            ..transfer
+        };
+        let payload = with_payload.payload();
+        assert_eq!(payload[0], 3);
+        assert_eq!(&payload[101..133], &[0xCC; 32]);
+        assert_eq!(&payload[133..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_attestation_payload() {
+        let attestation = TokenAttestation {
+            token_address: [0xAA; 32],
+            token_chain: 2,
+            decimals: 18,
+            symbol: "WETH".to_string(),
+            name: "Wrapped Ether".to_string(),
+        };
+        let payload = attestation.payload();
+        assert_eq!(payload.len(), 100);
+        assert_eq!(payload[0], 2);
+        assert_eq!(payload[35], 18);
+        assert_eq!(&payload[36..40], b"WETH");
+        assert_eq!(&payload[40..68], &[0u8; 28]);
+        assert_eq!(&payload[68..81], b"Wrapped Ether");
+    }
+
+    #[test]
+    fn test_register_chain_vaa() {
+        let vaa = TestVaa::register_chain(1, 2, [0xEE; 32]);
+        assert_eq!(&vaa.payload[21..32], b"TokenBridge");
+        assert_eq!(vaa.payload[32], 1);
+        assert_eq!(&vaa.payload[33..37], &[0, 0, 0, 2]);
+        assert_eq!(&vaa.payload[37..], &[0xEE; 32]);
+        assert_eq!(vaa.emitter_address, crate::GOVERNANCE_EMITTER);
+    }
+
+    #[test]
+    fn test_accounts_without_program() {
+        let mut svm = LiteSVM::new();
+        let network = WormholeNetwork::SOLANA_MAINNET;
+
+        let config = create_token_bridge_config(&mut svm, &TOKEN_BRIDGE_PROGRAM_ID, &network);
+        let data = svm.get_account(&config).unwrap().data;
+        assert_eq!(data, network.core_bridge.to_bytes());
+
+        let endpoint = register_foreign_emitter(&mut svm, &TOKEN_BRIDGE_PROGRAM_ID, 2, [0xEE; 32]);
+        assert_eq!(
+            endpoint,
+            endpoint_address(&TOKEN_BRIDGE_PROGRAM_ID, 2, &[0xEE; 32])
+        );
+        let account = svm.get_account(&endpoint).unwrap();
+        assert_eq!(account.owner, TOKEN_BRIDGE_PROGRAM_ID);
+        assert_eq!(&account.data[..2], &2u16.to_le_bytes());
+    }
+
+    #[test]
+    fn test_assert_wrapped_mint() {
+        let mut svm = LiteSVM::new();
+        let mint = wrapped_mint_address(&TOKEN_BRIDGE_PROGRAM_ID, 2, &[0xAA; 32]);
+        let (authority, _) =
+            Pubkey::find_program_address(&[b"mint_signer"], &TOKEN_BRIDGE_PROGRAM_ID);
+
+        let mut data = vec![0u8; 82];
+        data[0] = 1;
+        data[4..36].copy_from_slice(authority.as_ref());
+        data[44] = 8;
+        data[45] = 1;
+        set_program_account(&mut svm, mint, &SPL_TOKEN_PROGRAM_ID, data);
+
+        // 18-decimal tokens are wrapped with 8 decimals
+        let found = assert_wrapped_mint(&svm, &TOKEN_BRIDGE_PROGRAM_ID, 2, &[0xAA; 32], 18);
+        assert_eq!(found, mint);
+    }
+}
@@ -1,5 +1,7 @@
 //! VAA construction utilities for testing.
 
+use std::sync::Arc;
+
 use sha3::{Digest, Keccak256};
 
 use crate::TestGuardianSet;
@@ -27,12 +29,25 @@ pub enum ReplayProtection {
 /// By default all checks are enabled. Disable specific checks for instructions
 /// where a field is intentionally unchecked (e.g. `initialize` derives its PDA
 /// from the emitter address, so any address is valid).
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct VaaChecks {
     /// Test that the program rejects a VAA with a different emitter chain.
     pub emitter_chain: bool,
     /// Test that the program rejects a VAA with a different emitter address.
     pub emitter_address: bool,
+    /// Test that the program rejects a VAA signed by one guardian short of
+    /// quorum. Only runs for sets of more than one guardian.
+    pub quorum: bool,
+    /// Test that the program rejects signatures posted under a different
+    /// guardian set index than the guardian set it verifies against.
+    pub guardian_set_index: bool,
+    /// Test that the program rejects a VAA signed by the guardian set after
+    /// that set has expired.
+    pub expired_guardian_set: bool,
+    /// Test that the program rejects a properly signed VAA whose payload has
+    /// its first byte (usually the payload type) flipped. Off by default: only
+    /// enable it for programs that validate their payload format.
+    pub payload_mutation: bool,
     /// Test that the program rejects a replayed VAA.
     pub replay: ReplayProtection,
 }
@@ -42,11 +57,64 @@ impl Default for VaaChecks {
         Self {
             emitter_chain: true,
             emitter_address: true,
+            quorum: true,
+            guardian_set_index: true,
+            expired_guardian_set: true,
+            payload_mutation: false,
             replay: ReplayProtection::default(),
         }
     }
 }
 
+/// A custom negative test for `with_vaa`: a change to the VAA that the
+/// program must reject, such as a recipient it doesn't own or an amount over
+/// its cap.
+///
+/// `with_vaa` signs the mutated VAA with the same guardians, posts the
+/// signatures under the same guardian set index and runs it on a cloned SVM;
+/// if the program accepts it, it fails with `MutationAccepted`.
+/// The mutation must actually change the VAA, or the program will (rightly)
+/// accept it.
+pub trait VaaMutation: Send + Sync {
+    /// The mutated VAA.
+    fn mutate(&self, vaa: &TestVaa) -> TestVaa;
+
+    /// What the program should have rejected, for the error message
+    /// (e.g. "recipient not owned by the program").
+    fn expected_failure(&self) -> String;
+}
+
+/// A [`VaaMutation`] from a closure and a description.
+pub struct FnMutation<F> {
+    expected_failure: String,
+    mutate: F,
+}
+
+impl<F> FnMutation<F>
+where
+    F: Fn(&TestVaa) -> TestVaa + Send + Sync,
+{
+    pub fn new(expected_failure: impl Into<String>, mutate: F) -> Self {
+        Self {
+            expected_failure: expected_failure.into(),
+            mutate,
+        }
+    }
+}
+
+impl<F> VaaMutation for FnMutation<F>
+where
+    F: Fn(&TestVaa) -> TestVaa + Send + Sync,
+{
+    fn mutate(&self, vaa: &TestVaa) -> TestVaa {
+        (self.mutate)(vaa)
+    }
+
+    fn expected_failure(&self) -> String {
+        self.expected_failure.clone()
+    }
+}
+
 /// A test VAA for construction and signing.
 #[derive(Clone)]
 pub struct TestVaa {
@@ -68,6 +136,30 @@ pub struct TestVaa {
     pub guardian_set_index: u32,
     /// Which automatic negative tests to run in `with_vaa`.
     pub checks: VaaChecks,
+    /// Additional negative tests for `with_vaa` (see [`VaaMutation`]).
+    pub mutations: Vec<Arc<dyn VaaMutation>>,
+}
+
+impl std::fmt::Debug for TestVaa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mutations: Vec<String> = self
+            .mutations
+            .iter()
+            .map(|mutation| mutation.expected_failure())
+            .collect();
+        f.debug_struct("TestVaa")
+            .field("emitter_chain", &self.emitter_chain)
+            .field("emitter_address", &hex::encode(self.emitter_address))
+            .field("sequence", &self.sequence)
+            .field("payload", &hex::encode(&self.payload))
+            .field("timestamp", &self.timestamp)
+            .field("nonce", &self.nonce)
+            .field("consistency_level", &self.consistency_level)
+            .field("guardian_set_index", &self.guardian_set_index)
+            .field("checks", &self.checks)
+            .field("mutations", &mutations)
+            .finish()
+    }
 }
 
 impl TestVaa {
@@ -88,9 +180,46 @@ impl TestVaa {
             consistency_level: 1,
             guardian_set_index: 0,
             checks: VaaChecks::default(),
+            mutations: Vec::new(),
         }
     }
 
+    /// Set the timestamp.
+    pub fn with_timestamp(mut self, timestamp: u32) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the nonce.
+    pub fn with_nonce(mut self, nonce: u32) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Set the consistency level.
+    pub fn with_consistency_level(mut self, consistency_level: u8) -> Self {
+        self.consistency_level = consistency_level;
+        self
+    }
+
+    /// Set the guardian set index the VAA claims to be signed by.
+    pub fn with_guardian_set_index(mut self, guardian_set_index: u32) -> Self {
+        self.guardian_set_index = guardian_set_index;
+        self
+    }
+
+    /// Set which automatic negative tests `with_vaa` runs.
+    pub fn with_checks(mut self, checks: VaaChecks) -> Self {
+        self.checks = checks;
+        self
+    }
+
+    /// Add a negative test for `with_vaa` to run.
+    pub fn with_mutation(mut self, mutation: impl VaaMutation + 'static) -> Self {
+        self.mutations.push(Arc::new(mutation));
+        self
+    }
+
     /// Build the VAA body bytes (without version, guardian set index, or signatures).
     pub fn body(&self) -> Vec<u8> {
         let mut body = Vec::new();
@@ -140,6 +269,18 @@ impl TestVaa {
         self.build_signed_vaa(&body, &signatures)
     }
 
+    /// Build a signed VAA with exactly a quorum of guardians.
+    pub fn sign_quorum(&self, guardians: &TestGuardianSet) -> Vec<u8> {
+        let body = self.body();
+        let signatures = guardians.sign_vaa_body_quorum(&body);
+        self.build_signed_vaa(&body, &signatures)
+    }
+
+    /// Get quorum guardian signatures for use with post_signatures instruction.
+    pub fn guardian_signatures_quorum(&self, guardians: &TestGuardianSet) -> Vec<[u8; 66]> {
+        guardians.sign_vaa_body_quorum(&self.body())
+    }
+
     /// Get guardian signatures for use with post_signatures instruction.
     pub fn guardian_signatures(&self, guardians: &TestGuardianSet) -> Vec<[u8; 66]> {
         let body = self.body();
@@ -147,7 +288,7 @@ impl TestVaa {
     }
 
     /// Build the full signed VAA bytes.
-    fn build_signed_vaa(&self, body: &[u8], signatures: &[[u8; 66]]) -> Vec<u8> {
+    pub(crate) fn build_signed_vaa(&self, body: &[u8], signatures: &[[u8; 66]]) -> Vec<u8> {
         let mut vaa = Vec::new();
 
         // Version (1 byte)
@@ -190,6 +331,50 @@ mod tests {
     use super::*;
     use crate::TestGuardian;
 
+    #[test]
+    fn test_with_mutation() {
+        let vaa = TestVaa::new(2, [0xAB; 32], 5, vec![1, 2, 3]).with_mutation(FnMutation::new(
+            "payload too long",
+            |vaa: &TestVaa| {
+                let mut mutated = vaa.clone();
+                mutated.payload.push(0);
+                mutated
+            },
+        ));
+
+        assert_eq!(vaa.mutations.len(), 1);
+        let mutation = &vaa.mutations[0];
+        assert_eq!(mutation.expected_failure(), "payload too long");
+        assert_eq!(mutation.mutate(&vaa).payload, vec![1, 2, 3, 0]);
+        // Clones share the registered mutations
+        assert_eq!(vaa.clone().mutations.len(), 1);
+    }
+
+    #[test]
+    fn test_builder_methods() {
+        let checks = VaaChecks {
+            replay: ReplayProtection::Replayable,
+            ..VaaChecks::default()
+        };
+        let vaa = TestVaa::new(2, [0xAB; 32], 5, vec![1])
+            .with_timestamp(1_700_000_000)
+            .with_nonce(9)
+            .with_consistency_level(32)
+            .with_guardian_set_index(4)
+            .with_checks(checks);
+
+        assert_eq!(vaa.timestamp, 1_700_000_000);
+        assert_eq!(vaa.nonce, 9);
+        assert_eq!(vaa.consistency_level, 32);
+        assert_eq!(vaa.guardian_set_index, 4);
+        assert_eq!(vaa.checks.replay, ReplayProtection::Replayable);
+
+        let body = vaa.body();
+        assert_eq!(&body[0..4], &1_700_000_000u32.to_be_bytes());
+        assert_eq!(&body[4..8], &9u32.to_be_bytes());
+        assert_eq!(body[50], 32);
+    }
+
     #[test]
     fn test_vaa_body_structure() {
         let vaa = TestVaa::new(
@@ -238,6 +423,17 @@ mod tests {
         assert_eq!(signed[5], 1);
     }
 
+    #[test]
+    fn test_sign_quorum() {
+        let guardians = TestGuardianSet::mainnet_like();
+        let vaa = TestVaa::new(1, [0xAB; 32], 1, vec![]);
+
+        let signed = vaa.sign_quorum(&guardians);
+        assert_eq!(signed[5], 13);
+        assert_eq!(signed.len(), 6 + 13 * 66 + vaa.body().len());
+        assert_eq!(vaa.guardian_signatures_quorum(&guardians).len(), 13);
+    }
+
     #[test]
     fn test_multi_guardian_signing() {
         let guardians = TestGuardianSet::generate(3, 123);
@@ -0,0 +1,94 @@
+//! Integration test of VAA verification across a guardian set rotation:
+//! VAAs signed by the old set verify during its grace period and are rejected
+//! after it expires, while the new set keeps working.
+
+#![cfg(feature = "bundled-fixtures")]
+
+use litesvm::LiteSVM;
+use solana_sdk::{clock::Clock, pubkey::Pubkey, signature::Signer, transaction::Transaction};
+use wormhole_svm_test::{
+    emitter_address_from_20, TestGuardianSet, TestVaa, WormholeTestEnv, WormholeTestError,
+};
+
+fn load_example_program(svm: &mut LiteSVM) {
+    svm.add_program_from_file(
+        vaa_verifier_example::ID,
+        "../../target/deploy/vaa_verifier_example.so",
+    )
+    .expect("Failed to load vaa_verifier_example program");
+}
+
+/// Verify `vaa` against `guardian_set` with the example program.
+fn verify(
+    env: &mut WormholeTestEnv,
+    guardians: &TestGuardianSet,
+    guardian_set_index: u32,
+    guardian_set: (Pubkey, u8),
+    vaa: &TestVaa,
+) -> Result<(), WormholeTestError> {
+    let payer = env.payer.insecure_clone();
+    wormhole_svm_test::with_vaa_unchecked(
+        &mut env.svm,
+        &payer,
+        guardians,
+        guardian_set_index,
+        vaa,
+        |svm, sigs_pubkey, vaa_body| {
+            let ix = vaa_verifier_example::build_verify_vaa_instruction(
+                &payer.pubkey(),
+                &guardian_set.0,
+                sigs_pubkey,
+                guardian_set.1,
+                vaa_body,
+            );
+            let tx = Transaction::new_signed_with_payer(
+                &[ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                svm.latest_blockhash(),
+            );
+            svm.send_transaction(tx)
+                .map(|_| ())
+                .map_err(|e| format!("{:?}", e))
+        },
+    )
+}
+
+#[test]
+fn test_old_guardian_set_during_grace_period_and_after_expiry() {
+    let mut env = WormholeTestEnv::builder().build().unwrap();
+    load_example_program(&mut env.svm);
+
+    let old_guardians = env.guardians.clone();
+    let old_set = (env.accounts.guardian_set, env.accounts.guardian_set_bump);
+    let rotation = env
+        .rotate_guardian_set(TestGuardianSet::generate(3, 99))
+        .unwrap();
+    let new_guardians = env.guardians.clone();
+    let new_set = (rotation.guardian_set, rotation.guardian_set_bump);
+
+    let vaa = TestVaa::new(
+        1,
+        emitter_address_from_20([0xAB; 20]),
+        1,
+        b"rotation".to_vec(),
+    );
+
+    // Grace period: both sets verify
+    verify(&mut env, &old_guardians, rotation.old_index, old_set, &vaa)
+        .expect("old set should verify during its grace period");
+    verify(&mut env, &new_guardians, rotation.index, new_set, &vaa).expect("new set should verify");
+
+    // Past the expiration: only the new set verifies
+    let mut clock = env.svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = rotation.old_set_expiration as i64 + 1;
+    env.svm.set_sysvar(&clock);
+    env.svm.expire_blockhash();
+
+    assert!(
+        verify(&mut env, &old_guardians, rotation.old_index, old_set, &vaa).is_err(),
+        "old set should be rejected after expiry"
+    );
+    verify(&mut env, &new_guardians, rotation.index, new_set, &vaa)
+        .expect("new set should still verify");
+}
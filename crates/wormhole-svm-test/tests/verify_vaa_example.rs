@@ -15,8 +15,8 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use wormhole_svm_test::{
-    close_signatures, emitter_address_from_20, post_signatures, setup_wormhole, TestGuardian,
-    TestGuardianSet, TestVaa, WormholeProgramsConfig,
+    close_signatures, emitter_address_from_20, post_signatures, setup_wormhole, CheckOutcome,
+    TestGuardian, TestGuardianSet, TestVaa, WormholeProgramsConfig,
 };
 
 const GUARDIAN_SET_INDEX: u32 = 0;
@@ -116,6 +116,60 @@ fn test_end_to_end_vaa_verification() {
     println!("Test complete!");
 }
 
+/// Verify a VAA signed by exactly a quorum (13) of a mainnet-sized (19)
+/// guardian set, as mainnet VAAs usually are.
+#[test]
+fn test_mainnet_like_quorum_verification() {
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 100_000_000_000).unwrap();
+
+    let guardians = TestGuardianSet::mainnet_like();
+    let wormhole = setup_wormhole(
+        &mut svm,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        WormholeProgramsConfig::default(),
+    )
+    .expect("Failed to setup Wormhole");
+    load_example_program(&mut svm);
+
+    let vaa = TestVaa::new(
+        1,
+        emitter_address_from_20([0xAB; 20]),
+        43,
+        b"mainnet-like".to_vec(),
+    );
+    let signatures = vaa.guardian_signatures_quorum(&guardians);
+    assert_eq!(signatures.len(), 13);
+
+    let posted = post_signatures(&mut svm, &payer, GUARDIAN_SET_INDEX, &signatures)
+        .expect("post_signatures failed");
+    let verify_ix = vaa_verifier_example::build_verify_vaa_instruction(
+        &payer.pubkey(),
+        &wormhole.guardian_set,
+        &posted.pubkey,
+        wormhole.guardian_set_bump,
+        &vaa.body(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[verify_ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    );
+    let meta = svm
+        .send_transaction(tx)
+        .expect("quorum-signed VAA should verify");
+    println!(
+        "Verified 13 of 19 signatures in {} CUs",
+        meta.compute_units_consumed
+    );
+
+    close_signatures(&mut svm, &payer, &posted.pubkey, &payer.pubkey())
+        .expect("close_signatures failed");
+}
+
 /// Test using the with_vaa bracket helper (recommended approach).
 ///
 /// This is the cleanest API - just provide the VAA and let the helper
@@ -184,10 +238,88 @@ fn test_with_vaa_helper() {
         },
     );
 
-    assert!(result.is_ok(), "with_vaa test failed: {:?}", result);
+    let (_, report) = result.expect("with_vaa test failed");
+    assert!(report.ran("signatures"));
+    assert!(report.ran("guardian_set_index"));
+    assert!(report.ran("expired_guardian_set"));
+    assert_eq!(
+        report.outcome("emitter_chain"),
+        Some(&CheckOutcome::Skipped("disabled in VaaChecks".to_string()))
+    );
+    // Single guardian: no quorum check
+    assert!(!report.ran("quorum"));
+    assert!(!report.ran("replay"));
     println!("with_vaa helper test complete!");
 }
 
+/// Test with_vaa with a multi-guardian set, which adds the quorum check.
+///
+/// The verify shim rejects a signatures account one guardian short of quorum,
+/// so the example program passes.
+#[test]
+fn test_with_vaa_quorum_check() {
+    use wormhole_svm_test::{with_vaa, ReplayProtection, VaaChecks};
+
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 100_000_000_000).unwrap();
+
+    let guardians = TestGuardianSet::generate(5, 42);
+    let wormhole = setup_wormhole(
+        &mut svm,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        WormholeProgramsConfig::default(),
+    )
+    .expect("Failed to setup Wormhole");
+    load_example_program(&mut svm);
+
+    // Example program only verifies signatures, not emitter fields or replays.
+    let vaa = TestVaa::new(
+        1,
+        emitter_address_from_20([0xEF; 20]),
+        1000,
+        b"quorum test".to_vec(),
+    )
+    .with_checks(VaaChecks {
+        emitter_chain: false,
+        emitter_address: false,
+        quorum: true,
+        guardian_set_index: true,
+        expired_guardian_set: true,
+        payload_mutation: false,
+        replay: ReplayProtection::Replayable,
+    });
+
+    let result = with_vaa(
+        &mut svm,
+        &payer,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        &vaa,
+        |svm, sigs_pubkey, vaa_body| {
+            let verify_ix = vaa_verifier_example::build_verify_vaa_instruction(
+                &payer.pubkey(),
+                &wormhole.guardian_set,
+                sigs_pubkey,
+                wormhole.guardian_set_bump,
+                vaa_body,
+            );
+            let tx = Transaction::new_signed_with_payer(
+                &[verify_ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                svm.latest_blockhash(),
+            );
+            svm.send_transaction(tx)
+                .map_err(|e| format!("tx failed: {:?}", e))
+        },
+    );
+
+    let (_, report) = result.expect("with_vaa quorum test failed");
+    assert!(report.ran("quorum"));
+}
+
 /// Test that with_vaa catches programs that skip VAA verification.
 ///
 /// This test uses the insecure `skip_verify` instruction which parses
@@ -348,6 +480,140 @@ fn test_with_vaa_catches_missing_replay_protection() {
     println!("with_vaa correctly detected the program lacks replay protection!");
 }
 
+/// Test that the opt-in payload mutation check catches a program that
+/// accepts any payload, as the example program does.
+#[test]
+fn test_with_vaa_catches_missing_payload_validation() {
+    use wormhole_svm_test::{with_vaa, ReplayProtection, WormholeTestError};
+
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 100_000_000_000).unwrap();
+
+    let guardians = TestGuardianSet::single(TestGuardian::default());
+    let wormhole = setup_wormhole(
+        &mut svm,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        WormholeProgramsConfig::default(),
+    )
+    .expect("Failed to setup Wormhole");
+    load_example_program(&mut svm);
+
+    let mut vaa = TestVaa::new(
+        1,
+        emitter_address_from_20([0xDE; 20]),
+        556,
+        b"\x01typed payload".to_vec(),
+    );
+    vaa.checks.emitter_chain = false;
+    vaa.checks.emitter_address = false;
+    vaa.checks.replay = ReplayProtection::Replayable;
+    vaa.checks.payload_mutation = true;
+
+    let result = with_vaa(
+        &mut svm,
+        &payer,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        &vaa,
+        |svm, sigs_pubkey, vaa_body| {
+            let verify_ix = vaa_verifier_example::build_verify_vaa_instruction(
+                &payer.pubkey(),
+                &wormhole.guardian_set,
+                sigs_pubkey,
+                wormhole.guardian_set_bump,
+                vaa_body,
+            );
+            let tx = Transaction::new_signed_with_payer(
+                &[verify_ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                svm.latest_blockhash(),
+            );
+            svm.send_transaction(tx)
+                .map_err(|e| format!("tx failed: {:?}", e))
+        },
+    );
+
+    let err = result.expect_err("with_vaa should have detected missing payload validation");
+    assert!(
+        matches!(err, WormholeTestError::PayloadValidationMissing(_)),
+        "Expected PayloadValidationMissing error, got: {:?}",
+        err
+    );
+}
+
+/// Test that with_vaa runs caller-registered mutations: the example program
+/// doesn't look at the payload, so a mutated "amount" is accepted.
+#[test]
+fn test_with_vaa_runs_custom_mutations() {
+    use wormhole_svm_test::{with_vaa, FnMutation, ReplayProtection, WormholeTestError};
+
+    let mut svm = LiteSVM::new();
+    let payer = Keypair::new();
+    svm.airdrop(&payer.pubkey(), 100_000_000_000).unwrap();
+
+    let guardians = TestGuardianSet::single(TestGuardian::default());
+    let wormhole = setup_wormhole(
+        &mut svm,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        WormholeProgramsConfig::default(),
+    )
+    .expect("Failed to setup Wormhole");
+    load_example_program(&mut svm);
+
+    // Payload: amount (u64, big-endian)
+    let mut vaa = TestVaa::new(
+        1,
+        emitter_address_from_20([0xDE; 20]),
+        557,
+        100u64.to_be_bytes().to_vec(),
+    )
+    .with_mutation(FnMutation::new("amount over the cap", |vaa: &TestVaa| {
+        let mut mutated = vaa.clone();
+        mutated.payload = u64::MAX.to_be_bytes().to_vec();
+        mutated
+    }));
+    vaa.checks.emitter_chain = false;
+    vaa.checks.emitter_address = false;
+    vaa.checks.replay = ReplayProtection::Replayable;
+
+    let result = with_vaa(
+        &mut svm,
+        &payer,
+        &guardians,
+        GUARDIAN_SET_INDEX,
+        &vaa,
+        |svm, sigs_pubkey, vaa_body| {
+            let verify_ix = vaa_verifier_example::build_verify_vaa_instruction(
+                &payer.pubkey(),
+                &wormhole.guardian_set,
+                sigs_pubkey,
+                wormhole.guardian_set_bump,
+                vaa_body,
+            );
+            let tx = Transaction::new_signed_with_payer(
+                &[verify_ix],
+                Some(&payer.pubkey()),
+                &[&payer],
+                svm.latest_blockhash(),
+            );
+            svm.send_transaction(tx)
+                .map_err(|e| format!("tx failed: {:?}", e))
+        },
+    );
+
+    let err = result.expect_err("with_vaa should have run the custom mutation");
+    match err {
+        WormholeTestError::MutationAccepted(message) => {
+            assert!(message.contains("amount over the cap"), "{}", message)
+        }
+        err => panic!("Expected MutationAccepted error, got: {:?}", err),
+    }
+}
+
 /// Test using the with_posted_signatures bracket helper (lower-level).
 #[test]
 fn test_with_posted_signatures_pattern() {